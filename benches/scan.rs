@@ -0,0 +1,50 @@
+/* benches/scan.rs */
+#![allow(missing_docs)]
+
+use std::hint::black_box;
+
+use clienthello::{Builder, scan};
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+
+/// About 16 MiB of pseudo-random bytes with a ClientHello record every
+/// 64 KiB.
+fn haystack() -> Vec<u8> {
+	let record = Builder::new()
+		.cipher_suites(&[0x1301, 0x1302, 0xc02b])
+		.server_name("bench.example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0304, 0x0303])
+		.build_record()
+		.unwrap();
+	let mut state = 0x2545_f491_4f6c_dd1d_u64;
+	let mut out = Vec::with_capacity(16 << 20);
+	while out.len() < 16 << 20 {
+		for _ in 0..(64 << 10) / 8 {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			out.extend_from_slice(&state.to_le_bytes());
+		}
+		out.extend_from_slice(&record);
+	}
+	out
+}
+
+fn bench_scan(c: &mut Criterion) {
+	let data = haystack();
+	let mut group = c.benchmark_group("scan");
+	group.throughput(Throughput::Bytes(data.len() as u64));
+	group.bench_function("records", |b| {
+		b.iter(|| scan::records(black_box(&data)).count());
+	});
+	group.bench_function("server_names", |b| {
+		b.iter(|| scan::server_names(black_box(&data)).count());
+	});
+	group.bench_function("client_hellos", |b| {
+		b.iter(|| scan::client_hellos(black_box(&data)).count());
+	});
+	group.finish();
+}
+
+criterion_group!(benches, bench_scan);
+criterion_main!(benches);
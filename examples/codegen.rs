@@ -0,0 +1,54 @@
+/* examples/codegen.rs */
+#![allow(missing_docs)]
+
+//! Print code reproducing a captured ClientHello.
+//!
+//! ```text
+//! cargo run --example codegen -- [--go] [HEX]
+//! ```
+//!
+//! The capture is read as hex from the argument or stdin, either a TLS
+//! record or a bare handshake message. Prints a Rust `Builder` chain, or
+//! a uTLS `ClientHelloSpec` with `--go`.
+
+use std::io::Read;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+	let mut go = false;
+	let mut input = None;
+	for arg in std::env::args().skip(1) {
+		match arg.as_str() {
+			"--go" => go = true,
+			_ => input = Some(arg),
+		}
+	}
+	let input = input.unwrap_or_else(|| {
+		let mut text = String::new();
+		let _ = std::io::stdin().read_to_string(&mut text);
+		text
+	});
+	let hex: String = input.split_whitespace().collect();
+	let bytes = match hex::decode(&hex) {
+		Ok(bytes) => bytes,
+		Err(e) => {
+			eprintln!("invalid hex: {e}");
+			return ExitCode::FAILURE;
+		}
+	};
+
+	let parsed = if bytes.first() == Some(&0x16) {
+		clienthello::parse_from_record(&bytes)
+	} else {
+		clienthello::parse(&bytes)
+	};
+	match parsed {
+		Ok(hello) if go => print!("{}", clienthello::codegen::utls(&hello)),
+		Ok(hello) => print!("{}", clienthello::codegen::rust(&hello)),
+		Err(e) => {
+			eprintln!("parse error: {e}");
+			return ExitCode::FAILURE;
+		}
+	}
+	ExitCode::SUCCESS
+}
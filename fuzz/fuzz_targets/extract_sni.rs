@@ -0,0 +1,9 @@
+/* fuzz/fuzz_targets/extract_sni.rs */
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	let _ = clienthello::extract_sni(data);
+	let _ = clienthello::extract_sni_from_record(data);
+});
@@ -0,0 +1,19 @@
+/* fuzz/fuzz_targets/parse.rs */
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	for hello in [clienthello::parse(data), clienthello::parse_from_record(data)]
+		.into_iter()
+		.flatten()
+	{
+		let _ = hello.server_name();
+		let _ = hello.alpn_protocols();
+		let _ = hello.inferred_quic_versions();
+	}
+	let (a, b) = data.split_at(data.len() / 2);
+	let mut scratch = Vec::new();
+	let _ = clienthello::parse_from_chunks([a, b], &mut scratch);
+	let _ = clienthello::parse_from_record_chunks([a, b], &mut scratch);
+});
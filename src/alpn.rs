@@ -0,0 +1,89 @@
+/* src/alpn.rs */
+
+use crate::ClientHello;
+use crate::typed::Alpn;
+
+/// Fixed set of ALPN protocol identifiers to match hellos against.
+///
+/// Built once, typically in a `static`, and checked on every connection
+/// by a demultiplexer deciding where a stream goes. Matching never
+/// allocates: a bitmap of the member lengths rejects most offered
+/// identifiers before any bytes are compared.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x33, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x08, 0x00, 0x10, 0x00, 0x04, 0x00, 0x02]);
+/// #     d.extend_from_slice(&[0x01, b'x']);
+/// #     d
+/// # }
+/// # let msg = build();
+/// use clienthello::AlpnSet;
+///
+/// static HTTP: AlpnSet<'static> = AlpnSet::new(&[b"h2", b"http/1.1"]);
+/// static TUNNEL: AlpnSet<'static> = AlpnSet::new(&[b"x"]);
+///
+/// let hello = clienthello::parse(&msg).unwrap();
+/// assert!(!HTTP.contains_any(&hello));
+/// assert!(TUNNEL.contains_any(&hello));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct AlpnSet<'p> {
+	protocols: &'p [&'p [u8]],
+	/// Bit `n` is set when some member is `n` bytes long.
+	lengths: [u64; 4],
+}
+
+impl<'p> AlpnSet<'p> {
+	/// Build a set from its member identifiers.
+	///
+	/// Identifiers that are empty or longer than 255 bytes cannot appear
+	/// in an ALPN extension and never match.
+	#[must_use]
+	pub const fn new(protocols: &'p [&'p [u8]]) -> Self {
+		let mut lengths = [0u64; 4];
+		let mut i = 0;
+		while i < protocols.len() {
+			let len = protocols[i].len();
+			if len > 0 && len <= 255 {
+				lengths[len / 64] |= 1 << (len % 64);
+			}
+			i += 1;
+		}
+		Self { protocols, lengths }
+	}
+
+	/// Member identifiers, in the order given to [`AlpnSet::new`].
+	#[must_use]
+	pub const fn protocols(&self) -> &'p [&'p [u8]] {
+		self.protocols
+	}
+
+	/// Check whether `proto` is a member.
+	#[must_use]
+	pub fn contains(&self, proto: &[u8]) -> bool {
+		let len = proto.len();
+		let word = self.lengths.get(len / 64).copied().unwrap_or(0);
+		word & (1 << (len % 64)) != 0 && self.protocols.contains(&proto)
+	}
+
+	/// Check whether the hello offers any member protocol.
+	#[must_use]
+	pub fn contains_any(&self, hello: &ClientHello<'_>) -> bool {
+		self.first_match(hello).is_some()
+	}
+
+	/// Return the first protocol in the client's preference order that
+	/// is a member.
+	#[must_use]
+	pub fn first_match<'a>(&self, hello: &ClientHello<'a>) -> Option<&'a [u8]> {
+		hello
+			.get::<Alpn>()
+			.unwrap_or_default()
+			.iter()
+			.copied()
+			.find(|proto| self.contains(proto))
+	}
+}
@@ -0,0 +1,125 @@
+/* src/alps.rs */
+
+//! Application-Layer Protocol Settings (ALPS) payloads.
+//!
+//! A ClientHello's `application_settings` extension (type `0x44cd`, or
+//! `0x4469` before Chrome 130) only lists the ALPN identifiers the
+//! client has settings for; see [`ClientHello::alps_protocols`]. The
+//! settings themselves travel encrypted, in the `application_settings`
+//! extension of the server's EncryptedExtensions and the client's
+//! ClientEncryptedExtensions, as an opaque payload for the negotiated
+//! protocol. [`AlpsSettings`] pairs such a payload with its protocol and
+//! decodes the HTTP/2 form, a SETTINGS frame body, whose values differ
+//! between browser versions.
+//!
+//! ```
+//! use clienthello::alps::{AlpsSettings, Http2Setting};
+//!
+//! let payload = [0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00];
+//! let settings = AlpsSettings::new(b"h2", &payload);
+//! let decoded = settings.http2().unwrap().unwrap();
+//! assert_eq!(decoded[0], Http2Setting { id: 0x1, value: 65536 });
+//! assert_eq!(decoded[1].name(), Some("ENABLE_PUSH"));
+//! ```
+//!
+//! [`ClientHello::alps_protocols`]: crate::ClientHello::alps_protocols
+
+use alloc::vec::Vec;
+
+use crate::Error;
+use crate::tls_reader::Reader;
+
+/// Current `application_settings` extension type.
+pub const EXTENSION_TYPE: u16 = crate::consts::EXT_APPLICATION_SETTINGS;
+/// Extension type used by Chrome before version 130.
+pub const EXTENSION_TYPE_OLD: u16 = crate::consts::EXT_APPLICATION_SETTINGS_OLD;
+
+/// The settings payload sent for one ALPN protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlpsSettings<'a> {
+	/// ALPN identifier the settings apply to.
+	pub protocol: &'a [u8],
+	/// Raw settings payload.
+	pub data: &'a [u8],
+}
+
+impl<'a> AlpsSettings<'a> {
+	/// Pair the body of an EncryptedExtensions `application_settings`
+	/// extension with the negotiated protocol.
+	#[must_use]
+	pub fn new(protocol: &'a [u8], data: &'a [u8]) -> Self {
+		Self { protocol, data }
+	}
+
+	/// Decode the payload as HTTP/2 settings, in wire order.
+	///
+	/// Returns `None` for protocols other than `h2`, whose payload
+	/// formats this crate does not know.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when the payload is not a whole
+	/// number of 6-byte settings.
+	#[must_use]
+	pub fn http2(&self) -> Option<Result<Vec<Http2Setting>, Error>> {
+		(self.protocol == b"h2").then(|| parse_http2(self.data))
+	}
+}
+
+/// One HTTP/2 setting (RFC 9113 §6.5.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Http2Setting {
+	/// Setting identifier.
+	pub id: u16,
+	/// Setting value.
+	pub value: u32,
+}
+
+impl Http2Setting {
+	/// Return the registered name of the setting, or `None` for
+	/// unassigned and GREASE identifiers.
+	#[must_use]
+	pub fn name(&self) -> Option<&'static str> {
+		Some(match self.id {
+			0x1 => "HEADER_TABLE_SIZE",
+			0x2 => "ENABLE_PUSH",
+			0x3 => "MAX_CONCURRENT_STREAMS",
+			0x4 => "INITIAL_WINDOW_SIZE",
+			0x5 => "MAX_FRAME_SIZE",
+			0x6 => "MAX_HEADER_LIST_SIZE",
+			0x8 => "ENABLE_CONNECT_PROTOCOL",
+			0x9 => "NO_RFC7540_PRIORITIES",
+			_ => return None,
+		})
+	}
+}
+
+fn parse_http2(data: &[u8]) -> Result<Vec<Http2Setting>, Error> {
+	if !data.len().is_multiple_of(6) {
+		return Err(Error::Truncated {
+			field: "HTTP/2 setting",
+		});
+	}
+	let mut r = Reader::new(data);
+	let mut settings = Vec::with_capacity(data.len() / 6);
+	while !r.is_empty() {
+		let id = r.read_u16("HTTP/2 setting identifier")?;
+		let hi = r.read_u16("HTTP/2 setting value")?;
+		let lo = r.read_u16("HTTP/2 setting value")?;
+		settings.push(Http2Setting {
+			id,
+			value: u32::from(hi) << 16 | u32::from(lo),
+		});
+	}
+	Ok(settings)
+}
+
+/// Decode the protocol list of a ClientHello `application_settings`
+/// body, stopping at the first entry that overruns it.
+pub(crate) fn protocols(data: &[u8]) -> Vec<&[u8]> {
+	Reader::new(data)
+		.read_nested_u16("ALPS protocol list", |r| {
+			Ok::<_, Error>(core::iter::from_fn(|| r.read_vec_u8("ALPS protocol").ok()).collect())
+		})
+		.unwrap_or_default()
+}
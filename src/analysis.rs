@@ -0,0 +1,182 @@
+/* src/analysis.rs */
+
+//! Heuristics for hellos sent by TLS-intercepting middleboxes.
+//!
+//! Corporate proxies, antivirus web shields, and other interception
+//! appliances terminate the browser's connection and open their own to
+//! the server, with a hello from their own TLS stack. Those stacks are
+//! often patched together from older code: TLS 1.3 bolted onto a 2010s
+//! cipher list, extensions browsers dropped years ago, and no GREASE.
+//! [`interception_score`] weighs such traits; no single one proves
+//! interception, but several together rarely come from a real browser.
+//!
+//! ```
+//! use clienthello::analysis::{InterceptionSignal, interception_score};
+//!
+//! let msg = clienthello::Builder::new()
+//!     .cipher_suites(&[0xc013, 0x1301, 0x0005])
+//!     .supported_versions(&[0x0304, 0x0303])
+//!     .extension(0x000f, &[0x01])
+//!     .build()
+//!     .unwrap();
+//! let hello = clienthello::parse(&msg).unwrap();
+//! let result = interception_score(&hello);
+//! assert!(result.signals.contains(&InterceptionSignal::ObsoleteCiphersWithTls13));
+//! assert_eq!(result.score, 100);
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::ClientHello;
+use crate::consts::EXT_KEY_SHARE;
+
+/// Extensions no mainstream client has sent since TLS 1.3 shipped:
+/// client_certificate_url, trusted_ca_keys, truncated_hmac, heartbeat,
+/// and NPN.
+const LEGACY_EXTENSIONS: &[u16] = &[0x0002, 0x0003, 0x0004, 0x000f, 0x3374];
+
+/// Cipher suites with NULL, EXPORT, RC4, or single-DES protection.
+/// 3DES is left out; browsers offered it until 2023.
+const OBSOLETE_SUITES: &[u16] = &[
+	0x0000, 0x0001, 0x0002, 0x0003, 0x0004, 0x0005, 0x0006, 0x0008, 0x0009, 0x000b, 0x000c, 0x000e,
+	0x000f, 0x0011, 0x0012, 0x0014, 0x0015, 0x0017, 0x0018, 0x0019, 0x001a, 0xc001, 0xc002, 0xc006,
+	0xc007, 0xc00b, 0xc00c, 0xc010, 0xc011, 0xc015, 0xc016,
+];
+
+/// A trait contributing to an [`InterceptionScore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum InterceptionSignal {
+	/// TLS 1.3 offered together with NULL, EXPORT, RC4, or DES suites.
+	ObsoleteCiphersWithTls13,
+	/// TLS 1.3 offered together with extensions browsers dropped, such
+	/// as heartbeat or NPN.
+	LegacyExtensionsWithTls13,
+	/// TLS 1.3 offered without a `key_share` extension, which forces a
+	/// HelloRetryRequest on every connection.
+	Tls13WithoutKeyShare,
+	/// TLS 1.3 cipher suites offered after TLS 1.2 ones; every
+	/// mainstream stack lists them first.
+	Tls13SuitesNotFirst,
+	/// The most preferred TLS 1.2 suite is CBC rather than AEAD.
+	CbcPreferred,
+	/// TLS 1.3 offered without GREASE. Firefox does this too, so it
+	/// weighs little on its own.
+	MissingGrease,
+}
+
+impl InterceptionSignal {
+	/// Short human-readable explanation of the signal.
+	#[must_use]
+	pub const fn description(self) -> &'static str {
+		match self {
+			Self::ObsoleteCiphersWithTls13 => "obsolete cipher suites next to TLS 1.3",
+			Self::LegacyExtensionsWithTls13 => "legacy extensions next to TLS 1.3",
+			Self::Tls13WithoutKeyShare => "TLS 1.3 without key share",
+			Self::Tls13SuitesNotFirst => "TLS 1.3 cipher suites not first",
+			Self::CbcPreferred => "CBC suite preferred over AEAD",
+			Self::MissingGrease => "TLS 1.3 without GREASE",
+		}
+	}
+
+	const fn weight(self) -> u8 {
+		match self {
+			Self::ObsoleteCiphersWithTls13 => 35,
+			Self::LegacyExtensionsWithTls13 => 30,
+			Self::Tls13WithoutKeyShare | Self::Tls13SuitesNotFirst => 25,
+			Self::CbcPreferred => 20,
+			Self::MissingGrease => 10,
+		}
+	}
+}
+
+impl fmt::Display for InterceptionSignal {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.description())
+	}
+}
+
+/// Result of [`interception_score`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterceptionScore {
+	/// Likelihood from 0 (browser-like) to 100 (almost certainly an
+	/// interception appliance).
+	pub score: u8,
+	/// Signals that fired, in declaration order of
+	/// [`InterceptionSignal`].
+	pub signals: Vec<InterceptionSignal>,
+}
+
+/// Score how likely `hello` comes from a TLS-intercepting middlebox
+/// rather than the end client.
+///
+/// Each [`InterceptionSignal`] that fires adds a fixed weight and the
+/// sum is capped at 100. Every signal but
+/// [`CbcPreferred`](InterceptionSignal::CbcPreferred) involves TLS 1.3,
+/// so TLS 1.2-only legacy clients score low; the heuristics look for
+/// modern versions grafted onto old stacks.
+#[must_use]
+pub fn interception_score(hello: &ClientHello<'_>) -> InterceptionScore {
+	let tls13 = hello.effective_versions().contains(&0x0304);
+	let suites = &hello.cipher_suites;
+	let checks = [
+		(
+			InterceptionSignal::ObsoleteCiphersWithTls13,
+			tls13 && suites.iter().any(|s| OBSOLETE_SUITES.contains(s)),
+		),
+		(
+			InterceptionSignal::LegacyExtensionsWithTls13,
+			tls13
+				&& LEGACY_EXTENSIONS
+					.iter()
+					.any(|&t| hello.extensions.contains(t)),
+		),
+		(
+			InterceptionSignal::Tls13WithoutKeyShare,
+			tls13 && !hello.extensions.contains(EXT_KEY_SHARE),
+		),
+		(
+			InterceptionSignal::Tls13SuitesNotFirst,
+			suites
+				.iter()
+				.skip_while(|&&s| is_tls13_suite(s))
+				.any(|&s| is_tls13_suite(s)),
+		),
+		(
+			InterceptionSignal::CbcPreferred,
+			suites
+				.iter()
+				.find(|&&s| !is_tls13_suite(s))
+				.is_some_and(|&s| !is_aead(s)),
+		),
+		(
+			InterceptionSignal::MissingGrease,
+			tls13 && !hello.has_grease,
+		),
+	];
+	let mut score: u8 = 0;
+	let mut signals = Vec::new();
+	for (signal, fired) in checks {
+		if fired {
+			score = score.saturating_add(signal.weight());
+			signals.push(signal);
+		}
+	}
+	InterceptionScore {
+		score: score.min(100),
+		signals,
+	}
+}
+
+fn is_tls13_suite(suite: u16) -> bool {
+	suite >> 8 == 0x13
+}
+
+/// GCM, CCM, and ChaCha20-Poly1305 suites for TLS 1.2.
+fn is_aead(suite: u16) -> bool {
+	matches!(
+		suite,
+		0x009c..=0x00a7 | 0xc02b..=0xc032 | 0xc09c..=0xc0af | 0xcca8..=0xccae | 0xd001..=0xd005
+	)
+}
@@ -0,0 +1,126 @@
+/* src/bitmap.rs */
+
+/// Extension types below this are tracked exactly, one bit each.
+const EXACT_RANGE: u16 = 128;
+
+/// A fixed-size set of extension types for constant-time pre-filtering.
+///
+/// Types below 128, which covers the IANA-assigned range in everyday
+/// use, get one bit each and are tested exactly. Higher types such as
+/// ECH (`0xfe0d`), ALPS (`0x4469`), and renegotiation info (`0xff01`) are
+/// hashed into 64 buckets, so [`contains`](Self::contains) can report a
+/// false positive for them but never a false negative: a filter that
+/// rejects on a missing bit only ever skips hellos that cannot match.
+///
+/// [`summarize`](crate::summarize) fills one in its single allocation-free
+/// pass, and [`ClientHello::extension_bitmap`](crate::ClientHello::extension_bitmap)
+/// builds one from a parsed hello. Filters can be declared as constants:
+///
+/// ```
+/// use clienthello::ExtensionBitmap;
+///
+/// const TLS13_WITH_ECH: ExtensionBitmap = ExtensionBitmap::from_types(&[0x002b, 0x0033, 0xfe0d]);
+///
+/// let seen: ExtensionBitmap = [0x0000, 0x002b, 0x0033, 0xfe0d].into_iter().collect();
+/// assert!(seen.contains_all(&TLS13_WITH_ECH));
+/// assert!(!ExtensionBitmap::from_types(&[0x002b]).contains_all(&TLS13_WITH_ECH));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ExtensionBitmap {
+	low: u128,
+	high: u64,
+}
+
+impl ExtensionBitmap {
+	/// Create an empty bitmap.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self { low: 0, high: 0 }
+	}
+
+	/// Create a bitmap holding `types`.
+	#[must_use]
+	pub const fn from_types(types: &[u16]) -> Self {
+		let mut out = Self::new();
+		let mut i = 0;
+		while i < types.len() {
+			out = out.with(types[i]);
+			i += 1;
+		}
+		out
+	}
+
+	/// Return this bitmap with `type_id` added.
+	#[must_use]
+	pub const fn with(self, type_id: u16) -> Self {
+		if type_id < EXACT_RANGE {
+			Self {
+				low: self.low | 1 << type_id,
+				..self
+			}
+		} else {
+			Self {
+				high: self.high | 1 << bucket(type_id),
+				..self
+			}
+		}
+	}
+
+	/// Add `type_id`.
+	pub fn insert(&mut self, type_id: u16) {
+		*self = self.with(type_id);
+	}
+
+	/// Check whether `type_id` may be present.
+	///
+	/// Exact for types below 128; for higher types `true` can be a false
+	/// positive from another type in the same bucket.
+	#[must_use]
+	pub const fn contains(&self, type_id: u16) -> bool {
+		if type_id < EXACT_RANGE {
+			self.low & 1 << type_id != 0
+		} else {
+			self.high & 1 << bucket(type_id) != 0
+		}
+	}
+
+	/// Check whether every type in `other` may be present, with the
+	/// false positives of [`contains`](Self::contains).
+	#[must_use]
+	pub const fn contains_all(&self, other: &Self) -> bool {
+		self.low & other.low == other.low && self.high & other.high == other.high
+	}
+
+	/// Check whether any type in `other` may be present, with the false
+	/// positives of [`contains`](Self::contains).
+	#[must_use]
+	pub const fn intersects(&self, other: &Self) -> bool {
+		self.low & other.low != 0 || self.high & other.high != 0
+	}
+
+	/// Check whether no type was added.
+	#[must_use]
+	pub const fn is_empty(&self) -> bool {
+		self.low == 0 && self.high == 0
+	}
+}
+
+impl FromIterator<u16> for ExtensionBitmap {
+	fn from_iter<I: IntoIterator<Item = u16>>(iter: I) -> Self {
+		let mut out = Self::new();
+		out.extend(iter);
+		out
+	}
+}
+
+impl Extend<u16> for ExtensionBitmap {
+	fn extend<I: IntoIterator<Item = u16>>(&mut self, iter: I) {
+		iter.into_iter().for_each(|t| self.insert(t));
+	}
+}
+
+/// Fibonacci-hash a type of 128 or above into one of 64 buckets; the
+/// common high types land in distinct buckets.
+const fn bucket(type_id: u16) -> u32 {
+	(type_id as u32).wrapping_mul(0x9e37_79b1) >> 26
+}
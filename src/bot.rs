@@ -0,0 +1,318 @@
+/* src/bot.rs */
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::ClientHello;
+use crate::consts::{EXT_ALPN, EXT_SERVER_NAME};
+use crate::session_id::offers_tls13;
+
+/// Highest cipher suite code point in the obsolete range: NULL, EXPORT,
+/// RC4, IDEA, DES, 3DES, and anonymous DH suites.
+const LAST_OBSOLETE_SUITE: u16 = 0x001b;
+/// Mainstream clients offer at least this many cipher suites.
+const MIN_COMMON_SUITES: usize = 4;
+/// Below this many extensions an ascending order is not meaningful.
+const MIN_ORDERED_EXTENSIONS: usize = 4;
+/// Default limit on the SNI extension body; a DNS name is at most 255
+/// bytes.
+const MAX_SERVER_NAME_LEN: usize = 1024;
+/// Default limit on any other extension body; the largest legitimate
+/// ones, hybrid post-quantum key shares and PSK tickets, stay near 2 kB.
+const MAX_EXTENSION_LEN: usize = 4096;
+/// Default limit on the extension count; browsers send about 20.
+const MAX_EXTENSIONS: usize = 40;
+
+/// An anomaly contributing to a [`BotScore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BotSignal {
+	/// No GREASE values, which every Chromium-based browser sends.
+	MissingGrease,
+	/// Extensions sorted by type identifier, typical of hand-assembled
+	/// hellos rather than real TLS stacks.
+	StaticExtensionOrder,
+	/// Very few cipher suites, or obsolete ones (NULL, EXPORT, RC4, DES).
+	RareCipherSet,
+	/// No ALPN extension, so no HTTP version was negotiated.
+	NoAlpn,
+	/// SNI is sent but TLS 1.3 is not offered.
+	Tls12OnlyWithSni,
+	/// The SNI extension body exceeds
+	/// [`BotScorer::max_server_name_len`], a sign of data smuggled in the
+	/// hostname.
+	OversizedServerName,
+	/// Another extension body exceeds [`BotScorer::max_extension_len`],
+	/// a sign of exfiltration over the ClientHello or fuzzing.
+	OversizedExtension,
+	/// More extensions than [`BotScorer::max_extensions`], typical of
+	/// fuzzers.
+	ExcessiveExtensions,
+}
+
+impl BotSignal {
+	/// Short human-readable explanation of the signal.
+	#[must_use]
+	pub const fn description(self) -> &'static str {
+		match self {
+			Self::MissingGrease => "no GREASE values",
+			Self::StaticExtensionOrder => "extensions sorted by type",
+			Self::RareCipherSet => "unusual or obsolete cipher suites",
+			Self::NoAlpn => "no ALPN extension",
+			Self::Tls12OnlyWithSni => "SNI without TLS 1.3 support",
+			Self::OversizedServerName => "oversized SNI extension",
+			Self::OversizedExtension => "oversized extension",
+			Self::ExcessiveExtensions => "too many extensions",
+		}
+	}
+}
+
+impl fmt::Display for BotSignal {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.description())
+	}
+}
+
+/// Result of [`BotScorer::score`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotScore {
+	/// Likelihood from 0 (browser-like) to 100 (almost certainly
+	/// automated).
+	pub score: u8,
+	/// Signals that fired, in declaration order of [`BotSignal`].
+	pub signals: Vec<BotSignal>,
+}
+
+/// Tunable weights and thresholds for bot-likelihood scoring.
+///
+/// Each [`BotSignal`] that fires adds its weight; the sum is capped at
+/// 100. The defaults of the five browser-likeness signals add up to 100,
+/// and each size anomaly weighs 50 on its own, since no mainstream client
+/// trips one. Set a weight to zero to ignore a signal, e.g.
+/// `missing_grease` when most legitimate traffic comes from Firefox or
+/// Safari.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d
+/// # }
+/// # let data = build();
+/// use clienthello::{BotScorer, BotSignal};
+///
+/// let hello = clienthello::parse(&data).unwrap();
+/// let result = BotScorer::new().missing_grease(0).score(&hello);
+/// assert!(result.signals.contains(&BotSignal::NoAlpn));
+/// assert_eq!(result.score, 35);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BotScorer {
+	missing_grease: u8,
+	static_extension_order: u8,
+	rare_cipher_set: u8,
+	no_alpn: u8,
+	tls12_only_with_sni: u8,
+	oversized_server_name: u8,
+	oversized_extension: u8,
+	excessive_extensions: u8,
+	max_server_name_len: usize,
+	max_extension_len: usize,
+	max_extensions: usize,
+}
+
+impl Default for BotScorer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl BotScorer {
+	/// Create a scorer with the default weights: 25 for missing GREASE,
+	/// 20 for a static extension order, 20 for a rare cipher set, 15 for
+	/// no ALPN, 20 for TLS 1.2-only with SNI, and 50 for each size
+	/// anomaly. The default thresholds flag an SNI body over 1024 bytes,
+	/// any other extension body over 4096 bytes, and more than 40
+	/// extensions.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			missing_grease: 25,
+			static_extension_order: 20,
+			rare_cipher_set: 20,
+			no_alpn: 15,
+			tls12_only_with_sni: 20,
+			oversized_server_name: 50,
+			oversized_extension: 50,
+			excessive_extensions: 50,
+			max_server_name_len: MAX_SERVER_NAME_LEN,
+			max_extension_len: MAX_EXTENSION_LEN,
+			max_extensions: MAX_EXTENSIONS,
+		}
+	}
+
+	/// Set the weight of [`BotSignal::MissingGrease`].
+	#[must_use]
+	pub const fn missing_grease(mut self, weight: u8) -> Self {
+		self.missing_grease = weight;
+		self
+	}
+
+	/// Set the weight of [`BotSignal::StaticExtensionOrder`].
+	#[must_use]
+	pub const fn static_extension_order(mut self, weight: u8) -> Self {
+		self.static_extension_order = weight;
+		self
+	}
+
+	/// Set the weight of [`BotSignal::RareCipherSet`].
+	#[must_use]
+	pub const fn rare_cipher_set(mut self, weight: u8) -> Self {
+		self.rare_cipher_set = weight;
+		self
+	}
+
+	/// Set the weight of [`BotSignal::NoAlpn`].
+	#[must_use]
+	pub const fn no_alpn(mut self, weight: u8) -> Self {
+		self.no_alpn = weight;
+		self
+	}
+
+	/// Set the weight of [`BotSignal::Tls12OnlyWithSni`].
+	#[must_use]
+	pub const fn tls12_only_with_sni(mut self, weight: u8) -> Self {
+		self.tls12_only_with_sni = weight;
+		self
+	}
+
+	/// Set the weight of [`BotSignal::OversizedServerName`].
+	#[must_use]
+	pub const fn oversized_server_name(mut self, weight: u8) -> Self {
+		self.oversized_server_name = weight;
+		self
+	}
+
+	/// Set the weight of [`BotSignal::OversizedExtension`].
+	#[must_use]
+	pub const fn oversized_extension(mut self, weight: u8) -> Self {
+		self.oversized_extension = weight;
+		self
+	}
+
+	/// Set the weight of [`BotSignal::ExcessiveExtensions`].
+	#[must_use]
+	pub const fn excessive_extensions(mut self, weight: u8) -> Self {
+		self.excessive_extensions = weight;
+		self
+	}
+
+	/// Set the largest SNI extension body, in bytes, that does not fire
+	/// [`BotSignal::OversizedServerName`].
+	#[must_use]
+	pub const fn max_server_name_len(mut self, len: usize) -> Self {
+		self.max_server_name_len = len;
+		self
+	}
+
+	/// Set the largest body of any other extension, in bytes, that does
+	/// not fire [`BotSignal::OversizedExtension`].
+	#[must_use]
+	pub const fn max_extension_len(mut self, len: usize) -> Self {
+		self.max_extension_len = len;
+		self
+	}
+
+	/// Set the largest extension count, GREASE excluded, that does not
+	/// fire [`BotSignal::ExcessiveExtensions`].
+	#[must_use]
+	pub const fn max_extensions(mut self, count: usize) -> Self {
+		self.max_extensions = count;
+		self
+	}
+
+	/// Score `hello`, reporting every signal that fired.
+	///
+	/// Signals with a zero weight are not reported.
+	#[must_use]
+	pub fn score(&self, hello: &ClientHello<'_>) -> BotScore {
+		let has = |type_id: u16| hello.extensions.contains(type_id);
+		let checks = [
+			(
+				BotSignal::MissingGrease,
+				self.missing_grease,
+				!hello.has_grease,
+			),
+			(
+				BotSignal::StaticExtensionOrder,
+				self.static_extension_order,
+				sorted_extensions(hello),
+			),
+			(
+				BotSignal::RareCipherSet,
+				self.rare_cipher_set,
+				rare_ciphers(hello),
+			),
+			(BotSignal::NoAlpn, self.no_alpn, !has(EXT_ALPN)),
+			(
+				BotSignal::Tls12OnlyWithSni,
+				self.tls12_only_with_sni,
+				has(EXT_SERVER_NAME) && !offers_tls13(hello) && hello.legacy_version < 0x0304,
+			),
+			(
+				BotSignal::OversizedServerName,
+				self.oversized_server_name,
+				lengths(hello).any(|(t, len)| t == EXT_SERVER_NAME && len > self.max_server_name_len),
+			),
+			(
+				BotSignal::OversizedExtension,
+				self.oversized_extension,
+				lengths(hello).any(|(t, len)| t != EXT_SERVER_NAME && len > self.max_extension_len),
+			),
+			(
+				BotSignal::ExcessiveExtensions,
+				self.excessive_extensions,
+				hello.extensions.len() > self.max_extensions,
+			),
+		];
+		let mut score: u8 = 0;
+		let mut signals = Vec::new();
+		for (signal, weight, fired) in checks {
+			if fired && weight > 0 {
+				score = score.saturating_add(weight);
+				signals.push(signal);
+			}
+		}
+		BotScore {
+			score: score.min(100),
+			signals,
+		}
+	}
+}
+
+/// Pair each extension type with its body length.
+fn lengths<'h>(hello: &'h ClientHello<'_>) -> impl Iterator<Item = (u16, usize)> + 'h {
+	hello.extensions.type_ids().zip(
+		hello
+			.extension_body_lengths
+			.iter()
+			.map(|&len| usize::from(len)),
+	)
+}
+
+/// Whether the extensions appear in ascending type order.
+fn sorted_extensions(hello: &ClientHello<'_>) -> bool {
+	hello.extensions.len() >= MIN_ORDERED_EXTENSIONS
+		&& hello
+			.extensions
+			.is_sorted_by(|a, b| a.type_id() < b.type_id())
+}
+
+fn rare_ciphers(hello: &ClientHello<'_>) -> bool {
+	hello.cipher_suites.len() < MIN_COMMON_SUITES
+		|| hello
+			.cipher_suites
+			.iter()
+			.any(|&c| c <= LAST_OBSOLETE_SUITE)
+}
@@ -0,0 +1,537 @@
+/* src/builder.rs */
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Error;
+use crate::tls_writer::Writer;
+use crate::types::{ContentType, HandshakeType};
+
+/// Encoder producing ClientHello messages from individual fields.
+///
+/// Extensions are emitted in the order they were first added; setting
+/// an extension type again replaces its contents in place.
+///
+/// ```
+/// let msg = clienthello::Builder::new()
+///     .cipher_suites(&[0x1301, 0x1302])
+///     .server_name("example.com")
+///     .alpn(&[b"h2"])
+///     .build()
+///     .unwrap();
+/// let hello = clienthello::parse(&msg).unwrap();
+/// assert_eq!(hello.server_name(), Some("example.com"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Builder {
+	record_version: u16,
+	legacy_version: u16,
+	random: [u8; 32],
+	session_id: Vec<u8>,
+	cipher_suites: Vec<u16>,
+	compression_methods: Vec<u8>,
+	extensions: Vec<BuilderExtension>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BuilderExtension {
+	ServerName(String),
+	Alpn(Vec<Vec<u8>>),
+	SupportedVersions(Vec<u16>),
+	SupportedGroups(Vec<u16>),
+	SignatureAlgorithms(Vec<u16>),
+	KeyShare(Vec<(u16, Vec<u8>)>),
+	PskExchangeModes(Vec<u8>),
+	RenegotiationInfo(Vec<u8>),
+	SessionTicket(Vec<u8>),
+	/// PSK identities with their obfuscated ticket ages, and one
+	/// zero-filled binder of `binder_len` bytes per identity.
+	PreSharedKey {
+		identities: Vec<(Vec<u8>, u32)>,
+		binder_len: usize,
+	},
+	/// Padding sized at encode time to reach the target length.
+	Padding {
+		target: usize,
+	},
+	Raw {
+		type_id: u16,
+		data: Vec<u8>,
+	},
+}
+
+impl BuilderExtension {
+	fn type_id(&self) -> u16 {
+		match self {
+			Self::ServerName(_) => 0x0000,
+			Self::Alpn(_) => 0x0010,
+			Self::SupportedVersions(_) => 0x002b,
+			Self::SupportedGroups(_) => 0x000a,
+			Self::SignatureAlgorithms(_) => 0x000d,
+			Self::KeyShare(_) => 0x0033,
+			Self::PskExchangeModes(_) => 0x002d,
+			Self::RenegotiationInfo(_) => 0xff01,
+			Self::SessionTicket(_) => 0x0023,
+			Self::PreSharedKey { .. } => 0x0029,
+			Self::Padding { .. } => 0x0015,
+			Self::Raw { type_id, .. } => *type_id,
+		}
+	}
+
+	/// Encoded body length, with `padding` bytes for [`Self::Padding`].
+	fn body_len(&self, padding: usize) -> usize {
+		match self {
+			Self::ServerName(host) => 2 + 1 + 2 + host.len(),
+			Self::Alpn(protocols) => 2 + protocols.iter().map(|p| 1 + p.len()).sum::<usize>(),
+			Self::SupportedVersions(versions) => 1 + 2 * versions.len(),
+			Self::SupportedGroups(values) | Self::SignatureAlgorithms(values) => 2 + 2 * values.len(),
+			Self::KeyShare(entries) => 2 + entries.iter().map(|(_, k)| 4 + k.len()).sum::<usize>(),
+			Self::PskExchangeModes(data) | Self::RenegotiationInfo(data) => 1 + data.len(),
+			Self::SessionTicket(ticket) => ticket.len(),
+			Self::PreSharedKey { .. } => self.identities_len() + self.binders_len(),
+			Self::Padding { .. } => padding,
+			Self::Raw { data, .. } => data.len(),
+		}
+	}
+
+	/// Encoded length of the PSK identities list, length prefix included.
+	fn identities_len(&self) -> usize {
+		match self {
+			Self::PreSharedKey { identities, .. } => {
+				2 + identities
+					.iter()
+					.map(|(id, _)| 2 + id.len() + 4)
+					.sum::<usize>()
+			}
+			_ => 0,
+		}
+	}
+
+	/// Encoded length of the PSK binders list, length prefix included.
+	fn binders_len(&self) -> usize {
+		match self {
+			Self::PreSharedKey {
+				identities,
+				binder_len,
+			} => 2 + identities.len() * (1 + binder_len),
+			_ => 0,
+		}
+	}
+
+	fn encode_body(&self, w: &mut Writer, padding: usize) -> Result<(), Error> {
+		match self {
+			Self::ServerName(host) => w.write_vec_u16(|w| {
+				w.write_u8(0x00);
+				w.write_opaque_u16(host.as_bytes())
+			}),
+			Self::Alpn(protocols) => w.write_vec_u16(|w| {
+				for proto in protocols {
+					w.write_opaque_u8(proto)?;
+				}
+				Ok(())
+			}),
+			Self::SupportedVersions(versions) => w.write_vec_u8(|w| {
+				versions.iter().for_each(|&v| w.write_u16(v));
+				Ok(())
+			}),
+			Self::SupportedGroups(values) | Self::SignatureAlgorithms(values) => w.write_vec_u16(|w| {
+				values.iter().for_each(|&v| w.write_u16(v));
+				Ok(())
+			}),
+			Self::KeyShare(entries) => w.write_vec_u16(|w| {
+				for (group, key) in entries {
+					w.write_u16(*group);
+					w.write_opaque_u16(key)?;
+				}
+				Ok(())
+			}),
+			Self::PskExchangeModes(data) | Self::RenegotiationInfo(data) => w.write_opaque_u8(data),
+			Self::SessionTicket(ticket) => {
+				w.write_bytes(ticket);
+				Ok(())
+			}
+			Self::PreSharedKey {
+				identities,
+				binder_len,
+			} => {
+				w.write_vec_u16(|w| {
+					for (identity, age) in identities {
+						w.write_opaque_u16(identity)?;
+						w.write_bytes(&age.to_be_bytes());
+					}
+					Ok(())
+				})?;
+				w.write_vec_u16(|w| {
+					for _ in identities {
+						w.write_opaque_u8(&alloc::vec![0u8; *binder_len])?;
+					}
+					Ok(())
+				})
+			}
+			Self::Padding { .. } => {
+				w.write_bytes(&alloc::vec![0u8; padding]);
+				Ok(())
+			}
+			Self::Raw { data, .. } => {
+				w.write_bytes(data);
+				Ok(())
+			}
+		}
+	}
+}
+
+impl Default for Builder {
+	fn default() -> Self {
+		Self {
+			record_version: 0x0301,
+			legacy_version: 0x0303,
+			random: [0u8; 32],
+			session_id: Vec::new(),
+			cipher_suites: Vec::new(),
+			compression_methods: alloc::vec![0x00],
+			extensions: Vec::new(),
+		}
+	}
+}
+
+impl Builder {
+	/// Create a builder with TLS 1.0 record version, TLS 1.2 legacy
+	/// version, a zeroed random, no session ID, null compression, and no
+	/// cipher suites or extensions.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the record-layer version written by
+	/// [`build_record`](Self::build_record) (defaults to `0x0301`).
+	///
+	/// Browsers send their first record as TLS 1.0 for compatibility
+	/// with old middleboxes (RFC 8446 §5.1), while some clients use
+	/// `0x0303`. The handshake's legacy version is set separately with
+	/// [`legacy_version`](Self::legacy_version).
+	#[must_use]
+	pub fn record_version(mut self, version: u16) -> Self {
+		self.record_version = version;
+		self
+	}
+
+	/// Set the legacy protocol version.
+	#[must_use]
+	pub fn legacy_version(mut self, version: u16) -> Self {
+		self.legacy_version = version;
+		self
+	}
+
+	/// Set the 32-byte client random.
+	#[must_use]
+	pub fn random(mut self, random: [u8; 32]) -> Self {
+		self.random = random;
+		self
+	}
+
+	/// Set the legacy session ID.
+	#[must_use]
+	pub fn session_id(mut self, session_id: &[u8]) -> Self {
+		self.session_id = session_id.to_vec();
+		self
+	}
+
+	/// Set the offered cipher suites, in preference order.
+	#[must_use]
+	pub fn cipher_suites(mut self, suites: &[u16]) -> Self {
+		self.cipher_suites = suites.to_vec();
+		self
+	}
+
+	/// Set the compression methods (defaults to `[0x00]`).
+	#[must_use]
+	pub fn compression_methods(mut self, methods: &[u8]) -> Self {
+		self.compression_methods = methods.to_vec();
+		self
+	}
+
+	/// Add a Server Name Indication extension with a single DNS hostname.
+	#[must_use]
+	pub fn server_name(self, host: &str) -> Self {
+		self.with_extension(BuilderExtension::ServerName(host.into()))
+	}
+
+	/// Add an ALPN extension.
+	#[must_use]
+	pub fn alpn(self, protocols: &[&[u8]]) -> Self {
+		let protocols = protocols.iter().map(|p| p.to_vec()).collect();
+		self.with_extension(BuilderExtension::Alpn(protocols))
+	}
+
+	/// Add a supported versions extension.
+	#[must_use]
+	pub fn supported_versions(self, versions: &[u16]) -> Self {
+		self.with_extension(BuilderExtension::SupportedVersions(versions.to_vec()))
+	}
+
+	/// Add a supported groups extension.
+	#[must_use]
+	pub fn supported_groups(self, groups: &[u16]) -> Self {
+		self.with_extension(BuilderExtension::SupportedGroups(groups.to_vec()))
+	}
+
+	/// Add a signature algorithms extension.
+	#[must_use]
+	pub fn signature_algorithms(self, algs: &[u16]) -> Self {
+		self.with_extension(BuilderExtension::SignatureAlgorithms(algs.to_vec()))
+	}
+
+	/// Add a key share extension from `(group, key_exchange)` pairs.
+	#[must_use]
+	pub fn key_share(self, entries: &[(u16, &[u8])]) -> Self {
+		let entries = entries.iter().map(|(g, k)| (*g, k.to_vec())).collect();
+		self.with_extension(BuilderExtension::KeyShare(entries))
+	}
+
+	/// Add a PSK key exchange modes extension.
+	#[must_use]
+	pub fn psk_exchange_modes(self, modes: &[u8]) -> Self {
+		self.with_extension(BuilderExtension::PskExchangeModes(modes.to_vec()))
+	}
+
+	/// Add a renegotiation info extension.
+	#[must_use]
+	pub fn renegotiation_info(self, data: &[u8]) -> Self {
+		self.with_extension(BuilderExtension::RenegotiationInfo(data.to_vec()))
+	}
+
+	/// Add an extension with a pre-encoded body.
+	#[must_use]
+	pub fn extension(self, type_id: u16, data: &[u8]) -> Self {
+		self.with_extension(BuilderExtension::Raw {
+			type_id,
+			data: data.to_vec(),
+		})
+	}
+
+	/// Add a session ticket extension (type `0x0023`) carrying `ticket`.
+	///
+	/// An empty ticket advertises support without resuming.
+	#[must_use]
+	pub fn session_ticket(self, ticket: &[u8]) -> Self {
+		self.with_extension(BuilderExtension::SessionTicket(ticket.to_vec()))
+	}
+
+	/// Add a pre-shared key offer (type `0x0029`) from
+	/// `(identity, obfuscated_ticket_age)` pairs.
+	///
+	/// Each identity gets a zero-filled binder of `binder_len` bytes, the
+	/// output size of the PSK's hash (32 for SHA-256, 48 for SHA-384).
+	/// The extension is always encoded last, as RFC 8446 §4.2.11
+	/// requires. Use [`build_with_binders`](Self::build_with_binders) to
+	/// fill in real binders.
+	#[must_use]
+	pub fn pre_shared_key(self, identities: &[(&[u8], u32)], binder_len: usize) -> Self {
+		let identities = identities
+			.iter()
+			.map(|(id, age)| (id.to_vec(), *age))
+			.collect();
+		self.with_extension(BuilderExtension::PreSharedKey {
+			identities,
+			binder_len,
+		})
+	}
+
+	/// Add a padding extension (type `0x0015`) sized so the handshake
+	/// message is `total_len` bytes long.
+	///
+	/// The padding length is worked out when the hello is encoded, so
+	/// fields set afterwards are accounted for. When the hello is already
+	/// `total_len` bytes or longer, the padding extension is empty. Pair
+	/// with [`estimated_size`](Self::estimated_size) to reproduce the
+	/// size buckets of a particular browser, e.g. BoringSSL padding
+	/// hellos of 256 to 511 bytes up to 512.
+	#[must_use]
+	pub fn pad_to(self, total_len: usize) -> Self {
+		self.with_extension(BuilderExtension::Padding { target: total_len })
+	}
+
+	/// Return the length of the handshake message [`build`](Self::build)
+	/// produces, header included.
+	///
+	/// [`build_record`](Self::build_record) adds the 5-byte record
+	/// header. Computed without encoding anything.
+	#[must_use]
+	pub fn estimated_size(&self) -> usize {
+		self.size_with_padding(self.padding_len())
+	}
+
+	fn size_with_padding(&self, padding: usize) -> usize {
+		let exts = if self.extensions.is_empty() {
+			0
+		} else {
+			2 + self
+				.extensions
+				.iter()
+				.map(|e| 4 + e.body_len(padding))
+				.sum::<usize>()
+		};
+		4 + 2
+			+ 32
+			+ 1 + self.session_id.len()
+			+ 2 + 2 * self.cipher_suites.len()
+			+ 1 + self.compression_methods.len()
+			+ exts
+	}
+
+	/// Bytes of padding needed to reach the [`pad_to`](Self::pad_to)
+	/// target, or 0 without one.
+	fn padding_len(&self) -> usize {
+		self
+			.extensions
+			.iter()
+			.find_map(|e| match e {
+				BuilderExtension::Padding { target } => Some(*target),
+				_ => None,
+			})
+			.map_or(0, |target| target.saturating_sub(self.size_with_padding(0)))
+	}
+
+	/// Length of the SNI hostname, when one is set.
+	#[cfg(feature = "hpke")]
+	pub(crate) fn server_name_len(&self) -> Option<usize> {
+		self.extensions.iter().find_map(|e| match e {
+			BuilderExtension::ServerName(host) => Some(host.len()),
+			_ => None,
+		})
+	}
+
+	fn with_extension(mut self, ext: BuilderExtension) -> Self {
+		let type_id = ext.type_id();
+		match self.extensions.iter_mut().find(|e| e.type_id() == type_id) {
+			Some(slot) => *slot = ext,
+			None => self.extensions.push(ext),
+		}
+		self
+	}
+
+	/// Encode a raw handshake message (starting with `0x01`).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when any field exceeds the size
+	/// its length prefix can express.
+	pub fn build(&self) -> Result<Vec<u8>, Error> {
+		let mut w = Writer::new();
+		self.write_handshake(&mut w)?;
+		Ok(w.into_bytes())
+	}
+
+	/// Encode a TLS record (starting with `0x16`) containing the
+	/// handshake message.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when any field exceeds the size
+	/// its length prefix can express, including the record length.
+	pub fn build_record(&self) -> Result<Vec<u8>, Error> {
+		let mut w = Writer::new();
+		w.write_u8(ContentType::Handshake.to_u8());
+		w.write_u16(self.record_version);
+		w.write_vec_u16(|w| self.write_handshake(w))?;
+		Ok(w.into_bytes())
+	}
+
+	/// Encode a raw handshake message and fill the PSK binders.
+	///
+	/// `binder` is called once per identity of the
+	/// [`pre_shared_key`](Self::pre_shared_key) offer with the identity
+	/// index, the partial ClientHello the binder covers (the message up
+	/// to, not including, the binders list; RFC 8446 §4.2.11.2), and the
+	/// binder to write. Without a PSK offer `binder` is never called.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when any field exceeds the size
+	/// its length prefix can express.
+	pub fn build_with_binders<F>(&self, binder: F) -> Result<Vec<u8>, Error>
+	where
+		F: FnMut(usize, &[u8], &mut [u8]),
+	{
+		let mut msg = self.build()?;
+		self.fill_binders(&mut msg, 0, binder);
+		Ok(msg)
+	}
+
+	/// Encode a TLS record and fill the PSK binders, as
+	/// [`build_with_binders`](Self::build_with_binders) does.
+	///
+	/// The partial ClientHello passed to `binder` excludes the record
+	/// header.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when any field exceeds the size
+	/// its length prefix can express, including the record length.
+	pub fn build_record_with_binders<F>(&self, binder: F) -> Result<Vec<u8>, Error>
+	where
+		F: FnMut(usize, &[u8], &mut [u8]),
+	{
+		let mut msg = self.build_record()?;
+		self.fill_binders(&mut msg, 5, binder);
+		Ok(msg)
+	}
+
+	/// Overwrite the binders at the end of `msg`, whose handshake message
+	/// starts at `start`.
+	fn fill_binders<F>(&self, msg: &mut [u8], start: usize, mut binder: F)
+	where
+		F: FnMut(usize, &[u8], &mut [u8]),
+	{
+		let Some((count, binder_len)) = self.extensions.iter().find_map(|e| match e {
+			BuilderExtension::PreSharedKey {
+				identities,
+				binder_len,
+			} => Some((identities.len(), *binder_len)),
+			_ => None,
+		}) else {
+			return;
+		};
+		let split = msg.len() - (2 + count * (1 + binder_len));
+		let (head, binders) = msg.split_at_mut(split);
+		let partial = &head[start..];
+		for (i, entry) in binders[2..].chunks_exact_mut(1 + binder_len).enumerate() {
+			binder(i, partial, &mut entry[1..]);
+		}
+	}
+
+	fn write_handshake(&self, w: &mut Writer) -> Result<(), Error> {
+		w.write_u8(HandshakeType::ClientHello.to_u8());
+		w.write_vec_u24(|w| self.write_body(w))
+	}
+
+	fn write_body(&self, w: &mut Writer) -> Result<(), Error> {
+		w.write_u16(self.legacy_version);
+		w.write_bytes(&self.random);
+		w.write_opaque_u8(&self.session_id)?;
+		w.write_vec_u16(|w| {
+			self.cipher_suites.iter().for_each(|&cs| w.write_u16(cs));
+			Ok(())
+		})?;
+		w.write_opaque_u8(&self.compression_methods)?;
+		if self.extensions.is_empty() {
+			return Ok(());
+		}
+		let padding = self.padding_len();
+		// pre_shared_key must be the last extension.
+		let is_psk = |e: &&BuilderExtension| matches!(e, BuilderExtension::PreSharedKey { .. });
+		let ordered = self
+			.extensions
+			.iter()
+			.filter(|e| !is_psk(e))
+			.chain(self.extensions.iter().filter(is_psk));
+		w.write_vec_u16(|w| {
+			for ext in ordered {
+				w.write_u16(ext.type_id());
+				w.write_vec_u16(|w| ext.encode_body(w, padding))?;
+			}
+			Ok(())
+		})
+	}
+}
@@ -0,0 +1,227 @@
+/* src/cache.rs */
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+
+/// Sentinel for an absent list link.
+const NIL: usize = usize::MAX;
+
+struct Node<V> {
+	hash: u64,
+	key: Box<[u8]>,
+	value: V,
+	prev: usize,
+	next: usize,
+}
+
+/// Least-recently-used cache of values derived from raw hello bytes.
+///
+/// Busy gateways see the same byte-identical hellos over and over; this
+/// memoizes whatever is computed from them (a parse summary, a
+/// fingerprint, a policy decision) so the work is done once per distinct
+/// hello. Entries are keyed by a randomly seeded SipHash of the bytes,
+/// and the bytes are stored and compared on lookup, so a hash collision
+/// is a miss rather than a wrong answer.
+///
+/// Because [`ClientHello`](crate::ClientHello) borrows its input, cache
+/// owned values derived from it. Wrap them in `Result` so malformed
+/// input is remembered too.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d
+/// # }
+/// # let data = build();
+/// use clienthello::HelloCache;
+///
+/// let mut cache = HelloCache::new(1024);
+/// let summarize = |bytes: &[u8]| clienthello::parse(bytes).map(|h| h.cipher_suites.len());
+/// assert_eq!(*cache.get_or_insert_with(&data, summarize), Ok(1));
+/// assert_eq!(*cache.get_or_insert_with(&data, summarize), Ok(1));
+/// assert_eq!((cache.hits(), cache.misses()), (1, 1));
+/// ```
+pub struct HelloCache<V> {
+	capacity: usize,
+	map: HashMap<u64, usize>,
+	nodes: Vec<Node<V>>,
+	/// Most recently used entry.
+	head: usize,
+	/// Least recently used entry, evicted first.
+	tail: usize,
+	state: RandomState,
+	hits: u64,
+	misses: u64,
+}
+
+impl<V> core::fmt::Debug for HelloCache<V> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("HelloCache")
+			.field("capacity", &self.capacity)
+			.field("len", &self.nodes.len())
+			.field("hits", &self.hits)
+			.field("misses", &self.misses)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<V> HelloCache<V> {
+	/// Create a cache holding at most `capacity` entries (at least one).
+	#[must_use]
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			capacity: capacity.max(1),
+			map: HashMap::new(),
+			nodes: Vec::new(),
+			head: NIL,
+			tail: NIL,
+			state: RandomState::new(),
+			hits: 0,
+			misses: 0,
+		}
+	}
+
+	/// Maximum number of entries.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Number of cached entries.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// Check whether the cache holds no entries.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+
+	/// Number of lookups answered from the cache.
+	#[must_use]
+	pub fn hits(&self) -> u64 {
+		self.hits
+	}
+
+	/// Number of lookups that missed the cache.
+	#[must_use]
+	pub fn misses(&self) -> u64 {
+		self.misses
+	}
+
+	/// Return the cached value for `data`, marking it most recently used.
+	///
+	/// Counts as a hit or a miss.
+	pub fn get(&mut self, data: &[u8]) -> Option<&V> {
+		let hash = self.state.hash_one(data);
+		let Some(i) = self.find(hash, data) else {
+			self.misses += 1;
+			return None;
+		};
+		self.hits += 1;
+		self.touch(i);
+		Some(&self.nodes[i].value)
+	}
+
+	/// Return the cached value for `data`, computing and inserting it
+	/// with `f` on a miss.
+	///
+	/// When the cache is full, the least recently used entry is evicted.
+	pub fn get_or_insert_with<F>(&mut self, data: &[u8], f: F) -> &V
+	where
+		F: FnOnce(&[u8]) -> V,
+	{
+		let hash = self.state.hash_one(data);
+		if let Some(i) = self.find(hash, data) {
+			self.hits += 1;
+			self.touch(i);
+			return &self.nodes[i].value;
+		}
+		self.misses += 1;
+		let value = f(data);
+		let i = self.insert(hash, data, value);
+		&self.nodes[i].value
+	}
+
+	/// Remove every entry and reset the hit and miss counters.
+	pub fn clear(&mut self) {
+		self.map.clear();
+		self.nodes.clear();
+		self.head = NIL;
+		self.tail = NIL;
+		self.hits = 0;
+		self.misses = 0;
+	}
+
+	fn find(&self, hash: u64, data: &[u8]) -> Option<usize> {
+		let &i = self.map.get(&hash)?;
+		(*self.nodes[i].key == *data).then_some(i)
+	}
+
+	/// Store a new entry and return its slot. A colliding entry with the
+	/// same hash is replaced; otherwise a free or the oldest slot is used.
+	fn insert(&mut self, hash: u64, data: &[u8], value: V) -> usize {
+		let slot = match self.map.get(&hash) {
+			Some(&i) => Some(i),
+			None if self.nodes.len() >= self.capacity => {
+				let oldest = self.tail;
+				self.map.remove(&self.nodes[oldest].hash);
+				Some(oldest)
+			}
+			None => None,
+		};
+		let i = if let Some(i) = slot {
+			self.unlink(i);
+			let node = &mut self.nodes[i];
+			node.hash = hash;
+			node.key = data.into();
+			node.value = value;
+			i
+		} else {
+			self.nodes.push(Node {
+				hash,
+				key: data.into(),
+				value,
+				prev: NIL,
+				next: NIL,
+			});
+			self.nodes.len() - 1
+		};
+		self.map.insert(hash, i);
+		self.push_front(i);
+		i
+	}
+
+	fn touch(&mut self, i: usize) {
+		if self.head != i {
+			self.unlink(i);
+			self.push_front(i);
+		}
+	}
+
+	fn unlink(&mut self, i: usize) {
+		let (prev, next) = (self.nodes[i].prev, self.nodes[i].next);
+		match prev {
+			NIL => self.head = next,
+			p => self.nodes[p].next = next,
+		}
+		match next {
+			NIL => self.tail = prev,
+			n => self.nodes[n].prev = prev,
+		}
+	}
+
+	fn push_front(&mut self, i: usize) {
+		self.nodes[i].prev = NIL;
+		self.nodes[i].next = self.head;
+		match self.head {
+			NIL => self.tail = i,
+			h => self.nodes[h].prev = i,
+		}
+		self.head = i;
+	}
+}
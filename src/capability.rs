@@ -0,0 +1,245 @@
+/* src/capability.rs */
+
+use alloc::string::String;
+use core::fmt::{Display, Write};
+
+use crate::ClientHello;
+use crate::consts::{
+	EXT_APPLICATION_SETTINGS, EXT_APPLICATION_SETTINGS_OLD, EXT_COMPRESS_CERTIFICATE,
+	EXT_DELEGATED_CREDENTIAL, EXT_EARLY_DATA, EXT_ENCRYPT_THEN_MAC, EXT_ENCRYPTED_CLIENT_HELLO,
+	EXT_EXTENDED_MASTER_SECRET, EXT_POST_HANDSHAKE_AUTH, EXT_PRE_SHARED_KEY, EXT_RECORD_SIZE_LIMIT,
+	EXT_SESSION_TICKET, EXT_SIGNED_CERTIFICATE_TIMESTAMP, EXT_STATUS_REQUEST, HYBRID_GROUPS,
+};
+
+/// A protocol feature a client may support, one column of the
+/// capability matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Capability {
+	/// TLS 1.3 among the offered versions.
+	Tls13,
+	/// A hybrid post-quantum group in Supported Groups or Key Share.
+	PostQuantum,
+	/// Encrypted Client Hello, real or GREASE.
+	Ech,
+	/// Application-Layer Protocol Settings, either code point.
+	Alps,
+	/// TLS 1.2 session tickets (RFC 5077).
+	SessionTickets,
+	/// A pre-shared key offer, i.e. TLS 1.3 resumption.
+	Psk,
+	/// 0-RTT early data.
+	EarlyData,
+	/// OCSP stapling via `status_request`.
+	Ocsp,
+	/// Signed certificate timestamps.
+	Sct,
+	/// Extended master secret (RFC 7627).
+	ExtendedMasterSecret,
+	/// Encrypt-then-MAC (RFC 7366).
+	EncryptThenMac,
+	/// Certificate compression (RFC 8879).
+	CertificateCompression,
+	/// Delegated credentials (RFC 9345).
+	DelegatedCredentials,
+	/// Record size limit (RFC 8449).
+	RecordSizeLimit,
+	/// Post-handshake client authentication.
+	PostHandshakeAuth,
+	/// GREASE in any list.
+	Grease,
+}
+
+impl Capability {
+	/// Every capability, in column order.
+	pub const ALL: &'static [Self] = &[
+		Self::Tls13,
+		Self::PostQuantum,
+		Self::Ech,
+		Self::Alps,
+		Self::SessionTickets,
+		Self::Psk,
+		Self::EarlyData,
+		Self::Ocsp,
+		Self::Sct,
+		Self::ExtendedMasterSecret,
+		Self::EncryptThenMac,
+		Self::CertificateCompression,
+		Self::DelegatedCredentials,
+		Self::RecordSizeLimit,
+		Self::PostHandshakeAuth,
+		Self::Grease,
+	];
+
+	/// Column name, e.g. `"post_quantum"`.
+	#[must_use]
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Tls13 => "tls13",
+			Self::PostQuantum => "post_quantum",
+			Self::Ech => "ech",
+			Self::Alps => "alps",
+			Self::SessionTickets => "session_tickets",
+			Self::Psk => "psk",
+			Self::EarlyData => "early_data",
+			Self::Ocsp => "ocsp",
+			Self::Sct => "sct",
+			Self::ExtendedMasterSecret => "extended_master_secret",
+			Self::EncryptThenMac => "encrypt_then_mac",
+			Self::CertificateCompression => "certificate_compression",
+			Self::DelegatedCredentials => "delegated_credentials",
+			Self::RecordSizeLimit => "record_size_limit",
+			Self::PostHandshakeAuth => "post_handshake_auth",
+			Self::Grease => "grease",
+		}
+	}
+
+	const fn bit(self) -> u32 {
+		1 << self as u32
+	}
+}
+
+impl Display for Capability {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// The capabilities one hello advertises, one row of the capability
+/// matrix; see [`ClientHello::capabilities`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+	/// Check whether the hello advertises `capability`.
+	#[must_use]
+	pub const fn contains(self, capability: Capability) -> bool {
+		self.0 & capability.bit() != 0
+	}
+
+	/// Iterate the advertised capabilities in column order.
+	pub fn iter(self) -> impl Iterator<Item = Capability> {
+		Capability::ALL
+			.iter()
+			.copied()
+			.filter(move |&c| self.contains(c))
+	}
+
+	/// The CSV header row, `label` followed by every
+	/// [`Capability::name`], without a line terminator.
+	#[must_use]
+	pub fn csv_header() -> String {
+		let mut out = String::from("label");
+		for capability in Capability::ALL {
+			out.push(',');
+			out.push_str(capability.name());
+		}
+		out
+	}
+
+	/// One CSV row: `label`, quoted as RFC 4180 requires, then `1` or
+	/// `0` per capability in header order, without a line terminator.
+	#[must_use]
+	pub fn to_csv_row(self, label: &str) -> String {
+		let mut out = String::new();
+		if label.contains([',', '"', '\r', '\n']) {
+			out.push('"');
+			out.push_str(&label.replace('"', "\"\""));
+			out.push('"');
+		} else {
+			out.push_str(label);
+		}
+		for &capability in Capability::ALL {
+			out.push_str(if self.contains(capability) {
+				",1"
+			} else {
+				",0"
+			});
+		}
+		out
+	}
+
+	/// Write a whole matrix as CSV, a header then one row per labeled
+	/// hello, each line ending in CRLF.
+	///
+	/// Labels identify the rows, e.g. a flow or a server name.
+	#[must_use]
+	pub fn to_csv<L, I>(rows: I) -> String
+	where
+		L: Display,
+		I: IntoIterator<Item = (L, Self)>,
+	{
+		let mut out = Self::csv_header();
+		out.push_str("\r\n");
+		let mut label = String::new();
+		for (l, capabilities) in rows {
+			label.clear();
+			let _ = write!(label, "{l}");
+			out.push_str(&capabilities.to_csv_row(&label));
+			out.push_str("\r\n");
+		}
+		out
+	}
+}
+
+impl FromIterator<Capability> for Capabilities {
+	fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+		Self(iter.into_iter().fold(0, |bits, c| bits | c.bit()))
+	}
+}
+
+pub(crate) fn detect(hello: &ClientHello<'_>) -> Capabilities {
+	let has = |type_id| hello.extensions.contains(type_id);
+	let grease = &hello.grease;
+	let checks = [
+		(
+			Capability::Tls13,
+			hello.effective_versions().contains(&0x0304),
+		),
+		(
+			Capability::PostQuantum,
+			hello
+				.supported_groups()
+				.iter()
+				.chain(hello.key_share_groups())
+				.any(|g| HYBRID_GROUPS.contains(g)),
+		),
+		(Capability::Ech, has(EXT_ENCRYPTED_CLIENT_HELLO)),
+		(
+			Capability::Alps,
+			has(EXT_APPLICATION_SETTINGS) || has(EXT_APPLICATION_SETTINGS_OLD),
+		),
+		(Capability::SessionTickets, has(EXT_SESSION_TICKET)),
+		(Capability::Psk, has(EXT_PRE_SHARED_KEY)),
+		(Capability::EarlyData, has(EXT_EARLY_DATA)),
+		(Capability::Ocsp, has(EXT_STATUS_REQUEST)),
+		(Capability::Sct, has(EXT_SIGNED_CERTIFICATE_TIMESTAMP)),
+		(
+			Capability::ExtendedMasterSecret,
+			has(EXT_EXTENDED_MASTER_SECRET),
+		),
+		(Capability::EncryptThenMac, has(EXT_ENCRYPT_THEN_MAC)),
+		(
+			Capability::CertificateCompression,
+			has(EXT_COMPRESS_CERTIFICATE),
+		),
+		(
+			Capability::DelegatedCredentials,
+			has(EXT_DELEGATED_CREDENTIAL),
+		),
+		(Capability::RecordSizeLimit, has(EXT_RECORD_SIZE_LIMIT)),
+		(Capability::PostHandshakeAuth, has(EXT_POST_HANDSHAKE_AUTH)),
+		(
+			Capability::Grease,
+			!(grease.cipher_suites.is_empty()
+				&& grease.extensions.is_empty()
+				&& grease.supported_versions.is_empty()
+				&& grease.supported_groups.is_empty()
+				&& grease.key_share.is_empty()),
+		),
+	];
+	checks
+		.into_iter()
+		.filter_map(|(capability, present)| present.then_some(capability))
+		.collect()
+}
@@ -0,0 +1,166 @@
+/* src/chunks.rs */
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use crate::{ClientHello, Error, parse, parse_from_record};
+
+/// Parse a raw Handshake ClientHello spread across several buffers.
+///
+/// The chunks are consumed in order, as they would come off a ring
+/// buffer or an mbuf chain. When the first chunk already holds the whole
+/// message it is parsed in place and `scratch` is left untouched.
+/// Otherwise the chunks are copied into `scratch` until the message is
+/// complete and the result borrows from there. Chunks after the end of
+/// the message are not read.
+///
+/// Any split of the message, even one inside its last byte, costs one
+/// full copy of it: the parsed fields borrow from a single contiguous
+/// buffer, so copying only the fields that straddle a chunk boundary is
+/// not possible. Reuse `scratch` across calls to keep its allocation.
+///
+/// # Errors
+///
+/// Returns the same errors as [`parse`]; a message that is still
+/// incomplete when the chunks run out is reported as truncated.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d
+/// # }
+/// # let data = build();
+/// let (a, b) = data.split_at(10);
+/// let mut scratch = Vec::new();
+/// let hello = clienthello::parse_from_chunks([a, b], &mut scratch).unwrap();
+/// assert_eq!(hello.cipher_suites, vec![0x1301]);
+/// ```
+pub fn parse_from_chunks<'a, I>(
+	chunks: I,
+	scratch: &'a mut Vec<u8>,
+) -> Result<ClientHello<'a>, Error>
+where
+	I: IntoIterator<Item = &'a [u8]>,
+{
+	let data = assemble(chunks, scratch, 4, |h| 4 + u24_at(h, 1));
+	parse(data)
+}
+
+/// Parse a TLS record containing a ClientHello spread across several
+/// buffers.
+///
+/// Behaves like [`parse_from_chunks`] but expects the record-layer
+/// framing accepted by [`parse_from_record`].
+///
+/// # Errors
+///
+/// Returns the same errors as [`parse_from_record`].
+pub fn parse_from_record_chunks<'a, I>(
+	chunks: I,
+	scratch: &'a mut Vec<u8>,
+) -> Result<ClientHello<'a>, Error>
+where
+	I: IntoIterator<Item = &'a [u8]>,
+{
+	let data = assemble(chunks, scratch, 5, |h| 5 + u16_at(h, 3));
+	parse_from_record(data)
+}
+
+/// Parse a raw Handshake ClientHello from a vectored receive.
+///
+/// Accepts any slice of buffers that dereference to bytes, such as
+/// `&[&[u8]]`, `&[Vec<u8>]`, or `&[std::io::IoSlice]` as filled by
+/// `readv`-style and io_uring receives. Buffering rules are those of
+/// [`parse_from_chunks`].
+///
+/// # Errors
+///
+/// Returns the same errors as [`parse`].
+///
+/// ```
+/// use std::io::IoSlice;
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d
+/// # }
+/// # let data = build();
+/// let (a, b) = data.split_at(30);
+/// let bufs = [IoSlice::new(a), IoSlice::new(b)];
+/// let mut scratch = Vec::new();
+/// let hello = clienthello::parse_vectored(&bufs, &mut scratch).unwrap();
+/// assert_eq!(hello.legacy_version, 0x0303);
+/// ```
+pub fn parse_vectored<'a, B>(
+	bufs: &'a [B],
+	scratch: &'a mut Vec<u8>,
+) -> Result<ClientHello<'a>, Error>
+where
+	B: Deref<Target = [u8]>,
+{
+	parse_from_chunks(bufs.iter().map(|b| &**b), scratch)
+}
+
+/// Parse a TLS record containing a ClientHello from a vectored receive.
+///
+/// See [`parse_vectored`] for the accepted buffer types.
+///
+/// # Errors
+///
+/// Returns the same errors as [`parse_from_record`].
+pub fn parse_from_record_vectored<'a, B>(
+	bufs: &'a [B],
+	scratch: &'a mut Vec<u8>,
+) -> Result<ClientHello<'a>, Error>
+where
+	B: Deref<Target = [u8]>,
+{
+	parse_from_record_chunks(bufs.iter().map(|b| &**b), scratch)
+}
+
+/// Return a contiguous view of the first message in `chunks`, borrowing
+/// the first chunk when possible and copying into `scratch` otherwise.
+///
+/// `header_len` bytes are needed before `message_len` can compute the
+/// total length of the message from its header.
+fn assemble<'a, I>(
+	chunks: I,
+	scratch: &'a mut Vec<u8>,
+	header_len: usize,
+	message_len: fn(&[u8]) -> usize,
+) -> &'a [u8]
+where
+	I: IntoIterator<Item = &'a [u8]>,
+{
+	let mut chunks = chunks.into_iter();
+	let first = chunks.next().unwrap_or_default();
+	if first.len() >= header_len && first.len() >= message_len(first) {
+		return first;
+	}
+	scratch.clear();
+	scratch.extend_from_slice(first);
+	for chunk in chunks {
+		if scratch.len() >= header_len && scratch.len() >= message_len(scratch) {
+			break;
+		}
+		scratch.extend_from_slice(chunk);
+	}
+	scratch
+}
+
+fn u16_at(data: &[u8], at: usize) -> usize {
+	match data.get(at..).unwrap_or_default() {
+		[b0, b1, ..] => usize::from(u16::from_be_bytes([*b0, *b1])),
+		_ => 0,
+	}
+}
+
+fn u24_at(data: &[u8], at: usize) -> usize {
+	match data.get(at..).unwrap_or_default() {
+		[b0, b1, b2, ..] => usize::from(*b0) << 16 | usize::from(u16::from_be_bytes([*b1, *b2])),
+		_ => 0,
+	}
+}
@@ -0,0 +1,115 @@
+/* src/cluster.rs */
+
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ClientHello;
+use crate::consts::{EXT_ALPN, EXT_SERVER_NAME};
+use crate::fingerprint::PER_CONNECTION_EXTENSIONS;
+
+/// A family of similar hellos found by [`cluster`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cluster {
+	/// Index of the hello that founded the cluster; every member scored
+	/// at least the threshold against it.
+	pub representative: usize,
+	/// Indices of all members in input order, the representative
+	/// included.
+	pub members: Vec<usize>,
+	/// JA4 fingerprint of the representative.
+	pub fingerprint: String,
+	/// Distinct JA4 fingerprints among the members, sorted, for spotting
+	/// the variants a family has.
+	pub fingerprints: Vec<String>,
+}
+
+/// Group a corpus of hellos into families of similar clients.
+///
+/// Hellos are taken in order; each joins the existing cluster whose
+/// representative it is most similar to, by
+/// [`ClientHello::similarity`], if that similarity is at least
+/// `threshold`, and otherwise founds a new cluster. Earlier clusters win
+/// ties. The result is in order of each cluster's first member, and
+/// depends on the input order only in which hello represents a family.
+///
+/// The comparisons grow with the number of hellos times the number of
+/// clusters, so deduplicate byte-identical hellos first on large
+/// captures.
+///
+/// ```
+/// use clienthello::Builder;
+///
+/// let chrome = Builder::new().cipher_suites(&[0x1301, 0x1302, 0xc02b]).build().unwrap();
+/// let chrome2 = Builder::new().cipher_suites(&[0x1301, 0x1302]).build().unwrap();
+/// let legacy = Builder::new().cipher_suites(&[0x002f, 0x0035]).build().unwrap();
+/// let hellos: Vec<_> = [&chrome, &legacy, &chrome2]
+///     .into_iter()
+///     .map(|msg| clienthello::parse(msg).unwrap())
+///     .collect();
+///
+/// let clusters = clienthello::cluster(&hellos, 90);
+/// assert_eq!(clusters.len(), 2);
+/// assert_eq!(clusters[0].members, [0, 2]);
+/// assert_eq!(clusters[1].members, [1]);
+/// ```
+#[must_use]
+pub fn cluster(hellos: &[ClientHello<'_>], threshold: u8) -> Vec<Cluster> {
+	let mut clusters: Vec<Cluster> = Vec::new();
+	for (i, hello) in hellos.iter().enumerate() {
+		let best = clusters
+			.iter_mut()
+			.map(|c| (similarity(&hellos[c.representative], hello), c))
+			.filter(|(score, _)| *score >= threshold)
+			.reduce(|best, next| if next.0 > best.0 { next } else { best });
+		let fingerprint = crate::ja4::ja4(hello);
+		match best {
+			Some((_, c)) => {
+				c.members.push(i);
+				if let Err(at) = c.fingerprints.binary_search(&fingerprint) {
+					c.fingerprints.insert(at, fingerprint);
+				}
+			}
+			None => clusters.push(Cluster {
+				representative: i,
+				members: alloc::vec![i],
+				fingerprints: alloc::vec![fingerprint.clone()],
+				fingerprint,
+			}),
+		}
+	}
+	clusters
+}
+
+pub(crate) fn similarity(a: &ClientHello<'_>, b: &ClientHello<'_>) -> u8 {
+	let fields = [
+		(a.cipher_suites.as_slice(), b.cipher_suites.as_slice()),
+		(&extension_types(a), &extension_types(b)),
+		(a.supported_groups(), b.supported_groups()),
+		(a.signature_algorithms(), b.signature_algorithms()),
+		(a.supported_versions(), b.supported_versions()),
+	];
+	let total: usize = fields
+		.iter()
+		.map(|(a, b)| {
+			let a: BTreeSet<u16> = a.iter().copied().collect();
+			let b: BTreeSet<u16> = b.iter().copied().collect();
+			let union = a.union(&b).count();
+			(a.intersection(&b).count() * 100)
+				.checked_div(union)
+				.unwrap_or(100)
+		})
+		.sum();
+	// Five fields of at most 100 each.
+	u8::try_from(total / fields.len()).unwrap_or(100)
+}
+
+/// Extension types that describe the client stack rather than the
+/// connection, as [`Profile`](crate::Profile) compares them.
+fn extension_types(hello: &ClientHello<'_>) -> Vec<u16> {
+	hello
+		.extensions
+		.type_ids()
+		.filter(|&t| t != EXT_SERVER_NAME && t != EXT_ALPN && !PER_CONNECTION_EXTENSIONS.contains(&t))
+		.collect()
+}
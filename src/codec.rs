@@ -0,0 +1,148 @@
+/* src/codec.rs */
+
+//! [`tokio_util::codec`] integration for TLS record streams.
+//!
+//! [`ClientHelloCodec`] frames TLS records from a byte stream. The first
+//! record is parsed as a ClientHello and emitted as
+//! [`TlsFrame::ClientHello`]; every later record is passed through as
+//! [`TlsFrame::Record`]. Plug it into a `FramedRead` to inspect the hello
+//! before forwarding the rest of the connection.
+//!
+//! ```
+//! # fn build() -> Vec<u8> {
+//! #     let mut d = vec![0x16, 0x03, 0x01, 0x00, 0x2d];
+//! #     d.extend_from_slice(&[0x01, 0x00, 0x00, 0x29, 0x03, 0x03]);
+//! #     d.extend_from_slice(&[0u8; 32]);
+//! #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+//! #     d
+//! # }
+//! # let record = build();
+//! use bytes::BytesMut;
+//! use clienthello::codec::{ClientHelloCodec, TlsFrame};
+//! use tokio_util::codec::Decoder;
+//!
+//! let mut codec = ClientHelloCodec::new();
+//! let mut buf = BytesMut::from(&record[..]);
+//! let Some(TlsFrame::ClientHello(frame)) = codec.decode(&mut buf).unwrap() else {
+//!     panic!("expected a ClientHello");
+//! };
+//! assert_eq!(frame.client_hello().cipher_suites, [0x1301]);
+//! ```
+
+use bytes::{Bytes, BytesMut};
+use tokio_util::codec::Decoder;
+
+use crate::consts::RECORD_HEADER_LEN;
+use crate::{ClientHello, Error, ParseOptions};
+
+/// Errors produced while decoding a record stream.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum CodecError {
+	/// The first record is not a valid ClientHello.
+	#[error("client hello: {0}")]
+	Parse(#[from] Error),
+
+	/// The underlying stream failed.
+	#[error("io: {0}")]
+	Io(#[from] std::io::Error),
+}
+
+/// A frame produced by [`ClientHelloCodec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TlsFrame {
+	/// The first record of the stream, holding a validated ClientHello.
+	ClientHello(HelloRecord),
+	/// Any later record, header included, passed through unparsed.
+	Record(Bytes),
+}
+
+/// A TLS record known to contain a valid ClientHello.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelloRecord {
+	record: Bytes,
+	opts: ParseOptions,
+}
+
+impl HelloRecord {
+	/// Borrow the parsed ClientHello.
+	///
+	/// The record was validated when it was decoded; this parses it
+	/// again, which is cheap and avoids a self-referential frame.
+	#[must_use]
+	pub fn client_hello(&self) -> ClientHello<'_> {
+		match self.opts.parse_from_record(&self.record) {
+			Ok(hello) => hello,
+			Err(_) => unreachable!("record was validated when decoded"),
+		}
+	}
+
+	/// The full record, header included.
+	#[must_use]
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.record
+	}
+
+	/// Take the full record, header included, e.g. to forward it.
+	#[must_use]
+	pub fn into_bytes(self) -> Bytes {
+		self.record
+	}
+}
+
+/// [`Decoder`] that frames TLS records and parses the first as a
+/// ClientHello.
+///
+/// A ClientHello must fit in the first record, as
+/// [`parse_from_record`](crate::parse_from_record) requires. Later
+/// records are framed by their length field only, so any content type
+/// passes through.
+#[derive(Debug, Clone, Default)]
+pub struct ClientHelloCodec {
+	opts: ParseOptions,
+	seen_hello: bool,
+}
+
+impl ClientHelloCodec {
+	/// Create a codec using the default [`ParseOptions`].
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a codec that parses the ClientHello with `opts`.
+	#[must_use]
+	pub fn with_options(opts: ParseOptions) -> Self {
+		Self {
+			opts,
+			seen_hello: false,
+		}
+	}
+}
+
+impl Decoder for ClientHelloCodec {
+	type Item = TlsFrame;
+	type Error = CodecError;
+
+	fn decode(&mut self, src: &mut BytesMut) -> Result<Option<TlsFrame>, CodecError> {
+		let Some(&[_, _, _, hi, lo]) = src.get(..RECORD_HEADER_LEN) else {
+			return Ok(None);
+		};
+		let frame_len = RECORD_HEADER_LEN + usize::from(u16::from_be_bytes([hi, lo]));
+		if src.len() < frame_len {
+			src.reserve(frame_len - src.len());
+			return Ok(None);
+		}
+		let record = src.split_to(frame_len).freeze();
+		if self.seen_hello {
+			return Ok(Some(TlsFrame::Record(record)));
+		}
+		self.opts.parse_from_record(&record)?;
+		self.seen_hello = true;
+		Ok(Some(TlsFrame::ClientHello(HelloRecord {
+			record,
+			opts: self.opts,
+		})))
+	}
+}
@@ -0,0 +1,448 @@
+/* src/codegen.rs */
+
+//! Source code that reproduces a captured hello.
+//!
+//! [`rust`] emits a [`Builder`](crate::Builder) expression and [`utls`]
+//! a Go [uTLS](https://github.com/refraction-networking/utls)
+//! `ClientHelloSpec`, so making a client look like a capture starts from
+//! generated code instead of a hand-transcribed hexdump. [`spec`] gives
+//! the same description as data, for tools that replay hellos with
+//! other stacks. Run
+//! `cargo run --example codegen -- <hex>` for the same from the shell.
+//!
+//! Cipher suites, extensions, and their order are reproduced exactly,
+//! with GREASE re-inserted at the positions it was captured at. Values
+//! that must differ per connection are placeholders: the random is left
+//! out, the session ID and key shares are zero-filled at their captured
+//! lengths, and uTLS fills in SNI, key shares, and padding itself.
+//!
+//! ```
+//! let msg = clienthello::Builder::new()
+//!     .cipher_suites(&[0x1301, 0x1302])
+//!     .server_name("example.com")
+//!     .build()
+//!     .unwrap();
+//! let hello = clienthello::parse(&msg).unwrap();
+//! assert_eq!(
+//!     clienthello::codegen::rust(&hello),
+//!     "clienthello::Builder::new()\n\
+//!     \t.cipher_suites(&[0x1301, 0x1302])\n\
+//!     \t.server_name(\"example.com\")\n"
+//! );
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+
+use crate::grease::{GREASE_PLACEHOLDER, interleave, restore_reserved};
+use crate::{ClientHello, Extension};
+
+/// Emit a [`Builder`](crate::Builder) expression reproducing `hello`.
+///
+/// Each call is on its own tab-indented line; calls that would repeat a
+/// [`Builder::new`](crate::Builder::new) default are left out.
+#[must_use]
+pub fn rust(hello: &ClientHello<'_>) -> String {
+	let mut out = String::from("clienthello::Builder::new()\n");
+	if hello.legacy_version != 0x0303 {
+		let _ = writeln!(out, "\t.legacy_version({:#06x})", hello.legacy_version);
+	}
+	if !hello.session_id.is_empty() {
+		let _ = writeln!(out, "\t.session_id(&[0; {}])", hello.session_id.len());
+	}
+	let suites = with_grease(
+		&hello.cipher_suites,
+		&hello.grease.cipher_suites,
+		&hello.reserved.cipher_suites,
+	);
+	let _ = writeln!(out, "\t.cipher_suites(&[{}])", u16_list(&suites));
+	if hello.compression_methods != [0x00] {
+		let _ = writeln!(
+			out,
+			"\t.compression_methods(&[{}])",
+			byte_list(hello.compression_methods)
+		);
+	}
+	let mut grease = (0..).map(grease_extension);
+	let mut calls: Vec<String> = extensions_with_grease(hello)
+		.into_iter()
+		.map(|ext| {
+			ext.map_or_else(
+				|| {
+					format!(
+						"extension({:#06x}, &[])",
+						grease.next().unwrap_or(GREASE_PLACEHOLDER)
+					)
+				},
+				|ext| rust_call(hello, ext),
+			)
+		})
+		.collect();
+	restore_reserved(&mut calls, &hello.reserved.extensions, |id| {
+		format!("extension({id:#06x}, &[])")
+	});
+	for call in calls {
+		let _ = writeln!(out, "\t.{call}");
+	}
+	out
+}
+
+/// Emit a uTLS `&tls.ClientHelloSpec{...}` literal reproducing `hello`.
+///
+/// Extensions uTLS models are emitted as their typed structs, anything
+/// else as a `tls.GenericExtension` with the captured body. Same as
+/// `spec(hello).to_utls()`.
+#[must_use]
+pub fn utls(hello: &ClientHello<'_>) -> String {
+	spec(hello).to_utls()
+}
+
+/// A captured hello in the shape of uTLS's `ClientHelloSpec`: cipher
+/// suites, compression methods, and extensions with their parameters,
+/// GREASE kept as placeholders at the captured positions.
+///
+/// Built by [`spec`] or
+/// [`ClientHello::to_spec`](crate::ClientHello::to_spec). Unlike the
+/// generated source, it can be inspected, edited, and with the `serde`
+/// feature stored, before a client stack replays it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientHelloSpec {
+	/// Cipher suites in wire order; `None` marks GREASE.
+	pub cipher_suites: Vec<Option<u16>>,
+	/// Compression methods.
+	pub compression_methods: Vec<u8>,
+	/// Extensions in wire order.
+	pub extensions: Vec<ExtensionSpec>,
+}
+
+/// One extension of a [`ClientHelloSpec`], named after the uTLS type
+/// that produces it.
+///
+/// Values that differ per connection are left for the client stack to
+/// fill in: the server name, key exchange keys, and padding length.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ExtensionSpec {
+	/// `UtlsGREASEExtension`.
+	Grease,
+	/// `SNIExtension`.
+	ServerName,
+	/// `ExtendedMasterSecretExtension`.
+	ExtendedMasterSecret,
+	/// `SessionTicketExtension`, empty.
+	SessionTicket,
+	/// `SCTExtension`.
+	SignedCertificateTimestamp,
+	/// `StatusRequestExtension`.
+	StatusRequest,
+	/// `UtlsPaddingExtension` with BoringSSL's padding rule.
+	Padding,
+	/// `RenegotiationInfoExtension` for an initial handshake.
+	RenegotiationInfo,
+	/// `ALPNExtension`.
+	Alpn(Vec<Vec<u8>>),
+	/// `SupportedVersionsExtension`; `None` marks GREASE.
+	SupportedVersions(Vec<Option<u16>>),
+	/// `SupportedCurvesExtension`; `None` marks GREASE.
+	SupportedGroups(Vec<Option<u16>>),
+	/// `SignatureAlgorithmsExtension`.
+	SignatureAlgorithms(Vec<u16>),
+	/// `KeyShareExtension` groups; `None` marks a GREASE share.
+	KeyShare(Vec<Option<u16>>),
+	/// `PSKKeyExchangeModesExtension`.
+	PskKeyExchangeModes(Vec<u8>),
+	/// `GenericExtension` with the body as captured, or as re-encoded for
+	/// decoded extensions uTLS has no type for.
+	Generic {
+		/// Extension type.
+		id: u16,
+		/// Extension body.
+		data: Vec<u8>,
+	},
+}
+
+/// Describe `hello` as a [`ClientHelloSpec`].
+#[must_use]
+pub fn spec(hello: &ClientHello<'_>) -> ClientHelloSpec {
+	let mut extensions: Vec<ExtensionSpec> = extensions_with_grease(hello)
+		.into_iter()
+		.map(|ext| ext.map_or(ExtensionSpec::Grease, |e| extension_spec(hello, e)))
+		.collect();
+	restore_reserved(&mut extensions, &hello.reserved.extensions, |id| {
+		ExtensionSpec::Generic {
+			id,
+			data: Vec::new(),
+		}
+	});
+	ClientHelloSpec {
+		cipher_suites: with_grease(
+			&hello.cipher_suites,
+			&hello.grease.cipher_suites,
+			&hello.reserved.cipher_suites,
+		),
+		compression_methods: hello.compression_methods.to_vec(),
+		extensions,
+	}
+}
+
+impl ClientHelloSpec {
+	/// Emit the spec as a uTLS `&tls.ClientHelloSpec{...}` literal.
+	#[must_use]
+	pub fn to_utls(&self) -> String {
+		let mut out = String::from("&tls.ClientHelloSpec{\n\tCipherSuites: []uint16{\n");
+		for &suite in &self.cipher_suites {
+			let _ = writeln!(out, "\t\t{},", go_u16(suite));
+		}
+		let _ = write!(
+			out,
+			"\t}},\n\tCompressionMethods: []byte{{{}}},\n\tExtensions: []tls.TLSExtension{{\n",
+			byte_list(&self.compression_methods)
+		);
+		for ext in &self.extensions {
+			let _ = writeln!(out, "\t\t{},", go_extension(ext));
+		}
+		out.push_str("\t},\n}\n");
+		out
+	}
+}
+
+fn rust_call(hello: &ClientHello<'_>, ext: &Extension<'_>) -> String {
+	match ext {
+		Extension::ServerName(names) => match names.as_slice() {
+			[name] if name.name_type == 0x00 => match core::str::from_utf8(name.name) {
+				Ok(host) => format!("server_name(\"{}\")", escape(host.as_bytes())),
+				Err(_) => raw_call(ext),
+			},
+			_ => raw_call(ext),
+		},
+		Extension::Alpn(protocols) => {
+			let list: Vec<String> = protocols
+				.iter()
+				.map(|p| format!("b\"{}\"", escape(p)))
+				.collect();
+			format!("alpn(&[{}])", list.join(", "))
+		}
+		Extension::SupportedVersions(v) => {
+			let v = with_grease(
+				v,
+				&hello.grease.supported_versions,
+				&hello.reserved.supported_versions,
+			);
+			format!("supported_versions(&[{}])", u16_list(&v))
+		}
+		Extension::SupportedGroups(v) => {
+			let v = with_grease(
+				v,
+				&hello.grease.supported_groups,
+				&hello.reserved.supported_groups,
+			);
+			format!("supported_groups(&[{}])", u16_list(&v))
+		}
+		Extension::SignatureAlgorithms(v) => {
+			let v = with_reserved(v, &hello.reserved.signature_algorithms);
+			format!("signature_algorithms(&[{}])", u16_list(&v))
+		}
+		Extension::KeyShare { entries, .. } => {
+			let mut list: Vec<String> = entries
+				.iter()
+				.map(|e| format!("({:#06x}, &[0; {}])", e.group, e.key.len()))
+				.collect();
+			restore_reserved(&mut list, &hello.reserved.key_share, |group| {
+				format!("({group:#06x}, &[0; 1])")
+			});
+			format!("key_share(&[{}])", list.join(", "))
+		}
+		Extension::PskExchangeModes(modes) => format!("psk_exchange_modes(&[{}])", byte_list(modes)),
+		Extension::RenegotiationInfo(data) => format!("renegotiation_info(&[{}])", byte_list(data)),
+		_ => raw_call(ext),
+	}
+}
+
+/// An `.extension(type, &[body])` call with the body as captured, or as
+/// re-encoded for structured variants.
+fn raw_call(ext: &Extension<'_>) -> String {
+	let body = ext.encode_body();
+	if !body.is_empty() && body.iter().all(|&b| b == 0) {
+		return format!("extension({:#06x}, &[0; {}])", ext.type_id(), body.len());
+	}
+	format!("extension({:#06x}, &[{}])", ext.type_id(), byte_list(&body))
+}
+
+fn extension_spec(hello: &ClientHello<'_>, ext: &Extension<'_>) -> ExtensionSpec {
+	match ext {
+		Extension::ServerName(_) => ExtensionSpec::ServerName,
+		Extension::Alpn(protocols) => {
+			ExtensionSpec::Alpn(protocols.iter().map(|p| p.to_vec()).collect())
+		}
+		Extension::SupportedVersions(v) => ExtensionSpec::SupportedVersions(with_grease(
+			v,
+			&hello.grease.supported_versions,
+			&hello.reserved.supported_versions,
+		)),
+		Extension::SupportedGroups(v) => ExtensionSpec::SupportedGroups(with_grease(
+			v,
+			&hello.grease.supported_groups,
+			&hello.reserved.supported_groups,
+		)),
+		Extension::SignatureAlgorithms(v) => {
+			ExtensionSpec::SignatureAlgorithms(with_reserved(v, &hello.reserved.signature_algorithms))
+		}
+		Extension::KeyShare { entries, .. } => {
+			let mut groups: Vec<Option<u16>> = entries
+				.iter()
+				.enumerate()
+				.map(|(i, e)| (!hello.grease.key_share.contains(&i)).then_some(e.group))
+				.collect();
+			restore_reserved(&mut groups, &hello.reserved.key_share, Some);
+			ExtensionSpec::KeyShare(groups)
+		}
+		Extension::PskExchangeModes(modes) => ExtensionSpec::PskKeyExchangeModes(modes.to_vec()),
+		Extension::RenegotiationInfo([]) => ExtensionSpec::RenegotiationInfo,
+		Extension::Unknown { type_id, data } => match (*type_id, data.is_empty()) {
+			(0x0017, true) => ExtensionSpec::ExtendedMasterSecret,
+			(0x0023, true) => ExtensionSpec::SessionTicket,
+			(0x0012, true) => ExtensionSpec::SignedCertificateTimestamp,
+			(0x0005, _) => ExtensionSpec::StatusRequest,
+			(0x0015, _) => ExtensionSpec::Padding,
+			_ => generic(ext),
+		},
+		_ => generic(ext),
+	}
+}
+
+fn generic(ext: &Extension<'_>) -> ExtensionSpec {
+	ExtensionSpec::Generic {
+		id: ext.type_id(),
+		data: ext.encode_body(),
+	}
+}
+
+fn go_extension(ext: &ExtensionSpec) -> String {
+	match ext {
+		ExtensionSpec::Grease => "&tls.UtlsGREASEExtension{}".into(),
+		ExtensionSpec::ServerName => "&tls.SNIExtension{}".into(),
+		ExtensionSpec::ExtendedMasterSecret => "&tls.ExtendedMasterSecretExtension{}".into(),
+		ExtensionSpec::SessionTicket => "&tls.SessionTicketExtension{}".into(),
+		ExtensionSpec::SignedCertificateTimestamp => "&tls.SCTExtension{}".into(),
+		ExtensionSpec::StatusRequest => "&tls.StatusRequestExtension{}".into(),
+		ExtensionSpec::Padding => {
+			"&tls.UtlsPaddingExtension{GetPaddingLen: tls.BoringPaddingStyle}".into()
+		}
+		ExtensionSpec::RenegotiationInfo => {
+			"&tls.RenegotiationInfoExtension{Renegotiation: tls.RenegotiateOnceAsClient}".into()
+		}
+		ExtensionSpec::Alpn(protocols) => {
+			let list: Vec<String> = protocols
+				.iter()
+				.map(|p| format!("\"{}\"", escape(p)))
+				.collect();
+			format!(
+				"&tls.ALPNExtension{{AlpnProtocols: []string{{{}}}}}",
+				list.join(", ")
+			)
+		}
+		ExtensionSpec::SupportedVersions(v) => format!(
+			"&tls.SupportedVersionsExtension{{Versions: []uint16{{{}}}}}",
+			go_list(v)
+		),
+		ExtensionSpec::SupportedGroups(v) => format!(
+			"&tls.SupportedCurvesExtension{{Curves: []tls.CurveID{{{}}}}}",
+			go_list(v)
+		),
+		ExtensionSpec::SignatureAlgorithms(v) => format!(
+			"&tls.SignatureAlgorithmsExtension{{SupportedSignatureAlgorithms: []tls.SignatureScheme{{{}}}}}",
+			u16_list(v)
+		),
+		ExtensionSpec::KeyShare(groups) => {
+			let list: Vec<String> = groups
+				.iter()
+				.map(|g| match g {
+					Some(group) => format!("{{Group: {group:#06x}}}"),
+					None => "{Group: tls.CurveID(tls.GREASE_PLACEHOLDER), Data: []byte{0}}".into(),
+				})
+				.collect();
+			format!(
+				"&tls.KeyShareExtension{{KeyShares: []tls.KeyShare{{{}}}}}",
+				list.join(", ")
+			)
+		}
+		ExtensionSpec::PskKeyExchangeModes(modes) => format!(
+			"&tls.PSKKeyExchangeModesExtension{{Modes: []uint8{{{}}}}}",
+			byte_list(modes)
+		),
+		ExtensionSpec::Generic { id, data } => format!(
+			"&tls.GenericExtension{{Id: {id:#06x}, Data: []byte{{{}}}}}",
+			byte_list(data)
+		),
+	}
+}
+
+/// Restore the wire order of a filtered list, `None` marking GREASE and
+/// reserved values put back as captured.
+fn with_grease(values: &[u16], positions: &[usize], reserved: &[(usize, u16)]) -> Vec<Option<u16>> {
+	let mut list = interleave(values.iter().copied(), positions).collect();
+	restore_reserved(&mut list, reserved, Some);
+	list
+}
+
+fn with_reserved(values: &[u16], reserved: &[(usize, u16)]) -> Vec<u16> {
+	let mut list = values.to_vec();
+	restore_reserved(&mut list, reserved, |v| v);
+	list
+}
+
+/// List the extensions in wire order, `None` marking GREASE.
+fn extensions_with_grease<'h, 'a>(hello: &'h ClientHello<'a>) -> Vec<Option<&'h Extension<'a>>> {
+	interleave(hello.extensions.iter(), &hello.grease.extensions).collect()
+}
+
+/// GREASE value for the `i`th GREASE extension; successive GREASE
+/// extensions get successive values so they stay distinct.
+fn grease_extension(i: usize) -> u16 {
+	let step = u16::try_from(i % 16).unwrap_or_default();
+	GREASE_PLACEHOLDER.wrapping_add(step.wrapping_mul(0x1010))
+}
+
+fn u16_list<T: Copy + Into<Option<u16>>>(values: &[T]) -> String {
+	let list: Vec<String> = values
+		.iter()
+		.map(|&v| format!("{:#06x}", v.into().unwrap_or(GREASE_PLACEHOLDER)))
+		.collect();
+	list.join(", ")
+}
+
+fn go_list(values: &[Option<u16>]) -> String {
+	let list: Vec<String> = values.iter().map(|&v| go_u16(v)).collect();
+	list.join(", ")
+}
+
+fn go_u16(value: Option<u16>) -> String {
+	value.map_or_else(|| "tls.GREASE_PLACEHOLDER".into(), |v| format!("{v:#06x}"))
+}
+
+fn byte_list(bytes: &[u8]) -> String {
+	let list: Vec<String> = bytes.iter().map(|b| format!("{b:#04x}")).collect();
+	list.join(", ")
+}
+
+/// Escape bytes for a string literal valid in both Rust and Go.
+fn escape(bytes: &[u8]) -> String {
+	let mut out = String::new();
+	for &b in bytes {
+		match b {
+			b'"' | b'\\' => {
+				out.push('\\');
+				out.push(char::from(b));
+			}
+			0x20..=0x7e => out.push(char::from(b)),
+			_ => {
+				let _ = write!(out, "\\x{b:02x}");
+			}
+		}
+	}
+	out
+}
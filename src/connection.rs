@@ -0,0 +1,314 @@
+/* src/connection.rs */
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::consts::{HANDSHAKE_HEADER_LEN, RECORD_HEADER_LEN};
+use crate::{ClientHello, Error, ParseOptions, Transport};
+
+/// Errors produced while collecting a connection's ClientHello.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ConnectionError {
+	/// The collected bytes are not a valid ClientHello.
+	#[error("client hello: {0}")]
+	Parse(#[from] Error),
+	/// TCP bytes and QUIC CRYPTO data were pushed to one connection.
+	#[error("TCP and QUIC data pushed to one connection")]
+	MixedTransports,
+	/// The ClientHello, or CRYPTO data offered for it, extends past
+	/// [`Connection::MAX_LEN`].
+	#[error("client hello of {len} bytes exceeds limit of {max}")]
+	TooLarge {
+		/// End offset of the message or data.
+		len: usize,
+		/// [`Connection::MAX_LEN`].
+		max: usize,
+	},
+	/// Retransmitted CRYPTO data differs from what was received before at
+	/// the same offset.
+	#[error("CRYPTO data at offset {offset} differs from earlier data")]
+	ConflictingCrypto {
+		/// Offset of the first differing byte.
+		offset: usize,
+	},
+	/// A QUIC datagram holds a long header packet of a version other
+	/// than 1.
+	#[cfg(feature = "quic-initial")]
+	#[error("unsupported QUIC version {version:#010x}")]
+	UnsupportedQuicVersion {
+		/// Version field of the packet.
+		version: u32,
+	},
+	/// A QUIC Initial packet could not be parsed or decrypted.
+	#[cfg(feature = "quic-initial")]
+	#[error("invalid QUIC Initial packet: {reason}")]
+	InvalidQuicPacket {
+		/// What was wrong with the packet.
+		reason: &'static str,
+	},
+}
+
+/// Collect the ClientHello of one connection, over TCP or QUIC.
+///
+/// Feed it TCP payload with [`push_tcp`](Self::push_tcp) in stream
+/// order, or the data of QUIC Initial CRYPTO frames with
+/// [`push_quic_crypto`](Self::push_quic_crypto) in any order. The
+/// connection buffers what it needs and picks the framing from the first
+/// push: a TLS record for TCP, a bare handshake message reassembled by
+/// offset for QUIC, where clients split the hello across several frames
+/// and packets and may reorder and retransmit them. With the
+/// `quic-initial` feature, [`push_quic_datagram`](Self::push_quic_datagram)
+/// takes the UDP payloads a QUIC version 1 client sends and decrypts
+/// their Initial packets itself; otherwise decrypting the packets and
+/// extracting their CRYPTO frames is left to the caller.
+///
+/// Once a push returns `Ok(true)` the hello is complete and valid, and
+/// [`client_hello`](Self::client_hello) returns it; later pushes are
+/// ignored.
+///
+/// ```
+/// use clienthello::{Builder, Connection, Transport};
+///
+/// let msg = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+/// let (head, tail) = msg.split_at(20);
+///
+/// let mut conn = Connection::new();
+/// assert!(!conn.push_quic_crypto(20, tail).unwrap());
+/// assert!(conn.push_quic_crypto(0, head).unwrap());
+/// assert_eq!(conn.transport(), Some(Transport::Handshake));
+/// assert_eq!(conn.client_hello().unwrap().cipher_suites, [0x1301]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Connection {
+	opts: ParseOptions,
+	transport: Option<Transport>,
+	buf: Vec<u8>,
+	/// Received CRYPTO ranges, sorted and merged.
+	received: Vec<Range<usize>>,
+	complete: bool,
+}
+
+impl Connection {
+	/// Largest ClientHello a connection collects, in bytes of its framing:
+	/// one full TLS record, or a QUIC handshake message of the same size.
+	pub const MAX_LEN: usize = RECORD_HEADER_LEN + u16::MAX as usize;
+
+	/// Create a connection using the default [`ParseOptions`].
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create a connection that parses the ClientHello with `opts`.
+	#[must_use]
+	pub fn with_options(opts: ParseOptions) -> Self {
+		Self {
+			opts,
+			..Self::default()
+		}
+	}
+
+	/// Push the next TCP payload bytes of the connection.
+	///
+	/// Returns whether the ClientHello is complete. It must fit in the
+	/// first TLS record, as [`parse_from_record`](crate::parse_from_record)
+	/// requires; bytes after that record are not kept.
+	///
+	/// # Errors
+	///
+	/// Returns [`ConnectionError::MixedTransports`] after QUIC data, and
+	/// [`ConnectionError::Parse`] when the completed record is not a valid
+	/// ClientHello.
+	pub fn push_tcp(&mut self, data: &[u8]) -> Result<bool, ConnectionError> {
+		self.claim(Transport::TlsRecord)?;
+		if self.complete {
+			return Ok(true);
+		}
+		let want = self.record_len().unwrap_or(RECORD_HEADER_LEN);
+		let take = want.saturating_sub(self.buf.len()).min(data.len());
+		self.buf.extend_from_slice(&data[..take]);
+		// The header may have just completed; top up to the record end.
+		if let Some(len) = self.record_len() {
+			let more = len.saturating_sub(self.buf.len()).min(data.len() - take);
+			self.buf.extend_from_slice(&data[take..take + more]);
+			if self.buf.len() == len {
+				self.opts.parse_from_record(&self.buf)?;
+				self.complete = true;
+			}
+		}
+		Ok(self.complete)
+	}
+
+	/// Push the data of a QUIC Initial CRYPTO frame found at `offset` of
+	/// the crypto stream.
+	///
+	/// Frames may arrive in any order and may repeat. Returns whether the
+	/// ClientHello is complete.
+	///
+	/// # Errors
+	///
+	/// Returns [`ConnectionError::MixedTransports`] after TCP data,
+	/// [`ConnectionError::TooLarge`] for data or a message extending past
+	/// [`MAX_LEN`](Self::MAX_LEN), [`ConnectionError::ConflictingCrypto`]
+	/// when a retransmission differs from earlier data, and
+	/// [`ConnectionError::Parse`] when the completed message is not a valid
+	/// ClientHello.
+	pub fn push_quic_crypto(&mut self, offset: u64, data: &[u8]) -> Result<bool, ConnectionError> {
+		self.claim(Transport::Handshake)?;
+		if self.complete {
+			return Ok(true);
+		}
+		let too_large = ConnectionError::TooLarge {
+			len: usize::MAX,
+			max: Self::MAX_LEN,
+		};
+		let start = usize::try_from(offset).map_err(|_| too_large.clone())?;
+		let end = start.checked_add(data.len()).ok_or(too_large)?;
+		if end > Self::MAX_LEN {
+			return Err(ConnectionError::TooLarge {
+				len: end,
+				max: Self::MAX_LEN,
+			});
+		}
+		for range in &self.received {
+			let (lo, hi) = (range.start.max(start), range.end.min(end));
+			if let Some(i) = (lo..hi).find(|&i| self.buf[i] != data[i - start]) {
+				return Err(ConnectionError::ConflictingCrypto { offset: i });
+			}
+		}
+		if self.buf.len() < end {
+			self.buf.resize(end, 0);
+		}
+		self.buf[start..end].copy_from_slice(data);
+		self.insert_range(start..end);
+
+		let contiguous = self
+			.received
+			.first()
+			.filter(|r| r.start == 0)
+			.map_or(0, |r| r.end);
+		let Some(len) = handshake_len(&self.buf[..contiguous]) else {
+			return Ok(false);
+		};
+		if len > Self::MAX_LEN {
+			return Err(ConnectionError::TooLarge {
+				len,
+				max: Self::MAX_LEN,
+			});
+		}
+		if contiguous >= len {
+			self.opts.parse(&self.buf[..len])?;
+			self.buf.truncate(len);
+			self.complete = true;
+		}
+		Ok(self.complete)
+	}
+
+	/// Push a UDP datagram sent by a QUIC version 1 client.
+	///
+	/// Each Initial packet in the datagram is unprotected with the keys
+	/// RFC 9001 derives from its Destination Connection ID, and the data
+	/// of its CRYPTO frames is pushed as with
+	/// [`push_quic_crypto`](Self::push_quic_crypto). Coalesced 0-RTT and
+	/// Handshake packets, and a trailing short header packet, are
+	/// skipped. Returns whether the ClientHello is complete.
+	///
+	/// # Errors
+	///
+	/// Returns [`ConnectionError::UnsupportedQuicVersion`] for a long
+	/// header packet of another version,
+	/// [`ConnectionError::InvalidQuicPacket`] when an Initial packet is
+	/// malformed or fails to decrypt, and the errors of
+	/// [`push_quic_crypto`](Self::push_quic_crypto).
+	#[cfg(feature = "quic-initial")]
+	pub fn push_quic_datagram(&mut self, datagram: &[u8]) -> Result<bool, ConnectionError> {
+		self.claim(Transport::Handshake)?;
+		if self.complete {
+			return Ok(true);
+		}
+		crate::quic_initial::crypto_frames(datagram, |offset, data| {
+			self.push_quic_crypto(offset, data).map(drop)
+		})?;
+		Ok(self.complete)
+	}
+
+	/// Check whether a valid ClientHello has been collected.
+	#[must_use]
+	pub fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// Return the transport picked by the first push.
+	#[must_use]
+	pub fn transport(&self) -> Option<Transport> {
+		self.transport
+	}
+
+	/// Borrow the ClientHello once it is complete.
+	///
+	/// The message was validated when it completed; this parses it again,
+	/// which is cheap and keeps the connection free of self-references.
+	#[must_use]
+	pub fn client_hello(&self) -> Option<ClientHello<'_>> {
+		if !self.complete {
+			return None;
+		}
+		let parsed = match self.transport {
+			Some(Transport::TlsRecord) => self.opts.parse_from_record(&self.buf),
+			_ => self.opts.parse(&self.buf),
+		};
+		match parsed {
+			Ok(hello) => Some(hello),
+			Err(_) => unreachable!("message was validated when it completed"),
+		}
+	}
+
+	/// Return the bytes of the complete ClientHello, in its framing.
+	#[must_use]
+	pub fn as_bytes(&self) -> Option<&[u8]> {
+		self.complete.then_some(self.buf.as_slice())
+	}
+
+	fn claim(&mut self, transport: Transport) -> Result<(), ConnectionError> {
+		match self.transport {
+			Some(t) if t != transport => Err(ConnectionError::MixedTransports),
+			_ => {
+				self.transport = Some(transport);
+				Ok(())
+			}
+		}
+	}
+
+	/// Return the full record length once its header is buffered.
+	fn record_len(&self) -> Option<usize> {
+		let &[_, _, _, hi, lo] = self.buf.get(..RECORD_HEADER_LEN)? else {
+			return None;
+		};
+		Some(RECORD_HEADER_LEN + usize::from(u16::from_be_bytes([hi, lo])))
+	}
+
+	fn insert_range(&mut self, new: Range<usize>) {
+		if new.is_empty() {
+			return;
+		}
+		let mut merged = new;
+		self.received.retain(|r| {
+			let overlaps = r.start <= merged.end && merged.start <= r.end;
+			if overlaps {
+				merged = merged.start.min(r.start)..merged.end.max(r.end);
+			}
+			!overlaps
+		});
+		let at = self.received.partition_point(|r| r.start < merged.start);
+		self.received.insert(at, merged);
+	}
+}
+
+/// Return the full handshake message length once its header is known.
+fn handshake_len(data: &[u8]) -> Option<usize> {
+	let &[_, a, b, c] = data.get(..HANDSHAKE_HEADER_LEN)? else {
+		return None;
+	};
+	Some(HANDSHAKE_HEADER_LEN + (usize::from(a) << 16 | usize::from(b) << 8 | usize::from(c)))
+}
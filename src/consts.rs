@@ -0,0 +1,127 @@
+/* src/consts.rs */
+
+//! Wire-format constants used by the parser.
+//!
+//! Header sizes, field offsets, and code points exactly as this crate
+//! reads and writes them, for code that frames or pre-filters traffic
+//! before handing it over and needs to agree with the parser.
+//!
+//! ```
+//! use clienthello::consts::{CONTENT_TYPE_HANDSHAKE, RECORD_HEADER_LEN, RECORD_LENGTH_OFFSET};
+//!
+//! fn record_len(buf: &[u8]) -> Option<usize> {
+//!     if *buf.first()? != CONTENT_TYPE_HANDSHAKE {
+//!         return None;
+//!     }
+//!     let len = buf.get(RECORD_LENGTH_OFFSET..RECORD_LENGTH_OFFSET + 2)?;
+//!     Some(RECORD_HEADER_LEN + usize::from(u16::from_be_bytes([len[0], len[1]])))
+//! }
+//!
+//! assert_eq!(record_len(&[0x16, 0x03, 0x01, 0x00, 0x2d]), Some(50));
+//! ```
+
+/// TLS record header length: content type, version, and length.
+pub const RECORD_HEADER_LEN: usize = 5;
+/// Offset of the content type in the record header.
+pub const RECORD_CONTENT_TYPE_OFFSET: usize = 0;
+/// Offset of the 2-byte protocol version in the record header.
+pub const RECORD_VERSION_OFFSET: usize = 1;
+/// Offset of the 2-byte payload length in the record header.
+pub const RECORD_LENGTH_OFFSET: usize = 3;
+/// Largest plaintext record payload (RFC 8446 §5.1).
+pub const MAX_RECORD_PAYLOAD_LEN: usize = 1 << 14;
+
+/// Handshake header length: message type and 3-byte length.
+pub const HANDSHAKE_HEADER_LEN: usize = 4;
+/// Offset of the message type in the handshake header.
+pub const HANDSHAKE_TYPE_OFFSET: usize = 0;
+/// Offset of the 3-byte body length in the handshake header.
+pub const HANDSHAKE_LENGTH_OFFSET: usize = 1;
+
+/// Offset of the client random in the ClientHello body.
+pub const RANDOM_OFFSET: usize = 2;
+/// Client random length.
+pub const RANDOM_LEN: usize = 32;
+/// Offset of the session ID length byte in the ClientHello body.
+pub const SESSION_ID_OFFSET: usize = RANDOM_OFFSET + RANDOM_LEN;
+/// Longest legacy session ID (RFC 8446 §4.1.2).
+pub const MAX_SESSION_ID_LEN: usize = 32;
+
+/// Record content type of handshake records.
+pub const CONTENT_TYPE_HANDSHAKE: u8 = 0x16;
+/// Handshake message type of a ClientHello.
+pub const HANDSHAKE_TYPE_CLIENT_HELLO: u8 = 0x01;
+/// Handshake message type of a ServerHello.
+pub const HANDSHAKE_TYPE_SERVER_HELLO: u8 = 0x02;
+
+/// `TLS_EMPTY_RENEGOTIATION_INFO_SCSV` signaling cipher suite (RFC 5746).
+pub const TLS_EMPTY_RENEGOTIATION_INFO_SCSV: u16 = 0x00ff;
+/// `TLS_FALLBACK_SCSV` signaling cipher suite (RFC 7507).
+pub const TLS_FALLBACK_SCSV: u16 = 0x5600;
+
+/// `server_name` extension (RFC 6066).
+pub const EXT_SERVER_NAME: u16 = 0x0000;
+/// `max_fragment_length` extension (RFC 6066).
+pub const EXT_MAX_FRAGMENT_LENGTH: u16 = 0x0001;
+/// `status_request` extension (RFC 6066).
+pub const EXT_STATUS_REQUEST: u16 = 0x0005;
+/// `supported_groups` extension (RFC 8422).
+pub const EXT_SUPPORTED_GROUPS: u16 = 0x000a;
+/// `ec_point_formats` extension (RFC 8422).
+pub const EXT_EC_POINT_FORMATS: u16 = 0x000b;
+/// `signature_algorithms` extension (RFC 8446).
+pub const EXT_SIGNATURE_ALGORITHMS: u16 = 0x000d;
+/// `application_layer_protocol_negotiation` extension (RFC 7301).
+pub const EXT_ALPN: u16 = 0x0010;
+/// `use_srtp` extension (RFC 5764).
+pub const EXT_USE_SRTP: u16 = 0x000e;
+/// `signed_certificate_timestamp` extension (RFC 6962).
+pub const EXT_SIGNED_CERTIFICATE_TIMESTAMP: u16 = 0x0012;
+/// `padding` extension (RFC 7685).
+pub const EXT_PADDING: u16 = 0x0015;
+/// `encrypt_then_mac` extension (RFC 7366).
+pub const EXT_ENCRYPT_THEN_MAC: u16 = 0x0016;
+/// `extended_master_secret` extension (RFC 7627).
+pub const EXT_EXTENDED_MASTER_SECRET: u16 = 0x0017;
+/// `compress_certificate` extension (RFC 8879).
+pub const EXT_COMPRESS_CERTIFICATE: u16 = 0x001b;
+/// `record_size_limit` extension (RFC 8449).
+pub const EXT_RECORD_SIZE_LIMIT: u16 = 0x001c;
+/// `delegated_credential` extension (RFC 9345).
+pub const EXT_DELEGATED_CREDENTIAL: u16 = 0x0022;
+/// `session_ticket` extension (RFC 5077).
+pub const EXT_SESSION_TICKET: u16 = 0x0023;
+/// `pre_shared_key` extension (RFC 8446).
+pub const EXT_PRE_SHARED_KEY: u16 = 0x0029;
+/// `early_data` extension (RFC 8446).
+pub const EXT_EARLY_DATA: u16 = 0x002a;
+/// `supported_versions` extension (RFC 8446).
+pub const EXT_SUPPORTED_VERSIONS: u16 = 0x002b;
+/// `cookie` extension (RFC 8446).
+pub const EXT_COOKIE: u16 = 0x002c;
+/// `psk_key_exchange_modes` extension (RFC 8446).
+pub const EXT_PSK_KEY_EXCHANGE_MODES: u16 = 0x002d;
+/// `post_handshake_auth` extension (RFC 8446).
+pub const EXT_POST_HANDSHAKE_AUTH: u16 = 0x0031;
+/// `key_share` extension (RFC 8446).
+pub const EXT_KEY_SHARE: u16 = 0x0033;
+/// `quic_transport_parameters` extension (RFC 9001).
+pub const EXT_QUIC_TRANSPORT_PARAMETERS: u16 = 0x0039;
+/// `next_protocol_negotiation` extension (NPN, superseded by ALPN).
+pub const EXT_NEXT_PROTOCOL_NEGOTIATION: u16 = 0x3374;
+/// `application_settings` extension code point used before Chrome 130.
+pub const EXT_APPLICATION_SETTINGS_OLD: u16 = 0x4469;
+/// `application_settings` (ALPS) extension.
+pub const EXT_APPLICATION_SETTINGS: u16 = 0x44cd;
+/// `channel_id` extension, sent by older Chrome builds.
+pub const EXT_CHANNEL_ID: u16 = 0x7550;
+/// `encrypted_client_hello` extension (RFC 9849).
+pub const EXT_ENCRYPTED_CLIENT_HELLO: u16 = 0xfe0d;
+/// `renegotiation_info` extension (RFC 5746).
+pub const EXT_RENEGOTIATION_INFO: u16 = 0xff01;
+/// `quic_transport_parameters` code point used by QUIC drafts.
+pub const EXT_QUIC_TRANSPORT_PARAMETERS_DRAFT: u16 = 0xffa5;
+
+/// Hybrid post-quantum key exchange groups: SecP256r1MLKEM768,
+/// X25519MLKEM768, SecP384r1MLKEM1024, and the two Kyber768 drafts.
+pub const HYBRID_GROUPS: &[u16] = &[0x11eb, 0x11ec, 0x11ed, 0x6399, 0x639a];
@@ -0,0 +1,303 @@
+/* src/db.rs */
+
+//! In-memory JA3 / JA4 fingerprint database loaded at runtime.
+//!
+//! [`FingerprintDb`] maps fingerprints to client labels and answers
+//! [`FingerprintDb::lookup`] for parsed hellos. Lists can be loaded from
+//! CSV or from the JSON dumps published by fingerprint services:
+//!
+//! - ja3er-style arrays of `{"md5": ..., "User-Agent": ...}` objects.
+//! - ja4db exports with `ja4_fingerprint` / `ja4_fingerprint_string`
+//!   and `application` / `library` / `user_agent_string` labels.
+//!
+//! ```
+//! # fn build() -> Vec<u8> {
+//! #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+//! #     d.extend_from_slice(&[0u8; 32]);
+//! #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+//! #     d
+//! # }
+//! # let data = build();
+//! use clienthello::db::FingerprintDb;
+//!
+//! let csv = "fingerprint,label\n\"771,4865,,,\",tiny client\n";
+//! let db = FingerprintDb::from_csv(csv).unwrap();
+//! let hello = clienthello::parse(&data).unwrap();
+//! assert_eq!(db.lookup(&hello), ["tiny client"]);
+//! ```
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{ClientHello, ja3, ja4};
+
+/// Column names recognised as holding a fingerprint, in CSV headers and
+/// JSON objects.
+const FINGERPRINT_KEYS: &[&str] = &[
+	"fingerprint",
+	"ja3",
+	"ja3_hash",
+	"ja3_md5",
+	"md5",
+	"ja4",
+	"ja4_fingerprint",
+	"ja4_r",
+	"ja4_fingerprint_string",
+];
+
+/// Column names recognised as holding a label, in preference order.
+const LABEL_KEYS: &[&str] = &[
+	"label",
+	"client",
+	"application",
+	"library",
+	"user-agent",
+	"user_agent",
+	"user_agent_string",
+	"name",
+];
+
+/// Errors produced while loading a fingerprint list.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum DbError {
+	/// The JSON input could not be parsed.
+	#[error("json: {0}")]
+	Json(#[from] serde_json::Error),
+
+	/// The JSON input is not an array of objects.
+	#[error("expected a JSON array of objects")]
+	JsonShape,
+
+	/// A CSV row could not be interpreted.
+	#[error("csv line {line}: {reason}")]
+	Csv {
+		/// 1-based line number.
+		line: usize,
+		/// What was wrong with the row.
+		reason: &'static str,
+	},
+}
+
+/// Lookup table from JA3 / JA4 fingerprints to client labels.
+///
+/// Fingerprints are matched case-insensitively. JA3 entries may be the
+/// full JA3 string or its MD5 digest; JA4 entries may be the hashed or
+/// the raw `JA4_r` form.
+#[derive(Debug, Clone, Default)]
+pub struct FingerprintDb {
+	entries: HashMap<String, Vec<String>>,
+}
+
+impl FingerprintDb {
+	/// Create an empty database.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Number of distinct fingerprints.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Check whether the database holds no fingerprints.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Associate `label` with `fingerprint`.
+	///
+	/// Blank fingerprints or labels are ignored, as are labels already
+	/// recorded for the fingerprint.
+	pub fn insert(&mut self, fingerprint: &str, label: &str) {
+		let (fingerprint, label) = (fingerprint.trim(), label.trim());
+		if fingerprint.is_empty() || label.is_empty() {
+			return;
+		}
+		let labels = self
+			.entries
+			.entry(fingerprint.to_ascii_lowercase())
+			.or_default();
+		if !labels.iter().any(|l| l == label) {
+			labels.push(label.to_owned());
+		}
+	}
+
+	/// Load a CSV list into a new database.
+	///
+	/// See [`FingerprintDb::load_csv`] for the accepted layout.
+	///
+	/// # Errors
+	///
+	/// Returns [`DbError::Csv`] when a row cannot be interpreted.
+	pub fn from_csv(text: &str) -> Result<Self, DbError> {
+		let mut db = Self::new();
+		db.load_csv(text)?;
+		Ok(db)
+	}
+
+	/// Load a JSON dump into a new database.
+	///
+	/// See [`FingerprintDb::load_json`] for the accepted layout.
+	///
+	/// # Errors
+	///
+	/// Returns [`DbError::Json`] for malformed JSON and
+	/// [`DbError::JsonShape`] when the top level is not an array.
+	pub fn from_json(text: &str) -> Result<Self, DbError> {
+		let mut db = Self::new();
+		db.load_json(text)?;
+		Ok(db)
+	}
+
+	/// Add entries from CSV text.
+	///
+	/// When the first row names a fingerprint column (`ja3`, `md5`,
+	/// `ja4`, `ja4_fingerprint`, ...) and a label column (`label`,
+	/// `client`, `application`, `user_agent`, ...), those columns are
+	/// used. Otherwise every row is read as `fingerprint,label`. Fields
+	/// may be double-quoted; a JA3 string must be, since it contains
+	/// commas. Blank lines and lines starting with `#` are skipped.
+	///
+	/// # Errors
+	///
+	/// Returns [`DbError::Csv`] when a row has an unterminated quote or
+	/// is missing a column.
+	pub fn load_csv(&mut self, text: &str) -> Result<(), DbError> {
+		let mut columns = (0, 1);
+		let mut first = true;
+		for (i, line) in text.lines().enumerate() {
+			let line = line.trim_end_matches('\r');
+			if line.trim().is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let fields = split_csv(line).ok_or(DbError::Csv {
+				line: i + 1,
+				reason: "unterminated quoted field",
+			})?;
+			if core::mem::take(&mut first)
+				&& let Some(header) = header_columns(&fields)
+			{
+				columns = header;
+				continue;
+			}
+			let (Some(fingerprint), Some(label)) = (fields.get(columns.0), fields.get(columns.1)) else {
+				return Err(DbError::Csv {
+					line: i + 1,
+					reason: "missing fingerprint or label column",
+				});
+			};
+			self.insert(fingerprint, label);
+		}
+		Ok(())
+	}
+
+	/// Add entries from a JSON array of objects.
+	///
+	/// Every recognised fingerprint field of an object (`md5`, `ja3`,
+	/// `ja4_fingerprint`, `ja4_fingerprint_string`, ...) is associated
+	/// with the object's first non-empty label field (`label`,
+	/// `application`, `library`, `User-Agent`, `user_agent_string`, ...).
+	/// Objects without either are skipped.
+	///
+	/// # Errors
+	///
+	/// Returns [`DbError::Json`] for malformed JSON and
+	/// [`DbError::JsonShape`] when the top level is not an array.
+	pub fn load_json(&mut self, text: &str) -> Result<(), DbError> {
+		let Value::Array(items) = serde_json::from_str(text)? else {
+			return Err(DbError::JsonShape);
+		};
+		for item in &items {
+			let Value::Object(obj) = item else {
+				return Err(DbError::JsonShape);
+			};
+			let field = |key: &str| {
+				obj
+					.iter()
+					.find(|(k, _)| k.eq_ignore_ascii_case(key))
+					.and_then(|(_, v)| v.as_str())
+					.filter(|s| !s.trim().is_empty())
+			};
+			let Some(label) = LABEL_KEYS.iter().find_map(|k| field(k)) else {
+				continue;
+			};
+			for fingerprint in FINGERPRINT_KEYS.iter().filter_map(|k| field(k)) {
+				self.insert(fingerprint, label);
+			}
+		}
+		Ok(())
+	}
+
+	/// Return the labels of every entry matching `hello`.
+	///
+	/// The hello's JA4, JA4_r, JA3 digest, and JA3 string are looked up
+	/// in that order; labels are returned once each in that order.
+	#[must_use]
+	pub fn lookup(&self, hello: &ClientHello<'_>) -> Vec<&str> {
+		let keys = [
+			ja4::ja4(hello),
+			ja4::ja4_raw(hello),
+			ja3::ja3_hash(hello),
+			ja3::ja3_string(hello),
+		];
+		let mut labels: Vec<&str> = Vec::new();
+		for key in keys {
+			let Some(found) = self.entries.get(&key.to_ascii_lowercase()) else {
+				continue;
+			};
+			for label in found {
+				if !labels.contains(&label.as_str()) {
+					labels.push(label);
+				}
+			}
+		}
+		labels
+	}
+}
+
+/// Map header names to `(fingerprint, label)` column indices, or `None`
+/// when the row is not a recognised header.
+fn header_columns(fields: &[String]) -> Option<(usize, usize)> {
+	let position = |keys: &[&str]| {
+		keys.iter().find_map(|key| {
+			fields
+				.iter()
+				.position(|f| f.trim().eq_ignore_ascii_case(key))
+		})
+	};
+	Some((position(FINGERPRINT_KEYS)?, position(LABEL_KEYS)?))
+}
+
+/// Split one CSV line into fields, honouring double quotes and `""`
+/// escapes. Returns `None` for an unterminated quoted field.
+fn split_csv(line: &str) -> Option<Vec<String>> {
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut chars = line.chars().peekable();
+	let mut quoted = false;
+	while let Some(c) = chars.next() {
+		match (quoted, c) {
+			(true, '"') if chars.peek() == Some(&'"') => {
+				chars.next();
+				field.push('"');
+			}
+			(true, '"') => quoted = false,
+			(false, '"') if field.trim().is_empty() => {
+				field.clear();
+				quoted = true;
+			}
+			(false, ',') => fields.push(core::mem::take(&mut field)),
+			_ => field.push(c),
+		}
+	}
+	if quoted {
+		return None;
+	}
+	fields.push(field);
+	Some(fields)
+}
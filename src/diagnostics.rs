@@ -0,0 +1,166 @@
+/* src/diagnostics.rs */
+
+use alloc::vec::Vec;
+
+/// Irregularities the parser tolerated while producing a
+/// [`ClientHello`](crate::ClientHello).
+///
+/// The flag fields record things that a strict
+/// [`ParseOptions`](crate::ParseOptions) setting would reject;
+/// [`warnings`](Self::warnings) lists those and everything else the parser
+/// let through, in the order encountered. A hello from a well-behaved
+/// client leaves every field empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Diagnostics {
+	/// Type identifiers of structured extensions whose body had bytes
+	/// left after its content, in wire order.
+	pub trailing_bytes: Vec<u16>,
+	/// The SNI extension listed more than one `host_name` entry; only
+	/// the one chosen by [`SniPolicy`](crate::SniPolicy) was kept.
+	pub multiple_server_names: bool,
+	/// The record payload exceeded 2^14 bytes.
+	pub oversized_record: bool,
+	/// The record payload held more than the ClientHello handshake
+	/// message.
+	pub handshake_length_mismatch: bool,
+	/// Every tolerated irregularity, in the order encountered.
+	pub warnings: Warnings,
+}
+
+impl Diagnostics {
+	/// Check whether nothing irregular was recorded.
+	#[must_use]
+	pub fn is_clean(&self) -> bool {
+		self.trailing_bytes.is_empty()
+			&& !self.multiple_server_names
+			&& !self.oversized_record
+			&& !self.handshake_length_mismatch
+			&& self.warnings.is_empty()
+	}
+}
+
+/// Something odd the parser tolerated instead of failing the hello.
+///
+/// Collected in [`Warnings`]. None of these affect the parsed fields
+/// beyond what their description says, so they are safe to export as
+/// metrics and ignore otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Warning {
+	/// A structured extension body had `len` bytes left after its
+	/// content; they were ignored.
+	TrailingBytes {
+		/// Extension type.
+		type_id: u16,
+		/// Number of ignored bytes.
+		len: usize,
+	},
+	/// A list ended with `len` bytes too short for another entry; they
+	/// were ignored. `type_id` is `None` for the extension list itself.
+	PartialListEntry {
+		/// Extension type, or `None` for the extension list.
+		type_id: Option<u16>,
+		/// Number of ignored bytes.
+		len: usize,
+	},
+	/// `len` bytes followed the compression methods or the extension
+	/// list; they were ignored.
+	TrailingBodyBytes {
+		/// Number of ignored bytes.
+		len: usize,
+	},
+	/// An extension that must list at least one entry was sent with an
+	/// empty list.
+	EmptyList {
+		/// Extension type.
+		type_id: u16,
+	},
+	/// A GREASE cipher suite value was offered more than once.
+	RepeatedGreaseCipherSuite {
+		/// The repeated GREASE value.
+		value: u16,
+	},
+	/// A GREASE extension type was sent more than once.
+	RepeatedGreaseExtension {
+		/// The repeated GREASE value.
+		value: u16,
+	},
+	/// The SNI extension listed `count` `host_name` entries.
+	MultipleServerNames {
+		/// Number of `host_name` entries.
+		count: usize,
+	},
+	/// An extension failed to decode and was kept as
+	/// [`Extension::Malformed`](crate::Extension::Malformed).
+	MalformedExtension {
+		/// Extension type.
+		type_id: u16,
+	},
+	/// The record payload exceeded 2^14 bytes.
+	OversizedRecord {
+		/// Payload length.
+		len: usize,
+	},
+	/// The record payload held more than the ClientHello message.
+	HandshakeLengthMismatch {
+		/// Length of the handshake message, header included.
+		handshake: usize,
+		/// Length of the record payload.
+		record: usize,
+	},
+}
+
+/// The [`Warning`]s recorded for one parsed hello, in the order
+/// encountered.
+///
+/// ```
+/// use clienthello::Warning;
+///
+/// # let mut msg = clienthello::Builder::new().extension(0x0042, &[]).build().unwrap();
+/// // A hello followed by a stray byte after its extension list.
+/// msg.push(0x00);
+/// msg[3] += 1;
+/// let hello = clienthello::parse(&msg).unwrap();
+/// assert_eq!(hello.diagnostics.warnings[..], [Warning::TrailingBodyBytes { len: 1 }]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Warnings(Vec<Warning>);
+
+impl Warnings {
+	/// Check whether a warning matching `predicate` was recorded.
+	pub fn any(&self, predicate: impl FnMut(&Warning) -> bool) -> bool {
+		self.0.iter().any(predicate)
+	}
+
+	/// Unwrap into the underlying list.
+	#[must_use]
+	pub fn into_vec(self) -> Vec<Warning> {
+		self.0
+	}
+
+	pub(crate) fn push(&mut self, warning: Warning) {
+		self.0.push(warning);
+	}
+}
+
+impl core::ops::Deref for Warnings {
+	type Target = [Warning];
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<'s> IntoIterator for &'s Warnings {
+	type Item = &'s Warning;
+	type IntoIter = core::slice::Iter<'s, Warning>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter()
+	}
+}
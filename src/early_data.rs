@@ -0,0 +1,139 @@
+/* src/early_data.rs */
+
+use alloc::vec::Vec;
+
+use crate::consts::EXT_PRE_SHARED_KEY;
+#[cfg(feature = "ext-alpn")]
+use crate::consts::{EXT_ALPN, EXT_EARLY_DATA};
+use crate::tls_reader::Reader;
+use crate::{ClientHello, Error, Extension};
+
+/// One identity offered in the `pre_shared_key` extension (RFC 8446
+/// §4.2.11).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PskIdentity<'a> {
+	/// The identity, usually a session ticket issued by the server.
+	pub identity: &'a [u8],
+	/// Ticket age in milliseconds plus the ticket's `ticket_age_add`,
+	/// modulo 2^32.
+	pub obfuscated_ticket_age: u32,
+}
+
+/// What a server recorded about a session ticket when it issued it.
+///
+/// Passed to [`ClientHello::early_data`] once the server has looked up
+/// the ticket named by the first [`PskIdentity`].
+#[cfg(feature = "ext-alpn")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TicketContext<'t> {
+	/// `max_early_data_size` from the ticket's `early_data` extension;
+	/// zero when the ticket does not allow 0-RTT.
+	pub max_early_data_size: u32,
+	/// Cipher suite of the connection that issued the ticket.
+	pub cipher_suite: u16,
+	/// ALPN protocol of the connection that issued the ticket.
+	pub alpn: Option<&'t [u8]>,
+}
+
+/// Early data a hello will send under a ticket, as predicted by
+/// [`ClientHello::early_data`].
+#[cfg(feature = "ext-alpn")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EarlyData<'a> {
+	/// Identity of the PSK the early data is encrypted under.
+	pub identity: PskIdentity<'a>,
+	/// ALPN protocol the early data belongs to; the server must select
+	/// it to accept the data.
+	pub alpn: Option<&'a [u8]>,
+	/// Most bytes of early data the client may send.
+	pub max_size: u32,
+	/// Cipher suite the early data is encrypted with.
+	pub cipher_suite: u16,
+}
+
+/// Why [`ClientHello::early_data`] predicts no accepted 0-RTT data.
+#[cfg(feature = "ext-alpn")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[non_exhaustive]
+pub enum EarlyDataRejection {
+	/// The hello has no `early_data` extension.
+	#[error("early data not offered")]
+	NotOffered,
+	/// `early_data` without a decodable `pre_shared_key` offer.
+	#[error("early data offered without a PSK")]
+	NoPsk,
+	/// The ticket's `max_early_data_size` is zero.
+	#[error("ticket does not allow early data")]
+	NotAllowedByTicket,
+	/// The hello does not offer the ticket's cipher suite.
+	#[error("ticket cipher suite {0:#06x} not offered")]
+	CipherSuite(u16),
+	/// The hello does not offer the ticket's ALPN protocol, or offers
+	/// ALPN for a ticket issued without one, so the server cannot select
+	/// the same protocol as the original connection.
+	#[error("ALPN does not match the ticket")]
+	Alpn,
+}
+
+pub(crate) fn psk_identities<'a>(hello: &ClientHello<'a>) -> Vec<PskIdentity<'a>> {
+	match hello.extensions.by_type(EXT_PRE_SHARED_KEY) {
+		Some(Extension::Unknown { data, .. }) => decode_identities(data).unwrap_or_default(),
+		_ => Vec::new(),
+	}
+}
+
+fn decode_identities(data: &[u8]) -> Result<Vec<PskIdentity<'_>>, Error> {
+	Reader::new(data).read_nested_u16("PSK identities", |r| {
+		let mut out = Vec::new();
+		while !r.is_empty() {
+			let identity = r.read_vec_u16("PSK identity")?;
+			let obfuscated_ticket_age = r.read_u32("obfuscated ticket age")?;
+			out.push(PskIdentity {
+				identity,
+				obfuscated_ticket_age,
+			});
+		}
+		Ok(out)
+	})
+}
+
+#[cfg(feature = "ext-alpn")]
+pub(crate) fn predict<'a>(
+	hello: &ClientHello<'a>,
+	ticket: &TicketContext<'_>,
+) -> Result<EarlyData<'a>, EarlyDataRejection> {
+	if !hello.extensions.contains(EXT_EARLY_DATA) {
+		return Err(EarlyDataRejection::NotOffered);
+	}
+	// Early data is always encrypted under the first PSK (RFC 8446
+	// §4.2.10).
+	let identity = *psk_identities(hello)
+		.first()
+		.ok_or(EarlyDataRejection::NoPsk)?;
+	if ticket.max_early_data_size == 0 {
+		return Err(EarlyDataRejection::NotAllowedByTicket);
+	}
+	if !hello.cipher_suites.contains(&ticket.cipher_suite) {
+		return Err(EarlyDataRejection::CipherSuite(ticket.cipher_suite));
+	}
+	let offered: &[&'a [u8]] = match hello.extensions.by_type(EXT_ALPN) {
+		Some(Extension::Alpn(protocols)) => protocols,
+		_ => &[],
+	};
+	let alpn = match ticket.alpn {
+		None if offered.is_empty() => None,
+		Some(proto) => Some(
+			*offered
+				.iter()
+				.find(|&&p| p == proto)
+				.ok_or(EarlyDataRejection::Alpn)?,
+		),
+		None => return Err(EarlyDataRejection::Alpn),
+	};
+	Ok(EarlyData {
+		identity,
+		alpn,
+		max_size: ticket.max_early_data_size,
+		cipher_suite: ticket.cipher_suite,
+	})
+}
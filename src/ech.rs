@@ -0,0 +1,512 @@
+/* src/ech.rs */
+
+//! Encrypted Client Hello offers.
+//!
+//! [`Builder::ech_grease`] adds a GREASE `encrypted_client_hello`
+//! extension: a well-formed outer offer with random contents that no
+//! server can decrypt, which clients send when they have no ECH
+//! configuration so that real offers do not stand out. With the `hpke`
+//! feature, [`Builder::build_ech`] seals a ClientHelloInner to a
+//! server's [`EchConfig`] and returns the ClientHelloOuter carrying it.
+//! Configurations are usually obtained as an `ECHConfigList` from the
+//! `ech` parameter of a DNS HTTPS record; [`parse_config_list`] decodes
+//! one.
+//!
+//! Only `ECHConfig` version `0xfe0d` (RFC 9849) is understood. Sealing
+//! supports DHKEM(X25519, HKDF-SHA256) with HKDF-SHA256 and AES-128-GCM
+//! or AES-256-GCM, the suites deployed servers publish.
+//!
+//! ```
+//! use clienthello::Builder;
+//!
+//! let msg = Builder::new()
+//!     .cipher_suites(&[0x1301])
+//!     .server_name("example.com")
+//!     .ech_grease(|buf| buf.fill(0x5a))
+//!     .build()
+//!     .unwrap();
+//! let hello = clienthello::parse(&msg).unwrap();
+//! let ech = hello.find_extension(0xfe0d).unwrap();
+//! assert_eq!(ech[0], 0x00); // outer
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::consts::EXT_ENCRYPTED_CLIENT_HELLO;
+use crate::tls_reader::Reader;
+use crate::tls_writer::Writer;
+use crate::{Builder, Error};
+
+/// The only `ECHConfig` version understood.
+const VERSION: u16 = 0xfe0d;
+/// `ECHClientHelloType` of an offer in the outer hello.
+const OUTER: u8 = 0x00;
+/// `ECHClientHelloType` marking the inner hello.
+const INNER: u8 = 0x01;
+
+/// Errors produced when decoding an `ECHConfig` or sealing an offer to
+/// it.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum EchError {
+	/// The configuration is truncated, or an encoded length overflows
+	/// while building the offer.
+	#[error(transparent)]
+	Tls(#[from] Error),
+
+	/// The configuration has a version other than `0xfe0d`.
+	#[error("unsupported ECHConfig version {0:#06x}")]
+	UnsupportedVersion(u16),
+
+	/// The configuration uses a KEM offers cannot be sealed with.
+	#[error("unsupported HPKE KEM {0:#06x}")]
+	UnsupportedKem(u16),
+
+	/// None of the configuration's cipher suites can be used.
+	#[error("no supported HPKE cipher suite")]
+	UnsupportedSuite,
+
+	/// A field holds a value the format does not allow.
+	#[error("malformed ECHConfig: {0}")]
+	Malformed(&'static str),
+}
+
+/// HPKE symmetric cipher suite: a KDF and an AEAD identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HpkeSuite {
+	/// HPKE KDF identifier (`0x0001` for HKDF-SHA256).
+	pub kdf_id: u16,
+	/// HPKE AEAD identifier (`0x0001` for AES-128-GCM).
+	pub aead_id: u16,
+}
+
+/// A server's ECH configuration, as published in the `ech` parameter of
+/// its HTTPS DNS record.
+///
+/// Fields borrow from the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EchConfig<'a> {
+	/// Identifier the server uses to pick its key.
+	pub config_id: u8,
+	/// HPKE KEM identifier (`0x0020` for DHKEM(X25519, HKDF-SHA256)).
+	pub kem_id: u16,
+	/// HPKE public key.
+	pub public_key: &'a [u8],
+	/// Cipher suites the server accepts, in its preference order.
+	pub cipher_suites: Vec<HpkeSuite>,
+	/// Longest server name the client should pad the inner SNI to.
+	pub maximum_name_length: u8,
+	/// Name sent in the outer hello's SNI.
+	pub public_name: &'a str,
+	/// Raw `ECHConfigExtension` list.
+	pub extensions: &'a [u8],
+	raw: &'a [u8],
+}
+
+impl<'a> EchConfig<'a> {
+	/// Decode one `ECHConfig`, version and length prefix included.
+	///
+	/// # Errors
+	///
+	/// Returns [`EchError::UnsupportedVersion`] for versions other than
+	/// `0xfe0d`, [`EchError::Malformed`] when the public name is empty or
+	/// not UTF-8 or bytes follow the configuration, and
+	/// [`EchError::Tls`] when a field is truncated.
+	pub fn parse(data: &'a [u8]) -> Result<Self, EchError> {
+		let mut r = Reader::new(data);
+		let config = Self::read(&mut r)?;
+		if !r.is_empty() {
+			return Err(EchError::Malformed("trailing bytes"));
+		}
+		Ok(config)
+	}
+
+	/// Decode one `ECHConfig` from `r`. The configuration is consumed
+	/// even when its version is unsupported.
+	fn read(r: &mut Reader<'a>) -> Result<Self, EchError> {
+		let start = r.rest();
+		let version = r.read_u16("ECHConfig version")?;
+		let contents = r.read_vec_u16("ECHConfig contents")?;
+		let raw = &start[..4 + contents.len()];
+		if version != VERSION {
+			return Err(EchError::UnsupportedVersion(version));
+		}
+		let mut c = Reader::new(contents);
+		let config_id = c.read_u8("ECHConfig config_id")?;
+		let kem_id = c.read_u16("ECHConfig kem_id")?;
+		let public_key = c.read_vec_u16("ECHConfig public_key")?;
+		let suites = c.read_vec_u16("ECHConfig cipher_suites")?;
+		if suites.is_empty() || !suites.len().is_multiple_of(4) {
+			return Err(EchError::Malformed("cipher suite list length"));
+		}
+		let cipher_suites = suites
+			.chunks_exact(4)
+			.map(|s| HpkeSuite {
+				kdf_id: u16::from_be_bytes([s[0], s[1]]),
+				aead_id: u16::from_be_bytes([s[2], s[3]]),
+			})
+			.collect();
+		let maximum_name_length = c.read_u8("ECHConfig maximum_name_length")?;
+		let public_name = c.read_vec_u8("ECHConfig public_name")?;
+		let public_name = core::str::from_utf8(public_name)
+			.ok()
+			.filter(|name| !name.is_empty())
+			.ok_or(EchError::Malformed("public name"))?;
+		let extensions = c.read_vec_u16("ECHConfig extensions")?;
+		Ok(Self {
+			config_id,
+			kem_id,
+			public_key,
+			cipher_suites,
+			maximum_name_length,
+			public_name,
+			extensions,
+			raw,
+		})
+	}
+
+	/// The encoded configuration, version and length prefix included.
+	#[must_use]
+	pub fn as_bytes(&self) -> &'a [u8] {
+		self.raw
+	}
+}
+
+/// Decode an `ECHConfigList`, the value of the `ech` parameter of a DNS
+/// HTTPS record.
+///
+/// Configurations with a version other than `0xfe0d` are skipped, as
+/// RFC 9849 §4 requires of clients, so the result may be empty. The
+/// remaining ones are returned in list order, which is the server's
+/// preference order.
+///
+/// # Errors
+///
+/// Returns [`EchError::Malformed`] when the list is empty or bytes follow
+/// it, and the errors of [`EchConfig::parse`] other than
+/// [`EchError::UnsupportedVersion`] for a malformed configuration.
+pub fn parse_config_list(data: &[u8]) -> Result<Vec<EchConfig<'_>>, EchError> {
+	let mut outer = Reader::new(data);
+	let configs = outer.read_nested_u16("ECHConfigList", |r| {
+		if r.is_empty() {
+			return Err(EchError::Malformed("empty ECHConfigList"));
+		}
+		let mut configs = Vec::new();
+		while !r.is_empty() {
+			match EchConfig::read(r) {
+				Ok(config) => configs.push(config),
+				Err(EchError::UnsupportedVersion(_)) => {}
+				Err(e) => return Err(e),
+			}
+		}
+		Ok(configs)
+	})?;
+	if !outer.is_empty() {
+		return Err(EchError::Malformed("trailing bytes"));
+	}
+	Ok(configs)
+}
+
+/// A decoded `encrypted_client_hello` extension body, the
+/// `ECHClientHello` of RFC 9849 §5.
+///
+/// A GREASE offer decodes as [`Outer`](Self::Outer) like a real one;
+/// the two only differ in whether the server can decrypt the payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EchClientHello<'a> {
+	/// An offer in the ClientHelloOuter.
+	Outer {
+		/// HPKE suite the payload is sealed with.
+		cipher_suite: HpkeSuite,
+		/// Identifier of the server configuration used.
+		config_id: u8,
+		/// HPKE encapsulated key; empty after a HelloRetryRequest.
+		enc: &'a [u8],
+		/// The sealed ClientHelloInner.
+		payload: &'a [u8],
+	},
+	/// The marker in a ClientHelloInner, which has no other fields.
+	Inner,
+}
+
+impl<'a> EchClientHello<'a> {
+	/// Decode an `encrypted_client_hello` extension body.
+	///
+	/// # Errors
+	///
+	/// Returns [`EchError::Malformed`] for a type other than outer or
+	/// inner or when bytes follow the body, and [`EchError::Tls`] when a
+	/// field is truncated.
+	///
+	/// ```
+	/// use clienthello::ech::EchClientHello;
+	///
+	/// let body = [0x00, 0x00, 0x01, 0x00, 0x01, 0x2a, 0x00, 0x01, 0xee, 0x00, 0x02, 0xaa, 0xbb];
+	/// let Ok(EchClientHello::Outer { config_id, enc, payload, .. }) = EchClientHello::parse(&body)
+	/// else {
+	///     panic!("not an outer offer");
+	/// };
+	/// assert_eq!((config_id, enc, payload), (0x2a, &[0xee][..], &[0xaa, 0xbb][..]));
+	/// assert_eq!(EchClientHello::parse(&[0x01]), Ok(EchClientHello::Inner));
+	/// ```
+	pub fn parse(data: &'a [u8]) -> Result<Self, EchError> {
+		let mut r = Reader::new(data);
+		let ech = Self::read(&mut r)?.ok_or(EchError::Malformed("ECHClientHello type"))?;
+		if !r.is_empty() {
+			return Err(EchError::Malformed("trailing bytes"));
+		}
+		Ok(ech)
+	}
+
+	/// Decode an `ECHClientHello` from `r`, or return `None` without
+	/// consuming more than the type byte when the type is unknown.
+	pub(crate) fn read(r: &mut Reader<'a>) -> Result<Option<Self>, Error> {
+		match r.read_u8("ECHClientHello type")? {
+			OUTER => {
+				let cipher_suite = HpkeSuite {
+					kdf_id: r.read_u16("ECH kdf_id")?,
+					aead_id: r.read_u16("ECH aead_id")?,
+				};
+				Ok(Some(Self::Outer {
+					cipher_suite,
+					config_id: r.read_u8("ECH config_id")?,
+					enc: r.read_vec_u16("ECH enc")?,
+					payload: r.read_vec_u16("ECH payload")?,
+				}))
+			}
+			INNER => Ok(Some(Self::Inner)),
+			_ => Ok(None),
+		}
+	}
+}
+
+impl Builder {
+	/// Add a GREASE `encrypted_client_hello` extension (type `0xfe0d`).
+	///
+	/// The offer names HKDF-SHA256 and AES-128-GCM and carries a random
+	/// config ID, a 32-byte X25519-sized encapsulated key, and a random
+	/// payload of 144, 176, 208, or 240 bytes, the sizes a real offer for
+	/// a padded inner hello takes. `fill` is called to fill each random
+	/// field and should be backed by a cryptographic RNG, with fresh
+	/// output for every connection.
+	#[must_use]
+	pub fn ech_grease<F>(self, mut fill: F) -> Self
+	where
+		F: FnMut(&mut [u8]),
+	{
+		let mut head = [0u8; 2];
+		fill(&mut head);
+		let [config_id, size] = head;
+		let mut enc = [0u8; 32];
+		fill(&mut enc);
+		let mut payload = alloc::vec![0u8; 144 + 32 * usize::from(size % 4)];
+		fill(&mut payload);
+		let suite = HpkeSuite {
+			kdf_id: 0x0001,
+			aead_id: 0x0001,
+		};
+		// Cannot fail: both vectors are far below 2^16 bytes.
+		let body = outer_body(suite, config_id, &enc, &payload).unwrap_or_default();
+		self.extension(EXT_ENCRYPTED_CLIENT_HELLO, &body)
+	}
+
+	/// Encode a ClientHelloOuter offering ECH for the hello `inner`.
+	///
+	/// `self` is the outer hello; its SNI is replaced with the
+	/// configuration's public name. `inner` is encoded with an
+	/// inner-type `encrypted_client_hello` extension and without its
+	/// session ID, which the server restores from the outer hello, then
+	/// padded as RFC 9849 §6.1.3 recommends and sealed with HPKE base
+	/// mode to the
+	/// configuration's key, using `ephemeral_secret` as the X25519
+	/// ephemeral key. The first supported cipher suite of the
+	/// configuration is used.
+	///
+	/// `ephemeral_secret` must be fresh random bytes for every offer;
+	/// reusing it lets observers link connections.
+	///
+	/// # Errors
+	///
+	/// Returns [`EchError::UnsupportedKem`] unless the configuration
+	/// uses DHKEM(X25519, HKDF-SHA256), [`EchError::UnsupportedSuite`]
+	/// when none of its suites uses HKDF-SHA256 with AES-GCM, and
+	/// [`EchError::Tls`] when either hello overflows a length prefix.
+	#[cfg(feature = "hpke")]
+	pub fn build_ech(
+		&self,
+		inner: &Self,
+		config: &EchConfig<'_>,
+		ephemeral_secret: [u8; 32],
+	) -> Result<Vec<u8>, EchError> {
+		Ok(self.ech_outer(inner, config, ephemeral_secret)?.build()?)
+	}
+
+	/// Encode a TLS record containing a ClientHelloOuter, as
+	/// [`build_ech`](Self::build_ech) does.
+	///
+	/// # Errors
+	///
+	/// Returns the same errors as [`build_ech`](Self::build_ech),
+	/// including [`EchError::Tls`] when the record length overflows.
+	#[cfg(feature = "hpke")]
+	pub fn build_ech_record(
+		&self,
+		inner: &Self,
+		config: &EchConfig<'_>,
+		ephemeral_secret: [u8; 32],
+	) -> Result<Vec<u8>, EchError> {
+		Ok(
+			self
+				.ech_outer(inner, config, ephemeral_secret)?
+				.build_record()?,
+		)
+	}
+
+	/// Return the outer hello with the sealed offer added.
+	#[cfg(feature = "hpke")]
+	fn ech_outer(
+		&self,
+		inner: &Self,
+		config: &EchConfig<'_>,
+		ephemeral_secret: [u8; 32],
+	) -> Result<Self, EchError> {
+		use crate::hpke;
+
+		let public_key = <[u8; 32]>::try_from(config.public_key)
+			.ok()
+			.filter(|_| config.kem_id == hpke::KEM_X25519)
+			.ok_or(EchError::UnsupportedKem(config.kem_id))?;
+		let suite = config
+			.cipher_suites
+			.iter()
+			.copied()
+			.find(|s| hpke::supports(s.kdf_id, s.aead_id))
+			.ok_or(EchError::UnsupportedSuite)?;
+
+		// EncodedClientHelloInner: the inner body with an empty session
+		// ID, followed by zero padding.
+		let encoded = inner
+			.clone()
+			.session_id(&[])
+			.extension(EXT_ENCRYPTED_CLIENT_HELLO, &[INNER])
+			.build()?;
+		let mut plaintext = encoded[4..].to_vec();
+		let max_name = usize::from(config.maximum_name_length);
+		let mut padding = match inner.server_name_len() {
+			Some(len) => max_name.saturating_sub(len),
+			None => max_name + 9,
+		};
+		padding += 31 - (plaintext.len() + padding - 1) % 32;
+		plaintext.resize(plaintext.len() + padding, 0);
+
+		let mut info = b"tls ech\0".to_vec();
+		info.extend_from_slice(config.as_bytes());
+		let (enc, context) = hpke::setup_base_s(public_key, ephemeral_secret, suite.aead_id, &info);
+
+		// The AAD is the outer ClientHello with a zeroed payload.
+		let outer = self.clone().server_name(config.public_name);
+		let zeros = alloc::vec![0u8; plaintext.len() + hpke::TAG_LEN];
+		let aad = outer
+			.clone()
+			.extension(
+				EXT_ENCRYPTED_CLIENT_HELLO,
+				&outer_body(suite, config.config_id, &enc, &zeros)?,
+			)
+			.build()?;
+		let payload = context
+			.seal(&aad[4..], &plaintext)
+			.ok_or(Error::LengthOverflow {
+				len: plaintext.len(),
+				max: u16::MAX.into(),
+			})?;
+		let body = outer_body(suite, config.config_id, &enc, &payload)?;
+		Ok(outer.extension(EXT_ENCRYPTED_CLIENT_HELLO, &body))
+	}
+}
+
+/// Encode an outer-type `ECHClientHello` body.
+fn outer_body(
+	suite: HpkeSuite,
+	config_id: u8,
+	enc: &[u8],
+	payload: &[u8],
+) -> Result<Vec<u8>, Error> {
+	let mut w = Writer::new();
+	w.write_u8(OUTER);
+	w.write_u16(suite.kdf_id);
+	w.write_u16(suite.aead_id);
+	w.write_u8(config_id);
+	w.write_opaque_u16(enc)?;
+	w.write_opaque_u16(payload)?;
+	Ok(w.into_bytes())
+}
+
+#[cfg(all(test, feature = "hpke"))]
+mod tests {
+	use super::*;
+	use crate::hpke;
+
+	const SK_R: [u8; 32] = [0x42; 32];
+
+	fn config_bytes(public_key: &[u8]) -> Vec<u8> {
+		let mut contents = alloc::vec![0x07, 0x00, 0x20, 0x00, 0x20];
+		contents.extend_from_slice(public_key);
+		contents.extend_from_slice(&[0x00, 0x04, 0x00, 0x01, 0x00, 0x01, 32]);
+		contents.extend_from_slice(&[0x0b]);
+		contents.extend_from_slice(b"public.test");
+		contents.extend_from_slice(&[0x00, 0x00]);
+		let mut config = alloc::vec![0xfe, 0x0d, 0x00, contents.len() as u8];
+		config.extend_from_slice(&contents);
+		config
+	}
+
+	#[test]
+	fn sealed_offer_opens_to_padded_inner() {
+		let pk_r = x25519_dalek::PublicKey::from(&x25519_dalek::StaticSecret::from(SK_R));
+		let raw = config_bytes(pk_r.as_bytes());
+		let config = EchConfig::parse(&raw).unwrap();
+		let inner = Builder::new()
+			.cipher_suites(&[0x1301])
+			.server_name("secret.example")
+			.supported_versions(&[0x0304]);
+		let outer = Builder::new()
+			.session_id(&[0x11; 32])
+			.cipher_suites(&[0x1301])
+			.supported_versions(&[0x0304]);
+		let msg = outer.build_ech(&inner, &config, [0x24; 32]).unwrap();
+
+		let hello = crate::parse(&msg).unwrap();
+		let body = hello.find_extension(EXT_ENCRYPTED_CLIENT_HELLO).unwrap();
+		let mut r = Reader::new(body);
+		assert_eq!(r.read_u8("type").unwrap(), OUTER);
+		assert_eq!(r.read_u16("kdf").unwrap(), 0x0001);
+		assert_eq!(r.read_u16("aead").unwrap(), 0x0001);
+		assert_eq!(r.read_u8("config_id").unwrap(), 0x07);
+		let enc: [u8; 32] = r.read_vec_u16("enc").unwrap().try_into().unwrap();
+		let payload = r.read_vec_u16("payload").unwrap();
+
+		// Rebuild the AAD by zeroing the payload in place.
+		let mut aad = msg[4..].to_vec();
+		let offset = payload.as_ptr() as usize - msg[4..].as_ptr() as usize;
+		aad[offset..offset + payload.len()].fill(0);
+		let mut info = b"tls ech\0".to_vec();
+		info.extend_from_slice(&raw);
+		let plaintext = hpke::setup_base_r(enc, SK_R, 0x0001, &info)
+			.open(&aad, payload)
+			.unwrap();
+
+		let expected = inner
+			.session_id(&[])
+			.extension(EXT_ENCRYPTED_CLIENT_HELLO, &[INNER])
+			.build()
+			.unwrap();
+		let encoded = &expected[4..];
+		assert_eq!(&plaintext[..encoded.len()], encoded);
+		assert!(plaintext[encoded.len()..].iter().all(|&b| b == 0));
+		assert_eq!(plaintext.len() % 32, 0);
+		// 32 - len("secret.example") bytes of name padding at least.
+		assert!(plaintext.len() - encoded.len() >= 18);
+	}
+}
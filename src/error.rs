@@ -1,6 +1,12 @@
 /* src/error.rs */
 
+use crate::types::{ContentType, HandshakeType};
+
 /// Errors produced during TLS ClientHello parsing.
+///
+/// Implements [`core::error::Error`] with or without the `std` feature,
+/// so `no_std` callers can box it as `dyn core::error::Error` or wrap it
+/// with `?` in their own error types.
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 #[non_exhaustive]
 pub enum Error {
@@ -14,12 +20,17 @@ pub enum Error {
 	},
 
 	/// TLS record content type is not Handshake (`0x16`).
-	#[error("unexpected content type: expected 0x16 (Handshake), got {0:#04x}")]
-	NotHandshakeRecord(u8),
+	#[error("unexpected content type: expected Handshake (0x16), got {0}")]
+	NotHandshakeRecord(ContentType),
 
 	/// Handshake message type is not ClientHello (`0x01`).
-	#[error("unexpected handshake type: expected 0x01 (ClientHello), got {0:#04x}")]
-	NotClientHello(u8),
+	#[error("unexpected handshake type: expected ClientHello (0x01), got {0}")]
+	NotClientHello(HandshakeType),
+
+	/// Handshake message type is not ServerHello (`0x02`), from
+	/// [`parse_server_hello`](crate::parse_server_hello).
+	#[error("unexpected handshake type: expected ServerHello (0x02), got {0}")]
+	NotServerHello(HandshakeType),
 
 	/// A required field was truncated in the input.
 	#[error("truncated {field}")]
@@ -27,4 +38,68 @@ pub enum Error {
 		/// Name of the truncated field.
 		field: &'static str,
 	},
+
+	/// An encoded length does not fit in its length prefix.
+	#[error("length {len} exceeds maximum {max}")]
+	LengthOverflow {
+		/// Length that was produced.
+		len: usize,
+		/// Largest value the prefix can hold.
+		max: usize,
+	},
+
+	/// A structured extension body has bytes left after its content, in
+	/// strict mode (see
+	/// [`ParseOptions::strict_extension_bodies`](crate::ParseOptions::strict_extension_bodies)).
+	#[error("extension {type_id:#06x} has {len} trailing bytes")]
+	TrailingBytes {
+		/// TLS extension type identifier.
+		type_id: u16,
+		/// Number of unconsumed bytes.
+		len: usize,
+	},
+
+	/// The record payload exceeds the 2^14-byte plaintext limit, in
+	/// strict mode (see
+	/// [`ParseOptions::strict_record_framing`](crate::ParseOptions::strict_record_framing)).
+	#[error("record length {len} exceeds maximum {max}")]
+	RecordTooLong {
+		/// Record payload length.
+		len: usize,
+		/// Largest allowed payload length.
+		max: usize,
+	},
+
+	/// The record payload is longer than the handshake message it
+	/// carries, in strict mode.
+	#[error("handshake message of {handshake} bytes does not fill record payload of {record}")]
+	HandshakeLengthMismatch {
+		/// Handshake message length, header included.
+		handshake: usize,
+		/// Record payload length.
+		record: usize,
+	},
+
+	/// The SNI extension lists more than one `host_name` entry, under
+	/// [`SniPolicy::Reject`](crate::SniPolicy::Reject).
+	#[error("SNI extension lists {count} host names")]
+	MultipleServerNames {
+		/// Number of `host_name` entries.
+		count: usize,
+	},
+
+	/// A list holds more entries than the configured
+	/// [`ParseOptions`](crate::ParseOptions) limit allows.
+	#[error("{field} exceeds limit of {max} entries")]
+	LimitExceeded {
+		/// Name of the list that exceeded its limit.
+		field: &'static str,
+		/// Configured maximum number of entries.
+		max: usize,
+	},
 }
+
+// `thiserror` targets `core::error::Error` when its `std` feature is off;
+// keep that guarantee from regressing silently in `no_std` builds.
+const _: fn() = assert_error::<Error>;
+fn assert_error<E: core::error::Error + Send + Sync + 'static>() {}
@@ -0,0 +1,95 @@
+/* src/export.rs */
+
+//! Compact binary export and import of parsed hellos.
+//!
+//! Hellos are encoded with [`bincode`] inside a small envelope that
+//! starts with [`SCHEMA_VERSION`]. Importing borrows byte fields
+//! directly from the encoded buffer, so a collector can decode millions
+//! of records without copying their payloads.
+//!
+//! ```
+//! # fn build() -> Vec<u8> {
+//! #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+//! #     d.extend_from_slice(&[0u8; 32]);
+//! #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+//! #     d
+//! # }
+//! # let data = build();
+//! use clienthello::export;
+//!
+//! let hello = clienthello::parse(&data).unwrap();
+//! let bytes = export::to_bincode(&hello).unwrap();
+//! assert_eq!(export::from_bincode(&bytes).unwrap(), hello);
+//! ```
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ClientHello;
+
+/// Version of the exported envelope layout.
+///
+/// Bumped whenever a change to [`ClientHello`] or its nested types
+/// alters the encoded form.
+pub const SCHEMA_VERSION: u16 = 13;
+
+/// Errors produced while exporting or importing hellos.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ExportError {
+	/// The encoded data was written with a different schema version.
+	#[error("unsupported schema version {found}, expected {expected}")]
+	SchemaVersion {
+		/// Version found in the envelope.
+		found: u16,
+		/// Version this build understands.
+		expected: u16,
+	},
+
+	/// The bincode encoder or decoder failed.
+	#[error("bincode: {0}")]
+	Bincode(#[from] bincode::Error),
+}
+
+/// Versioned wrapper around a hello. Encoding wraps a `&ClientHello`
+/// so the hello is not cloned; both forms produce the same bytes.
+#[derive(Serialize, Deserialize)]
+struct Envelope<H> {
+	schema_version: u16,
+	hello: H,
+}
+
+/// Encode a hello into the versioned bincode envelope.
+///
+/// # Errors
+///
+/// Returns [`ExportError::Bincode`] if serialization fails.
+pub fn to_bincode(hello: &ClientHello<'_>) -> Result<Vec<u8>, ExportError> {
+	let envelope = Envelope {
+		schema_version: SCHEMA_VERSION,
+		hello,
+	};
+	Ok(bincode::serialize(&envelope)?)
+}
+
+/// Decode a hello from the versioned bincode envelope.
+///
+/// Byte fields of the returned hello borrow from `data`.
+///
+/// # Errors
+///
+/// Returns [`ExportError::SchemaVersion`] when the envelope was written
+/// by an incompatible schema, or [`ExportError::Bincode`] when the data
+/// is malformed.
+pub fn from_bincode(data: &[u8]) -> Result<ClientHello<'_>, ExportError> {
+	let found: u16 = bincode::deserialize(data)?;
+	if found != SCHEMA_VERSION {
+		return Err(ExportError::SchemaVersion {
+			found,
+			expected: SCHEMA_VERSION,
+		});
+	}
+	let envelope: Envelope<ClientHello<'_>> = bincode::deserialize(data)?;
+	Ok(envelope.hello)
+}
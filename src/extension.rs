@@ -1,27 +1,55 @@
 /* src/extension.rs */
 
+use alloc::string::String;
 use alloc::vec::Vec;
 
-use crate::Error;
+use crate::ech::EchClientHello;
+#[cfg(feature = "ext-key-share")]
 use crate::grease::is_grease;
-use crate::reader::Reader;
+#[cfg(feature = "ext-sni")]
+use crate::options::SniPolicy;
+#[cfg(any(
+	feature = "ext-sni",
+	feature = "ext-alpn",
+	feature = "ext-supported-versions",
+	feature = "ext-supported-groups",
+	feature = "ext-signature-algorithms",
+	feature = "ext-key-share",
+))]
+use crate::options::check_limit;
+use crate::tls_reader::Reader;
+use crate::tls_writer::Writer;
+use crate::{Diagnostics, Error, GreasePositions, ParseOptions, ReservedValues, Warning};
 
 /// A parsed TLS extension from the ClientHello message.
+///
+/// Structured variants are only produced when the matching `ext-*`
+/// cargo feature is enabled; with the feature disabled the extension is
+/// kept as [`Extension::Unknown`].
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum Extension<'a> {
 	/// Server Name Indication (type `0x0000`).
-	ServerName(Vec<ServerName<'a>>),
+	ServerName(#[cfg_attr(feature = "serde", serde(borrow))] Vec<ServerName<'a>>),
 	/// Application-Layer Protocol Negotiation (type `0x0010`).
-	Alpn(Vec<&'a [u8]>),
+	Alpn(#[cfg_attr(feature = "serde", serde(borrow))] Vec<&'a [u8]>),
 	/// Supported Versions (type `0x002b`), GREASE values excluded.
 	SupportedVersions(Vec<u16>),
 	/// Supported Groups / Named Curves (type `0x000a`), GREASE values excluded.
 	SupportedGroups(Vec<u16>),
 	/// Signature Algorithms (type `0x000d`), GREASE values excluded.
 	SignatureAlgorithms(Vec<u16>),
-	/// Key Share entry groups (type `0x0033`), GREASE values excluded.
-	KeyShareGroups(Vec<u16>),
+	/// Key Share (type `0x0033`).
+	KeyShare {
+		/// Entry groups in wire order, GREASE values excluded.
+		groups: Vec<u16>,
+		/// Every entry in wire order, GREASE included, with its key
+		/// exchange bytes. Entries whose group is accepted by
+		/// [`ParseOptions::reserved_values`] are left out.
+		#[cfg_attr(feature = "serde", serde(borrow))]
+		entries: Vec<KeyShareEntry<'a>>,
+	},
 	/// PSK Key Exchange Modes (type `0x002d`).
 	PskExchangeModes(&'a [u8]),
 	/// Renegotiation Info (type `0xff01`).
@@ -33,10 +61,281 @@ pub enum Extension<'a> {
 		/// Raw extension data.
 		data: &'a [u8],
 	},
+	/// Extension whose body failed to decode, recorded instead of
+	/// aborting the parse when
+	/// [`ParseOptions::recover_malformed_extensions`](crate::ParseOptions::recover_malformed_extensions)
+	/// is enabled.
+	Malformed {
+		/// TLS extension type identifier.
+		type_id: u16,
+		/// Why the body failed to decode, as the [`Error`] message. The
+		/// message rather than the error is kept so that the variant
+		/// survives a serde round trip.
+		error: String,
+		/// Raw extension data.
+		raw: &'a [u8],
+	},
+	/// Encrypted Client Hello (type `0xfe0d`). Bodies of an unknown
+	/// `ECHClientHello` type, or that fail to decode, are kept as
+	/// [`Extension::Unknown`].
+	EncryptedClientHello {
+		/// The decoded body.
+		#[cfg_attr(feature = "serde", serde(borrow))]
+		ech: EchClientHello<'a>,
+		/// Raw extension data.
+		raw: &'a [u8],
+	},
+}
+
+impl Extension<'_> {
+	/// Return the TLS extension type identifier.
+	#[must_use]
+	pub fn type_id(&self) -> u16 {
+		match self {
+			Self::ServerName(_) => 0x0000,
+			Self::Alpn(_) => 0x0010,
+			Self::SupportedVersions(_) => 0x002b,
+			Self::SupportedGroups(_) => 0x000a,
+			Self::SignatureAlgorithms(_) => 0x000d,
+			Self::KeyShare { .. } => 0x0033,
+			Self::PskExchangeModes(_) => 0x002d,
+			Self::RenegotiationInfo(_) => 0xff01,
+			Self::EncryptedClientHello { .. } => 0xfe0d,
+			Self::Unknown { type_id, .. } | Self::Malformed { type_id, .. } => *type_id,
+		}
+	}
+
+	/// Copy the borrowed data into an [`ExtensionOwned`] that does not
+	/// borrow the input buffer.
+	///
+	/// Keeps one extension, such as a raw ECH payload, past the life of
+	/// the buffer without cloning the whole hello.
+	///
+	/// ```
+	/// use clienthello::{Extension, ExtensionOwned};
+	///
+	/// let owned = {
+	///     let buf = vec![0xab, 0xcd];
+	///     Extension::Unknown { type_id: 0xfe0d, data: &buf }.into_owned()
+	/// };
+	/// assert_eq!(owned, ExtensionOwned::Unknown { type_id: 0xfe0d, data: vec![0xab, 0xcd] });
+	/// assert_eq!(owned.type_id(), 0xfe0d);
+	/// ```
+	#[must_use]
+	pub fn into_owned(self) -> ExtensionOwned {
+		match self {
+			Self::ServerName(names) => ExtensionOwned::ServerName(
+				names
+					.into_iter()
+					.map(|n| ServerNameOwned {
+						name_type: n.name_type,
+						name: n.name.to_vec(),
+					})
+					.collect(),
+			),
+			Self::Alpn(protocols) => {
+				ExtensionOwned::Alpn(protocols.into_iter().map(<[u8]>::to_vec).collect())
+			}
+			Self::SupportedVersions(v) => ExtensionOwned::SupportedVersions(v),
+			Self::SupportedGroups(v) => ExtensionOwned::SupportedGroups(v),
+			Self::SignatureAlgorithms(v) => ExtensionOwned::SignatureAlgorithms(v),
+			Self::KeyShare { groups, entries } => ExtensionOwned::KeyShare {
+				groups,
+				entries: entries
+					.into_iter()
+					.map(|e| KeyShareEntryOwned {
+						group: e.group,
+						key: e.key.to_vec(),
+					})
+					.collect(),
+			},
+			Self::PskExchangeModes(data) => ExtensionOwned::PskExchangeModes(data.to_vec()),
+			Self::RenegotiationInfo(data) => ExtensionOwned::RenegotiationInfo(data.to_vec()),
+			Self::EncryptedClientHello { raw, .. } => ExtensionOwned::EncryptedClientHello(raw.to_vec()),
+			Self::Unknown { type_id, data } => ExtensionOwned::Unknown {
+				type_id,
+				data: data.to_vec(),
+			},
+			Self::Malformed {
+				type_id,
+				error,
+				raw,
+			} => ExtensionOwned::Malformed {
+				type_id,
+				error,
+				raw: raw.to_vec(),
+			},
+		}
+	}
+
+	/// Return the body as captured, or re-encoded for structured
+	/// variants, which keep GREASE positions but not the GREASE values.
+	pub(crate) fn encode_body(&self) -> Vec<u8> {
+		let mut w = Writer::new();
+		let u16s = |w: &mut Writer, values: &[u16]| {
+			values.iter().for_each(|&v| w.write_u16(v));
+			Ok(())
+		};
+		let _ = match self {
+			Self::Unknown { data: raw, .. }
+			| Self::EncryptedClientHello { raw, .. }
+			| Self::Malformed { raw, .. } => return raw.to_vec(),
+			Self::ServerName(names) => w.write_vec_u16(|w| {
+				for name in names {
+					w.write_u8(name.name_type);
+					w.write_opaque_u16(name.name)?;
+				}
+				Ok(())
+			}),
+			Self::Alpn(protocols) => {
+				w.write_vec_u16(|w| protocols.iter().try_for_each(|p| w.write_opaque_u8(p)))
+			}
+			Self::SupportedVersions(v) => w.write_vec_u8(|w| u16s(w, v)),
+			Self::SupportedGroups(v) | Self::SignatureAlgorithms(v) => w.write_vec_u16(|w| u16s(w, v)),
+			Self::KeyShare { entries, .. } => w.write_vec_u16(|w| {
+				for entry in entries {
+					w.write_u16(entry.group);
+					w.write_opaque_u16(entry.key)?;
+				}
+				Ok(())
+			}),
+			Self::PskExchangeModes(data) | Self::RenegotiationInfo(data) => w.write_opaque_u8(data),
+		};
+		w.into_bytes()
+	}
+}
+
+/// The extensions of a ClientHello, in wire order with GREASE removed.
+///
+/// Iteration order is part of the API: every iterator here, and the
+/// slice itself, yields extensions in the order they appeared on the
+/// wire, duplicates included, and that will not change with the
+/// parser's internals. For output that must not depend on the client's
+/// order either, e.g. snapshots of shuffling clients, use
+/// [`sorted`](Self::sorted).
+///
+/// Dereferences to `[Extension]`, so slice methods such as `len`,
+/// `iter`, and indexing work directly; the methods here add lookups by
+/// type identifier.
+///
+/// ```
+/// use clienthello::{Extension, Extensions};
+///
+/// let exts = Extensions::from(vec![
+///     Extension::Alpn(vec![b"h2"]),
+///     Extension::Unknown { type_id: 0x0042, data: &[] },
+/// ]);
+/// assert_eq!(exts.len(), 2);
+/// assert!(exts.contains(0x0042));
+/// assert_eq!(exts.type_ids().collect::<Vec<_>>(), [0x0010, 0x0042]);
+/// assert!(matches!(exts.by_type(0x0010), Some(Extension::Alpn(_))));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Extensions<'a>(#[cfg_attr(feature = "serde", serde(borrow))] Vec<Extension<'a>>);
+
+impl<'a> Extensions<'a> {
+	/// Wrap a list of extensions.
+	#[must_use]
+	pub fn new(extensions: Vec<Extension<'a>>) -> Self {
+		Self(extensions)
+	}
+
+	/// Return the first extension with the given type identifier.
+	#[must_use]
+	pub fn by_type(&self, type_id: u16) -> Option<&Extension<'a>> {
+		self.0.iter().find(|ext| ext.type_id() == type_id)
+	}
+
+	/// Iterate every extension with the given type identifier, in wire
+	/// order. Duplicates only occur in malformed or hostile hellos.
+	pub fn all_by_type(&self, type_id: u16) -> impl Iterator<Item = &Extension<'a>> {
+		self.0.iter().filter(move |ext| ext.type_id() == type_id)
+	}
+
+	/// Check whether an extension with the given type identifier is
+	/// present.
+	#[must_use]
+	pub fn contains(&self, type_id: u16) -> bool {
+		self.by_type(type_id).is_some()
+	}
+
+	/// Iterate the extension type identifiers in wire order.
+	pub fn type_ids(&self) -> impl Iterator<Item = u16> + '_ {
+		self.0.iter().map(Extension::type_id)
+	}
+
+	/// Return the extensions sorted by type identifier, duplicates in
+	/// wire order.
+	///
+	/// The order depends only on which extensions were sent, so it stays
+	/// the same for clients that shuffle their extensions per connection.
+	#[must_use]
+	pub fn sorted(&self) -> Vec<&Extension<'a>> {
+		let mut sorted: Vec<&Extension<'a>> = self.0.iter().collect();
+		sorted.sort_by_key(|ext| ext.type_id());
+		sorted
+	}
+
+	/// Unwrap into the underlying list.
+	#[must_use]
+	pub fn into_vec(self) -> Vec<Extension<'a>> {
+		self.0
+	}
+}
+
+impl<'a> core::ops::Deref for Extensions<'a> {
+	type Target = [Extension<'a>];
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<'a> From<Vec<Extension<'a>>> for Extensions<'a> {
+	fn from(extensions: Vec<Extension<'a>>) -> Self {
+		Self(extensions)
+	}
+}
+
+impl<'a> FromIterator<Extension<'a>> for Extensions<'a> {
+	fn from_iter<I: IntoIterator<Item = Extension<'a>>>(iter: I) -> Self {
+		Self(iter.into_iter().collect())
+	}
+}
+
+impl<'s, 'a> IntoIterator for &'s Extensions<'a> {
+	type Item = &'s Extension<'a>;
+	type IntoIter = core::slice::Iter<'s, Extension<'a>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter()
+	}
+}
+
+impl<'a> IntoIterator for Extensions<'a> {
+	type Item = Extension<'a>;
+	type IntoIter = alloc::vec::IntoIter<Extension<'a>>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
+/// A single entry in the Key Share list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyShareEntry<'a> {
+	/// Named group of the key.
+	pub group: u16,
+	/// Raw key exchange bytes.
+	pub key: &'a [u8],
 }
 
 /// A single entry in the SNI (Server Name Indication) list.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ServerName<'a> {
 	/// Name type byte; `0x00` indicates a DNS hostname.
 	pub name_type: u8,
@@ -44,47 +343,301 @@ pub struct ServerName<'a> {
 	pub name: &'a [u8],
 }
 
+/// An [`Extension`] that owns its data, from [`Extension::into_owned`].
+///
+/// Variants mirror [`Extension`] with every borrowed slice copied,
+/// except that Encrypted Client Hello keeps only its raw body and
+/// [`as_extension`](Self::as_extension) decodes it again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ExtensionOwned {
+	/// Server Name Indication (type `0x0000`).
+	ServerName(Vec<ServerNameOwned>),
+	/// Application-Layer Protocol Negotiation (type `0x0010`).
+	Alpn(Vec<Vec<u8>>),
+	/// Supported Versions (type `0x002b`), GREASE values excluded.
+	SupportedVersions(Vec<u16>),
+	/// Supported Groups / Named Curves (type `0x000a`), GREASE values excluded.
+	SupportedGroups(Vec<u16>),
+	/// Signature Algorithms (type `0x000d`), GREASE values excluded.
+	SignatureAlgorithms(Vec<u16>),
+	/// Key Share (type `0x0033`).
+	KeyShare {
+		/// Entry groups in wire order, GREASE values excluded.
+		groups: Vec<u16>,
+		/// Every entry in wire order, GREASE included, with its key
+		/// exchange bytes.
+		entries: Vec<KeyShareEntryOwned>,
+	},
+	/// PSK Key Exchange Modes (type `0x002d`).
+	PskExchangeModes(Vec<u8>),
+	/// Renegotiation Info (type `0xff01`).
+	RenegotiationInfo(Vec<u8>),
+	/// Unknown or unhandled extension preserved as raw bytes.
+	Unknown {
+		/// TLS extension type identifier.
+		type_id: u16,
+		/// Raw extension data.
+		data: Vec<u8>,
+	},
+	/// Extension whose body failed to decode.
+	Malformed {
+		/// TLS extension type identifier.
+		type_id: u16,
+		/// Why the body failed to decode, as the [`Error`] message. The
+		/// message rather than the error is kept so that the variant
+		/// survives a serde round trip.
+		error: String,
+		/// Raw extension data.
+		raw: Vec<u8>,
+	},
+	/// Raw body of an Encrypted Client Hello (type `0xfe0d`).
+	EncryptedClientHello(Vec<u8>),
+}
+
+impl ExtensionOwned {
+	/// Return the TLS extension type identifier.
+	#[must_use]
+	pub fn type_id(&self) -> u16 {
+		self.as_extension().type_id()
+	}
+
+	/// Borrow as an [`Extension`], e.g. to pass to code written for
+	/// parsed hellos.
+	#[must_use]
+	pub fn as_extension(&self) -> Extension<'_> {
+		match self {
+			Self::ServerName(names) => Extension::ServerName(
+				names
+					.iter()
+					.map(|n| ServerName {
+						name_type: n.name_type,
+						name: &n.name,
+					})
+					.collect(),
+			),
+			Self::Alpn(protocols) => Extension::Alpn(protocols.iter().map(Vec::as_slice).collect()),
+			Self::SupportedVersions(v) => Extension::SupportedVersions(v.clone()),
+			Self::SupportedGroups(v) => Extension::SupportedGroups(v.clone()),
+			Self::SignatureAlgorithms(v) => Extension::SignatureAlgorithms(v.clone()),
+			Self::KeyShare { groups, entries } => Extension::KeyShare {
+				groups: groups.clone(),
+				entries: entries
+					.iter()
+					.map(|e| KeyShareEntry {
+						group: e.group,
+						key: &e.key,
+					})
+					.collect(),
+			},
+			Self::PskExchangeModes(data) => Extension::PskExchangeModes(data),
+			Self::RenegotiationInfo(data) => Extension::RenegotiationInfo(data),
+			Self::EncryptedClientHello(raw) => match EchClientHello::read(&mut Reader::new(raw)) {
+				Ok(Some(ech)) => Extension::EncryptedClientHello { ech, raw },
+				_ => Extension::Unknown {
+					type_id: 0xfe0d,
+					data: raw,
+				},
+			},
+			Self::Unknown { type_id, data } => Extension::Unknown {
+				type_id: *type_id,
+				data,
+			},
+			Self::Malformed {
+				type_id,
+				error,
+				raw,
+			} => Extension::Malformed {
+				type_id: *type_id,
+				error: error.clone(),
+				raw,
+			},
+		}
+	}
+}
+
+impl From<Extension<'_>> for ExtensionOwned {
+	fn from(extension: Extension<'_>) -> Self {
+		extension.into_owned()
+	}
+}
+
+/// A [`KeyShareEntry`] that owns its key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyShareEntryOwned {
+	/// Named group of the key.
+	pub group: u16,
+	/// Raw key exchange bytes.
+	pub key: Vec<u8>,
+}
+
+/// A [`ServerName`] that owns its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServerNameOwned {
+	/// Name type byte; `0x00` indicates a DNS hostname.
+	pub name_type: u8,
+	/// Raw name bytes.
+	pub name: Vec<u8>,
+}
+
+/// Decode one extension body.
+///
+/// Bytes left over after a structured body are reported as
+/// [`Error::TrailingBytes`] in strict mode and recorded in
+/// `diagnostics` otherwise.
+#[cfg_attr(
+	not(feature = "ext-all"),
+	allow(
+		unused_variables,
+		unused_mut,
+		unreachable_code,
+		clippy::needless_pass_by_ref_mut
+	)
+)]
 pub(crate) fn parse_extension<'a>(
 	type_id: u16,
 	data: &'a [u8],
+	opts: &ParseOptions,
 	has_grease: &mut bool,
+	grease: &mut GreasePositions,
+	reserved: &mut ReservedValues,
+	diagnostics: &mut Diagnostics,
 ) -> Result<Extension<'a>, Error> {
+	let max = opts.max_list_entries;
+	let mut r = Reader::new(data);
+	let ext = match type_id {
+		#[cfg(feature = "ext-sni")]
+		0x0000 => parse_sni(&mut r, opts, diagnostics)?,
+		#[cfg(feature = "ext-supported-groups")]
+		0x000a => Extension::SupportedGroups(parse_u16_list_filtered(
+			&mut r,
+			opts,
+			has_grease,
+			&mut grease.supported_groups,
+			&mut reserved.supported_groups,
+		)?),
+		#[cfg(feature = "ext-signature-algorithms")]
+		0x000d => parse_sig_algs(&mut r, opts, has_grease, &mut reserved.signature_algorithms)?,
+		#[cfg(feature = "ext-alpn")]
+		0x0010 => parse_alpn(&mut r, max)?,
+		#[cfg(feature = "ext-supported-versions")]
+		0x002b => parse_supported_versions(
+			&mut r,
+			opts,
+			has_grease,
+			&mut grease.supported_versions,
+			&mut reserved.supported_versions,
+		)?,
+		#[cfg(feature = "ext-psk-modes")]
+		0x002d => parse_psk_modes(&mut r)?,
+		#[cfg(feature = "ext-key-share")]
+		0x0033 => parse_key_share(
+			&mut r,
+			opts,
+			has_grease,
+			&mut grease.key_share,
+			&mut reserved.key_share,
+			diagnostics,
+		)?,
+		#[cfg(feature = "ext-renegotiation-info")]
+		0xff01 => parse_renegotiation_info(&mut r)?,
+		#[cfg(feature = "ext-ech")]
+		0xfe0d => match EchClientHello::read(&mut r) {
+			Ok(Some(ech)) => Extension::EncryptedClientHello { ech, raw: data },
+			Err(e) if opts.recover_malformed_extensions => return Err(e),
+			// A body this crate cannot decode parsed fine before ECH was
+			// decoded, so it must not fail the hello now.
+			Ok(None) | Err(_) => {
+				return Ok(Extension::Unknown {
+					type_id,
+					data: opts.unknown_extensions.apply(data),
+				});
+			}
+		},
+		_ => {
+			return Ok(Extension::Unknown {
+				type_id,
+				data: opts.unknown_extensions.apply(data),
+			});
+		}
+	};
+	let len = r.remaining();
+	if len > 0 {
+		if opts.strict_extension_bodies {
+			return Err(Error::TrailingBytes { type_id, len });
+		}
+		diagnostics.trailing_bytes.push(type_id);
+		diagnostics
+			.warnings
+			.push(Warning::TrailingBytes { type_id, len });
+	}
+	if declares_empty_list(type_id, data) {
+		diagnostics.warnings.push(Warning::EmptyList { type_id });
+	}
+	Ok(ext)
+}
+
+/// Check whether a decoded body declares an empty list where the RFCs
+/// require at least one entry. An empty key share list is valid: it
+/// asks the server for a HelloRetryRequest.
+fn declares_empty_list(type_id: u16, data: &[u8]) -> bool {
 	match type_id {
-		0x0000 => parse_sni(data),
-		0x000a => parse_groups(data, has_grease),
-		0x000d => parse_sig_algs(data, has_grease),
-		0x0010 => parse_alpn(data),
-		0x002b => parse_supported_versions(data, has_grease),
-		0x002d => parse_psk_modes(data),
-		0x0033 => parse_key_share(data, has_grease),
-		0xff01 => parse_renegotiation_info(data),
-		_ => Ok(Extension::Unknown { type_id, data }),
+		0x0000 | 0x000a | 0x000d | 0x0010 => data.starts_with(&[0x00, 0x00]),
+		0x002b | 0x002d => data.first() == Some(&0x00),
+		_ => false,
 	}
 }
 
-fn parse_sni<'a>(data: &'a [u8]) -> Result<Extension<'a>, Error> {
-	let mut r = Reader::new(data);
+#[cfg(feature = "ext-sni")]
+fn parse_sni<'a>(
+	r: &mut Reader<'a>,
+	opts: &ParseOptions,
+	diagnostics: &mut Diagnostics,
+) -> Result<Extension<'a>, Error> {
 	let list_len = r.read_u16("SNI list length")? as usize;
 	let list_data = r.read_bytes(list_len, "SNI list data")?;
 	let mut inner = Reader::new(list_data);
 	let mut names = Vec::new();
 	while inner.remaining() > 0 {
+		check_limit(names.len() + 1, opts.max_list_entries, "SNI list")?;
 		let name_type = inner.read_u8("SNI name type")?;
 		let name_len = inner.read_u16("SNI name length")? as usize;
 		let name = inner.read_bytes(name_len, "SNI name")?;
 		names.push(ServerName { name_type, name });
 	}
+	let count = names.iter().filter(|sn| sn.name_type == 0x00).count();
+	if count > 1 {
+		let keep = match opts.sni_policy {
+			SniPolicy::First => 0,
+			SniPolicy::Last => count - 1,
+			SniPolicy::Reject => return Err(Error::MultipleServerNames { count }),
+		};
+		let mut seen = 0;
+		names.retain(|sn| {
+			if sn.name_type != 0x00 {
+				return true;
+			}
+			seen += 1;
+			seen - 1 == keep
+		});
+		diagnostics.multiple_server_names = true;
+		diagnostics
+			.warnings
+			.push(Warning::MultipleServerNames { count });
+	}
 	Ok(Extension::ServerName(names))
 }
 
-fn parse_groups<'a>(data: &'a [u8], has_grease: &mut bool) -> Result<Extension<'a>, Error> {
-	Ok(Extension::SupportedGroups(parse_u16_list_filtered(
-		data, has_grease,
-	)?))
-}
-
-fn parse_sig_algs<'a>(data: &'a [u8], has_grease: &mut bool) -> Result<Extension<'a>, Error> {
-	let mut r = Reader::new(data);
+#[cfg(feature = "ext-signature-algorithms")]
+fn parse_sig_algs<'a>(
+	r: &mut Reader<'a>,
+	opts: &ParseOptions,
+	has_grease: &mut bool,
+	reserved: &mut Vec<(usize, u16)>,
+) -> Result<Extension<'a>, Error> {
 	let list_len = r.read_u16("signature algorithms length")? as usize;
 	if !list_len.is_multiple_of(2) {
 		return Err(Error::Truncated {
@@ -92,26 +645,28 @@ fn parse_sig_algs<'a>(data: &'a [u8], has_grease: &mut bool) -> Result<Extension
 		});
 	}
 	let list_data = r.read_bytes(list_len, "signature algorithms data")?;
+	check_limit(list_len / 2, opts.max_list_entries, "signature algorithms")?;
 	let mut inner = Reader::new(list_data);
-	let mut algs = Vec::new();
+	let mut algs = Vec::with_capacity(list_len / 2);
+	let mut grease = Vec::new();
 	while inner.remaining() >= 2 {
 		let val = inner.read_u16("signature algorithm")?;
-		if is_grease(val) {
-			*has_grease = true;
-		} else {
+		// GREASE positions are not tracked for signature algorithms.
+		if !opts.filter(val, algs.len(), &mut grease, reserved, has_grease) {
 			algs.push(val);
 		}
 	}
 	Ok(Extension::SignatureAlgorithms(algs))
 }
 
-fn parse_alpn<'a>(data: &'a [u8]) -> Result<Extension<'a>, Error> {
-	let mut r = Reader::new(data);
+#[cfg(feature = "ext-alpn")]
+fn parse_alpn<'a>(r: &mut Reader<'a>, max: usize) -> Result<Extension<'a>, Error> {
 	let list_len = r.read_u16("ALPN list length")? as usize;
 	let list_data = r.read_bytes(list_len, "ALPN list data")?;
 	let mut inner = Reader::new(list_data);
 	let mut protocols = Vec::new();
 	while inner.remaining() > 0 {
+		check_limit(protocols.len() + 1, max, "ALPN list")?;
 		let proto_len = inner.read_u8("ALPN protocol length")? as usize;
 		let proto = inner.read_bytes(proto_len, "ALPN protocol")?;
 		protocols.push(proto);
@@ -119,11 +674,14 @@ fn parse_alpn<'a>(data: &'a [u8]) -> Result<Extension<'a>, Error> {
 	Ok(Extension::Alpn(protocols))
 }
 
+#[cfg(feature = "ext-supported-versions")]
 fn parse_supported_versions<'a>(
-	data: &'a [u8],
+	r: &mut Reader<'a>,
+	opts: &ParseOptions,
 	has_grease: &mut bool,
+	grease: &mut Vec<usize>,
+	reserved: &mut Vec<(usize, u16)>,
 ) -> Result<Extension<'a>, Error> {
-	let mut r = Reader::new(data);
 	// RFC 8446 §4.2.1: length is a single byte (unlike most TLS length fields).
 	let list_len = r.read_u8("supported versions length")? as usize;
 	if !list_len.is_multiple_of(2) {
@@ -132,54 +690,83 @@ fn parse_supported_versions<'a>(
 		});
 	}
 	let list_data = r.read_bytes(list_len, "supported versions data")?;
+	check_limit(list_len / 2, opts.max_list_entries, "supported versions")?;
 	let mut inner = Reader::new(list_data);
-	let mut versions = Vec::new();
+	let mut versions = Vec::with_capacity(list_len / 2);
 	while inner.remaining() >= 2 {
 		let ver = inner.read_u16("supported version")?;
-		if is_grease(ver) {
-			*has_grease = true;
-		} else {
+		if !opts.filter(ver, versions.len(), grease, reserved, has_grease) {
 			versions.push(ver);
 		}
 	}
 	Ok(Extension::SupportedVersions(versions))
 }
 
-fn parse_psk_modes(data: &[u8]) -> Result<Extension<'_>, Error> {
-	let mut r = Reader::new(data);
+#[cfg(feature = "ext-psk-modes")]
+fn parse_psk_modes<'a>(r: &mut Reader<'a>) -> Result<Extension<'a>, Error> {
 	let list_len = r.read_u8("PSK modes length")? as usize;
 	let list_data = r.read_bytes(list_len, "PSK modes data")?;
 	Ok(Extension::PskExchangeModes(list_data))
 }
 
-fn parse_renegotiation_info(data: &[u8]) -> Result<Extension<'_>, Error> {
-	let mut r = Reader::new(data);
+#[cfg(feature = "ext-renegotiation-info")]
+fn parse_renegotiation_info<'a>(r: &mut Reader<'a>) -> Result<Extension<'a>, Error> {
 	let info_len = r.read_u8("renegotiation info length")? as usize;
 	let info_data = r.read_bytes(info_len, "renegotiation info data")?;
 	Ok(Extension::RenegotiationInfo(info_data))
 }
 
-fn parse_key_share<'a>(data: &'a [u8], has_grease: &mut bool) -> Result<Extension<'a>, Error> {
-	let mut r = Reader::new(data);
+#[cfg(feature = "ext-key-share")]
+fn parse_key_share<'a>(
+	r: &mut Reader<'a>,
+	opts: &ParseOptions,
+	has_grease: &mut bool,
+	grease: &mut Vec<usize>,
+	reserved: &mut Vec<(usize, u16)>,
+	diagnostics: &mut Diagnostics,
+) -> Result<Extension<'a>, Error> {
 	let list_len = r.read_u16("key share list length")? as usize;
 	let list_data = r.read_bytes(list_len, "key share list data")?;
 	let mut inner = Reader::new(list_data);
 	let mut groups = Vec::new();
+	let mut entries = Vec::new();
 	while inner.remaining() >= 4 {
+		check_limit(
+			entries.len() + reserved.len() + 1,
+			opts.max_list_entries,
+			"key share list",
+		)?;
 		let group = inner.read_u16("key share group")?;
 		let key_len = inner.read_u16("key share key length")? as usize;
-		let _key = inner.read_bytes(key_len, "key share key data")?;
-		if is_grease(group) {
-			*has_grease = true;
-		} else {
+		let key = inner.read_bytes(key_len, "key share key data")?;
+		// `entries` keeps GREASE, so GREASE shares count as kept here.
+		let kept = entries.len() - grease.len();
+		let filtered = opts.filter(group, kept, grease, reserved, has_grease);
+		if filtered && !is_grease(group) {
+			continue;
+		}
+		if !filtered {
 			groups.push(group);
 		}
+		entries.push(KeyShareEntry { group, key });
 	}
-	Ok(Extension::KeyShareGroups(groups))
+	if !inner.is_empty() {
+		diagnostics.warnings.push(Warning::PartialListEntry {
+			type_id: Some(0x0033),
+			len: inner.remaining(),
+		});
+	}
+	Ok(Extension::KeyShare { groups, entries })
 }
 
-fn parse_u16_list_filtered(data: &[u8], has_grease: &mut bool) -> Result<Vec<u16>, Error> {
-	let mut r = Reader::new(data);
+#[cfg(feature = "ext-supported-groups")]
+fn parse_u16_list_filtered(
+	r: &mut Reader<'_>,
+	opts: &ParseOptions,
+	has_grease: &mut bool,
+	grease: &mut Vec<usize>,
+	reserved: &mut Vec<(usize, u16)>,
+) -> Result<Vec<u16>, Error> {
 	let list_len = r.read_u16("u16 list length")? as usize;
 	if !list_len.is_multiple_of(2) {
 		return Err(Error::Truncated {
@@ -187,13 +774,12 @@ fn parse_u16_list_filtered(data: &[u8], has_grease: &mut bool) -> Result<Vec<u16
 		});
 	}
 	let list_data = r.read_bytes(list_len, "u16 list data")?;
+	check_limit(list_len / 2, opts.max_list_entries, "u16 list")?;
 	let mut inner = Reader::new(list_data);
-	let mut values = Vec::new();
+	let mut values = Vec::with_capacity(list_len / 2);
 	while inner.remaining() >= 2 {
 		let val = inner.read_u16("u16 list entry")?;
-		if is_grease(val) {
-			*has_grease = true;
-		} else {
+		if !opts.filter(val, values.len(), grease, reserved, has_grease) {
 			values.push(val);
 		}
 	}
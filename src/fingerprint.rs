@@ -0,0 +1,293 @@
+/* src/fingerprint.rs */
+
+#[cfg(feature = "peetprint")]
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "peetprint")]
+use core::fmt::Write;
+
+#[cfg(feature = "peetprint")]
+use crate::consts::EXT_COMPRESS_CERTIFICATE;
+use crate::consts::{EXT_PRE_SHARED_KEY, HANDSHAKE_TYPE_CLIENT_HELLO, RANDOM_LEN};
+#[cfg(feature = "peetprint")]
+use crate::grease::interleave;
+use crate::tls_reader::Reader;
+use crate::tls_writer::Writer;
+use crate::{ClientHello, Error, Extension};
+
+/// Extensions a client sends on some connections but not others:
+/// padding depends on the SNI length, and the PSK and early data only
+/// appear on resumption.
+pub(crate) const PER_CONNECTION_EXTENSIONS: [u16; 3] = [0x0015, 0x0029, 0x002a];
+
+/// Encode the fingerprint-relevant fields of a hello.
+///
+/// The encoding covers the legacy version, cipher suites, extension
+/// type order, supported versions, supported groups, signature
+/// algorithms, and ALPN identifiers, each as a TLS-style length-prefixed
+/// vector. Per-connection values (random, session ID, key material,
+/// SNI) are left out so that repeated connections from the same client
+/// stack encode identically.
+#[cfg(feature = "digest")]
+pub(crate) fn canonical_input(hello: &ClientHello<'_>) -> Result<Vec<u8>, Error> {
+	let types: Vec<u16> = hello.extensions.type_ids().collect();
+	encode(hello, &types)
+}
+
+/// Encode the fields of [`canonical_input`] with the extension types
+/// sorted, and without the extensions that come and go between
+/// connections, so hellos whose extension order is shuffled encode
+/// identically.
+pub(crate) fn order_insensitive_input(hello: &ClientHello<'_>) -> Result<Vec<u8>, Error> {
+	let mut types: Vec<u16> = hello
+		.extensions
+		.type_ids()
+		.filter(|t| !PER_CONNECTION_EXTENSIONS.contains(t))
+		.collect();
+	types.sort_unstable();
+	encode(hello, &types)
+}
+
+fn encode(hello: &ClientHello<'_>, types: &[u16]) -> Result<Vec<u8>, Error> {
+	let mut w = Writer::new();
+	w.write_u16(hello.legacy_version);
+	write_u16_vec(&mut w, &hello.cipher_suites)?;
+	write_u16_vec(&mut w, types)?;
+	let mut versions: &[u16] = &[];
+	let mut groups: &[u16] = &[];
+	let mut sig_algs: &[u16] = &[];
+	let mut alpn: &[&[u8]] = &[];
+	for ext in &hello.extensions {
+		match ext {
+			Extension::SupportedVersions(v) => versions = v,
+			Extension::SupportedGroups(v) => groups = v,
+			Extension::SignatureAlgorithms(v) => sig_algs = v,
+			Extension::Alpn(v) => alpn = v,
+			_ => {}
+		}
+	}
+	write_u16_vec(&mut w, versions)?;
+	write_u16_vec(&mut w, groups)?;
+	write_u16_vec(&mut w, sig_algs)?;
+	w.write_vec_u16(|w| {
+		for proto in alpn {
+			w.write_vec_u8(|w| {
+				w.write_bytes(proto);
+				Ok(())
+			})?;
+		}
+		Ok(())
+	})?;
+	Ok(w.into_bytes())
+}
+
+fn write_u16_vec(w: &mut Writer, values: &[u16]) -> Result<(), Error> {
+	w.write_vec_u16(|w| {
+		values.iter().for_each(|&v| w.write_u16(v));
+		Ok(())
+	})
+}
+
+/// Encode a hello as a ClientHello handshake message with GREASE
+/// removed, extensions sorted by type, and the random, session ID, key
+/// share payloads, and PSK binders zeroed.
+pub(crate) fn canonical(hello: &ClientHello<'_>) -> Result<Vec<u8>, Error> {
+	let mut extensions: Vec<&Extension<'_>> = hello.extensions.iter().collect();
+	// Stable, so repeated extensions keep their wire order.
+	extensions.sort_by_key(|ext| ext.type_id());
+	let mut w = Writer::new();
+	w.write_u8(HANDSHAKE_TYPE_CLIENT_HELLO);
+	w.write_vec_u24(|w| {
+		w.write_u16(hello.legacy_version);
+		w.write_bytes(&[0; RANDOM_LEN]);
+		w.write_vec_u8(|w| {
+			hello.session_id.iter().for_each(|_| w.write_u8(0));
+			Ok(())
+		})?;
+		write_u16_vec(w, &hello.cipher_suites)?;
+		w.write_opaque_u8(hello.compression_methods)?;
+		if extensions.is_empty() {
+			return Ok(());
+		}
+		w.write_vec_u16(|w| {
+			for ext in &extensions {
+				w.write_u16(ext.type_id());
+				w.write_opaque_u16(&canonical_body(ext, &hello.grease.key_share))?;
+			}
+			Ok(())
+		})
+	})?;
+	Ok(w.into_bytes())
+}
+
+/// Extension body with the key shares at the GREASE `key_share_grease`
+/// positions dropped and key exchange values and PSK binders zeroed.
+fn canonical_body(ext: &Extension<'_>, key_share_grease: &[usize]) -> Vec<u8> {
+	match ext {
+		Extension::KeyShare { entries, .. } => {
+			let mut w = Writer::new();
+			let _ = w.write_vec_u16(|w| {
+				let kept = entries
+					.iter()
+					.enumerate()
+					.filter(|(i, _)| !key_share_grease.contains(i));
+				for (_, entry) in kept {
+					w.write_u16(entry.group);
+					w.write_vec_u16(|w| {
+						entry.key.iter().for_each(|_| w.write_u8(0));
+						Ok(())
+					})?;
+				}
+				Ok(())
+			});
+			w.into_bytes()
+		}
+		Extension::Unknown {
+			type_id: EXT_PRE_SHARED_KEY,
+			data,
+		} => {
+			let mut body = data.to_vec();
+			zero_binders(&mut body);
+			body
+		}
+		_ => ext.encode_body(),
+	}
+}
+
+/// Zero each binder of a `pre_shared_key` body, keeping the length
+/// prefixes; a body that does not decode is left as is.
+fn zero_binders(body: &mut [u8]) {
+	let mut r = Reader::new(body);
+	let Ok(identities) = r.read_vec_u16("PSK identities") else {
+		return;
+	};
+	let mut pos = 2 + identities.len() + 2;
+	let Ok(binders) = r.read_vec_u16("PSK binders") else {
+		return;
+	};
+	let mut r = Reader::new(binders);
+	let mut spans = Vec::new();
+	while let Ok(binder) = r.read_vec_u8("PSK binder") {
+		spans.push(pos + 1..pos + 1 + binder.len());
+		pos += 1 + binder.len();
+	}
+	for span in spans {
+		body[span].fill(0);
+	}
+}
+
+#[cfg(feature = "digest")]
+pub(crate) fn fingerprint_with<D: digest::Digest>(hello: &ClientHello<'_>) -> digest::Output<D> {
+	// Every list in a parsed hello came from a length-prefixed field of
+	// the same width, so re-encoding cannot overflow.
+	let input = canonical_input(hello).unwrap_or_default();
+	D::digest(input)
+}
+
+/// Build the peetprint string of tls.peet.ws:
+/// `versions|alpn|groups|sig_algs|psk_modes|cert_compression|ciphers|extensions`.
+///
+/// Lists are decimal and joined with `-`, GREASE values are kept in
+/// place as `GREASE`, and the extension ids are sorted as strings.
+#[cfg(feature = "peetprint")]
+pub(crate) fn peetprint(hello: &ClientHello<'_>) -> String {
+	let grease = &hello.grease;
+	let mut out = String::new();
+	push_greased(
+		&mut out,
+		hello.supported_versions(),
+		&grease.supported_versions,
+	);
+	out.push('|');
+	for (i, proto) in hello.alpn_protocols().iter().enumerate() {
+		if i > 0 {
+			out.push('-');
+		}
+		let proto = String::from_utf8_lossy(proto);
+		// `h2` and `http/1.1` are listed as `2` and `1.1`.
+		let short = proto.strip_prefix("http/").or_else(|| {
+			proto
+				.strip_prefix('h')
+				.filter(|v| v.bytes().all(|b| b.is_ascii_digit()))
+		});
+		out.push_str(short.unwrap_or(&proto));
+	}
+	out.push('|');
+	push_greased(&mut out, hello.supported_groups(), &grease.supported_groups);
+	out.push('|');
+	push_greased(&mut out, hello.signature_algorithms(), &[]);
+	out.push('|');
+	let modes = hello.extensions.iter().find_map(|ext| match ext {
+		Extension::PskExchangeModes(modes) => Some(*modes),
+		_ => None,
+	});
+	push_joined(
+		&mut out,
+		modes
+			.unwrap_or_default()
+			.iter()
+			.map(|&m| Some(u16::from(m))),
+	);
+	out.push('|');
+	push_joined(&mut out, cert_compression_algorithms(hello).map(Some));
+	out.push('|');
+	push_greased(&mut out, &hello.cipher_suites, &grease.cipher_suites);
+	out.push('|');
+	let mut extensions: Vec<String> = hello
+		.extensions
+		.type_ids()
+		.map(|t| alloc::format!("{t}"))
+		.chain(grease.extensions.iter().map(|_| String::from("GREASE")))
+		.collect();
+	extensions.sort_unstable();
+	out.push_str(&extensions.join("-"));
+	out
+}
+
+/// Lowercase hex MD5 of [`peetprint`], the `peetprint_hash` of
+/// tls.peet.ws.
+#[cfg(feature = "peetprint")]
+pub(crate) fn peetprint_hash(hello: &ClientHello<'_>) -> String {
+	use md5::{Digest, Md5};
+
+	let digest = Md5::digest(peetprint(hello).as_bytes());
+	let mut out = String::with_capacity(32);
+	for b in digest {
+		let _ = write!(out, "{b:02x}");
+	}
+	out
+}
+
+#[cfg(feature = "peetprint")]
+fn push_greased(out: &mut String, values: &[u16], positions: &[usize]) {
+	push_joined(out, interleave(values.iter().copied(), positions));
+}
+
+/// Join values with `-`, writing `None` as `GREASE`.
+#[cfg(feature = "peetprint")]
+fn push_joined(out: &mut String, values: impl Iterator<Item = Option<u16>>) {
+	for (i, v) in values.enumerate() {
+		if i > 0 {
+			out.push('-');
+		}
+		match v {
+			Some(v) => {
+				let _ = write!(out, "{v}");
+			}
+			None => out.push_str("GREASE"),
+		}
+	}
+}
+
+/// Algorithms listed in `compress_certificate` (RFC 8879), which this
+/// crate keeps as raw bytes.
+#[cfg(feature = "peetprint")]
+fn cert_compression_algorithms<'h>(hello: &'h ClientHello<'_>) -> impl Iterator<Item = u16> + 'h {
+	let list = hello
+		.find_extension(EXT_COMPRESS_CERTIFICATE)
+		.and_then(|body| Reader::new(body).read_vec_u8("compression algorithms").ok())
+		.unwrap_or_default();
+	list
+		.chunks_exact(2)
+		.map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+}
@@ -0,0 +1,227 @@
+/* src/fingerprints.rs */
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::consts::{
+	EXT_ALPN, EXT_EC_POINT_FORMATS, EXT_SIGNATURE_ALGORITHMS, EXT_SUPPORTED_GROUPS,
+	EXT_SUPPORTED_VERSIONS,
+};
+use crate::tls_reader::Reader;
+use crate::{Error, Ja3Version, Ja4Version, Transport, framing, is_grease, ja3, ja4};
+
+/// JA3, JA4, and extension order fingerprints of a ClientHello,
+/// computed by [`fingerprints`] without a full parse.
+///
+/// Each field equals what the matching [`ClientHello`](crate::ClientHello)
+/// method returns for the same message, so JA3 and JA4 are likewise
+/// pinned to [`Ja3Version::V1`] and [`Ja4Version::V1`]; use
+/// [`fingerprints_with`] to pick the revisions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fingerprints {
+	/// JA3 string, as [`ClientHello::ja3`](crate::ClientHello::ja3).
+	pub ja3: String,
+	/// JA3 MD5 hash, as
+	/// [`ClientHello::ja3_hash`](crate::ClientHello::ja3_hash).
+	pub ja3_hash: String,
+	/// JA4 fingerprint, as [`ClientHello::ja4`](crate::ClientHello::ja4).
+	pub ja4: String,
+	/// Hash of the extension order, as
+	/// [`ClientHello::extension_order_hash`](crate::ClientHello::extension_order_hash).
+	pub extension_order_hash: String,
+}
+
+/// Compute the fingerprints of a raw Handshake ClientHello in one pass.
+///
+/// For sensors whose only output is fingerprints: the cipher suites and
+/// the handful of extensions the fingerprints cover are read straight
+/// from `data` into flat lists, and every other extension is skipped
+/// undecoded. That also means a hello whose other extensions are
+/// malformed still gets fingerprints, where [`parse`](crate::parse)
+/// would reject it.
+///
+/// # Errors
+///
+/// Returns an error when the handshake header is invalid, a field is
+/// truncated, or a list the fingerprints cover has an odd length.
+///
+/// ```
+/// let msg = clienthello::Builder::new()
+///     .server_name("example.com")
+///     .alpn(&[b"h2"])
+///     .build()
+///     .unwrap();
+/// let fp = clienthello::fingerprints(&msg).unwrap();
+/// let hello = clienthello::parse(&msg).unwrap();
+/// assert_eq!(fp.ja3_hash, hello.ja3_hash());
+/// assert_eq!(fp.ja4, hello.ja4());
+/// ```
+pub fn fingerprints(data: &[u8]) -> Result<Fingerprints, Error> {
+	fingerprints_with(data, Ja3Version::V1, Ja4Version::V1)
+}
+
+/// Compute the fingerprints of a raw Handshake ClientHello as revisions
+/// `ja3` and `ja4` of the algorithms define them.
+///
+/// See [`fingerprints`], which is pinned to [`Ja3Version::V1`] and
+/// [`Ja4Version::V1`].
+///
+/// # Errors
+///
+/// Returns the errors of [`fingerprints`].
+pub fn fingerprints_with(
+	data: &[u8],
+	ja3: Ja3Version,
+	ja4: Ja4Version,
+) -> Result<Fingerprints, Error> {
+	fingerprint_body(
+		framing::client_hello_body(data)?,
+		Transport::Handshake,
+		ja3,
+		ja4,
+	)
+}
+
+/// Compute the fingerprints of a TLS record containing a ClientHello in
+/// one pass.
+///
+/// See [`fingerprints`]. The ClientHello must fit in the first record.
+///
+/// # Errors
+///
+/// Returns an error when the record layer is invalid, plus the errors of
+/// [`fingerprints`].
+pub fn fingerprints_from_record(data: &[u8]) -> Result<Fingerprints, Error> {
+	fingerprints_from_record_with(data, Ja3Version::V1, Ja4Version::V1)
+}
+
+/// Compute the fingerprints of a TLS record containing a ClientHello as
+/// revisions `ja3` and `ja4` of the algorithms define them.
+///
+/// See [`fingerprints_with`] and [`fingerprints_from_record`].
+///
+/// # Errors
+///
+/// Returns the errors of [`fingerprints_from_record`].
+pub fn fingerprints_from_record_with(
+	data: &[u8],
+	ja3: Ja3Version,
+	ja4: Ja4Version,
+) -> Result<Fingerprints, Error> {
+	let body = framing::client_hello_body(framing::record_payload(data)?)?;
+	fingerprint_body(body, Transport::TlsRecord, ja3, ja4)
+}
+
+fn fingerprint_body(
+	body: &[u8],
+	transport: Transport,
+	ja3_version: Ja3Version,
+	ja4_version: Ja4Version,
+) -> Result<Fingerprints, Error> {
+	let mut r = Reader::new(body);
+	let legacy_version = r.read_u16("legacy version")?;
+	r.read_bytes(32, "client random")?;
+	r.read_vec_u8("session ID")?;
+	let cipher_suites = non_grease(
+		r.read_vec_u16("cipher suites data")?,
+		"cipher suites (odd length)",
+	)?;
+	r.read_vec_u8("compression methods")?;
+
+	let mut extensions = Vec::new();
+	let mut versions: Option<Vec<u16>> = None;
+	let mut groups: Option<Vec<u16>> = None;
+	let mut sig_algs: Option<Vec<u16>> = None;
+	let mut formats: Option<&[u8]> = None;
+	let mut alpn: Option<Option<&[u8]>> = None;
+	if r.remaining() >= 2 {
+		let mut exts = Reader::new(r.read_vec_u16("extensions data")?);
+		while exts.remaining() >= 4 {
+			let type_id = exts.read_u16("extension type")?;
+			let ext_body = exts.read_vec_u16("extension body")?;
+			if is_grease(type_id) {
+				continue;
+			}
+			extensions.push(type_id);
+			// Only the first extension of each type counts, as with the
+			// `ClientHello` accessors.
+			match type_id {
+				EXT_SUPPORTED_VERSIONS if versions.is_none() => {
+					let list = Reader::new(ext_body).read_vec_u8("supported versions data")?;
+					versions = Some(non_grease(list, "supported versions (odd length)")?);
+				}
+				EXT_SUPPORTED_GROUPS if groups.is_none() => {
+					let list = Reader::new(ext_body).read_vec_u16("u16 list data")?;
+					groups = Some(non_grease(list, "u16 list (odd length)")?);
+				}
+				EXT_SIGNATURE_ALGORITHMS if sig_algs.is_none() => {
+					let list = Reader::new(ext_body).read_vec_u16("signature algorithms data")?;
+					sig_algs = Some(non_grease(list, "signature algorithms (odd length)")?);
+				}
+				EXT_EC_POINT_FORMATS if formats.is_none() => {
+					formats = Some(match ext_body.split_first() {
+						Some((&len, rest)) => rest.get(..usize::from(len)).unwrap_or(rest),
+						None => &[],
+					});
+				}
+				EXT_ALPN if alpn.is_none() => {
+					let mut list = Reader::new(Reader::new(ext_body).read_vec_u16("ALPN list data")?);
+					alpn = Some(if list.is_empty() {
+						None
+					} else {
+						Some(list.read_vec_u8("ALPN protocol")?)
+					});
+				}
+				_ => {}
+			}
+		}
+	}
+
+	let groups = groups.unwrap_or_default();
+	let ja3 = match ja3_version {
+		Ja3Version::V1 => ja3::build(
+			legacy_version,
+			&cipher_suites,
+			extensions.iter().copied(),
+			&groups,
+			formats.unwrap_or_default(),
+		),
+	};
+	let fields = ja4::Fields {
+		transport,
+		version: versions
+			.unwrap_or_default()
+			.into_iter()
+			.max()
+			.unwrap_or(legacy_version),
+		cipher_suites: &cipher_suites,
+		extensions: &extensions,
+		alpn: alpn.flatten(),
+		signature_algorithms: sig_algs.as_deref().unwrap_or_default(),
+	};
+	let ja4 = match ja4_version {
+		Ja4Version::V1 => ja4::hashed(&fields),
+	};
+	Ok(Fingerprints {
+		ja3_hash: ja3::md5_hex(&ja3),
+		ja3,
+		ja4,
+		extension_order_hash: ja4::extension_order_hash_with(&extensions, ja4_version),
+	})
+}
+
+/// Decode a list of big-endian `u16` values, dropping GREASE.
+fn non_grease(list: &[u8], odd_length: &'static str) -> Result<Vec<u16>, Error> {
+	if !list.len().is_multiple_of(2) {
+		return Err(Error::Truncated { field: odd_length });
+	}
+	Ok(
+		list
+			.chunks_exact(2)
+			.filter_map(|c| <[u8; 2]>::try_from(c).ok())
+			.map(u16::from_be_bytes)
+			.filter(|&v| !is_grease(v))
+			.collect(),
+	)
+}
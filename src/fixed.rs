@@ -0,0 +1,189 @@
+/* src/fixed.rs */
+
+use heapless::Vec;
+
+use crate::tls_reader::Reader;
+use crate::{Error, framing, is_grease, sni};
+
+/// An extension kept as its type identifier and raw body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawExtension<'a> {
+	/// TLS extension type identifier.
+	pub type_id: u16,
+	/// Raw extension data.
+	pub data: &'a [u8],
+}
+
+/// ClientHello parsed into fixed-capacity storage, for targets without
+/// an allocator.
+///
+/// Holds up to `N_EXT` extensions and `N_CS` cipher suites, GREASE
+/// values excluded. Entries beyond those bounds are dropped and
+/// reported through [`extensions_overflowed`](Self::extensions_overflowed)
+/// and [`cipher_suites_overflowed`](Self::cipher_suites_overflowed)
+/// rather than failing the parse. Extensions are kept raw; the accessors
+/// decode SNI, ALPN, and Supported Versions on demand.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x3b, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x10, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x0a, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x07]);
+/// #     d.extend_from_slice(b"example");
+/// #     d
+/// # }
+/// # let msg = build();
+/// use clienthello::ClientHelloFixed;
+///
+/// let hello = ClientHelloFixed::<16, 32>::parse(&msg).unwrap();
+/// assert_eq!(hello.cipher_suites, [0x1301]);
+/// assert_eq!(hello.server_name(), Some("example"));
+/// assert!(!hello.extensions_overflowed);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientHelloFixed<'a, const N_EXT: usize, const N_CS: usize> {
+	/// Legacy protocol version (usually `0x0303` for TLS 1.2).
+	pub legacy_version: u16,
+	/// 32-byte client random.
+	pub random: &'a [u8],
+	/// Session ID (may be empty).
+	pub session_id: &'a [u8],
+	/// Cipher suite identifiers with GREASE values removed.
+	pub cipher_suites: Vec<u16, N_CS>,
+	/// Compression method bytes.
+	pub compression_methods: &'a [u8],
+	/// Extensions in wire order with GREASE types removed.
+	pub extensions: Vec<RawExtension<'a>, N_EXT>,
+	/// Set to `true` when any GREASE cipher suite or extension type was
+	/// encountered.
+	pub has_grease: bool,
+	/// Set when more than `N_EXT` extensions were sent.
+	pub extensions_overflowed: bool,
+	/// Set when more than `N_CS` cipher suites were sent.
+	pub cipher_suites_overflowed: bool,
+}
+
+impl<'a, const N_EXT: usize, const N_CS: usize> ClientHelloFixed<'a, N_EXT, N_CS> {
+	/// Parse a raw Handshake ClientHello.
+	///
+	/// # Errors
+	///
+	/// Returns an error when the handshake header is invalid or a field
+	/// is truncated. Exceeding a capacity bound is not an error.
+	pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+		Self::parse_body(framing::client_hello_body(data)?)
+	}
+
+	/// Parse a TLS record containing a ClientHello.
+	///
+	/// # Errors
+	///
+	/// Returns an error when the record or handshake header is invalid or
+	/// a field is truncated.
+	pub fn parse_from_record(data: &'a [u8]) -> Result<Self, Error> {
+		Self::parse(framing::record_payload(data)?)
+	}
+
+	fn parse_body(body: &'a [u8]) -> Result<Self, Error> {
+		let mut r = Reader::new(body);
+		let legacy_version = r.read_u16("legacy version")?;
+		let random = r.read_bytes(32, "client random")?;
+		let session_id = r.read_vec_u8("session ID")?;
+		let suites = r.read_vec_u16("cipher suites data")?;
+		if !suites.len().is_multiple_of(2) {
+			return Err(Error::Truncated {
+				field: "cipher suites (odd length)",
+			});
+		}
+		let compression_methods = r.read_vec_u8("compression methods")?;
+
+		let mut hello = Self {
+			legacy_version,
+			random,
+			session_id,
+			cipher_suites: Vec::new(),
+			compression_methods,
+			extensions: Vec::new(),
+			has_grease: false,
+			extensions_overflowed: false,
+			cipher_suites_overflowed: false,
+		};
+		for suite in u16s(suites) {
+			if is_grease(suite) {
+				hello.has_grease = true;
+			} else if hello.cipher_suites.push(suite).is_err() {
+				hello.cipher_suites_overflowed = true;
+			}
+		}
+		if r.remaining() < 2 {
+			return Ok(hello);
+		}
+		let mut exts = Reader::new(r.read_vec_u16("extensions data")?);
+		while exts.remaining() >= 4 {
+			let type_id = exts.read_u16("extension type")?;
+			let data = exts.read_vec_u16("extension body")?;
+			if is_grease(type_id) {
+				hello.has_grease = true;
+			} else if hello
+				.extensions
+				.push(RawExtension { type_id, data })
+				.is_err()
+			{
+				hello.extensions_overflowed = true;
+			}
+		}
+		Ok(hello)
+	}
+
+	/// Find the raw data of the first extension with the given type.
+	#[must_use]
+	pub fn find_extension(&self, type_id: u16) -> Option<&'a [u8]> {
+		self
+			.extensions
+			.iter()
+			.find(|ext| ext.type_id == type_id)
+			.map(|ext| ext.data)
+	}
+
+	/// Return the first DNS hostname from the SNI extension.
+	///
+	/// Returns `None` when SNI is absent, malformed, or not valid UTF-8.
+	#[must_use]
+	pub fn server_name(&self) -> Option<&'a str> {
+		sni::host_name(self.find_extension(0x0000)?).ok()?
+	}
+
+	/// Iterate the ALPN protocol identifiers.
+	///
+	/// Stops at the first entry that overruns the list.
+	pub fn alpn_protocols(&self) -> impl Iterator<Item = &'a [u8]> + use<'a, N_EXT, N_CS> {
+		let list = self
+			.find_extension(0x0010)
+			.and_then(|data| Reader::new(data).read_vec_u16("ALPN list data").ok())
+			.unwrap_or_default();
+		let mut r = Reader::new(list);
+		core::iter::from_fn(move || r.read_vec_u8("ALPN protocol").ok())
+	}
+
+	/// Iterate the Supported Versions entries, GREASE excluded.
+	pub fn supported_versions(&self) -> impl Iterator<Item = u16> + use<'a, N_EXT, N_CS> {
+		let list = self
+			.find_extension(0x002b)
+			.and_then(|data| {
+				Reader::new(data)
+					.read_vec_u8("supported versions data")
+					.ok()
+			})
+			.unwrap_or_default();
+		u16s(list).filter(|&v| !is_grease(v))
+	}
+}
+
+fn u16s(list: &[u8]) -> impl Iterator<Item = u16> + '_ {
+	list
+		.chunks_exact(2)
+		.filter_map(|c| <[u8; 2]>::try_from(c).ok())
+		.map(u16::from_be_bytes)
+}
@@ -0,0 +1,85 @@
+/* src/framing.rs */
+
+use crate::Error;
+use crate::tls_reader::Reader;
+use crate::types::{ContentType, HandshakeType};
+
+/// Strip the TLS record header and return the record payload.
+pub(crate) fn record_payload(data: &[u8]) -> Result<&[u8], Error> {
+	if data.len() < 5 {
+		return Err(Error::BufferTooShort {
+			need: 5,
+			have: data.len(),
+		});
+	}
+	let mut r = Reader::new(data);
+	let content_type = ContentType::from_u8(r.read_u8("record content type")?);
+	if content_type != ContentType::Handshake {
+		return Err(Error::NotHandshakeRecord(content_type));
+	}
+	let _version = r.read_u16("record protocol version")?;
+	let record_len = r.read_u16("record length")? as usize;
+	r.read_bytes(record_len, "record payload")
+}
+
+/// Strip the handshake header and return the ClientHello body.
+pub(crate) fn client_hello_body(data: &[u8]) -> Result<&[u8], Error> {
+	if data.is_empty() {
+		return Err(Error::BufferTooShort { need: 1, have: 0 });
+	}
+	let mut r = Reader::new(data);
+	let hs_type = HandshakeType::from_u8(r.read_u8("handshake type")?);
+	if hs_type != HandshakeType::ClientHello {
+		return Err(Error::NotClientHello(hs_type));
+	}
+	let body_len = r.read_u24("handshake length")? as usize;
+	r.read_bytes(body_len, "handshake body")
+}
+
+/// Strip the DTLS record and handshake headers (RFC 6347 §4.1, §4.2.2)
+/// and return the ClientHello body.
+///
+/// Only an unfragmented ClientHello in the first record is accepted.
+#[cfg(feature = "client-hello")]
+pub(crate) fn dtls_client_hello_body(data: &[u8]) -> Result<&[u8], Error> {
+	let mut r = Reader::new(data);
+	let content_type = ContentType::from_u8(r.read_u8("record content type")?);
+	if content_type != ContentType::Handshake {
+		return Err(Error::NotHandshakeRecord(content_type));
+	}
+	// Version, epoch, and sequence number.
+	r.read_bytes(2 + 2 + 6, "DTLS record header")?;
+	let mut r = Reader::new(r.read_vec_u16("record payload")?);
+	let hs_type = HandshakeType::from_u8(r.read_u8("handshake type")?);
+	if hs_type != HandshakeType::ClientHello {
+		return Err(Error::NotClientHello(hs_type));
+	}
+	let len = r.read_u24("handshake length")?;
+	let _message_seq = r.read_u16("DTLS message sequence")?;
+	let offset = r.read_u24("DTLS fragment offset")?;
+	let fragment_len = r.read_u24("DTLS fragment length")?;
+	if offset != 0 || fragment_len != len {
+		return Err(Error::Truncated {
+			field: "DTLS handshake fragment",
+		});
+	}
+	r.read_bytes(len as usize, "handshake body")
+}
+
+/// Strip the two-byte SSL 2.0 record header (RFC 5246 Appendix E.2) and
+/// return the CLIENT-HELLO message, starting at its message type.
+#[cfg(feature = "client-hello")]
+pub(crate) fn sslv2_record_payload(data: &[u8]) -> Result<&[u8], Error> {
+	let mut r = Reader::new(data);
+	let len = r.read_u16("SSLv2 record length")? & 0x7fff;
+	r.read_bytes(len as usize, "SSLv2 record payload")
+}
+
+/// Return the cookie of a DTLS ClientHello record.
+#[cfg(feature = "client-hello")]
+pub(crate) fn dtls_cookie(data: &[u8]) -> Result<&[u8], Error> {
+	let mut r = Reader::new(dtls_client_hello_body(data)?);
+	r.read_bytes(2 + 32, "client random")?;
+	r.read_vec_u8("session ID")?;
+	r.read_vec_u8("DTLS cookie")
+}
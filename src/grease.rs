@@ -1,5 +1,8 @@
 /* src/grease.rs */
 
+#[cfg(feature = "client-hello")]
+use alloc::vec::Vec;
+
 /// Check whether a `u16` value is a GREASE value defined in RFC 8701.
 ///
 /// GREASE values have identical high and low bytes matching `0x_A`, producing the set
@@ -15,9 +18,118 @@ pub fn is_grease(value: u16) -> bool {
 	(value & 0x0F0F) == 0x0A0A && (value >> 8) == (value & 0xFF)
 }
 
+/// Value that stands in for a GREASE value the parser filtered out.
+///
+/// Only GREASE positions are kept, see [`GreasePositions`]; accessors
+/// that restore the wire order, such as
+/// [`ClientHello::wire_extension_types`](crate::ClientHello::wire_extension_types),
+/// put this first RFC 8701 value at each position.
+pub const GREASE_PLACEHOLDER: u16 = 0x0a0a;
+
+/// Where GREASE values appeared in the lists they were filtered from.
+///
+/// Each entry is an index into the list as sent on the wire, GREASE
+/// included. The values themselves are not kept: any GREASE value at a
+/// recorded position is treated as equivalent.
+///
+/// Positions inside an extension are only recorded when its `ext-*`
+/// decoder is enabled. Clients place GREASE differently, e.g. BoringSSL
+/// puts it first in Supported Versions, Supported Groups, and Key Share.
+#[cfg(feature = "client-hello")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GreasePositions {
+	/// Positions within the cipher suite list.
+	pub cipher_suites: Vec<usize>,
+	/// Positions within the extension list.
+	pub extensions: Vec<usize>,
+	/// Positions within the Supported Versions list.
+	pub supported_versions: Vec<usize>,
+	/// Positions within the Supported Groups list.
+	pub supported_groups: Vec<usize>,
+	/// Positions within the Key Share entries.
+	pub key_share: Vec<usize>,
+}
+
+/// Private code points filtered out by
+/// [`ParseOptions::reserved_values`](crate::ParseOptions::reserved_values),
+/// and where they appeared.
+///
+/// Each entry is `(index, value)`, the index being into the list as sent
+/// on the wire, GREASE and reserved values included. Unlike GREASE, the
+/// values are kept, so that [`codegen`](crate::codegen) can reproduce
+/// them; extension bodies and key exchange bytes are not. The
+/// [`GreasePositions`] of a hello index its lists with these values left
+/// out, so RFC 8701 GREASE placement reads the same with or without
+/// them.
+#[cfg(feature = "client-hello")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReservedValues {
+	/// Entries of the cipher suite list.
+	pub cipher_suites: Vec<(usize, u16)>,
+	/// Entries of the extension list, by extension type.
+	pub extensions: Vec<(usize, u16)>,
+	/// Entries of the Supported Versions list.
+	pub supported_versions: Vec<(usize, u16)>,
+	/// Entries of the Supported Groups list.
+	pub supported_groups: Vec<(usize, u16)>,
+	/// Entries of the Signature Algorithms list.
+	pub signature_algorithms: Vec<(usize, u16)>,
+	/// Entries of the Key Share list, by group.
+	pub key_share: Vec<(usize, u16)>,
+}
+
+#[cfg(feature = "client-hello")]
+impl ReservedValues {
+	/// Whether no reserved value was filtered out.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.cipher_suites.is_empty()
+			&& self.extensions.is_empty()
+			&& self.supported_versions.is_empty()
+			&& self.supported_groups.is_empty()
+			&& self.signature_algorithms.is_empty()
+			&& self.key_share.is_empty()
+	}
+}
+
+/// Put the `reserved` values back into `list` at their wire positions.
+#[cfg(feature = "client-hello")]
+pub(crate) fn restore_reserved<T>(
+	list: &mut Vec<T>,
+	reserved: &[(usize, u16)],
+	wrap: impl Fn(u16) -> T,
+) {
+	for &(index, value) in reserved {
+		list.insert(index.min(list.len()), wrap(value));
+	}
+}
+
+/// Interleave `None` at the GREASE `positions` among `values`, restoring
+/// the wire order of a filtered list.
+#[cfg(feature = "client-hello")]
+pub(crate) fn interleave<'p, T>(
+	mut values: impl Iterator<Item = T> + 'p,
+	positions: &'p [usize],
+) -> impl Iterator<Item = Option<T>> + 'p {
+	let mut index = 0;
+	let mut positions = positions.iter().peekable();
+	core::iter::from_fn(move || {
+		let item = if positions.next_if_eq(&&index).is_some() {
+			None
+		} else {
+			Some(values.next()?)
+		};
+		index += 1;
+		Some(item)
+	})
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
+	#[cfg(not(feature = "client-hello"))]
 	use alloc::vec::Vec;
 
 	#[test]
@@ -0,0 +1,1222 @@
+/* src/hello.rs */
+
+#[cfg(any(feature = "ja3", feature = "ja4", feature = "peetprint"))]
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::consts::EXT_EC_POINT_FORMATS;
+use crate::diagnostics::Diagnostics;
+use crate::extension::{Extension, Extensions};
+use crate::grease::{GreasePositions, ReservedValues};
+#[cfg(feature = "ext-alpn")]
+use crate::quic;
+
+/// Input framing a [`ClientHello`] was parsed from.
+///
+/// Downstream consumers use this to tell TCP and QUIC handshakes apart,
+/// e.g. for the `t` / `q` / `d` protocol marker in JA4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Transport {
+	/// TLS record layer over a stream transport, via [`parse_from_record`](crate::parse_from_record).
+	TlsRecord,
+	/// Raw handshake message without record layer (e.g. QUIC CRYPTO
+	/// frames), via [`parse`](crate::parse).
+	Handshake,
+	/// DTLS record layer, via [`parse_any`](crate::parse_any). The
+	/// cookie is skipped.
+	Dtls,
+	/// SSL 2.0 compatible CLIENT-HELLO, via
+	/// [`parse_any`](crate::parse_any). `random` holds the challenge,
+	/// which may be shorter than 32 bytes, and there are no compression
+	/// methods or extensions.
+	SslV2,
+}
+
+/// Parsed TLS ClientHello message holding zero-copy references into the
+/// original byte buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientHello<'a> {
+	/// Legacy protocol version (usually `0x0303` for TLS 1.2).
+	pub legacy_version: u16,
+	/// 32-byte client random.
+	pub random: &'a [u8],
+	/// Session ID (may be empty).
+	pub session_id: &'a [u8],
+	/// Cipher suite identifiers with GREASE values removed.
+	pub cipher_suites: Vec<u16>,
+	/// Compression method bytes.
+	pub compression_methods: &'a [u8],
+	/// Parsed extensions.
+	#[cfg_attr(feature = "serde", serde(borrow))]
+	pub extensions: Extensions<'a>,
+	/// Body length of each entry of `extensions` as it was on the wire,
+	/// at the same index.
+	pub extension_body_lengths: Vec<u16>,
+	/// Set to `true` when any RFC 8701 GREASE value was encountered
+	/// during parsing. Values accepted by
+	/// [`ParseOptions::reserved_values`](crate::ParseOptions::reserved_values)
+	/// do not count.
+	pub has_grease: bool,
+	/// Positions of the GREASE cipher suites and extensions that were
+	/// filtered out.
+	pub grease: GreasePositions,
+	/// Private code points filtered out by
+	/// [`ParseOptions::reserved_values`](crate::ParseOptions::reserved_values).
+	pub reserved: ReservedValues,
+	/// Irregularities tolerated while parsing.
+	pub diagnostics: Diagnostics,
+	/// Framing the message was parsed from.
+	pub transport: Transport,
+}
+
+impl<'a> ClientHello<'a> {
+	/// Return the first DNS hostname from the SNI extension.
+	#[cfg(feature = "ext-sni")]
+	#[must_use]
+	pub fn server_name(&self) -> Option<&str> {
+		core::str::from_utf8(self.host_name()?).ok()
+	}
+
+	/// Locate the first SNI hostname in `buf`, the buffer this hello was
+	/// parsed from, as `(offset, len)`.
+	///
+	/// Lets eBPF/XDP companion programs and in-place editors act on the
+	/// exact bytes the parser identified. The name is located even when
+	/// it is not valid UTF-8. Returns `None` without a hostname, or when
+	/// the hello does not borrow from `buf`.
+	///
+	/// ```
+	/// let msg = clienthello::Builder::new()
+	///     .server_name("example.com")
+	///     .build_record()
+	///     .unwrap();
+	/// let hello = clienthello::parse_from_record(&msg).unwrap();
+	/// let (offset, len) = hello.server_name_span(&msg).unwrap();
+	/// assert_eq!(&msg[offset..offset + len], b"example.com");
+	/// ```
+	#[cfg(feature = "ext-sni")]
+	#[must_use]
+	pub fn server_name_span(&self, buf: &[u8]) -> Option<(usize, usize)> {
+		span_in(buf, self.host_name()?)
+	}
+
+	#[cfg(feature = "ext-sni")]
+	fn host_name(&self) -> Option<&'a [u8]> {
+		self
+			.extensions
+			.iter()
+			.filter_map(|ext| <crate::typed::ServerNames as crate::FromExtension>::from_extension(ext))
+			.flatten()
+			.find(|sn| sn.name_type == 0x00)
+			.map(|sn| sn.name)
+	}
+
+	/// Locate the session ID in `buf`, the buffer this hello was parsed
+	/// from, as `(offset, len)`.
+	///
+	/// An empty session ID is located at the byte after its length
+	/// prefix. Returns `None` when the hello does not borrow from `buf`.
+	#[must_use]
+	pub fn session_id_span(&self, buf: &[u8]) -> Option<(usize, usize)> {
+		span_in(buf, self.session_id)
+	}
+
+	/// Collect all ALPN protocol identifiers.
+	#[cfg(feature = "ext-alpn")]
+	#[must_use]
+	pub fn alpn_protocols(&self) -> &[&[u8]] {
+		self.get::<crate::typed::Alpn>().unwrap_or_default()
+	}
+
+	/// Return the ALPN identifiers listed in the ALPS
+	/// (`application_settings`) extension, under either codepoint.
+	///
+	/// These are the protocols the client has settings for; the
+	/// settings are sent later, encrypted. See [`alps`](crate::alps).
+	#[must_use]
+	pub fn alps_protocols(&self) -> Vec<&[u8]> {
+		self
+			.find_extension(crate::alps::EXTENSION_TYPE)
+			.or_else(|| self.find_extension(crate::alps::EXTENSION_TYPE_OLD))
+			.map(crate::alps::protocols)
+			.unwrap_or_default()
+	}
+
+	/// Return supported TLS versions (GREASE values already excluded).
+	#[cfg(feature = "ext-supported-versions")]
+	#[must_use]
+	pub fn supported_versions(&self) -> &[u16] {
+		self
+			.get::<crate::typed::SupportedVersions>()
+			.unwrap_or_default()
+	}
+
+	/// Return the versions the client offers, applying the TLS 1.2
+	/// fallback rule.
+	///
+	/// Without a Supported Versions extension the client negotiates with
+	/// `legacy_version` alone (RFC 8446 §4.2.1), so this returns
+	/// `[legacy_version]`; otherwise it returns the extension's list, as
+	/// [`supported_versions`](Self::supported_versions) does.
+	#[cfg(feature = "ext-supported-versions")]
+	#[must_use]
+	pub fn effective_versions(&self) -> &[u16] {
+		self
+			.get::<crate::typed::SupportedVersions>()
+			.unwrap_or(core::slice::from_ref(&self.legacy_version))
+	}
+
+	/// Return supported groups / named curves (GREASE values already excluded).
+	///
+	/// Groups are in the client's preference order, most preferred first,
+	/// as sent on the wire.
+	#[cfg(feature = "ext-supported-groups")]
+	#[must_use]
+	pub fn supported_groups(&self) -> &[u16] {
+		self
+			.get::<crate::typed::SupportedGroups>()
+			.unwrap_or_default()
+	}
+
+	/// Return the client's most preferred group.
+	#[cfg(feature = "ext-supported-groups")]
+	#[must_use]
+	pub fn top_group(&self) -> Option<u16> {
+		self.supported_groups().first().copied()
+	}
+
+	/// Return the position of `group` in the client's preference order,
+	/// `0` being the most preferred.
+	#[cfg(feature = "ext-supported-groups")]
+	#[must_use]
+	pub fn group_rank(&self, group: u16) -> Option<usize> {
+		self.supported_groups().iter().position(|&g| g == group)
+	}
+
+	/// Check whether the client prefers group `a` over group `b`.
+	///
+	/// An offered group is preferred over one that is not offered.
+	/// Returns `false` when `a` is not offered or `a == b`.
+	#[cfg(feature = "ext-supported-groups")]
+	#[must_use]
+	pub fn prefers_group(&self, a: u16, b: u16) -> bool {
+		match (self.group_rank(a), self.group_rank(b)) {
+			(Some(a), Some(b)) => a < b,
+			(Some(_), None) => true,
+			(None, _) => false,
+		}
+	}
+
+	/// Return signature algorithm identifiers.
+	#[cfg(feature = "ext-signature-algorithms")]
+	#[must_use]
+	pub fn signature_algorithms(&self) -> &[u16] {
+		self
+			.get::<crate::typed::SignatureAlgorithms>()
+			.unwrap_or_default()
+	}
+
+	/// Iterate the offered signature schemes, in preference order.
+	#[cfg(feature = "ext-signature-algorithms")]
+	pub fn signature_schemes(&self) -> impl Iterator<Item = crate::SignatureScheme> + '_ {
+		self
+			.signature_algorithms()
+			.iter()
+			.map(|&v| crate::SignatureScheme::from_u16(v))
+	}
+
+	/// Check whether any RSASSA-PSS signature scheme is offered.
+	#[cfg(feature = "ext-signature-algorithms")]
+	#[must_use]
+	pub fn supports_pss(&self) -> bool {
+		self.signature_schemes().any(crate::SignatureScheme::is_pss)
+	}
+
+	/// Check whether Ed25519 signatures are offered.
+	#[cfg(feature = "ext-signature-algorithms")]
+	#[must_use]
+	pub fn supports_ed25519(&self) -> bool {
+		self
+			.signature_schemes()
+			.any(|s| s == crate::SignatureScheme::Ed25519)
+	}
+
+	/// Return the weakest hash among the offered signature schemes.
+	///
+	/// Schemes without a separate hash (EdDSA) and unknown code points
+	/// are skipped, so a hello offering only those returns `None`.
+	#[cfg(feature = "ext-signature-algorithms")]
+	#[must_use]
+	pub fn weakest_hash_offered(&self) -> Option<crate::HashAlgorithm> {
+		self
+			.signature_schemes()
+			.filter_map(crate::SignatureScheme::hash)
+			.min()
+	}
+
+	/// Return key-share group identifiers (GREASE values already excluded).
+	#[cfg(feature = "ext-key-share")]
+	#[must_use]
+	pub fn key_share_groups(&self) -> &[u16] {
+		self
+			.get::<crate::typed::KeyShareGroups>()
+			.unwrap_or_default()
+	}
+
+	/// Return every key share entry with its key, GREASE included.
+	#[cfg(feature = "ext-key-share")]
+	#[must_use]
+	pub fn key_share_entries(&self) -> &[crate::KeyShareEntry<'a>] {
+		self
+			.get::<crate::typed::KeyShareEntries>()
+			.unwrap_or_default()
+	}
+
+	/// Return the total length of the offered keys in bytes, GREASE
+	/// entries included.
+	///
+	/// Post-quantum hybrids push this past a kilobyte: an
+	/// X25519MLKEM768 share alone is 1216 bytes, against 32 for X25519.
+	#[cfg(feature = "ext-key-share")]
+	#[must_use]
+	pub fn key_share_size(&self) -> usize {
+		self.key_share_entries().iter().map(|e| e.key.len()).sum()
+	}
+
+	/// Check whether a key share is sent for a hybrid post-quantum group
+	/// (X25519MLKEM768, SecP256r1MLKEM768, SecP384r1MLKEM1024, or the
+	/// Kyber768 drafts).
+	///
+	/// Only key shares count: a hybrid group listed in Supported Groups
+	/// without a key does not put the large key on the wire.
+	#[cfg(feature = "ext-key-share")]
+	#[must_use]
+	pub fn hybrid_key_exchange_offered(&self) -> bool {
+		self
+			.key_share_groups()
+			.iter()
+			.any(|g| crate::consts::HYBRID_GROUPS.contains(g))
+	}
+
+	/// Check whether a renegotiation info extension is present.
+	#[cfg(feature = "ext-renegotiation-info")]
+	#[must_use]
+	pub fn has_renegotiation_info(&self) -> bool {
+		self.get::<crate::typed::RenegotiationInfo>().is_some()
+	}
+
+	/// Return the decoded Encrypted Client Hello extension, the outer
+	/// offer (real or GREASE) or the inner marker.
+	#[cfg(feature = "ext-ech")]
+	#[must_use]
+	pub fn encrypted_client_hello(&self) -> Option<&crate::ech::EchClientHello<'a>> {
+		self.get::<crate::typed::EncryptedClientHello>()
+	}
+
+	/// Cross-check the renegotiation SCSV against the
+	/// `renegotiation_info` extension.
+	///
+	/// Works with or without the `ext-renegotiation-info` decoder.
+	#[must_use]
+	pub fn renegotiation(&self) -> crate::Renegotiation {
+		crate::renegotiation::check(self)
+	}
+
+	/// Classify the session ID as empty, TLS 1.3 compatibility mode,
+	/// or resumption-style.
+	///
+	/// Works with or without the `ext-supported-versions` decoder.
+	#[must_use]
+	pub fn session_id_style(&self) -> crate::SessionIdStyle {
+		crate::session_id::classify(self)
+	}
+
+	/// Decode the identities offered in the `pre_shared_key` extension,
+	/// in wire order.
+	///
+	/// Returns an empty list when the extension is absent or its
+	/// identities cannot be decoded, e.g. after an
+	/// [`UnknownExtensionPolicy`](crate::UnknownExtensionPolicy)
+	/// shortened the body.
+	#[must_use]
+	pub fn psk_identities(&self) -> Vec<crate::PskIdentity<'a>> {
+		crate::early_data::psk_identities(self)
+	}
+
+	/// Predict whether 0-RTT data sent with this hello will be accepted
+	/// under `ticket`, and under which ALPN protocol and size limit.
+	///
+	/// Checks the RFC 8446 §4.2.10 conditions that depend on the hello:
+	/// an `early_data` offer, a PSK (early data always uses the first
+	/// identity), a ticket that allows early data, the ticket's cipher
+	/// suite, and the ticket's ALPN protocol. Anti-replay and ticket age
+	/// checks stay with the server.
+	///
+	/// # Errors
+	///
+	/// Returns the first [`EarlyDataRejection`](crate::EarlyDataRejection)
+	/// reason that applies.
+	///
+	/// ```
+	/// use clienthello::{Builder, TicketContext};
+	///
+	/// let msg = Builder::new()
+	///     .cipher_suites(&[0x1301])
+	///     .alpn(&[b"h2"])
+	///     .extension(0x002a, &[])
+	///     .pre_shared_key(&[(b"ticket", 0x1234)], 32)
+	///     .build()
+	///     .unwrap();
+	/// let hello = clienthello::parse(&msg).unwrap();
+	/// let ticket = TicketContext {
+	///     max_early_data_size: 16384,
+	///     cipher_suite: 0x1301,
+	///     alpn: Some(b"h2"),
+	/// };
+	/// let early = hello.early_data(&ticket).unwrap();
+	/// assert_eq!(early.alpn, Some(&b"h2"[..]));
+	/// assert_eq!(early.identity.identity, b"ticket");
+	/// ```
+	#[cfg(feature = "ext-alpn")]
+	pub fn early_data(
+		&self,
+		ticket: &crate::TicketContext<'_>,
+	) -> Result<crate::EarlyData<'a>, crate::EarlyDataRejection> {
+		crate::early_data::predict(self, ticket)
+	}
+
+	/// Predict the parameters a server configured as `server` would
+	/// select for this hello.
+	///
+	/// Each parameter is the first in the server's preference order that
+	/// the client supports, except that a TLS 1.3 group the client sent a
+	/// key share for wins over one that needs a HelloRetryRequest.
+	/// [`Negotiated::explain`](crate::Negotiated::explain) says why each
+	/// was chosen. Certificates, extensions other than ALPN, and the key
+	/// exchange of TLS 1.2 cipher suites are not modeled.
+	///
+	/// # Errors
+	///
+	/// Returns the first [`NegotiationError`](crate::NegotiationError)
+	/// that makes the handshake fail, in field order; its message names
+	/// what the client offered.
+	///
+	/// ```
+	/// use clienthello::{Builder, ServerConfig};
+	///
+	/// let msg = Builder::new()
+	///     .cipher_suites(&[0x1301, 0x1302])
+	///     .supported_versions(&[0x0304])
+	///     .supported_groups(&[0x001d, 0x0017])
+	///     .key_share(&[(0x001d, &[0; 32])])
+	///     .signature_algorithms(&[0x0804, 0x0403])
+	///     .build()
+	///     .unwrap();
+	/// let hello = clienthello::parse(&msg).unwrap();
+	/// let server = ServerConfig {
+	///     versions: &[0x0304],
+	///     cipher_suites: &[0x1302, 0x1301],
+	///     groups: &[0x0017, 0x001d],
+	///     signature_algorithms: &[0x0403],
+	///     alpn: &[],
+	/// };
+	/// let negotiated = hello.negotiate(&server).unwrap();
+	/// assert_eq!(negotiated.cipher_suite, 0x1302);
+	/// assert_eq!(negotiated.group, Some(0x001d));
+	/// assert!(!negotiated.hello_retry);
+	/// assert_eq!(negotiated.explain().len(), 5);
+	///
+	/// let tls12_only = ServerConfig { versions: &[0x0303], ..server };
+	/// let err = hello.negotiate(&tls12_only).unwrap_err();
+	/// assert_eq!(err.to_string(), "no common version: client offers 0x0304");
+	/// ```
+	#[cfg(feature = "negotiate")]
+	pub fn negotiate(
+		&self,
+		server: &crate::ServerConfig<'_>,
+	) -> Result<crate::Negotiated<'a>, crate::NegotiationError> {
+		crate::negotiate::negotiate(self, server)
+	}
+
+	/// Infer the QUIC versions the client is targeting.
+	///
+	/// Versions are taken from the `version_information` transport
+	/// parameter (RFC 9368) when present, followed by versions implied by
+	/// HTTP/3 ALPN identifiers (`h3` for version 1, `h3-29` for draft 29).
+	/// Duplicates are removed while preserving first-seen order. Returns
+	/// an empty list for hellos that carry no QUIC hints.
+	#[cfg(feature = "ext-alpn")]
+	#[must_use]
+	pub fn inferred_quic_versions(&self) -> Vec<u32> {
+		quic::inferred_versions(self)
+	}
+
+	/// Hash the fingerprint-relevant fields of this hello with digest `D`.
+	///
+	/// The hashed input is a deterministic encoding of the legacy
+	/// version, cipher suites, extension type order, supported versions,
+	/// supported groups, signature algorithms, and ALPN identifiers.
+	/// GREASE values are already excluded and per-connection fields
+	/// (random, session ID, key shares, SNI) are not included, so repeated
+	/// connections from one client stack hash identically.
+	///
+	/// ```
+	/// # fn build() -> Vec<u8> {
+	/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+	/// #     d.extend_from_slice(&[0u8; 32]);
+	/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+	/// #     d
+	/// # }
+	/// # let data = build();
+	/// let hello = clienthello::parse(&data).unwrap();
+	/// let hash = hello.fingerprint_with::<sha2::Sha256>();
+	/// assert_eq!(hash.len(), 32);
+	/// ```
+	#[cfg(feature = "digest")]
+	#[must_use]
+	pub fn fingerprint_with<D: digest::Digest>(&self) -> digest::Output<D> {
+		crate::fingerprint::fingerprint_with::<D>(self)
+	}
+
+	/// Encode this hello in a canonical form for stable hashing.
+	///
+	/// The result is a ClientHello handshake message with GREASE values
+	/// removed, extensions sorted by type, and the per-connection bytes
+	/// zeroed: the random, the session ID, key share payloads, and PSK
+	/// binders, each keeping its length. Two hellos from the same client
+	/// stack therefore encode identically even when the stack shuffles
+	/// its extensions, as Chrome does, and the result still parses.
+	///
+	/// ```
+	/// use clienthello::Builder;
+	///
+	/// let a = Builder::new()
+	///     .cipher_suites(&[0x1301])
+	///     .random([1; 32])
+	///     .supported_versions(&[0x0304])
+	///     .alpn(&[b"h2"]);
+	/// let b = Builder::new()
+	///     .cipher_suites(&[0x1301])
+	///     .random([2; 32])
+	///     .alpn(&[b"h2"])
+	///     .supported_versions(&[0x0304]);
+	/// let (a, b) = (a.build().unwrap(), b.build().unwrap());
+	/// let a = clienthello::parse(&a).unwrap().canonicalize();
+	/// assert_eq!(a, clienthello::parse(&b).unwrap().canonicalize());
+	/// assert_eq!(clienthello::parse(&a).unwrap().random, [0; 32]);
+	/// ```
+	#[must_use]
+	pub fn canonicalize(&self) -> Vec<u8> {
+		// Every field of a parsed hello came from a length-prefixed field
+		// of the same width, and nothing grows, so re-encoding cannot
+		// overflow.
+		crate::fingerprint::canonical(self).unwrap_or_default()
+	}
+
+	/// Describe this hello in the shape of a uTLS `ClientHelloSpec`, for
+	/// cloning it with another TLS stack.
+	///
+	/// Cipher suites and extensions keep their wire order with GREASE as
+	/// `None` or [`ExtensionSpec::Grease`](crate::codegen::ExtensionSpec::Grease)
+	/// placeholders; see [`codegen::spec`](crate::codegen::spec).
+	///
+	/// ```
+	/// use clienthello::codegen::ExtensionSpec;
+	///
+	/// let msg = clienthello::Builder::new()
+	///     .cipher_suites(&[0x0a0a, 0x1301])
+	///     .extension(0x1a1a, &[])
+	///     .server_name("example.com")
+	///     .build()
+	///     .unwrap();
+	/// let spec = clienthello::parse(&msg).unwrap().to_spec();
+	/// assert_eq!(spec.cipher_suites, [None, Some(0x1301)]);
+	/// assert_eq!(spec.extensions, [ExtensionSpec::Grease, ExtensionSpec::ServerName]);
+	/// ```
+	#[must_use]
+	pub fn to_spec(&self) -> crate::codegen::ClientHelloSpec {
+		crate::codegen::spec(self)
+	}
+
+	/// Return the JA3 string, `version,ciphers,extensions,groups,formats`.
+	///
+	/// Each field lists decimal values in wire order joined with `-`,
+	/// with GREASE excluded, e.g.
+	/// `769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0`.
+	/// This is the string Zeek and Suricata log and the salesforce/ja3
+	/// reference computes.
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3(&self) -> String {
+		crate::ja3::ja3_string(self)
+	}
+
+	/// Return the JA3 fingerprint: the lowercase hex MD5 digest of
+	/// [`ja3`](Self::ja3).
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3_hash(&self) -> String {
+		crate::ja3::ja3_hash(self)
+	}
+
+	/// Return the JA3 string as revision `version` of the algorithm
+	/// defines it.
+	///
+	/// [`ja3`](Self::ja3) is pinned to [`Ja3Version::V1`]; pass
+	/// [`Ja3Version::LATEST`] to follow revisions as they are added.
+	///
+	/// [`Ja3Version::V1`]: crate::Ja3Version::V1
+	/// [`Ja3Version::LATEST`]: crate::Ja3Version::LATEST
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3_with(&self, version: crate::Ja3Version) -> String {
+		crate::ja3::ja3_string_with(self, version)
+	}
+
+	/// Return the lowercase hex MD5 digest of
+	/// [`ja3_with`](Self::ja3_with).
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3_hash_with(&self, version: crate::Ja3Version) -> String {
+		crate::ja3::md5_hex(&self.ja3_with(version))
+	}
+
+	/// Return the JA3N string: [`ja3`](Self::ja3) with the extension ids
+	/// sorted in ascending order.
+	///
+	/// Chrome and other clients that shuffle their extensions produce a
+	/// new JA3 on almost every connection; JA3N stays the same across
+	/// those permutations while still telling apart hellos that differ
+	/// in which extensions they send.
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3n(&self) -> String {
+		crate::ja3::ja3n_string(self)
+	}
+
+	/// Return the JA3N fingerprint: the lowercase hex MD5 digest of
+	/// [`ja3n`](Self::ja3n).
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3n_hash(&self) -> String {
+		crate::ja3::ja3n_hash(self)
+	}
+
+	/// Return the JA3N string as revision `version` of the algorithm
+	/// defines it; [`ja3n`](Self::ja3n) is pinned to
+	/// [`Ja3Version::V1`](crate::Ja3Version::V1).
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3n_with(&self, version: crate::Ja3Version) -> String {
+		crate::ja3::ja3n_string_with(self, version)
+	}
+
+	/// Return the lowercase hex MD5 digest of
+	/// [`ja3n_with`](Self::ja3n_with).
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3n_hash_with(&self, version: crate::Ja3Version) -> String {
+		crate::ja3::md5_hex(&self.ja3n_with(version))
+	}
+
+	/// Return the peetprint of tls.peet.ws, as anti-bot services compare
+	/// against.
+	///
+	/// Eight `|`-separated fields list the supported versions, ALPN
+	/// protocols, supported groups, signature algorithms, PSK key exchange
+	/// modes, certificate compression algorithms, cipher suites, and
+	/// extension ids, e.g.
+	/// `GREASE-772-771|2-1.1|GREASE-29-23-24|1027-2052|1|2|GREASE-4865-4866|0-10-GREASE`.
+	/// GREASE values are kept in place as `GREASE`, ALPN `h2` and
+	/// `http/1.1` shorten to `2` and `1.1`, and the extension ids are
+	/// sorted as strings, so shuffled extension orders agree.
+	#[cfg(feature = "peetprint")]
+	#[must_use]
+	pub fn peetprint(&self) -> String {
+		crate::fingerprint::peetprint(self)
+	}
+
+	/// Return the lowercase hex MD5 digest of
+	/// [`peetprint`](Self::peetprint), as tls.peet.ws reports it.
+	#[cfg(feature = "peetprint")]
+	#[must_use]
+	pub fn peetprint_hash(&self) -> String {
+		crate::fingerprint::peetprint_hash(self)
+	}
+
+	/// Check this hello against a textual JA3 fingerprint.
+	///
+	/// `fingerprint` may be the full JA3 string
+	/// (`771,4865-4866-...,0-23-...,29-23-24,0`) or its 32-character MD5
+	/// hex digest, as found in policy configuration and JA3 databases.
+	/// Surrounding whitespace is ignored and the digest is matched
+	/// case-insensitively.
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn matches_ja3(&self, fingerprint: &str) -> bool {
+		crate::ja3::matches(self, fingerprint)
+	}
+
+	/// Check this hello against a textual JA3 fingerprint computed by
+	/// revision `version` of the algorithm.
+	///
+	/// [`matches_ja3`](Self::matches_ja3) is pinned to
+	/// [`Ja3Version::V1`](crate::Ja3Version::V1).
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn matches_ja3_with(&self, fingerprint: &str, version: crate::Ja3Version) -> bool {
+		crate::ja3::matches_with(self, fingerprint, version)
+	}
+
+	/// Return the JA4 fingerprint, FoxIO's `a_b_c` form such as
+	/// `t13d1516h2_8daaf6152771_e5627efa2ab1`.
+	///
+	/// `a` holds the protocol marker, highest offered version, SNI
+	/// presence, cipher suite and extension counts, and the first and
+	/// last character of the first ALPN protocol. `b` and `c` are the
+	/// truncated SHA-256 of the sorted cipher suites and of the sorted
+	/// extensions (SNI and ALPN excluded) with the signature algorithms
+	/// in wire order. GREASE is excluded throughout. Hellos parsed with
+	/// [`parse`](crate::parse) use the QUIC marker `q`, those parsed with
+	/// [`parse_from_record`](crate::parse_from_record) use `t`.
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn ja4(&self) -> String {
+		crate::ja4::ja4(self)
+	}
+
+	/// Return the raw JA4 fingerprint, `JA4_r`, with the `b` and `c`
+	/// sections unhashed.
+	///
+	/// The sections are comma-separated 4-digit hex lists: the sorted
+	/// cipher suites, then the sorted extensions (SNI and ALPN excluded)
+	/// followed by `_` and the signature algorithms in wire order, e.g.
+	/// `t13d1516h2_002f,0035,..._0005,000a,..._0403,0804,...`. Hashing
+	/// each section with SHA-256 and keeping 12 hex characters gives
+	/// [`ja4`](Self::ja4), so databases that store raw fingerprints can
+	/// use this directly.
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn ja4_raw(&self) -> String {
+		crate::ja4::ja4_raw(self)
+	}
+
+	/// Return the JA4 fingerprint as revision `version` of the FoxIO
+	/// spec defines it.
+	///
+	/// [`ja4`](Self::ja4) is pinned to [`Ja4Version::V1`]; pass
+	/// [`Ja4Version::LATEST`] to follow revisions as they are added.
+	///
+	/// ```
+	/// use clienthello::Ja4Version;
+	///
+	/// let msg = clienthello::Builder::new().build().unwrap();
+	/// let hello = clienthello::parse(&msg).unwrap();
+	/// assert_eq!(hello.ja4_with(Ja4Version::V1), hello.ja4());
+	/// ```
+	///
+	/// [`Ja4Version::V1`]: crate::Ja4Version::V1
+	/// [`Ja4Version::LATEST`]: crate::Ja4Version::LATEST
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn ja4_with(&self, version: crate::Ja4Version) -> String {
+		crate::ja4::ja4_with(self, version)
+	}
+
+	/// Return the raw `JA4_r` fingerprint as revision `version` of the
+	/// FoxIO spec defines it.
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn ja4_raw_with(&self, version: crate::Ja4Version) -> String {
+		crate::ja4::ja4_raw_with(self, version)
+	}
+
+	/// Return a 12-hex-character hash of the extension order.
+	///
+	/// The truncated SHA-256 of the extension types in wire order, as a
+	/// comma-separated 4-digit hex list without GREASE. JA4 sorts its
+	/// extension list, so this tells apart hellos that only differ in
+	/// order; it changes on every connection from a client that shuffles
+	/// its extensions.
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn extension_order_hash(&self) -> String {
+		let types: Vec<u16> = self.extensions.type_ids().collect();
+		crate::ja4::extension_order_hash(&types)
+	}
+
+	/// Return the extension order hash as revision `version` of the JA4
+	/// spec defines it; [`extension_order_hash`](Self::extension_order_hash)
+	/// is pinned to [`Ja4Version::V1`](crate::Ja4Version::V1).
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn extension_order_hash_with(&self, version: crate::Ja4Version) -> String {
+		let types: Vec<u16> = self.extensions.type_ids().collect();
+		crate::ja4::extension_order_hash_with(&types, version)
+	}
+
+	/// Check this hello against a textual JA4 fingerprint.
+	///
+	/// `fingerprint` may be the hashed JA4 form
+	/// (`t13d1516h2_8daaf6152771_e5627efa2ab1`) or the raw `JA4_r` form
+	/// with unhashed cipher and extension lists. Hellos parsed with
+	/// [`parse`](crate::parse) use the QUIC protocol marker `q`, those
+	/// parsed with [`parse_from_record`](crate::parse_from_record) use
+	/// `t`.
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn matches_ja4(&self, fingerprint: &str) -> bool {
+		crate::ja4::matches(self, fingerprint)
+	}
+
+	/// Check this hello against a textual JA4 fingerprint computed by
+	/// revision `version` of the spec.
+	///
+	/// [`matches_ja4`](Self::matches_ja4) is pinned to
+	/// [`Ja4Version::V1`](crate::Ja4Version::V1).
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn matches_ja4_with(&self, fingerprint: &str, version: crate::Ja4Version) -> bool {
+		crate::ja4::matches_with(self, fingerprint, version)
+	}
+
+	/// Rank the built-in known clients this hello resembles.
+	///
+	/// The hello's JA4 is compared section by section against a compact
+	/// table of default-configuration fingerprints for major browsers,
+	/// curl, `openssl s_client`, Go `crypto/tls`, Java JSSE, Node.js, and
+	/// the Python `requests`, `aiohttp`, and `httpx` libraries that bots
+	/// and scrapers are built on. A matching cipher section scores 50, a
+	/// matching extension section 30, and a matching prefix (version,
+	/// SNI, counts, ALPN) 20, so a full match scores 100. Clients with no
+	/// matching cipher or extension section are omitted; the result is
+	/// sorted by descending score.
+	#[cfg(feature = "known-clients")]
+	#[must_use]
+	pub fn identify(&self) -> Vec<crate::Candidate> {
+		crate::known::identify(self)
+	}
+
+	/// Look this hello up in the built-in fingerprint table and return
+	/// the best match.
+	///
+	/// A full JA4 match, or else a JA3N match, scores 100; otherwise this
+	/// is the first candidate of [`identify`](Self::identify). JA3N
+	/// ignores signature algorithms and ALPN protocols, so it still
+	/// recognizes a client configured with different ones. Returns `None`
+	/// when no client resembles the hello.
+	#[cfg(feature = "known-clients")]
+	#[must_use]
+	pub fn lookup_fingerprint(&self) -> Option<crate::Candidate> {
+		crate::known::lookup(self)
+	}
+
+	/// Compare this hello against the built-in library default
+	/// [`Profile`](crate::Profile)s and return the most similar one.
+	///
+	/// Meant for checking that a client under test is configured as
+	/// intended: an exact match has an empty
+	/// [`mismatched`](crate::ProfileMatch::mismatched) list, and otherwise
+	/// the list names the fields to look at. Ties go to the profile listed
+	/// first in [`Profile::BUILTIN`](crate::Profile::BUILTIN).
+	#[cfg(feature = "profiles")]
+	#[must_use]
+	pub fn closest_profile(&self) -> crate::ProfileMatch<'static> {
+		crate::profile::closest(self)
+	}
+
+	/// Score how alike this hello and `other` are, from 0 to 100.
+	///
+	/// Cipher suites, extension types, supported groups, signature
+	/// algorithms, and supported versions each score the share of values
+	/// the two hellos have in common (intersection over union), and the
+	/// result is the mean over the five fields, as in
+	/// [`Profile::compare`](crate::Profile::compare). Order is ignored, as
+	/// are SNI, ALPN, and extensions that vary per connection, so
+	/// connections from one client to different sites score 100.
+	/// [`cluster`](crate::cluster()) groups a corpus by this score.
+	#[cfg(feature = "cluster")]
+	#[must_use]
+	pub fn similarity(&self, other: &Self) -> u8 {
+		crate::cluster::similarity(self, other)
+	}
+
+	/// Check this hello for RFC violations and consistency lints.
+	///
+	/// Parsing is lenient by default so that odd clients can still be
+	/// routed; this runs the strict checks as a separate pass for the
+	/// places where a security decision depends on the hello. Everything
+	/// the parser tolerated, see [`Diagnostics::warnings`], is included.
+	#[cfg(feature = "validate")]
+	#[must_use]
+	pub fn validate(&self) -> crate::ValidationReport {
+		crate::validate::validate(self)
+	}
+
+	/// Guess the TLS implementation that produced this hello.
+	///
+	/// Unlike exact fingerprint matching, this weighs individual traits
+	/// that survive version bumps and configuration changes:
+	/// stack-specific extensions (GREASE and ALPS for BoringSSL,
+	/// `record_size_limit` and `delegated_credentials` for NSS,
+	/// `encrypt_then_mac` and `post_handshake_auth` for OpenSSL), the
+	/// algorithms offered for certificate compression, the default TLS
+	/// 1.3 cipher suite order, and fixed extension orders (Go
+	/// `crypto/tls`, Schannel).
+	///
+	/// Returns `None` when the evidence is weak or two stacks tie. The
+	/// result is a heuristic; clients can imitate any of these traits.
+	#[must_use]
+	pub fn infer_stack(&self) -> Option<crate::TlsStack> {
+		crate::stack::infer(self)
+	}
+
+	/// Guess the client family from its TLS habits rather than from an
+	/// exact fingerprint.
+	///
+	/// Weighs GREASE, ALPS and `channel_id`, extension shuffling and
+	/// fixed orders, certificate compression algorithms, key share
+	/// choices, cipher suite ordering, and extensions only one stack
+	/// sends, so a new browser version whose fingerprint hashes changed
+	/// still lands in the right family. Unlike
+	/// [`infer_stack`](Self::infer_stack), which names the TLS library,
+	/// this tells Chromium from Safari. Close or weak evidence gives
+	/// [`ClientFamily::Unknown`](crate::ClientFamily::Unknown); like any
+	/// heuristic, it is fooled by clients that imitate another.
+	#[must_use]
+	pub fn probable_client(&self) -> crate::ProbableClient {
+		crate::heuristics::probable_client(self)
+	}
+
+	/// Tell whether the extension order looks randomized per connection.
+	///
+	/// Chrome 110 and later shuffle their extensions on every connection,
+	/// while other stacks, and tools replaying a captured Chrome hello,
+	/// send a fixed order. A hello with GREASE extensions whose
+	/// recognised extensions are out of BoringSSL's pre-shuffle order is
+	/// [`Randomized`](crate::ExtensionOrder::Randomized); one in that
+	/// order, or without GREASE, is [`Fixed`](crate::ExtensionOrder::Fixed).
+	/// With fewer than three recognised extensions the verdict is
+	/// [`Unknown`](crate::ExtensionOrder::Unknown). BoringSSL keeps
+	/// padding and `pre_shared_key` after the shuffled block, so a hello
+	/// with either one elsewhere is `Fixed` too.
+	/// [`ExtensionOrder::is_stable`](crate::ExtensionOrder::is_stable)
+	/// tells whether JA3 or a sorted fingerprint suits grouping.
+	///
+	/// A single shuffled hello can land in the unshuffled order by
+	/// chance, but with the dozen or so extensions Chrome sends the odds
+	/// are negligible.
+	#[must_use]
+	pub fn extension_order(&self) -> crate::ExtensionOrder {
+		crate::stack::extension_order(self)
+	}
+
+	/// Score how likely this hello is to come from an automated client,
+	/// using the default [`BotScorer`](crate::BotScorer) weights.
+	///
+	/// The score runs from 0 to 100 and comes with the signals that
+	/// produced it. Use [`BotScorer`](crate::BotScorer) to tune the
+	/// weights for a particular traffic mix.
+	#[must_use]
+	pub fn bot_score(&self) -> crate::BotScore {
+		crate::BotScorer::new().score(self)
+	}
+
+	/// Score how likely the hello comes from a TLS-intercepting proxy
+	/// or antivirus rather than the end client; see
+	/// [`analysis::interception_score`](crate::analysis::interception_score).
+	#[cfg(feature = "analysis")]
+	#[must_use]
+	pub fn interception_score(&self) -> crate::analysis::InterceptionScore {
+		crate::analysis::interception_score(self)
+	}
+
+	/// Highlight extensions and cipher suites that are rare in typical
+	/// web traffic, using [`FrequencyTable::builtin`](crate::FrequencyTable::builtin).
+	///
+	/// The score is the percentage of entries below the 1% frequency
+	/// threshold; sort by it to pick which hellos deserve a closer look.
+	/// Use a [`FrequencyTable`](crate::FrequencyTable) measured from your
+	/// own traffic for better results.
+	#[must_use]
+	pub fn rarity_score(&self) -> crate::Rarity {
+		crate::FrequencyTable::builtin().rarity(self)
+	}
+
+	/// List the protocol features this hello advertises, one row of a
+	/// fleet-wide capability matrix.
+	///
+	/// Each [`Capability`](crate::Capability) is read from the offered
+	/// versions, groups, and extensions; an advertised feature says what
+	/// the client can do, not what a server will accept.
+	/// [`Capabilities::to_csv`](crate::Capabilities::to_csv) exports many
+	/// rows at once.
+	///
+	/// ```
+	/// use clienthello::{Builder, Capabilities, Capability};
+	///
+	/// let msg = Builder::new()
+	///     .cipher_suites(&[0x1301])
+	///     .supported_versions(&[0x0304])
+	///     .supported_groups(&[0x11ec, 0x001d])
+	///     .extension(0x0017, &[])
+	///     .build()
+	///     .unwrap();
+	/// let caps = clienthello::parse(&msg).unwrap().capabilities();
+	/// assert!(caps.contains(Capability::PostQuantum));
+	/// assert!(!caps.contains(Capability::Ech));
+	/// let csv = Capabilities::to_csv([("example", caps)]);
+	/// assert!(csv.starts_with("label,tls13,post_quantum,ech,"));
+	/// assert!(csv.lines().nth(1).unwrap().starts_with("example,1,1,0,"));
+	/// ```
+	#[cfg(feature = "capabilities")]
+	#[must_use]
+	pub fn capabilities(&self) -> crate::Capabilities {
+		crate::capability::detect(self)
+	}
+
+	/// Render the hello as indented, ANSI-colored text for terminals.
+	///
+	/// Intended for CLI output and quick inspection while debugging:
+	/// `println!("{}", hello.pretty())`. See [`Pretty`](crate::Pretty)
+	/// for the color scheme.
+	#[cfg(feature = "color")]
+	#[must_use]
+	pub fn pretty(&self) -> crate::Pretty<'_, 'a> {
+		crate::Pretty::new(self)
+	}
+
+	/// Compare two hellos treating GREASE entries as wildcards.
+	///
+	/// Only the fields a client stack sends the same way on every
+	/// connection are compared: the legacy version, cipher suites,
+	/// compression methods, extension types in order, and the decoded
+	/// supported versions, supported groups, signature algorithms, ALPN,
+	/// and PSK exchange modes lists, with key shares compared by group.
+	/// GREASE entries must occupy the same positions, but their values
+	/// may differ. The random, session ID, SNI, key exchange bytes, and
+	/// the bodies of other extensions such as padding or a PSK are
+	/// ignored, so live traffic matches a stored template of the same
+	/// client.
+	///
+	/// ```
+	/// let template = clienthello::Builder::new()
+	///     .random([0x01; 32])
+	///     .cipher_suites(&[0x0a0a, 0x1301])
+	///     .key_share(&[(0x001d, &[0x11; 32])])
+	///     .build()
+	///     .unwrap();
+	/// let live = clienthello::Builder::new()
+	///     .random([0x02; 32])
+	///     .cipher_suites(&[0x7a7a, 0x1301])
+	///     .key_share(&[(0x001d, &[0x22; 32])])
+	///     .build()
+	///     .unwrap();
+	/// let template = clienthello::parse(&template).unwrap();
+	/// let live = clienthello::parse(&live).unwrap();
+	/// assert!(template.eq_ignoring_grease(&live));
+	/// ```
+	#[must_use]
+	pub fn eq_ignoring_grease(&self, other: &Self) -> bool {
+		let same_grease = self.grease.cipher_suites == other.grease.cipher_suites
+			&& self.grease.extensions == other.grease.extensions
+			&& self.grease.supported_versions == other.grease.supported_versions
+			&& self.grease.supported_groups == other.grease.supported_groups
+			&& self.grease.key_share == other.grease.key_share;
+		self.legacy_version == other.legacy_version
+			&& self.cipher_suites == other.cipher_suites
+			&& self.compression_methods == other.compression_methods
+			&& self.has_grease == other.has_grease
+			&& same_grease
+			&& self.extensions.len() == other.extensions.len()
+			&& self
+				.extensions
+				.iter()
+				.zip(other.extensions.iter())
+				.all(|(a, b)| stable_eq(a, b))
+	}
+
+	/// Iterate the extension types in their original wire order, with
+	/// [`GREASE_PLACEHOLDER`](crate::GREASE_PLACEHOLDER) at each position
+	/// a GREASE extension held.
+	///
+	/// [`extensions`](Self::extensions) drops GREASE; this restores the
+	/// order as sent, for original-order fingerprints such as JA4_ro
+	/// (which skip the placeholder) and for order analyses that count
+	/// GREASE slots, without reparsing the raw bytes. Extensions filtered
+	/// by [`ParseOptions::reserved_values`](crate::ParseOptions::reserved_values)
+	/// stay left out; see [`reserved`](Self::reserved).
+	///
+	/// ```
+	/// use clienthello::GREASE_PLACEHOLDER;
+	///
+	/// let msg = clienthello::Builder::new()
+	///     .cipher_suites(&[0x1301])
+	///     .extension(0x3a3a, &[])
+	///     .server_name("example.com")
+	///     .extension(0x0017, &[])
+	///     .extension(0x5a5a, &[0x00])
+	///     .build()
+	///     .unwrap();
+	/// let hello = clienthello::parse(&msg).unwrap();
+	/// assert_eq!(
+	///     hello.wire_extension_types().collect::<Vec<_>>(),
+	///     [GREASE_PLACEHOLDER, 0x0000, 0x0017, GREASE_PLACEHOLDER]
+	/// );
+	/// ```
+	pub fn wire_extension_types(&self) -> impl Iterator<Item = u16> + '_ {
+		crate::grease::interleave(self.extensions.type_ids(), &self.grease.extensions)
+			.map(|t| t.unwrap_or(crate::GREASE_PLACEHOLDER))
+	}
+
+	/// Return the set of extension types present, GREASE excluded, as a
+	/// fixed-size [`ExtensionBitmap`](crate::ExtensionBitmap) for cheap
+	/// repeated filtering.
+	#[must_use]
+	pub fn extension_bitmap(&self) -> crate::ExtensionBitmap {
+		self.extensions.type_ids().collect()
+	}
+
+	/// Return `(type_id, body_length)` for each extension, in wire order
+	/// with GREASE extensions removed.
+	///
+	/// Lengths are those on the wire, so they stay exact for bodies with
+	/// trailing bytes or GREASE entries that the decoded extensions drop.
+	///
+	/// ```
+	/// let msg = clienthello::Builder::new()
+	///     .server_name("example.com")
+	///     .extension(0x0a0a, &[])
+	///     .alpn(&[b"h2"])
+	///     .build()
+	///     .unwrap();
+	/// let hello = clienthello::parse(&msg).unwrap();
+	/// assert_eq!(hello.extension_lengths(), [(0x0000, 16), (0x0010, 5)]);
+	/// ```
+	#[must_use]
+	pub fn extension_lengths(&self) -> Vec<(u16, u16)> {
+		self
+			.extensions
+			.type_ids()
+			.zip(self.extension_body_lengths.iter().copied())
+			.collect()
+	}
+
+	/// Return the extensions sorted by type identifier, GREASE excluded
+	/// and duplicates kept in wire order.
+	///
+	/// [`extensions`](Self::extensions) iterates in wire order; this view
+	/// is deterministic across clients that shuffle their extensions, for
+	/// snapshot tests and canonical exports. See
+	/// [`Extensions::sorted`](crate::Extensions::sorted).
+	///
+	/// ```
+	/// let msg = clienthello::Builder::new()
+	///     .alpn(&[b"h2"])
+	///     .extension(0x0a0a, &[])
+	///     .server_name("example.com")
+	///     .build()
+	///     .unwrap();
+	/// let hello = clienthello::parse(&msg).unwrap();
+	/// let types: Vec<u16> = hello.extensions_sorted().iter().map(|e| e.type_id()).collect();
+	/// assert_eq!(types, [0x0000, 0x0010]);
+	/// ```
+	#[must_use]
+	pub fn extensions_sorted(&self) -> Vec<&Extension<'a>> {
+		self.extensions.sorted()
+	}
+
+	/// Iterate every extension with the given type identifier, in wire
+	/// order.
+	///
+	/// RFC 8446 forbids repeating an extension, but malformed and
+	/// hostile hellos do, and the parser keeps each copy. The other
+	/// accessors only look at the first occurrence; use this to inspect
+	/// all of them.
+	pub fn extensions_of_type(&self, type_id: u16) -> impl Iterator<Item = &Extension<'a>> {
+		self.extensions.all_by_type(type_id)
+	}
+
+	/// Look up an extension by marker type and return its typed data.
+	///
+	/// Returns the data of the first matching extension, or `None` when
+	/// it is absent. See [`typed`](crate::typed) for the markers.
+	///
+	/// ```
+	/// # fn build() -> Vec<u8> {
+	/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+	/// #     d.extend_from_slice(&[0u8; 32]);
+	/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+	/// #     d
+	/// # }
+	/// # let data = build();
+	/// use clienthello::typed::SupportedVersions;
+	///
+	/// let hello = clienthello::parse(&data).unwrap();
+	/// assert_eq!(hello.get::<SupportedVersions>(), None);
+	/// ```
+	#[must_use]
+	pub fn get<T: crate::FromExtension>(&self) -> Option<T::Output<'_, 'a>> {
+		self.extensions.iter().find_map(T::from_extension)
+	}
+
+	/// Return the offered EC point formats in wire order, empty when the
+	/// `ec_point_formats` extension is absent.
+	///
+	/// A list overrunning its extension is cut at the extension end.
+	#[must_use]
+	pub fn ec_point_formats(&self) -> &[u8] {
+		match self
+			.find_extension(EXT_EC_POINT_FORMATS)
+			.and_then(<[u8]>::split_first)
+		{
+			Some((&len, rest)) => rest.get(..usize::from(len)).unwrap_or(rest),
+			None => &[],
+		}
+	}
+
+	/// Find the raw data of an extension by its type identifier.
+	///
+	/// Searches unknown, malformed, renegotiation info, and Encrypted
+	/// Client Hello extensions.
+	/// Returns `None` for extension types that were parsed into
+	/// structured variants.
+	#[must_use]
+	pub fn find_extension(&self, type_id: u16) -> Option<&[u8]> {
+		self.extensions.iter().find_map(|ext| match ext {
+			Extension::PskExchangeModes(data) if type_id == 0x002D => Some(*data),
+			Extension::RenegotiationInfo(data) if type_id == 0xFF01 => Some(*data),
+			Extension::EncryptedClientHello { raw, .. } if type_id == 0xFE0D => Some(*raw),
+			Extension::Malformed {
+				type_id: id, raw, ..
+			} if *id == type_id => Some(*raw),
+			Extension::Unknown { type_id: id, data } if *id == type_id => Some(*data),
+			_ => None,
+		})
+	}
+}
+
+/// Compare the parts of two extensions that stay the same across
+/// connections from one client: the type and, for decoded lists, their
+/// entries, with key shares reduced to their groups.
+fn stable_eq(a: &Extension<'_>, b: &Extension<'_>) -> bool {
+	match (a, b) {
+		(Extension::SupportedVersions(x), Extension::SupportedVersions(y))
+		| (Extension::SupportedGroups(x), Extension::SupportedGroups(y))
+		| (Extension::SignatureAlgorithms(x), Extension::SignatureAlgorithms(y))
+		| (Extension::KeyShare { groups: x, .. }, Extension::KeyShare { groups: y, .. }) => x == y,
+		(Extension::Alpn(x), Extension::Alpn(y)) => x == y,
+		(Extension::PskExchangeModes(x), Extension::PskExchangeModes(y)) => x == y,
+		_ => a.type_id() == b.type_id(),
+	}
+}
+
+/// Return the offset and length of `part` within `buf`, if it lies
+/// inside it.
+fn span_in(buf: &[u8], part: &[u8]) -> Option<(usize, usize)> {
+	let offset = (part.as_ptr() as usize).checked_sub(buf.as_ptr() as usize)?;
+	(offset.checked_add(part.len())? <= buf.len()).then_some((offset, part.len()))
+}
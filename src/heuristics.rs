@@ -0,0 +1,176 @@
+/* src/heuristics.rs */
+
+use alloc::vec::Vec;
+
+use crate::consts::{
+	EXT_APPLICATION_SETTINGS, EXT_APPLICATION_SETTINGS_OLD, EXT_CHANNEL_ID, EXT_KEY_SHARE,
+};
+use crate::stack::{self, BROTLI, Tally, ZLIB, ZSTD};
+use crate::tls_reader::Reader;
+use crate::{ClientHello, Error, Extension, ExtensionOrder, TlsStack, is_grease};
+
+/// Client family a hello most likely comes from, as guessed by
+/// [`ClientHello::probable_client`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ClientFamily {
+	/// Chrome, Edge, and other Chromium-based browsers.
+	Chromium,
+	/// Firefox and other NSS-based clients.
+	Firefox,
+	/// Safari and other clients of Apple's system TLS.
+	Safari,
+	/// Go `crypto/tls`.
+	Go,
+	/// OpenSSL-based clients: curl, Python, command-line tools.
+	OpenSsl,
+	/// No family stands out.
+	Unknown,
+}
+
+/// How strongly the evidence points at a [`ClientFamily`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Confidence {
+	/// Weak or conflicting evidence; always the case for
+	/// [`ClientFamily::Unknown`].
+	Low,
+	/// Several traits agree and no other family is close.
+	Medium,
+	/// Traits specific to the family dominate.
+	High,
+}
+
+/// Best guess of the client family behind a hello.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProbableClient {
+	/// Most likely family.
+	pub family: ClientFamily,
+	/// Strength of the guess.
+	pub confidence: Confidence,
+}
+
+const FAMILIES: [ClientFamily; 5] = [
+	ClientFamily::Chromium,
+	ClientFamily::Firefox,
+	ClientFamily::Safari,
+	ClientFamily::Go,
+	ClientFamily::OpenSsl,
+];
+
+/// Evidence needed before a family is reported at all.
+const MIN_SCORE: u8 = 3;
+/// Score and lead over the runner-up for [`Confidence::Medium`].
+const MEDIUM_SCORE: u8 = 5;
+const MEDIUM_LEAD: u8 = 3;
+/// Score and lead over the runner-up for [`Confidence::High`].
+const HIGH_SCORE: u8 = 8;
+const HIGH_LEAD: u8 = 5;
+
+/// Key exchange groups.
+const SECP256R1: u16 = 0x0017;
+const X25519: u16 = 0x001d;
+
+pub(crate) fn probable_client(hello: &ClientHello<'_>) -> ProbableClient {
+	let mut tally = Tally::new(FAMILIES);
+	let has = |type_id: u16| hello.extensions.contains(type_id);
+
+	// GREASE: BoringSSL and Apple's stack send it, nobody else does.
+	if hello.has_grease {
+		tally.add(ClientFamily::Chromium, 2);
+		tally.add(ClientFamily::Safari, 2);
+	}
+	if has(EXT_APPLICATION_SETTINGS) || has(EXT_APPLICATION_SETTINGS_OLD) {
+		tally.add(ClientFamily::Chromium, 4);
+	}
+	// `channel_id`, sent by Chromium before version 116.
+	if has(EXT_CHANNEL_ID) {
+		tally.add(ClientFamily::Chromium, 3);
+	}
+	// Only Chromium shuffles; Safari keeps a fixed order with GREASE.
+	match stack::extension_order(hello) {
+		ExtensionOrder::Randomized => tally.add(ClientFamily::Chromium, 3),
+		ExtensionOrder::Fixed if hello.has_grease => tally.add(ClientFamily::Safari, 2),
+		_ => {}
+	}
+
+	match stack::cert_compression(hello).as_slice() {
+		[BROTLI] => tally.add(ClientFamily::Chromium, 1),
+		[ZLIB] => tally.add(ClientFamily::Safari, 2),
+		list if list.contains(&ZSTD) || list.len() >= 3 => tally.add(ClientFamily::Firefox, 2),
+		_ => {}
+	}
+
+	tally.add(ClientFamily::Firefox, stack::nss_extensions(hello));
+	// Firefox sends a P-256 share next to X25519; Chromium and Go send
+	// X25519 alone or with a post-quantum hybrid.
+	let shares = key_share_groups(hello);
+	if shares.contains(&X25519) && shares.contains(&SECP256R1) {
+		tally.add(ClientFamily::Firefox, 2);
+	}
+
+	tally.add(ClientFamily::OpenSsl, stack::openssl_defaults(hello));
+
+	// Cipher ordering.
+	match stack::tls13_cipher_order(hello) {
+		Some(TlsStack::OpenSsl) => tally.add(ClientFamily::OpenSsl, 2),
+		Some(TlsStack::Nss) => tally.add(ClientFamily::Firefox, 2),
+		_ => {}
+	}
+	let mut tls12 = hello.cipher_suites.iter().filter(|&&c| c >> 8 != 0x13);
+	match tls12.next() {
+		// Apple puts AES-256 first; Chromium and Go put AES-128 first.
+		Some(0xc02c) if hello.has_grease => tally.add(ClientFamily::Safari, 2),
+		Some(0xc02b) if !hello.has_grease => tally.add(ClientFamily::Go, 1),
+		_ => {}
+	}
+
+	tally.add(ClientFamily::Go, stack::go_evidence(hello));
+
+	let Some((family, best, lead)) = tally
+		.leader()
+		.filter(|&(_, best, lead)| best >= MIN_SCORE && lead > 0)
+	else {
+		return ProbableClient {
+			family: ClientFamily::Unknown,
+			confidence: Confidence::Low,
+		};
+	};
+	let confidence = if best >= HIGH_SCORE && lead >= HIGH_LEAD {
+		Confidence::High
+	} else if best >= MEDIUM_SCORE && lead >= MEDIUM_LEAD {
+		Confidence::Medium
+	} else {
+		Confidence::Low
+	};
+	ProbableClient { family, confidence }
+}
+
+/// Key share groups, GREASE excluded, whether or not the extension was
+/// decoded.
+fn key_share_groups(hello: &ClientHello<'_>) -> Vec<u16> {
+	match hello.extensions.by_type(EXT_KEY_SHARE) {
+		Some(Extension::KeyShare { groups, .. }) => groups.clone(),
+		Some(ext) => {
+			// Without the decoder no GREASE positions were recorded, so
+			// only RFC 8701 values can be told apart here.
+			let body = ext.encode_body();
+			let mut groups = Vec::new();
+			let _: Result<(), Error> = Reader::new(&body).read_nested_u16("key shares", |r| {
+				while !r.is_empty() {
+					let group = r.read_u16("key share group")?;
+					r.read_vec_u16("key exchange")?;
+					if !is_grease(group) {
+						groups.push(group);
+					}
+				}
+				Ok(())
+			});
+			groups
+		}
+		None => Vec::new(),
+	}
+}
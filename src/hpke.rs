@@ -0,0 +1,213 @@
+/* src/hpke.rs */
+
+//! HPKE base mode (RFC 9180) for the ECH suites this crate offers:
+//! DHKEM(X25519, HKDF-SHA256) with HKDF-SHA256 and AES-GCM.
+
+use alloc::vec::Vec;
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes128Gcm, Aes256Gcm, KeyInit};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// DHKEM(X25519, HKDF-SHA256).
+pub(crate) const KEM_X25519: u16 = 0x0020;
+/// HKDF-SHA256.
+pub(crate) const KDF_HKDF_SHA256: u16 = 0x0001;
+/// AES-128-GCM.
+pub(crate) const AEAD_AES_128_GCM: u16 = 0x0001;
+/// AES-256-GCM.
+pub(crate) const AEAD_AES_256_GCM: u16 = 0x0002;
+/// Authentication tag length of both AEADs.
+pub(crate) const TAG_LEN: usize = 16;
+
+/// Check whether a KDF / AEAD pair is implemented.
+pub(crate) fn supports(kdf_id: u16, aead_id: u16) -> bool {
+	kdf_id == KDF_HKDF_SHA256 && matches!(aead_id, AEAD_AES_128_GCM | AEAD_AES_256_GCM)
+}
+
+/// Encryption context from [`setup_base_s`].
+pub(crate) struct Context {
+	aead_id: u16,
+	key: Vec<u8>,
+	nonce: Vec<u8>,
+}
+
+impl Context {
+	/// Encrypt the first message of the context (sequence number 0).
+	pub(crate) fn seal(&self, aad: &[u8], plaintext: &[u8]) -> Option<Vec<u8>> {
+		let payload = Payload {
+			msg: plaintext,
+			aad,
+		};
+		let nonce = self.nonce.as_slice().into();
+		match self.aead_id {
+			AEAD_AES_128_GCM => Aes128Gcm::new_from_slice(&self.key)
+				.ok()?
+				.encrypt(nonce, payload)
+				.ok(),
+			AEAD_AES_256_GCM => Aes256Gcm::new_from_slice(&self.key)
+				.ok()?
+				.encrypt(nonce, payload)
+				.ok(),
+			_ => None,
+		}
+	}
+
+	#[cfg(test)]
+	pub(crate) fn open(&self, aad: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+		let payload = Payload {
+			msg: ciphertext,
+			aad,
+		};
+		let nonce = self.nonce.as_slice().into();
+		match self.aead_id {
+			AEAD_AES_128_GCM => Aes128Gcm::new_from_slice(&self.key)
+				.ok()?
+				.decrypt(nonce, payload)
+				.ok(),
+			AEAD_AES_256_GCM => Aes256Gcm::new_from_slice(&self.key)
+				.ok()?
+				.decrypt(nonce, payload)
+				.ok(),
+			_ => None,
+		}
+	}
+}
+
+/// Set up a base-mode sender context for recipient key `pk_r`, using
+/// `sk_e` as the ephemeral secret. Returns the encapsulated key.
+pub(crate) fn setup_base_s(
+	pk_r: [u8; 32],
+	sk_e: [u8; 32],
+	aead_id: u16,
+	info: &[u8],
+) -> ([u8; 32], Context) {
+	let sk_e = StaticSecret::from(sk_e);
+	let enc = PublicKey::from(&sk_e).to_bytes();
+	let dh = sk_e.diffie_hellman(&PublicKey::from(pk_r));
+	let shared_secret = shared_secret(dh.as_bytes(), &enc, &pk_r);
+	(enc, key_schedule(&shared_secret, aead_id, info))
+}
+
+/// Set up the matching recipient context.
+#[cfg(test)]
+pub(crate) fn setup_base_r(enc: [u8; 32], sk_r: [u8; 32], aead_id: u16, info: &[u8]) -> Context {
+	let sk_r = StaticSecret::from(sk_r);
+	let pk_r = PublicKey::from(&sk_r).to_bytes();
+	let dh = sk_r.diffie_hellman(&PublicKey::from(enc));
+	let shared_secret = shared_secret(dh.as_bytes(), &enc, &pk_r);
+	key_schedule(&shared_secret, aead_id, info)
+}
+
+/// DHKEM `ExtractAndExpand` (RFC 9180 §4.1).
+fn shared_secret(dh: &[u8], enc: &[u8; 32], pk_r: &[u8; 32]) -> Vec<u8> {
+	let suite_id = kem_suite_id();
+	let mut kem_context = Vec::with_capacity(64);
+	kem_context.extend_from_slice(enc);
+	kem_context.extend_from_slice(pk_r);
+	let eae_prk = labeled_extract(b"", &suite_id, b"eae_prk", dh);
+	labeled_expand(&eae_prk, &suite_id, b"shared_secret", &kem_context, 32)
+}
+
+/// Base-mode `KeySchedule` (RFC 9180 §5.1).
+fn key_schedule(shared_secret: &[u8], aead_id: u16, info: &[u8]) -> Context {
+	let suite_id = hpke_suite_id(aead_id);
+	let psk_id_hash = labeled_extract(b"", &suite_id, b"psk_id_hash", b"");
+	let info_hash = labeled_extract(b"", &suite_id, b"info_hash", info);
+	let mut context = Vec::with_capacity(65);
+	context.push(0x00);
+	context.extend_from_slice(&psk_id_hash);
+	context.extend_from_slice(&info_hash);
+	let secret = labeled_extract(shared_secret, &suite_id, b"secret", b"");
+	let key_len = if aead_id == AEAD_AES_256_GCM { 32 } else { 16 };
+	Context {
+		aead_id,
+		key: labeled_expand(&secret, &suite_id, b"key", &context, key_len),
+		nonce: labeled_expand(&secret, &suite_id, b"base_nonce", &context, 12),
+	}
+}
+
+fn kem_suite_id() -> Vec<u8> {
+	let mut id = b"KEM".to_vec();
+	id.extend_from_slice(&KEM_X25519.to_be_bytes());
+	id
+}
+
+fn hpke_suite_id(aead_id: u16) -> Vec<u8> {
+	let mut id = b"HPKE".to_vec();
+	id.extend_from_slice(&KEM_X25519.to_be_bytes());
+	id.extend_from_slice(&KDF_HKDF_SHA256.to_be_bytes());
+	id.extend_from_slice(&aead_id.to_be_bytes());
+	id
+}
+
+fn labeled_extract(salt: &[u8], suite_id: &[u8], label: &[u8], ikm: &[u8]) -> [u8; 32] {
+	let mut input = b"HPKE-v1".to_vec();
+	input.extend_from_slice(suite_id);
+	input.extend_from_slice(label);
+	input.extend_from_slice(ikm);
+	Hkdf::<Sha256>::extract(Some(salt), &input).0.into()
+}
+
+fn labeled_expand(prk: &[u8], suite_id: &[u8], label: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+	let mut labeled_info = u16::try_from(len)
+		.unwrap_or(u16::MAX)
+		.to_be_bytes()
+		.to_vec();
+	labeled_info.extend_from_slice(b"HPKE-v1");
+	labeled_info.extend_from_slice(suite_id);
+	labeled_info.extend_from_slice(label);
+	labeled_info.extend_from_slice(info);
+	let mut out = alloc::vec![0u8; len];
+	// `prk` is always a full SHA-256 output and `len` at most 32, so
+	// neither call can fail.
+	if let Ok(hkdf) = Hkdf::<Sha256>::from_prk(prk) {
+		let _ = hkdf.expand(&labeled_info, &mut out);
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn unhex<const N: usize>(s: &str) -> [u8; N] {
+		let mut out = [0u8; N];
+		for (i, b) in out.iter_mut().enumerate() {
+			*b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+		}
+		out
+	}
+
+	/// RFC 9180 Appendix A.1.1: DHKEM(X25519, HKDF-SHA256), HKDF-SHA256,
+	/// AES-128-GCM, base mode, first encryption.
+	#[test]
+	fn rfc9180_base_vector() {
+		let sk_e = unhex::<32>("52c4a758a802cd8b936eceea314432798d5baf2d7e9235dc084ab1b9cfa2f736");
+		let sk_r = unhex::<32>("4612c550263fc8ad58375df3f557aac531d26850903e55a9f23f21d8534e8ac8");
+		let pk_r = PublicKey::from(&StaticSecret::from(sk_r)).to_bytes();
+		let info = b"Ode on a Grecian Urn";
+		let (enc, ctx) = setup_base_s(pk_r, sk_e, AEAD_AES_128_GCM, info);
+		assert_eq!(
+			enc,
+			unhex::<32>("37fda3567bdbd628e88668c3c8d7e97d1d1253b6d4ea6d44c150f741f1bf4431")
+		);
+		assert_eq!(ctx.key, unhex::<16>("4531685d41d65f03dc48f6b8302c05b0"));
+		assert_eq!(ctx.nonce, unhex::<12>("56d890e5accaaf011cff4b7d"));
+		let ct = ctx
+			.seal(b"Count-0", b"Beauty is truth, truth beauty")
+			.unwrap();
+		assert_eq!(
+			ct,
+			unhex::<45>(
+				"f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a"
+			)
+		);
+		let opened = setup_base_r(enc, sk_r, AEAD_AES_128_GCM, info)
+			.open(b"Count-0", &ct)
+			.unwrap();
+		assert_eq!(opened, b"Beauty is truth, truth beauty");
+	}
+}
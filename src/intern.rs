@@ -0,0 +1,112 @@
+/* src/intern.rs */
+
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::ClientHello;
+
+/// Deduplicating store for SNI hostnames and ALPN identifiers.
+///
+/// A parsed [`ClientHello`] borrows from its input buffer, so keeping
+/// its names around means copying them. Sensors that hold millions of
+/// records see the same few thousand hostnames and a handful of ALPN
+/// identifiers over and over; interning makes every copy of a value
+/// share one allocation.
+///
+/// Interned values are reference counted. [`Interner::purge`] drops the
+/// values no record refers to any more.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x3b, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x10, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x0a, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x07]);
+/// #     d.extend_from_slice(b"example");
+/// #     d
+/// # }
+/// # let msg = build();
+/// use std::sync::Arc;
+/// use clienthello::Interner;
+///
+/// let mut interner = Interner::new();
+/// let first = interner.server_name(&clienthello::parse(&msg).unwrap()).unwrap();
+/// let second = interner.server_name(&clienthello::parse(&msg).unwrap()).unwrap();
+/// assert_eq!(&*first, "example");
+/// assert!(Arc::ptr_eq(&first, &second));
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+	strings: BTreeSet<Arc<str>>,
+	bytes: BTreeSet<Arc<[u8]>>,
+}
+
+impl Interner {
+	/// Create an empty interner.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Number of distinct values held.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.strings.len() + self.bytes.len()
+	}
+
+	/// Check whether no values are held.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.strings.is_empty() && self.bytes.is_empty()
+	}
+
+	/// Return the shared copy of `value`, storing it on first use.
+	pub fn intern_str(&mut self, value: &str) -> Arc<str> {
+		if let Some(shared) = self.strings.get(value) {
+			return Arc::clone(shared);
+		}
+		let shared: Arc<str> = Arc::from(value);
+		self.strings.insert(Arc::clone(&shared));
+		shared
+	}
+
+	/// Return the shared copy of `value`, storing it on first use.
+	pub fn intern_bytes(&mut self, value: &[u8]) -> Arc<[u8]> {
+		if let Some(shared) = self.bytes.get(value) {
+			return Arc::clone(shared);
+		}
+		let shared: Arc<[u8]> = Arc::from(value);
+		self.bytes.insert(Arc::clone(&shared));
+		shared
+	}
+
+	/// Intern the hello's SNI hostname, as returned by
+	/// [`ClientHello::server_name`].
+	pub fn server_name(&mut self, hello: &ClientHello<'_>) -> Option<Arc<str>> {
+		hello.server_name().map(|name| self.intern_str(name))
+	}
+
+	/// Intern the hello's ALPN identifiers, in offered order.
+	pub fn alpn_protocols(&mut self, hello: &ClientHello<'_>) -> Vec<Arc<[u8]>> {
+		hello
+			.alpn_protocols()
+			.iter()
+			.map(|proto| self.intern_bytes(proto))
+			.collect()
+	}
+
+	/// Drop the values that are only referenced by the interner.
+	pub fn purge(&mut self) {
+		self.strings.retain(|s| Arc::strong_count(s) > 1);
+		self.bytes.retain(|b| Arc::strong_count(b) > 1);
+	}
+
+	/// Drop every value. Copies already handed out stay valid.
+	pub fn clear(&mut self) {
+		self.strings.clear();
+		self.bytes.clear();
+	}
+}
@@ -0,0 +1,169 @@
+/* src/ja3.rs */
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Write};
+
+use md5::{Digest, Md5};
+
+use crate::{ClientHello, ServerHello};
+
+/// Revision of the JA3 algorithm, for
+/// [`ClientHello::ja3_with`](crate::ClientHello::ja3_with).
+///
+/// The unversioned methods such as [`ja3`](crate::ClientHello::ja3)
+/// compute [`V1`](Self::V1) and always will, so rulesets built on their
+/// output keep matching. A corner case settled differently by a later
+/// revision becomes a new variant, adopted by passing it explicitly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Ja3Version {
+	/// The salesforce/ja3 algorithm as Zeek and Suricata compute it:
+	/// decimal fields in wire order, GREASE excluded.
+	#[default]
+	V1,
+}
+
+impl Ja3Version {
+	/// The newest revision this crate implements.
+	pub const LATEST: Self = Self::V1;
+}
+
+/// Build the JA3 string: `version,ciphers,extensions,groups,formats`.
+///
+/// Values are decimal and joined with `-`; GREASE values are excluded,
+/// matching what Zeek and Suricata compute.
+pub(crate) fn ja3_string(hello: &ClientHello<'_>) -> String {
+	build(
+		hello.legacy_version,
+		&hello.cipher_suites,
+		hello.extensions.type_ids(),
+		hello.supported_groups(),
+		hello.ec_point_formats(),
+	)
+}
+
+/// Build the JA3 string as revision `version` defines it.
+pub(crate) fn ja3_string_with(hello: &ClientHello<'_>, version: Ja3Version) -> String {
+	match version {
+		Ja3Version::V1 => ja3_string(hello),
+	}
+}
+
+/// Build the JA3N string: [`ja3_string`] with the extension ids sorted.
+pub(crate) fn ja3n_string(hello: &ClientHello<'_>) -> String {
+	let mut types: Vec<u16> = hello.extensions.type_ids().collect();
+	types.sort_unstable();
+	build(
+		hello.legacy_version,
+		&hello.cipher_suites,
+		types,
+		hello.supported_groups(),
+		hello.ec_point_formats(),
+	)
+}
+
+/// Build the JA3N string as revision `version` defines it.
+pub(crate) fn ja3n_string_with(hello: &ClientHello<'_>, version: Ja3Version) -> String {
+	match version {
+		Ja3Version::V1 => ja3n_string(hello),
+	}
+}
+
+/// Lowercase hex MD5 of [`ja3_string`].
+pub(crate) fn ja3_hash(hello: &ClientHello<'_>) -> String {
+	md5_hex(&ja3_string(hello))
+}
+
+/// Lowercase hex MD5 of [`ja3n_string`].
+pub(crate) fn ja3n_hash(hello: &ClientHello<'_>) -> String {
+	md5_hex(&ja3n_string(hello))
+}
+
+/// Build the JA3S string: `version,cipher,extensions`, decimal, with
+/// extensions in wire order.
+pub(crate) fn ja3s_string(hello: &ServerHello<'_>) -> String {
+	let mut out = String::new();
+	let _ = write!(out, "{},{},", hello.legacy_version, hello.cipher_suite);
+	push_list(&mut out, hello.extensions.type_ids());
+	out
+}
+
+/// Build the JA3S string as revision `version` defines it.
+pub(crate) fn ja3s_string_with(hello: &ServerHello<'_>, version: Ja3Version) -> String {
+	match version {
+		Ja3Version::V1 => ja3s_string(hello),
+	}
+}
+
+/// Lowercase hex MD5 of [`ja3s_string`].
+pub(crate) fn ja3s_hash(hello: &ServerHello<'_>) -> String {
+	md5_hex(&ja3s_string(hello))
+}
+
+/// Build the JA3 string from fields already stripped of GREASE.
+pub(crate) fn build<I>(
+	version: u16,
+	cipher_suites: &[u16],
+	extension_types: I,
+	groups: &[u16],
+	formats: &[u8],
+) -> String
+where
+	I: IntoIterator<Item = u16>,
+{
+	let mut out = String::new();
+	let _ = write!(out, "{version},");
+	push_list(&mut out, cipher_suites);
+	out.push(',');
+	push_list(&mut out, extension_types);
+	out.push(',');
+	push_list(&mut out, groups);
+	out.push(',');
+	push_list(&mut out, formats);
+	out
+}
+
+pub(crate) fn md5_hex(s: &str) -> String {
+	let digest = Md5::digest(s.as_bytes());
+	let mut out = String::with_capacity(32);
+	for b in digest {
+		let _ = write!(out, "{b:02x}");
+	}
+	out
+}
+
+/// Check `fingerprint` against either the JA3 string or its MD5 hash.
+pub(crate) fn matches(hello: &ClientHello<'_>, fingerprint: &str) -> bool {
+	let fingerprint = fingerprint.trim();
+	if fingerprint.len() == 32 && !fingerprint.contains(',') {
+		return fingerprint.eq_ignore_ascii_case(&ja3_hash(hello));
+	}
+	fingerprint == ja3_string(hello)
+}
+
+/// Check `fingerprint` against the JA3 string or hash of revision
+/// `version`.
+pub(crate) fn matches_with(
+	hello: &ClientHello<'_>,
+	fingerprint: &str,
+	version: Ja3Version,
+) -> bool {
+	match version {
+		Ja3Version::V1 => matches(hello, fingerprint),
+	}
+}
+
+fn push_list<I>(out: &mut String, values: I)
+where
+	I: IntoIterator,
+	I::Item: Display,
+{
+	for (i, v) in values.into_iter().enumerate() {
+		if i > 0 {
+			out.push('-');
+		}
+		let _ = write!(out, "{v}");
+	}
+}
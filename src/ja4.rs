@@ -0,0 +1,298 @@
+/* src/ja4.rs */
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+use crate::consts::{EXT_ALPN, EXT_SERVER_NAME};
+use crate::{ClientHello, ServerHello, Transport};
+
+/// Revision of the FoxIO JA4 algorithm, for
+/// [`ClientHello::ja4_with`](crate::ClientHello::ja4_with).
+///
+/// The unversioned methods such as [`ja4`](crate::ClientHello::ja4)
+/// compute [`V1`](Self::V1) and always will, so rulesets built on their
+/// output keep matching. When the spec changes how a section is
+/// computed, the new rules become a new variant, adopted by passing it
+/// explicitly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Ja4Version {
+	/// JA4 as FoxIO specifies it in version 1: non-alphanumeric ALPN
+	/// values are encoded by the first and last hex digit, and the
+	/// signature algorithms are appended in wire order.
+	#[default]
+	V1,
+}
+
+impl Ja4Version {
+	/// The newest revision this crate implements.
+	pub const LATEST: Self = Self::V1;
+}
+
+/// The ClientHello fields JA4 covers, with GREASE removed.
+///
+/// Gathered from a parsed [`ClientHello`] or straight from the wire by
+/// [`fingerprints`](crate::fingerprints).
+pub(crate) struct Fields<'x> {
+	pub(crate) transport: Transport,
+	/// Highest supported version, or the legacy version field.
+	pub(crate) version: u16,
+	pub(crate) cipher_suites: &'x [u16],
+	/// Extension types in wire order.
+	pub(crate) extensions: &'x [u16],
+	/// First ALPN protocol.
+	pub(crate) alpn: Option<&'x [u8]>,
+	pub(crate) signature_algorithms: &'x [u16],
+}
+
+impl<'x> Fields<'x> {
+	fn of(hello: &'x ClientHello<'_>, extensions: &'x [u16]) -> Self {
+		Self {
+			transport: hello.transport,
+			version: hello
+				.supported_versions()
+				.iter()
+				.copied()
+				.max()
+				.unwrap_or(hello.legacy_version),
+			cipher_suites: &hello.cipher_suites,
+			extensions,
+			alpn: hello.alpn_protocols().first().copied(),
+			signature_algorithms: hello.signature_algorithms(),
+		}
+	}
+}
+
+/// Build the JA4 fingerprint (`a_b_c`, FoxIO JA4 spec).
+pub(crate) fn ja4(hello: &ClientHello<'_>) -> String {
+	let extensions: Vec<u16> = hello.extensions.type_ids().collect();
+	hashed(&Fields::of(hello, &extensions))
+}
+
+/// Build the JA4 fingerprint as revision `version` defines it.
+pub(crate) fn ja4_with(hello: &ClientHello<'_>, version: Ja4Version) -> String {
+	match version {
+		Ja4Version::V1 => ja4(hello),
+	}
+}
+
+/// Build the raw JA4 fingerprint as revision `version` defines it.
+pub(crate) fn ja4_raw_with(hello: &ClientHello<'_>, version: Ja4Version) -> String {
+	match version {
+		Ja4Version::V1 => ja4_raw(hello),
+	}
+}
+
+/// Build the raw JA4 fingerprint (`JA4_r`) with unhashed sections.
+pub(crate) fn ja4_raw(hello: &ClientHello<'_>) -> String {
+	let extensions: Vec<u16> = hello.extensions.type_ids().collect();
+	let fields = Fields::of(hello, &extensions);
+	let (ciphers, extensions) = sections(&fields);
+	let mut out = prefix(&fields);
+	out.push('_');
+	out.push_str(&ciphers);
+	out.push('_');
+	out.push_str(&extensions);
+	out
+}
+
+/// Build the JA4 fingerprint of `fields`.
+pub(crate) fn hashed(fields: &Fields<'_>) -> String {
+	let (ciphers, extensions) = sections(fields);
+	let mut out = prefix(fields);
+	out.push('_');
+	push_truncated_hash(&mut out, &ciphers);
+	out.push('_');
+	push_truncated_hash(&mut out, &extensions);
+	out
+}
+
+/// Truncated SHA-256 of the extension types in wire order, as the `c`
+/// section of JA4 hashes them but unsorted and without signature
+/// algorithms.
+pub(crate) fn extension_order_hash(extensions: &[u16]) -> String {
+	let mut out = String::with_capacity(12);
+	push_truncated_hash(&mut out, &hex_list(extensions));
+	out
+}
+
+/// Extension order hash as revision `version` defines it.
+pub(crate) fn extension_order_hash_with(extensions: &[u16], version: Ja4Version) -> String {
+	match version {
+		Ja4Version::V1 => extension_order_hash(extensions),
+	}
+}
+
+/// Check `fingerprint` against either the JA4 or the JA4_r form.
+pub(crate) fn matches(hello: &ClientHello<'_>, fingerprint: &str) -> bool {
+	let fingerprint = fingerprint.trim();
+	fingerprint.eq_ignore_ascii_case(&ja4(hello)) || fingerprint.eq_ignore_ascii_case(&ja4_raw(hello))
+}
+
+/// Check `fingerprint` against the JA4 or JA4_r form of revision
+/// `version`.
+pub(crate) fn matches_with(
+	hello: &ClientHello<'_>,
+	fingerprint: &str,
+	version: Ja4Version,
+) -> bool {
+	match version {
+		Ja4Version::V1 => matches(hello, fingerprint),
+	}
+}
+
+/// Build the JA4S fingerprint (`a_b_c`, FoxIO JA4S spec).
+pub(crate) fn ja4s(hello: &ServerHello<'_>) -> String {
+	let mut out = ja4s_prefix(hello);
+	push_truncated_hash(&mut out, &ja4s_extensions(hello));
+	out
+}
+
+/// Build the JA4S fingerprint as revision `version` defines it.
+pub(crate) fn ja4s_with(hello: &ServerHello<'_>, version: Ja4Version) -> String {
+	match version {
+		Ja4Version::V1 => ja4s(hello),
+	}
+}
+
+/// Build the raw JA4S fingerprint as revision `version` defines it.
+pub(crate) fn ja4s_raw_with(hello: &ServerHello<'_>, version: Ja4Version) -> String {
+	match version {
+		Ja4Version::V1 => ja4s_raw(hello),
+	}
+}
+
+/// Build the raw JA4S fingerprint (`JA4S_r`) with the extension list
+/// unhashed.
+pub(crate) fn ja4s_raw(hello: &ServerHello<'_>) -> String {
+	let mut out = ja4s_prefix(hello);
+	out.push_str(&ja4s_extensions(hello));
+	out
+}
+
+/// The `a` and `b` sections of JA4S, each followed by `_`: protocol,
+/// version, extension count, and ALPN, then the cipher suite.
+fn ja4s_prefix(hello: &ServerHello<'_>) -> String {
+	let mut out = String::with_capacity(25);
+	out.push(protocol(hello.transport));
+	out.push_str(version_code(hello.version()));
+	let _ = write!(out, "{:02}", hello.extensions.len().min(99));
+	push_alpn(&mut out, hello.alpn());
+	let _ = write!(out, "_{:04x}_", hello.cipher_suite);
+	out
+}
+
+/// The unhashed `c` section of JA4S: extensions in wire order.
+fn ja4s_extensions(hello: &ServerHello<'_>) -> String {
+	let extensions: Vec<u16> = hello.extensions.type_ids().collect();
+	hex_list(&extensions)
+}
+
+/// The `a` section: protocol, version, SNI, counts, and ALPN.
+fn prefix(fields: &Fields<'_>) -> String {
+	let mut out = String::with_capacity(36);
+	out.push(protocol(fields.transport));
+	out.push_str(version_code(fields.version));
+	let has_sni = fields.extensions.contains(&EXT_SERVER_NAME);
+	out.push(if has_sni { 'd' } else { 'i' });
+	let _ = write!(
+		out,
+		"{:02}{:02}",
+		fields.cipher_suites.len().min(99),
+		fields.extensions.len().min(99)
+	);
+	push_alpn(&mut out, fields.alpn);
+	out
+}
+
+fn protocol(transport: Transport) -> char {
+	match transport {
+		Transport::TlsRecord | Transport::SslV2 => 't',
+		Transport::Handshake => 'q',
+		Transport::Dtls => 'd',
+	}
+}
+
+/// Two-character code of a protocol version. The ClientHello passes its
+/// highest non-GREASE supported version, falling back to the legacy
+/// version field.
+fn version_code(version: u16) -> &'static str {
+	match version {
+		0x0304 => "13",
+		0x0303 => "12",
+		0x0302 => "11",
+		0x0301 => "10",
+		0x0300 => "s3",
+		0x0002 => "s2",
+		0xfeff => "d1",
+		0xfefd => "d2",
+		0xfefc => "d3",
+		_ => "00",
+	}
+}
+
+/// First and last character of the first ALPN value, or of its hex
+/// form when either is not ASCII alphanumeric.
+fn push_alpn(out: &mut String, proto: Option<&[u8]>) {
+	let (Some(&first), Some(&last)) = (proto.and_then(<[u8]>::first), proto.and_then(<[u8]>::last))
+	else {
+		out.push_str("00");
+		return;
+	};
+	if first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric() {
+		out.push(char::from(first));
+		out.push(char::from(last));
+	} else {
+		let _ = write!(out, "{:x}{:x}", first >> 4, last & 0x0f);
+	}
+}
+
+/// The unhashed `b` and `c` sections: sorted cipher suites, and sorted
+/// extensions (without SNI and ALPN) followed by signature algorithms in
+/// wire order.
+fn sections(fields: &Fields<'_>) -> (String, String) {
+	let mut ciphers = fields.cipher_suites.to_vec();
+	ciphers.sort_unstable();
+
+	let mut extensions: Vec<u16> = fields
+		.extensions
+		.iter()
+		.copied()
+		.filter(|&t| t != EXT_SERVER_NAME && t != EXT_ALPN)
+		.collect();
+	extensions.sort_unstable();
+	let mut c = hex_list(&extensions);
+	let sig_algs = fields.signature_algorithms;
+	if !c.is_empty() && !sig_algs.is_empty() {
+		c.push('_');
+		c.push_str(&hex_list(sig_algs));
+	}
+	(hex_list(&ciphers), c)
+}
+
+fn hex_list(values: &[u16]) -> String {
+	let mut out = String::with_capacity(values.len() * 5);
+	for (i, v) in values.iter().enumerate() {
+		if i > 0 {
+			out.push(',');
+		}
+		let _ = write!(out, "{v:04x}");
+	}
+	out
+}
+
+/// First 12 hex characters of the SHA-256 of `section`, or twelve zeros
+/// for an empty section.
+fn push_truncated_hash(out: &mut String, section: &str) {
+	if section.is_empty() {
+		out.push_str("000000000000");
+		return;
+	}
+	for b in &Sha256::digest(section.as_bytes())[..6] {
+		let _ = write!(out, "{b:02x}");
+	}
+}
@@ -0,0 +1,242 @@
+/* src/json.rs */
+
+//! Versioned JSON records of parsed hellos for long-lived stores.
+//!
+//! [`HelloRecord`] is an owned, documented JSON shape that is kept
+//! stable across crate releases, unlike the derived `serde` form of
+//! [`ClientHello`], which follows the Rust types. Every record carries
+//! [`JSON_SCHEMA_VERSION`]:
+//!
+//! - Within a schema version, fields are only ever added. Readers must
+//!   ignore fields they do not know, and [`from_json`] does so.
+//! - Removing or renaming a field, or changing what it holds, bumps the
+//!   version. [`from_json`] reads records of every version up to its
+//!   own, filling fields a record lacks with their defaults.
+//!
+//! Version 1 has these fields:
+//!
+//! | Field | Type | Contents |
+//! |-------|------|----------|
+//! | `schema_version` | number | `1` |
+//! | `transport` | string | `tls_record`, `handshake`, `dtls`, or `sslv2` |
+//! | `legacy_version` | number | Legacy protocol version |
+//! | `random` | string | Client random, lowercase hex |
+//! | `session_id` | string | Legacy session ID, lowercase hex |
+//! | `cipher_suites` | number array | Cipher suites, GREASE removed |
+//! | `compression_methods` | number array | Compression methods |
+//! | `extensions` | object array | `type`, body `length`, and for bodies this crate does not decode, `data` in hex; GREASE removed |
+//! | `grease` | object | GREASE positions in `cipher_suites`, `extensions`, `supported_versions`, `supported_groups`, and `key_share` |
+//! | `server_name` | string or null | SNI hostname |
+//! | `alpn` | string array | ALPN protocols, non-UTF-8 bytes replaced |
+//! | `supported_versions` | number array | Supported Versions, GREASE removed |
+//! | `supported_groups` | number array | Supported Groups, GREASE removed |
+//! | `signature_algorithms` | number array | Signature Algorithms |
+//! | `key_share_groups` | number array | Key Share groups, GREASE removed |
+//!
+//! ```
+//! use clienthello::Builder;
+//! use clienthello::json::{self, HelloRecord};
+//!
+//! let msg = Builder::new().cipher_suites(&[0x1301]).server_name("example.com").build().unwrap();
+//! let hello = clienthello::parse(&msg).unwrap();
+//! let text = json::to_json(&hello);
+//! assert!(text.starts_with(r#"{"schema_version":1,"transport":"handshake""#));
+//! assert_eq!(json::from_json(&text).unwrap(), HelloRecord::from(&hello));
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ClientHello, Extension, Transport};
+
+/// Version of the [`HelloRecord`] JSON layout.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Errors produced while reading a [`HelloRecord`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum JsonError {
+	/// The record was written with a newer schema version, or has none.
+	#[error("unsupported schema version {found}, expected at most {supported}")]
+	SchemaVersion {
+		/// Version found in the record.
+		found: u32,
+		/// Newest version this build reads.
+		supported: u32,
+	},
+
+	/// The input is not a JSON record.
+	#[error("json: {0}")]
+	Json(#[from] serde_json::Error),
+}
+
+/// A hello in the versioned JSON layout described in the
+/// [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct HelloRecord {
+	/// Layout version the record was written with.
+	pub schema_version: u32,
+	/// Framing: `tls_record`, `handshake`, `dtls`, or `sslv2`.
+	pub transport: String,
+	/// Legacy protocol version.
+	pub legacy_version: u16,
+	/// Client random in lowercase hex.
+	pub random: String,
+	/// Legacy session ID in lowercase hex.
+	pub session_id: String,
+	/// Cipher suites with GREASE removed.
+	pub cipher_suites: Vec<u16>,
+	/// Compression methods.
+	pub compression_methods: Vec<u8>,
+	/// Extensions in wire order with GREASE removed.
+	pub extensions: Vec<ExtensionRecord>,
+	/// GREASE positions.
+	pub grease: GreaseRecord,
+	/// SNI hostname.
+	pub server_name: Option<String>,
+	/// ALPN protocols, with non-UTF-8 bytes replaced.
+	pub alpn: Vec<String>,
+	/// Supported Versions with GREASE removed.
+	pub supported_versions: Vec<u16>,
+	/// Supported Groups with GREASE removed.
+	pub supported_groups: Vec<u16>,
+	/// Signature Algorithms.
+	pub signature_algorithms: Vec<u16>,
+	/// Key Share groups with GREASE removed.
+	pub key_share_groups: Vec<u16>,
+}
+
+/// One extension of a [`HelloRecord`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct ExtensionRecord {
+	/// Extension type.
+	#[serde(rename = "type")]
+	pub type_id: u16,
+	/// Body length on the wire.
+	pub length: u16,
+	/// Body in lowercase hex, for extensions this crate does not decode
+	/// and for Encrypted Client Hello, which version 1 carried undecoded.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub data: Option<String>,
+}
+
+/// GREASE positions of a [`HelloRecord`], as in
+/// [`GreasePositions`](crate::GreasePositions).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[non_exhaustive]
+pub struct GreaseRecord {
+	/// Positions within the cipher suite list.
+	pub cipher_suites: Vec<usize>,
+	/// Positions within the extension list.
+	pub extensions: Vec<usize>,
+	/// Positions within the Supported Versions list.
+	pub supported_versions: Vec<usize>,
+	/// Positions within the Supported Groups list.
+	pub supported_groups: Vec<usize>,
+	/// Positions within the Key Share entries.
+	pub key_share: Vec<usize>,
+}
+
+impl From<&ClientHello<'_>> for HelloRecord {
+	fn from(hello: &ClientHello<'_>) -> Self {
+		let transport = match hello.transport {
+			Transport::TlsRecord => "tls_record",
+			Transport::Handshake => "handshake",
+			Transport::Dtls => "dtls",
+			Transport::SslV2 => "sslv2",
+		};
+		let extensions = hello
+			.extensions
+			.iter()
+			.zip(&hello.extension_body_lengths)
+			.map(|(ext, &length)| ExtensionRecord {
+				type_id: ext.type_id(),
+				length,
+				data: match ext {
+					Extension::Unknown { data: raw, .. }
+					| Extension::Malformed { raw, .. }
+					| Extension::EncryptedClientHello { raw, .. } => Some(hex(raw)),
+					_ => None,
+				},
+			})
+			.collect();
+		let grease = &hello.grease;
+		Self {
+			schema_version: JSON_SCHEMA_VERSION,
+			transport: transport.into(),
+			legacy_version: hello.legacy_version,
+			random: hex(hello.random),
+			session_id: hex(hello.session_id),
+			cipher_suites: hello.cipher_suites.clone(),
+			compression_methods: hello.compression_methods.to_vec(),
+			extensions,
+			grease: GreaseRecord {
+				cipher_suites: grease.cipher_suites.clone(),
+				extensions: grease.extensions.clone(),
+				supported_versions: grease.supported_versions.clone(),
+				supported_groups: grease.supported_groups.clone(),
+				key_share: grease.key_share.clone(),
+			},
+			server_name: hello.server_name().map(String::from),
+			alpn: hello
+				.alpn_protocols()
+				.iter()
+				.map(|p| String::from_utf8_lossy(p).into_owned())
+				.collect(),
+			supported_versions: hello.supported_versions().to_vec(),
+			supported_groups: hello.supported_groups().to_vec(),
+			signature_algorithms: hello.signature_algorithms().to_vec(),
+			key_share_groups: hello.key_share_groups().to_vec(),
+		}
+	}
+}
+
+/// Serialize a hello as a [`HelloRecord`] JSON object.
+#[must_use]
+pub fn to_json(hello: &ClientHello<'_>) -> String {
+	let Ok(text) = serde_json::to_string(&HelloRecord::from(hello)) else {
+		unreachable!("records have string keys and no fallible fields")
+	};
+	text
+}
+
+/// Read a [`HelloRecord`] written by this or an earlier release.
+///
+/// # Errors
+///
+/// Returns [`JsonError::SchemaVersion`] when the record was written with
+/// a newer schema version or lacks one, or [`JsonError::Json`] when the
+/// input is not a record.
+pub fn from_json(text: &str) -> Result<HelloRecord, JsonError> {
+	// Check the version first: a newer layout may not fit this one.
+	let Version { schema_version } = serde_json::from_str(text)?;
+	if schema_version == 0 || schema_version > JSON_SCHEMA_VERSION {
+		return Err(JsonError::SchemaVersion {
+			found: schema_version,
+			supported: JSON_SCHEMA_VERSION,
+		});
+	}
+	Ok(serde_json::from_str(text)?)
+}
+
+#[derive(Deserialize)]
+struct Version {
+	#[serde(default)]
+	schema_version: u32,
+}
+
+fn hex(bytes: &[u8]) -> String {
+	let mut out = String::with_capacity(bytes.len() * 2);
+	for b in bytes {
+		let _ = write!(out, "{b:02x}");
+	}
+	out
+}
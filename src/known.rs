@@ -0,0 +1,227 @@
+/* src/known.rs */
+
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::{ClientHello, ja3, ja4};
+
+/// Broad category of a known client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ClientKind {
+	/// Interactive web browser.
+	Browser,
+	/// TLS library or HTTP client library embedded in other software,
+	/// including scripted clients built on one.
+	Library,
+	/// Command-line tool.
+	Tool,
+}
+
+/// A known client that a hello resembles, as returned by
+/// [`ClientHello::identify`] and [`ClientHello::lookup_fingerprint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Candidate {
+	/// Client name, e.g. `"Chrome"` or `"curl (OpenSSL 3.2+)"`.
+	pub name: &'static str,
+	/// Client category.
+	pub kind: ClientKind,
+	/// Match strength from 1 to 100; 100 means the full JA4 or the JA3N
+	/// matched.
+	pub score: u8,
+}
+
+struct Known {
+	name: &'static str,
+	kind: ClientKind,
+	ja4: &'static str,
+	/// JA3N hash, for entries whose extension order and point formats
+	/// were reconstructed too.
+	ja3n: Option<&'static str>,
+}
+
+/// Default-configuration JA4 and JA3N fingerprints of widely deployed
+/// clients.
+///
+/// Each entry was checked by reconstructing the client's cipher suites,
+/// extensions, and signature algorithms and recomputing the JA4; the
+/// Java, Node.js, and Python entries come from hellos captured from
+/// OpenJDK 17, Node.js 20, and Python 3.11 on OpenSSL 3.0. JA3N rather
+/// than JA3 is listed because Chrome shuffles its extensions. Bots and
+/// scrapers written in Go match the `crypto/tls` entries, and headless
+/// browsers driven by automation match their browser's entry.
+const KNOWN: &[Known] = &[
+	Known {
+		name: "Chrome",
+		kind: ClientKind::Browser,
+		ja4: "t13d1516h2_8daaf6152771_02713d6af862",
+		ja3n: None,
+	},
+	Known {
+		name: "Chrome",
+		kind: ClientKind::Browser,
+		ja4: "t13d1516h2_8daaf6152771_e5627efa2ab1",
+		ja3n: Some("aa56c057ad164ec4fdcb7a5a283be9fc"),
+	},
+	Known {
+		name: "Firefox",
+		kind: ClientKind::Browser,
+		ja4: "t13d1715h2_5b57614c22b0_3d5424432f57",
+		ja3n: None,
+	},
+	Known {
+		name: "Firefox",
+		kind: ClientKind::Browser,
+		ja4: "t13d1716h2_5b57614c22b0_93c746dc12af",
+		ja3n: None,
+	},
+	Known {
+		name: "Safari",
+		kind: ClientKind::Browser,
+		ja4: "t13d2014h2_a09f3c656075_14788d8d241b",
+		ja3n: None,
+	},
+	Known {
+		name: "curl (OpenSSL 3.0/3.1)",
+		kind: ClientKind::Tool,
+		ja4: "t13d3112h2_e8f1e7e78f70_b26ce05bbdd6",
+		ja3n: None,
+	},
+	Known {
+		name: "curl (OpenSSL 3.2+)",
+		kind: ClientKind::Tool,
+		ja4: "t13d3112h2_e8f1e7e78f70_6bebaf5329ac",
+		ja3n: None,
+	},
+	Known {
+		name: "OpenSSL s_client",
+		kind: ClientKind::Tool,
+		ja4: "t13d311000_e8f1e7e78f70_518fb456ca59",
+		ja3n: Some("c1741bf218dcb47ce67e3c88bcc6dcd3"),
+	},
+	Known {
+		name: "Go crypto/tls",
+		kind: ClientKind::Library,
+		ja4: "t13d1311h2_f57a46bbacb6_e7c285222651",
+		ja3n: Some("34dbbdd12aa20534015ad2540a5debd8"),
+	},
+	Known {
+		name: "Go crypto/tls (pre-1.22 cipher suites)",
+		kind: ClientKind::Library,
+		ja4: "t13d191000_9dc949149365_e7c285222651",
+		ja3n: None,
+	},
+	Known {
+		name: "Java JSSE",
+		kind: ClientKind::Library,
+		ja4: "t13d3713h2_db35923f8641_7c76daad20ec",
+		ja3n: Some("76e411c852717cf74c4defebd5a733f8"),
+	},
+	Known {
+		name: "Java JSSE",
+		kind: ClientKind::Library,
+		ja4: "t13d371200_db35923f8641_7c76daad20ec",
+		ja3n: Some("3a3d27f9854e74b458c43e4440dce7ef"),
+	},
+	Known {
+		name: "Node.js",
+		kind: ClientKind::Library,
+		ja4: "t13d5911h1_a33745022dd6_1f22a2ca17c4",
+		ja3n: Some("6a3ff734b154e5d3dabaf834c4b68703"),
+	},
+	Known {
+		name: "Node.js",
+		kind: ClientKind::Library,
+		ja4: "t13d591000_a33745022dd6_1f22a2ca17c4",
+		ja3n: Some("2cdc372ba33ad43cbb1c09aad0566191"),
+	},
+	Known {
+		name: "Python requests",
+		kind: ClientKind::Library,
+		ja4: "t13d1812h1_85036bcba153_b26ce05bbdd6",
+		ja3n: Some("62fcc66dfa1611e219a93df2d1bb1b24"),
+	},
+	Known {
+		name: "Python aiohttp/httpx",
+		kind: ClientKind::Library,
+		ja4: "t13d1812h1_85036bcba153_d41ae481755e",
+		ja3n: Some("298951cff8a60675cc672d3d504d59fb"),
+	},
+	Known {
+		name: "Python aiohttp/httpx",
+		kind: ClientKind::Library,
+		ja4: "t13d181100_85036bcba153_d41ae481755e",
+		ja3n: Some("8e54c143443cebcdc83ab360910eab85"),
+	},
+];
+
+/// Score weights for matching JA4 sections. The cipher section is the
+/// most stable across versions of one client; the prefix changes with
+/// SNI and ALPN configuration.
+const CIPHERS_WEIGHT: u8 = 50;
+const EXTENSIONS_WEIGHT: u8 = 30;
+const PREFIX_WEIGHT: u8 = 20;
+
+pub(crate) fn identify(hello: &ClientHello<'_>) -> Vec<Candidate> {
+	let fingerprint = ja4::ja4(hello);
+	let observed = sections(&fingerprint);
+	let mut candidates: Vec<Candidate> = Vec::new();
+	for known in KNOWN {
+		let expected = sections(known.ja4);
+		let mut score = 0;
+		if observed.1 == expected.1 {
+			score += CIPHERS_WEIGHT;
+		}
+		if observed.2 == expected.2 {
+			score += EXTENSIONS_WEIGHT;
+		}
+		// A prefix match alone says little; it only adds to a section match.
+		if score == 0 {
+			continue;
+		}
+		if observed.0 == expected.0 {
+			score += PREFIX_WEIGHT;
+		}
+		match candidates.iter_mut().find(|c| c.name == known.name) {
+			Some(existing) => existing.score = existing.score.max(score),
+			None => candidates.push(Candidate {
+				name: known.name,
+				kind: known.kind,
+				score,
+			}),
+		}
+	}
+	candidates.sort_by_key(|c| Reverse(c.score));
+	candidates
+}
+
+/// Find the known client a hello matches best: an exact JA4 match, then
+/// an exact JA3N match, then the best partial JA4 match.
+pub(crate) fn lookup(hello: &ClientHello<'_>) -> Option<Candidate> {
+	let best = identify(hello).into_iter().next();
+	if best.is_some_and(|c| c.score == 100) {
+		return best;
+	}
+	let ja3n = ja3::ja3n_hash(hello);
+	KNOWN
+		.iter()
+		.find(|known| known.ja3n == Some(ja3n.as_str()))
+		.map(|known| Candidate {
+			name: known.name,
+			kind: known.kind,
+			score: 100,
+		})
+		.or(best)
+}
+
+/// Split a JA4 into its sections, dropping the protocol marker so TCP
+/// and QUIC hellos from the same stack compare equal.
+fn sections(ja4: &str) -> (&str, &str, &str) {
+	let mut parts = ja4.splitn(3, '_');
+	let a = parts.next().unwrap_or_default();
+	(
+		a.get(1..).unwrap_or_default(),
+		parts.next().unwrap_or_default(),
+		parts.next().unwrap_or_default(),
+	)
+}
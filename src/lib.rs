@@ -8,136 +8,263 @@
 //!   [`parse_from_record`].
 //! - Raw handshake messages without a record layer (first byte `0x01`)
 //!   via [`parse`], suitable for QUIC CRYPTO frames.
+//!
+//! [`Builder`] encodes ClientHello messages in either format.
+//!
+//! With `default-features = false` and the `sni-only` feature, only
+//! [`extract_sni`] / [`extract_sni_from_record`], [`summarize`] /
+//! [`summarize_from_record`], and the framing code they need are
+//! compiled, and no allocator is required. [`summarize`] gathers SNI,
+//! ALPN, the highest offered version, the cipher suite count, and ECH
+//! presence in one pass, for logging paths that need more than the SNI.
+//! The `fixed` feature adds [`ClientHelloFixed`], a structured result in
+//! const-generic bounded storage that also needs no allocator.
+//!
+//! `default-features = false` on its own no longer builds the structured
+//! parser: `no_std` users of [`parse`] and [`ClientHello`] enable
+//! `ext-all`, or `client-hello` for the parser without the extension
+//! decoders. Neither needs `std`, only an allocator.
+//!
+//! # Untrusted input
+//!
+//! Parsing never panics, whatever the input: every length field is
+//! bounds-checked with overflow-free arithmetic and malformed input is
+//! reported through [`Error`]. This holds for [`parse`],
+//! [`parse_from_record`], the chunked variants, [`extract_sni`], and the
+//! accessors on a parsed [`ClientHello`]. The guarantee is exercised by
+//! the fuzz targets under `fuzz/` and a regression corpus in the test
+//! suite. [`ParseOptions`] additionally bounds how many entries each
+//! parsed list may hold.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(any(feature = "client-hello", test))]
 extern crate alloc;
 
+#[cfg(feature = "ext-alpn")]
+mod alpn;
+#[cfg(feature = "client-hello")]
+pub mod alps;
+#[cfg(feature = "analysis")]
+pub mod analysis;
+#[cfg(feature = "sni-only")]
+mod bitmap;
+#[cfg(feature = "client-hello")]
+mod bot;
+#[cfg(feature = "client-hello")]
+mod builder;
+#[cfg(all(feature = "std", feature = "client-hello"))]
+mod cache;
+#[cfg(feature = "capabilities")]
+mod capability;
+#[cfg(feature = "client-hello")]
+mod chunks;
+#[cfg(feature = "cluster")]
+mod cluster;
+#[cfg(feature = "tokio-codec")]
+pub mod codec;
+#[cfg(feature = "client-hello")]
+pub mod codegen;
+#[cfg(feature = "client-hello")]
+mod connection;
+pub mod consts;
+#[cfg(feature = "db")]
+pub mod db;
+#[cfg(feature = "client-hello")]
+mod diagnostics;
+#[cfg(feature = "client-hello")]
+mod early_data;
+#[cfg(feature = "client-hello")]
+pub mod ech;
 mod error;
+#[cfg(feature = "bincode")]
+pub mod export;
+#[cfg(feature = "client-hello")]
 mod extension;
+#[cfg(feature = "client-hello")]
+mod fingerprint;
+#[cfg(all(feature = "ja3", feature = "ja4"))]
+mod fingerprints;
+#[cfg(feature = "fixed")]
+mod fixed;
+#[cfg(feature = "sni-only")]
+mod framing;
 mod grease;
+#[cfg(feature = "client-hello")]
+mod hello;
+#[cfg(feature = "client-hello")]
+mod heuristics;
+#[cfg(feature = "hpke")]
+mod hpke;
+#[cfg(feature = "intern")]
+mod intern;
+#[cfg(feature = "ja3")]
+mod ja3;
+#[cfg(feature = "ja4")]
+mod ja4;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "known-clients")]
+mod known;
+#[cfg(feature = "log")]
+mod logging;
+#[cfg(feature = "client-hello")]
+mod multi;
+#[cfg(feature = "negotiate")]
+mod negotiate;
+#[cfg(feature = "client-hello")]
+mod options;
+#[cfg(feature = "client-hello")]
 mod parser;
-mod reader;
-
-use alloc::vec::Vec;
+#[cfg(feature = "pcap")]
+pub mod pcap;
+#[cfg(feature = "color")]
+mod pretty;
+#[cfg(feature = "profiles")]
+mod profile;
+#[cfg(feature = "protobuf")]
+pub mod proto;
+#[cfg(feature = "ext-alpn")]
+mod quic;
+#[cfg(feature = "quic-initial")]
+mod quic_initial;
+#[cfg(feature = "client-hello")]
+mod rarity;
+#[cfg(feature = "client-hello")]
+mod registry;
+#[cfg(feature = "client-hello")]
+mod renegotiation;
+#[cfg(feature = "client-hello")]
+mod replay;
+#[cfg(feature = "report")]
+pub mod report;
+#[cfg(feature = "ext-sni")]
+pub mod router;
+#[cfg(feature = "scan")]
+pub mod scan;
+#[cfg(feature = "client-hello")]
+mod server_hello;
+#[cfg(feature = "client-hello")]
+mod session;
+#[cfg(feature = "client-hello")]
+mod session_id;
+#[cfg(feature = "sni-only")]
+mod sni;
+#[cfg(feature = "client-hello")]
+mod stack;
+#[cfg(feature = "sni-only")]
+mod summary;
+pub mod tls_reader;
+#[cfg(feature = "client-hello")]
+pub mod tls_writer;
+#[cfg(feature = "digest")]
+mod transcript;
+#[cfg(feature = "client-hello")]
+pub mod tunnel;
+#[cfg(feature = "client-hello")]
+pub mod typed;
+mod types;
+#[cfg(feature = "validate")]
+mod validate;
+#[cfg(feature = "client-hello")]
+pub mod vectors;
 
+#[cfg(feature = "ext-alpn")]
+pub use crate::alpn::AlpnSet;
+#[cfg(feature = "sni-only")]
+pub use crate::bitmap::ExtensionBitmap;
+#[cfg(feature = "client-hello")]
+pub use crate::bot::{BotScore, BotScorer, BotSignal};
+#[cfg(feature = "client-hello")]
+pub use crate::builder::Builder;
+#[cfg(all(feature = "std", feature = "client-hello"))]
+pub use crate::cache::HelloCache;
+#[cfg(feature = "capabilities")]
+pub use crate::capability::{Capabilities, Capability};
+#[cfg(feature = "client-hello")]
+pub use crate::chunks::{
+	parse_from_chunks, parse_from_record_chunks, parse_from_record_vectored, parse_vectored,
+};
+#[cfg(feature = "cluster")]
+pub use crate::cluster::{Cluster, cluster};
+#[cfg(feature = "client-hello")]
+pub use crate::connection::{Connection, ConnectionError};
+#[cfg(feature = "client-hello")]
+pub use crate::diagnostics::{Diagnostics, Warning, Warnings};
+#[cfg(feature = "client-hello")]
+pub use crate::early_data::PskIdentity;
+#[cfg(feature = "ext-alpn")]
+pub use crate::early_data::{EarlyData, EarlyDataRejection, TicketContext};
 pub use crate::error::Error;
-pub use crate::extension::{Extension, ServerName};
-pub use crate::grease::is_grease;
-pub use crate::parser::{parse, parse_from_record};
-
-/// Parsed TLS ClientHello message holding zero-copy references into the
-/// original byte buffer.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ClientHello<'a> {
-	/// Legacy protocol version (usually `0x0303` for TLS 1.2).
-	pub legacy_version: u16,
-	/// 32-byte client random.
-	pub random: &'a [u8],
-	/// Session ID (may be empty).
-	pub session_id: &'a [u8],
-	/// Cipher suite identifiers with GREASE values removed.
-	pub cipher_suites: Vec<u16>,
-	/// Compression method bytes.
-	pub compression_methods: &'a [u8],
-	/// Parsed extensions.
-	pub extensions: Vec<Extension<'a>>,
-	/// Set to `true` when any GREASE value was encountered during parsing.
-	pub has_grease: bool,
-}
-
-impl<'a> ClientHello<'a> {
-	/// Return the first DNS hostname from the SNI extension.
-	#[must_use]
-	pub fn server_name(&self) -> Option<&str> {
-		for ext in &self.extensions {
-			if let Extension::ServerName(names) = ext {
-				for sn in names {
-					if sn.name_type == 0x00 {
-						return core::str::from_utf8(sn.name).ok();
-					}
-				}
-			}
-		}
-		None
-	}
-
-	/// Collect all ALPN protocol identifiers.
-	#[must_use]
-	pub fn alpn_protocols(&self) -> &[&[u8]] {
-		for ext in &self.extensions {
-			if let Extension::Alpn(protos) = ext {
-				return protos;
-			}
-		}
-		&[]
-	}
-
-	/// Return supported TLS versions (GREASE values already excluded).
-	#[must_use]
-	pub fn supported_versions(&self) -> &[u16] {
-		for ext in &self.extensions {
-			if let Extension::SupportedVersions(v) = ext {
-				return v;
-			}
-		}
-		&[]
-	}
-
-	/// Return supported groups / named curves (GREASE values already excluded).
-	#[must_use]
-	pub fn supported_groups(&self) -> &[u16] {
-		for ext in &self.extensions {
-			if let Extension::SupportedGroups(v) = ext {
-				return v;
-			}
-		}
-		&[]
-	}
-
-	/// Return signature algorithm identifiers.
-	#[must_use]
-	pub fn signature_algorithms(&self) -> &[u16] {
-		for ext in &self.extensions {
-			if let Extension::SignatureAlgorithms(v) = ext {
-				return v;
-			}
-		}
-		&[]
-	}
-
-	/// Return key-share group identifiers (GREASE values already excluded).
-	#[must_use]
-	pub fn key_share_groups(&self) -> &[u16] {
-		for ext in &self.extensions {
-			if let Extension::KeyShareGroups(v) = ext {
-				return v;
-			}
-		}
-		&[]
-	}
-
-	/// Check whether a renegotiation info extension is present.
-	#[must_use]
-	pub fn has_renegotiation_info(&self) -> bool {
-		self
-			.extensions
-			.iter()
-			.any(|ext| matches!(ext, Extension::RenegotiationInfo(_)))
-	}
-
-	/// Find the raw data of an extension by its type identifier.
-	///
-	/// Searches unknown extensions and renegotiation info. Returns
-	/// `None` for extension types that were parsed into structured
-	/// variants.
-	#[must_use]
-	pub fn find_extension(&self, type_id: u16) -> Option<&[u8]> {
-		self.extensions.iter().find_map(|ext| match ext {
-			Extension::PskExchangeModes(data) if type_id == 0x002D => Some(*data),
-			Extension::RenegotiationInfo(data) if type_id == 0xFF01 => Some(*data),
-			Extension::Unknown { type_id: id, data } if *id == type_id => Some(*data),
-			_ => None,
-		})
-	}
-}
+#[cfg(feature = "client-hello")]
+pub use crate::extension::{
+	Extension, ExtensionOwned, Extensions, KeyShareEntry, KeyShareEntryOwned, ServerName,
+	ServerNameOwned,
+};
+#[cfg(all(feature = "ja3", feature = "ja4"))]
+pub use crate::fingerprints::{
+	Fingerprints, fingerprints, fingerprints_from_record, fingerprints_from_record_with,
+	fingerprints_with,
+};
+#[cfg(feature = "fixed")]
+pub use crate::fixed::{ClientHelloFixed, RawExtension};
+pub use crate::grease::{GREASE_PLACEHOLDER, is_grease};
+#[cfg(feature = "client-hello")]
+pub use crate::grease::{GreasePositions, ReservedValues};
+#[cfg(feature = "client-hello")]
+pub use crate::hello::{ClientHello, Transport};
+#[cfg(feature = "client-hello")]
+pub use crate::heuristics::{ClientFamily, Confidence, ProbableClient};
+#[cfg(feature = "intern")]
+pub use crate::intern::Interner;
+#[cfg(feature = "ja3")]
+pub use crate::ja3::Ja3Version;
+#[cfg(feature = "ja4")]
+pub use crate::ja4::Ja4Version;
+#[cfg(feature = "known-clients")]
+pub use crate::known::{Candidate, ClientKind};
+#[cfg(feature = "log")]
+pub use crate::logging::{LOG_TARGET, log_summary};
+#[cfg(feature = "client-hello")]
+pub use crate::multi::MultiFingerprint;
+#[cfg(feature = "client-hello")]
+#[cfg(feature = "negotiate")]
+pub use crate::negotiate::{Negotiated, NegotiationError, ServerConfig};
+#[cfg(feature = "client-hello")]
+pub use crate::options::{ParseOptions, SniPolicy, UnknownExtensionPolicy};
+#[cfg(feature = "client-hello")]
+pub use crate::parser::{parse, parse_any, parse_from_record};
+#[cfg(feature = "color")]
+pub use crate::pretty::Pretty;
+#[cfg(feature = "profiles")]
+pub use crate::profile::{Profile, ProfileField, ProfileMatch};
+#[cfg(feature = "client-hello")]
+pub use crate::rarity::{FrequencyTable, Rarity};
+#[cfg(feature = "client-hello")]
+pub use crate::registry::{DecodedExtension, DecoderRegistry};
+#[cfg(feature = "client-hello")]
+pub use crate::renegotiation::{Renegotiation, RenegotiationSignal};
+#[cfg(feature = "client-hello")]
+pub use crate::replay::FingerprintError;
+#[cfg(feature = "client-hello")]
+pub use crate::server_hello::{ServerHello, parse_server_hello, parse_server_hello_from_record};
+#[cfg(feature = "client-hello")]
+pub use crate::session::{HelloChanges, SessionTracker};
+#[cfg(feature = "client-hello")]
+pub use crate::session_id::SessionIdStyle;
+#[cfg(feature = "sni-only")]
+pub use crate::sni::{extract_sni, extract_sni_from_record};
+#[cfg(feature = "client-hello")]
+pub use crate::stack::{ExtensionOrder, TlsStack};
+#[cfg(feature = "sni-only")]
+pub use crate::summary::{Summary, summarize, summarize_from_record};
+#[cfg(feature = "digest")]
+pub use crate::transcript::{
+	binder_transcript_hash, binder_transcript_hash_from_record, transcript_hash,
+	transcript_hash_from_record,
+};
+#[cfg(feature = "client-hello")]
+pub use crate::typed::FromExtension;
+pub use crate::types::{ContentType, HandshakeType, HashAlgorithm, SignatureScheme};
+#[cfg(feature = "validate")]
+pub use crate::validate::{Severity, ValidationIssue, ValidationReport};
@@ -0,0 +1,64 @@
+/* src/logging.rs */
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ClientHello;
+
+/// Target of the records [`log_summary`] emits.
+pub const LOG_TARGET: &str = "clienthello";
+
+/// Log one structured record summarizing `hello` through the `log`
+/// crate.
+///
+/// The record has target [`LOG_TARGET`], message `client hello`, and
+/// always the same key-value pairs, so every service logs hellos
+/// alike:
+///
+/// | Key | Value |
+/// |-----|-------|
+/// | `sni` | SNI hostname, empty when absent |
+/// | `ja3` | JA3 hash |
+/// | `version` | Highest offered version in hex, e.g. `0x0304` |
+/// | `alpn` | ALPN protocols joined with `,`, non-UTF-8 bytes replaced |
+///
+/// Nothing is computed when `level` is disabled for the target.
+///
+/// ```
+/// let msg = clienthello::Builder::new()
+///     .cipher_suites(&[0x1301])
+///     .server_name("example.com")
+///     .build()
+///     .unwrap();
+/// let hello = clienthello::parse(&msg).unwrap();
+/// clienthello::log_summary(&hello, log::Level::Info);
+/// ```
+pub fn log_summary(hello: &ClientHello<'_>, level: log::Level) {
+	if !log::log_enabled!(target: LOG_TARGET, level) {
+		return;
+	}
+	let sni = hello.server_name().unwrap_or_default();
+	let ja3 = hello.ja3_hash();
+	let version = hello
+		.effective_versions()
+		.iter()
+		.max()
+		.map(|v| format!("{v:#06x}"))
+		.unwrap_or_default();
+	let alpn: Vec<String> = hello
+		.alpn_protocols()
+		.iter()
+		.map(|p| String::from_utf8_lossy(p).into_owned())
+		.collect();
+	let alpn = alpn.join(",");
+	log::log!(
+		target: LOG_TARGET,
+		level,
+		sni = sni,
+		ja3 = ja3.as_str(),
+		version = version.as_str(),
+		alpn = alpn.as_str();
+		"client hello"
+	);
+}
@@ -0,0 +1,133 @@
+/* src/multi.rs */
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::ClientHello;
+use crate::fingerprint::{PER_CONNECTION_EXTENSIONS, order_insensitive_input};
+
+/// Fingerprint of one client folded from several of its hellos.
+///
+/// A single-hello fingerprint that includes the extension order changes
+/// on every connection from a client that shuffles its extensions, as
+/// Chrome 110 and later do, while one that ignores the order throws away
+/// a strong signal from clients that do not. Trackers that see several
+/// connections per client can keep both: the *core* is an
+/// order-insensitive encoding of the offer that stays the same across
+/// connections, and every extension order seen is counted next to it.
+///
+/// The core holds the legacy version, cipher suites, sorted extension
+/// types, supported versions, groups, signature algorithms, and ALPN
+/// identifiers. Padding, pre-shared key, and early data extensions are
+/// left out because a client sends them on some connections only.
+/// The first observed hello sets the core; later hellos with a
+/// different core are counted as mismatches and otherwise ignored.
+///
+/// ```
+/// use clienthello::{Builder, MultiFingerprint};
+///
+/// let a = Builder::new().cipher_suites(&[0x1301]).server_name("a.example").alpn(&[b"h2"]);
+/// let b = Builder::new().cipher_suites(&[0x1301]).alpn(&[b"h2"]).server_name("b.example");
+///
+/// let mut multi = MultiFingerprint::new();
+/// for builder in [&a, &b, &a] {
+///     let msg = builder.build().unwrap();
+///     assert!(multi.observe(&clienthello::parse(&msg).unwrap()));
+/// }
+/// assert!(multi.is_stable());
+/// assert!(multi.is_order_randomized());
+/// assert_eq!(multi.orders().count(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MultiFingerprint {
+	core: Option<Vec<u8>>,
+	orders: BTreeMap<Vec<u16>, usize>,
+	observations: usize,
+	mismatches: usize,
+}
+
+impl MultiFingerprint {
+	/// Create a fingerprint with no observations.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Fold `hello` into the fingerprint.
+	///
+	/// Returns `false`, and records only the mismatch, when the hello's
+	/// core differs from the one set by the first observation.
+	pub fn observe(&mut self, hello: &ClientHello<'_>) -> bool {
+		// Every list in a parsed hello came from a length-prefixed field
+		// of the same width, so re-encoding cannot overflow.
+		let core = order_insensitive_input(hello).unwrap_or_default();
+		match &self.core {
+			Some(existing) if *existing != core => {
+				self.mismatches += 1;
+				return false;
+			}
+			Some(_) => {}
+			None => self.core = Some(core),
+		}
+		self.observations += 1;
+		let order = hello
+			.extensions
+			.type_ids()
+			.filter(|t| !PER_CONNECTION_EXTENSIONS.contains(t))
+			.collect();
+		*self.orders.entry(order).or_default() += 1;
+		true
+	}
+
+	/// Number of hellos folded in, mismatches excluded.
+	#[must_use]
+	pub fn observations(&self) -> usize {
+		self.observations
+	}
+
+	/// Number of hellos rejected because their core differed.
+	#[must_use]
+	pub fn mismatches(&self) -> usize {
+		self.mismatches
+	}
+
+	/// Check whether at least one hello was observed and none mismatched.
+	#[must_use]
+	pub fn is_stable(&self) -> bool {
+		self.observations > 0 && self.mismatches == 0
+	}
+
+	/// Return the order-insensitive core encoding, or `None` before the
+	/// first observation.
+	///
+	/// Equal cores mean equal offers; hash or compare them to match
+	/// clients across trackers.
+	#[must_use]
+	pub fn core(&self) -> Option<&[u8]> {
+		self.core.as_deref()
+	}
+
+	/// Iterate the distinct extension orders seen, with how many hellos
+	/// used each. Orders are yielded sorted and leave out GREASE and the
+	/// extensions excluded from the core.
+	pub fn orders(&self) -> impl Iterator<Item = (&[u16], usize)> {
+		self
+			.orders
+			.iter()
+			.map(|(order, &count)| (order.as_slice(), count))
+	}
+
+	/// Check whether more than one extension order was seen.
+	#[must_use]
+	pub fn is_order_randomized(&self) -> bool {
+		self.orders.len() > 1
+	}
+
+	/// Hash the core with digest `D`, or return `None` before the first
+	/// observation.
+	#[cfg(feature = "digest")]
+	#[must_use]
+	pub fn fingerprint_with<D: digest::Digest>(&self) -> Option<digest::Output<D>> {
+		self.core.as_deref().map(D::digest)
+	}
+}
@@ -0,0 +1,253 @@
+/* src/negotiate.rs */
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::consts::{EXT_ALPN, TLS_EMPTY_RENEGOTIATION_INFO_SCSV, TLS_FALLBACK_SCSV};
+use crate::{ClientHello, Extension, SignatureScheme};
+
+/// What a server supports, each list in the server's preference order.
+///
+/// Passed to [`ClientHello::negotiate`], which picks every parameter the
+/// way a server enforcing its own preference order does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ServerConfig<'s> {
+	/// Protocol versions, e.g. `[0x0304, 0x0303]`.
+	pub versions: &'s [u16],
+	/// Cipher suites for any of `versions`.
+	pub cipher_suites: &'s [u16],
+	/// Key exchange groups.
+	pub groups: &'s [u16],
+	/// Signature algorithms the server's certificates can sign with.
+	pub signature_algorithms: &'s [u16],
+	/// ALPN protocols; empty when the server does not use ALPN.
+	pub alpn: &'s [&'s [u8]],
+}
+
+/// Parameters a server would select for a hello, as predicted by
+/// [`ClientHello::negotiate`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Negotiated<'a> {
+	/// Protocol version.
+	pub version: u16,
+	/// Cipher suite.
+	pub cipher_suite: u16,
+	/// Key exchange group; `None` for TLS 1.2 when no group is shared,
+	/// leaving only cipher suites without (EC)DHE usable.
+	pub group: Option<u16>,
+	/// Whether the hello carries no key share for `group`, so a TLS 1.3
+	/// server must send a HelloRetryRequest.
+	pub hello_retry: bool,
+	/// Signature algorithm; `None` for TLS 1.2 when the hello has no
+	/// `signature_algorithms` extension and the server falls back to
+	/// SHA-1.
+	pub signature_algorithm: Option<u16>,
+	/// ALPN protocol; `None` when either side does not use ALPN.
+	pub alpn: Option<&'a [u8]>,
+	reasons: Vec<String>,
+}
+
+impl Negotiated<'_> {
+	/// Describe why each parameter was chosen, one sentence per
+	/// parameter in field order.
+	///
+	/// Meant for user-facing compatibility checkers; the wording is not
+	/// stable and should not be parsed.
+	#[must_use]
+	pub fn explain(&self) -> &[String] {
+		&self.reasons
+	}
+}
+
+/// Why [`ClientHello::negotiate`] predicts a handshake failure.
+///
+/// The [`Display`](core::fmt::Display) output explains the failure,
+/// listing what the client offered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, thiserror::Error)]
+#[non_exhaustive]
+pub enum NegotiationError {
+	/// No protocol version is supported by both sides.
+	#[error("no common version: client offers {}", offers(.client))]
+	NoCommonVersion {
+		/// Versions the client offers.
+		client: Vec<u16>,
+	},
+	/// No cipher suite is supported by both sides for the version.
+	#[error("no common cipher suite: client offers {} for {version:#06x}", offers(.client))]
+	NoCommonCipherSuite {
+		/// Negotiated version.
+		version: u16,
+		/// Suites the client offers for that version.
+		client: Vec<u16>,
+	},
+	/// TLS 1.3 without a key exchange group supported by both sides.
+	#[error("no common group: client offers {}, TLS 1.3 requires one", offers(.client))]
+	NoCommonGroup {
+		/// Groups the client offers.
+		client: Vec<u16>,
+	},
+	/// No signature algorithm is supported by both sides.
+	#[error("no common signature algorithm: client offers {}", offers(.client))]
+	NoCommonSignatureAlgorithm {
+		/// Algorithms the client offers.
+		client: Vec<u16>,
+	},
+	/// Both sides use ALPN without a protocol in common; the server
+	/// answers with a `no_application_protocol` alert (RFC 7301 §3.2).
+	#[error("no common ALPN protocol: server sends no_application_protocol")]
+	NoCommonAlpn,
+}
+
+pub(crate) fn negotiate<'a>(
+	hello: &ClientHello<'a>,
+	server: &ServerConfig<'_>,
+) -> Result<Negotiated<'a>, NegotiationError> {
+	let mut reasons = Vec::new();
+
+	let client_versions: Vec<u16> = if hello.supported_versions().is_empty() {
+		// Without supported_versions, legacy_version is the highest
+		// version and every lower one is acceptable (RFC 5246 E.1).
+		(0x0300..=hello.legacy_version.min(0x0303)).rev().collect()
+	} else {
+		hello.supported_versions().to_vec()
+	};
+	let version = first_common(server.versions, &client_versions).ok_or_else(|| {
+		NegotiationError::NoCommonVersion {
+			client: client_versions.clone(),
+		}
+	})?;
+	reasons.push(format!(
+		"version {version:#06x}: the server's most preferred version the client supports"
+	));
+	let tls13 = version >= 0x0304;
+
+	let client_suites: Vec<u16> = hello
+		.cipher_suites
+		.iter()
+		.copied()
+		.filter(|&s| !matches!(s, TLS_EMPTY_RENEGOTIATION_INFO_SCSV | TLS_FALLBACK_SCSV))
+		.filter(|&s| is_tls13_suite(s) == tls13)
+		.collect();
+	let cipher_suite = first_common(server.cipher_suites, &client_suites).ok_or(
+		NegotiationError::NoCommonCipherSuite {
+			version,
+			client: client_suites,
+		},
+	)?;
+	reasons.push(format!(
+		"cipher suite {cipher_suite:#06x}: the server's most preferred suite the client offers for {version:#06x}"
+	));
+
+	let client_groups = hello.supported_groups();
+	let shares = hello.key_share_groups();
+	// Servers accept a group the client sent a key share for over a more
+	// preferred one that would cost a round trip.
+	let shared = tls13
+		.then(|| first_common(server.groups, shares))
+		.flatten()
+		.filter(|g| client_groups.contains(g));
+	let (group, hello_retry) = match (shared, first_common(server.groups, client_groups)) {
+		(Some(group), _) => {
+			reasons.push(format!(
+				"group {group:#06x}: the server's most preferred group the client sent a key share for"
+			));
+			(Some(group), false)
+		}
+		(None, Some(group)) if tls13 => {
+			reasons.push(format!(
+				"group {group:#06x}: the server's most preferred group the client supports; \
+				 the client sent no key share for it, so the server sends a HelloRetryRequest"
+			));
+			(Some(group), true)
+		}
+		(None, Some(group)) => {
+			reasons.push(format!(
+				"group {group:#06x}: the server's most preferred group the client supports"
+			));
+			(Some(group), false)
+		}
+		(None, None) if tls13 => {
+			return Err(NegotiationError::NoCommonGroup {
+				client: client_groups.to_vec(),
+			});
+		}
+		(None, None) => {
+			reasons
+				.push("no group: none is shared, so only suites without (EC)DHE key exchange work".into());
+			(None, false)
+		}
+	};
+
+	let client_algorithms = hello.signature_algorithms();
+	let signature_algorithm = if client_algorithms.is_empty() && !tls13 {
+		reasons
+			.push("no signature algorithm: the client sent none, so TLS 1.2 falls back to SHA-1".into());
+		None
+	} else {
+		let algorithm =
+			first_common(server.signature_algorithms, client_algorithms).ok_or_else(|| {
+				NegotiationError::NoCommonSignatureAlgorithm {
+					client: client_algorithms.to_vec(),
+				}
+			})?;
+		reasons.push(format!(
+			"signature algorithm {}: the server's most preferred algorithm the client accepts",
+			SignatureScheme::from(algorithm)
+		));
+		Some(algorithm)
+	};
+
+	let client_alpn: &[&'a [u8]] = match hello.extensions.by_type(EXT_ALPN) {
+		Some(Extension::Alpn(protocols)) => protocols,
+		_ => &[],
+	};
+	let alpn = if client_alpn.is_empty() {
+		reasons.push("no ALPN: the client offers no protocols".into());
+		None
+	} else if server.alpn.is_empty() {
+		reasons.push("no ALPN: the server does not use ALPN and ignores the offer".into());
+		None
+	} else {
+		let protocol = server
+			.alpn
+			.iter()
+			.find_map(|s| client_alpn.iter().find(|&&c| c == *s))
+			.ok_or(NegotiationError::NoCommonAlpn)?;
+		reasons.push(format!(
+			"ALPN {:?}: the server's most preferred protocol the client offers",
+			String::from_utf8_lossy(protocol)
+		));
+		Some(*protocol)
+	};
+
+	Ok(Negotiated {
+		version,
+		cipher_suite,
+		group,
+		hello_retry,
+		signature_algorithm,
+		alpn,
+		reasons,
+	})
+}
+
+/// First value of `server` that `client` contains.
+fn first_common(server: &[u16], client: &[u16]) -> Option<u16> {
+	server.iter().copied().find(|v| client.contains(v))
+}
+
+/// TLS 1.3 cipher suites (RFC 8446 B.4) are not usable with earlier
+/// versions and vice versa.
+fn is_tls13_suite(suite: u16) -> bool {
+	suite >> 8 == 0x13
+}
+
+/// List a side's offer for an error message.
+fn offers(values: &[u16]) -> String {
+	if values.is_empty() {
+		return "none".into();
+	}
+	let list: Vec<String> = values.iter().map(|v| format!("{v:#06x}")).collect();
+	list.join(", ")
+}
@@ -0,0 +1,341 @@
+/* src/options.rs */
+
+use alloc::vec::Vec;
+
+use crate::parser;
+use crate::{ClientHello, Error, Transport, is_grease};
+
+/// Configuration for [`parse`](crate::parse) and
+/// [`parse_from_record`](crate::parse_from_record).
+///
+/// Every list the parser collects is bounded by a ceiling on its entry
+/// count. Declared lengths are checked against the remaining input and
+/// the ceiling before anything is allocated, so a hostile length field
+/// yields [`Error::LimitExceeded`] rather than a large allocation. Counts
+/// include GREASE entries even though those are filtered from the result.
+///
+/// The defaults are far above what any real client sends; tighten them
+/// when parse results are retained per connection.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d
+/// # }
+/// # let data = build();
+/// use clienthello::{Error, ParseOptions};
+///
+/// let opts = ParseOptions::new().max_cipher_suites(0);
+/// assert!(matches!(opts.parse(&data), Err(Error::LimitExceeded { .. })));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions {
+	pub(crate) max_cipher_suites: usize,
+	pub(crate) max_extensions: usize,
+	pub(crate) max_list_entries: usize,
+	pub(crate) recover_malformed_extensions: bool,
+	pub(crate) strict_extension_bodies: bool,
+	pub(crate) strict_record_framing: bool,
+	pub(crate) sni_policy: SniPolicy,
+	pub(crate) unknown_extensions: UnknownExtensionPolicy,
+	pub(crate) reserved_values: Option<fn(u16) -> bool>,
+}
+
+/// Which `host_name` entry to keep when an SNI extension lists several.
+///
+/// RFC 6066 allows at most one name per type, but clients can send more,
+/// and middleboxes disagree on which one they route by. Pick the policy
+/// of the device being emulated with [`ParseOptions::sni_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SniPolicy {
+	/// Keep the first `host_name` entry.
+	#[default]
+	First,
+	/// Keep the last `host_name` entry.
+	Last,
+	/// Fail the parse with [`Error::MultipleServerNames`].
+	Reject,
+}
+
+/// How much of each [`Extension::Unknown`] body to keep.
+///
+/// Parsed hellos borrow their input, but bodies of undecoded extensions
+/// are copied whenever a hello is serialized, exported, or stored, and
+/// those are mostly padding-like blobs nobody reads. Choose a policy with
+/// [`ParseOptions::unknown_extensions`]. The wire length of every body is
+/// still recorded in
+/// [`ClientHello::extension_body_lengths`](crate::ClientHello::extension_body_lengths).
+///
+/// [`Extension::Unknown`]: crate::Extension::Unknown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum UnknownExtensionPolicy {
+	/// Keep the whole body.
+	#[default]
+	Keep,
+	/// Keep only the type id; the body is left empty.
+	Drop,
+	/// Keep at most this many leading bytes of the body.
+	Truncate(usize),
+}
+
+impl UnknownExtensionPolicy {
+	pub(crate) fn apply(self, data: &[u8]) -> &[u8] {
+		match self {
+			Self::Keep => data,
+			Self::Drop => &[],
+			Self::Truncate(max) => &data[..data.len().min(max)],
+		}
+	}
+}
+
+// Predicates compare by address, which is all a function pointer
+// offers; the same function may still compare unequal across codegen
+// units.
+impl PartialEq for ParseOptions {
+	fn eq(&self, other: &Self) -> bool {
+		let reserved_values = match (self.reserved_values, other.reserved_values) {
+			(Some(a), Some(b)) => core::ptr::fn_addr_eq(a, b),
+			(a, b) => a.is_none() && b.is_none(),
+		};
+		self.max_cipher_suites == other.max_cipher_suites
+			&& self.max_extensions == other.max_extensions
+			&& self.max_list_entries == other.max_list_entries
+			&& self.recover_malformed_extensions == other.recover_malformed_extensions
+			&& self.strict_extension_bodies == other.strict_extension_bodies
+			&& self.strict_record_framing == other.strict_record_framing
+			&& self.sni_policy == other.sni_policy
+			&& self.unknown_extensions == other.unknown_extensions
+			&& reserved_values
+	}
+}
+
+impl Eq for ParseOptions {}
+
+impl Default for ParseOptions {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ParseOptions {
+	/// Create options with the default limits: 1024 cipher suites, 256
+	/// extensions, and 1024 entries per extension list.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			max_cipher_suites: 1024,
+			max_extensions: 256,
+			max_list_entries: 1024,
+			recover_malformed_extensions: false,
+			strict_extension_bodies: false,
+			strict_record_framing: false,
+			sni_policy: SniPolicy::First,
+			unknown_extensions: UnknownExtensionPolicy::Keep,
+			reserved_values: None,
+		}
+	}
+
+	/// Set the maximum number of cipher suites.
+	#[must_use]
+	pub const fn max_cipher_suites(mut self, max: usize) -> Self {
+		self.max_cipher_suites = max;
+		self
+	}
+
+	/// Set the maximum number of extensions.
+	#[must_use]
+	pub const fn max_extensions(mut self, max: usize) -> Self {
+		self.max_extensions = max;
+		self
+	}
+
+	/// Set the maximum number of entries in any single extension list
+	/// (server names, ALPN protocols, versions, groups, signature
+	/// algorithms, key shares).
+	#[must_use]
+	pub const fn max_list_entries(mut self, max: usize) -> Self {
+		self.max_list_entries = max;
+		self
+	}
+
+	/// Keep parsing when an extension body fails to decode (disabled by
+	/// default).
+	///
+	/// The extension is recorded as [`Extension::Malformed`] with the
+	/// decoding error's message and its raw bytes, and parsing continues with the
+	/// next extension, so one bad extension no longer hides everything
+	/// after it. Errors in the extension list framing itself, such as an
+	/// extension length running past the list, still abort the parse.
+	///
+	/// [`Extension::Malformed`]: crate::Extension::Malformed
+	#[must_use]
+	pub const fn recover_malformed_extensions(mut self, recover: bool) -> Self {
+		self.recover_malformed_extensions = recover;
+		self
+	}
+
+	/// Reject structured extension bodies with bytes left after their
+	/// content (disabled by default).
+	///
+	/// Appending bytes after an SNI or ALPN list is a known trick for
+	/// slipping past middleboxes that parse extensions differently.
+	/// Strict mode fails such hellos with [`Error::TrailingBytes`];
+	/// otherwise the extension type is recorded in
+	/// [`Diagnostics::trailing_bytes`](crate::Diagnostics::trailing_bytes).
+	/// Extensions kept as [`Extension::Unknown`](crate::Extension::Unknown)
+	/// are never checked.
+	#[must_use]
+	pub const fn strict_extension_bodies(mut self, strict: bool) -> Self {
+		self.strict_extension_bodies = strict;
+		self
+	}
+
+	/// Enforce record-layer length rules in
+	/// [`parse_from_record`](Self::parse_from_record) (disabled by
+	/// default).
+	///
+	/// Strict mode rejects record payloads over 2^14 bytes with
+	/// [`Error::RecordTooLong`], and payloads holding more than the
+	/// ClientHello message with [`Error::HandshakeLengthMismatch`].
+	/// Otherwise both are accepted and flagged in
+	/// [`Diagnostics`](crate::Diagnostics).
+	#[must_use]
+	pub const fn strict_record_framing(mut self, strict: bool) -> Self {
+		self.strict_record_framing = strict;
+		self
+	}
+
+	/// Choose how an SNI extension with several `host_name` entries is
+	/// handled (default [`SniPolicy::First`]).
+	///
+	/// Unless the policy rejects the hello, only the chosen entry is kept
+	/// in [`Extension::ServerName`](crate::Extension::ServerName), entries
+	/// of other name types are left alone, and
+	/// [`Diagnostics::multiple_server_names`](crate::Diagnostics::multiple_server_names)
+	/// is set.
+	#[must_use]
+	pub const fn sni_policy(mut self, policy: SniPolicy) -> Self {
+		self.sni_policy = policy;
+		self
+	}
+
+	/// Choose how much of each undecoded extension body to keep (default
+	/// [`UnknownExtensionPolicy::Keep`]).
+	///
+	/// Applies to extensions kept as
+	/// [`Extension::Unknown`](crate::Extension::Unknown), including those
+	/// whose decoder is compiled out, but not to
+	/// [`Extension::Malformed`](crate::Extension::Malformed) bodies.
+	/// Serialized and exported hellos carry the shortened bodies.
+	#[must_use]
+	pub const fn unknown_extensions(mut self, policy: UnknownExtensionPolicy) -> Self {
+		self.unknown_extensions = policy;
+		self
+	}
+
+	/// Treat the values `predicate` accepts like RFC 8701 GREASE (none
+	/// by default).
+	///
+	/// For private code points an organization sprinkles into its
+	/// clients' hellos the way GREASE is. Matching cipher suites,
+	/// extension types, supported versions, supported groups, signature
+	/// algorithms, and key shares are filtered out like GREASE, so
+	/// fingerprints ignore them too, and recorded with their values in
+	/// [`ClientHello::reserved`](crate::ClientHello::reserved). They are
+	/// not GREASE, though: they leave
+	/// [`GreasePositions`](crate::GreasePositions) and
+	/// [`has_grease`](crate::ClientHello::has_grease) alone, so the
+	/// client-stack heuristics that look for RFC 8701 GREASE are not
+	/// misled. GREASE itself is always filtered. The allocation-free paths,
+	/// [`summarize`](crate::summarize) and
+	/// [`extract_sni`](crate::extract_sni), take no options and only
+	/// know GREASE.
+	///
+	/// ```
+	/// use clienthello::{Builder, ParseOptions};
+	///
+	/// let msg = Builder::new()
+	///     .cipher_suites(&[0xff42, 0x1301])
+	///     .supported_groups(&[0xff42, 0x001d])
+	///     .build()
+	///     .unwrap();
+	/// let opts = ParseOptions::new().reserved_values(|v| v & 0xff00 == 0xff00);
+	/// let hello = opts.parse(&msg).unwrap();
+	/// assert_eq!(hello.cipher_suites, [0x1301]);
+	/// assert_eq!(hello.supported_groups(), [0x001d]);
+	/// assert_eq!(hello.reserved.cipher_suites, [(0, 0xff42)]);
+	/// assert!(!hello.has_grease);
+	/// ```
+	#[must_use]
+	pub const fn reserved_values(mut self, predicate: fn(u16) -> bool) -> Self {
+		self.reserved_values = Some(predicate);
+		self
+	}
+
+	/// Filter `value` out of a list if it is GREASE or accepted by
+	/// [`reserved_values`](Self::reserved_values), recording where it
+	/// was; `kept` values of the list preceded it. Returns whether it was
+	/// filtered out.
+	pub(crate) fn filter(
+		&self,
+		value: u16,
+		kept: usize,
+		grease: &mut Vec<usize>,
+		reserved: &mut Vec<(usize, u16)>,
+		has_grease: &mut bool,
+	) -> bool {
+		if is_grease(value) {
+			grease.push(kept + grease.len());
+			*has_grease = true;
+			true
+		} else if self.reserved_values.is_some_and(|f| f(value)) {
+			reserved.push((kept + grease.len() + reserved.len(), value));
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Parse a raw Handshake ClientHello with these options.
+	///
+	/// # Errors
+	///
+	/// Returns the same errors as [`parse`](crate::parse), plus
+	/// [`Error::LimitExceeded`] when a list exceeds its configured limit.
+	pub fn parse<'a>(&self, data: &'a [u8]) -> Result<ClientHello<'a>, Error> {
+		parser::parse_with(data, self)
+	}
+
+	/// Parse a TLS record containing a ClientHello with these options.
+	///
+	/// # Errors
+	///
+	/// Returns the same errors as
+	/// [`parse_from_record`](crate::parse_from_record), plus
+	/// [`Error::LimitExceeded`] when a list exceeds its configured limit.
+	pub fn parse_from_record<'a>(&self, data: &'a [u8]) -> Result<ClientHello<'a>, Error> {
+		parser::parse_from_record_with(data, self)
+	}
+
+	/// Parse a ClientHello in any supported framing with these options.
+	///
+	/// # Errors
+	///
+	/// Returns the same errors as [`parse_any`](crate::parse_any), plus
+	/// [`Error::LimitExceeded`] when a list exceeds its configured limit.
+	pub fn parse_any<'a>(&self, data: &'a [u8]) -> Result<(ClientHello<'a>, Transport), Error> {
+		parser::parse_any_with(data, self)
+	}
+}
+
+/// Fail with [`Error::LimitExceeded`] when `count` entries exceed `max`.
+pub(crate) fn check_limit(count: usize, max: usize, field: &'static str) -> Result<(), Error> {
+	if count > max {
+		return Err(Error::LimitExceeded { field, max });
+	}
+	Ok(())
+}
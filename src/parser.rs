@@ -1,12 +1,17 @@
 /* src/parser.rs */
 
+use alloc::string::ToString;
 use alloc::vec::Vec;
 
-use crate::ClientHello;
 use crate::Error;
-use crate::extension::{Extension, parse_extension};
-use crate::grease::is_grease;
-use crate::reader::Reader;
+use crate::consts::{CONTENT_TYPE_HANDSHAKE, HANDSHAKE_TYPE_CLIENT_HELLO, MAX_RECORD_PAYLOAD_LEN};
+use crate::extension::{Extension, Extensions, parse_extension};
+use crate::framing;
+use crate::grease::{GreasePositions, ReservedValues, is_grease};
+use crate::options::check_limit;
+use crate::tls_reader::Reader;
+use crate::types::{ContentType, HandshakeType};
+use crate::{ClientHello, Diagnostics, ParseOptions, Transport, Warning};
 
 /// Parse a TLS ClientHello from a raw Handshake message.
 ///
@@ -17,7 +22,8 @@ use crate::reader::Reader;
 /// # Errors
 ///
 /// Returns an error when the data is truncated, the handshake type
-/// is not ClientHello, or any field cannot be decoded.
+/// is not ClientHello, or any field cannot be decoded. Malformed input
+/// never causes a panic.
 ///
 /// ```
 /// # fn build() -> Vec<u8> {
@@ -31,17 +37,7 @@ use crate::reader::Reader;
 /// assert_eq!(hello.legacy_version, 0x0303);
 /// ```
 pub fn parse(data: &[u8]) -> Result<ClientHello<'_>, Error> {
-	if data.is_empty() {
-		return Err(Error::BufferTooShort { need: 1, have: 0 });
-	}
-	let mut r = Reader::new(data);
-	let hs_type = r.read_u8("handshake type")?;
-	if hs_type != 0x01 {
-		return Err(Error::NotClientHello(hs_type));
-	}
-	let body_len = r.read_u24("handshake length")? as usize;
-	let body = r.read_bytes(body_len, "handshake body")?;
-	parse_body(body)
+	parse_with(data, &ParseOptions::new())
 }
 
 /// Parse a TLS ClientHello from a TLS record-layer message.
@@ -69,43 +65,171 @@ pub fn parse(data: &[u8]) -> Result<ClientHello<'_>, Error> {
 /// assert_eq!(hello.cipher_suites, vec![0x1301]);
 /// ```
 pub fn parse_from_record(data: &[u8]) -> Result<ClientHello<'_>, Error> {
-	if data.len() < 5 {
-		return Err(Error::BufferTooShort {
-			need: 5,
-			have: data.len(),
+	parse_from_record_with(data, &ParseOptions::new())
+}
+
+/// Parse a ClientHello in any framing this crate understands.
+///
+/// The framing is detected from the first bytes: a TLS record (`0x16`),
+/// a DTLS record (`0x16 0xfe`), a raw handshake message (`0x01`), or an
+/// SSL 2.0 compatible CLIENT-HELLO (high bit set, message type `0x01`).
+/// The detected framing is returned alongside the hello and is also
+/// recorded in its [`transport`](ClientHello::transport) field, so
+/// captures from mixed sources can share one entry point.
+///
+/// # Errors
+///
+/// Returns [`Error::NotHandshakeRecord`] when the first byte matches no
+/// known framing, and otherwise the errors of the detected framing's
+/// parser. DTLS hellos split across several fragments are rejected
+/// with [`Error::Truncated`].
+///
+/// ```
+/// use clienthello::{Builder, Transport};
+///
+/// let hello = Builder::new().cipher_suites(&[0x1301]);
+/// let record = hello.build_record().unwrap();
+/// let (parsed, framing) = clienthello::parse_any(&record).unwrap();
+/// assert_eq!(framing, Transport::TlsRecord);
+/// assert_eq!(parsed.cipher_suites, [0x1301]);
+///
+/// let raw = hello.build().unwrap();
+/// assert_eq!(clienthello::parse_any(&raw).unwrap().1, Transport::Handshake);
+/// ```
+pub fn parse_any(data: &[u8]) -> Result<(ClientHello<'_>, Transport), Error> {
+	parse_any_with(data, &ParseOptions::new())
+}
+
+pub(crate) fn parse_with<'a>(
+	data: &'a [u8],
+	opts: &ParseOptions,
+) -> Result<ClientHello<'a>, Error> {
+	parse_handshake(data, Transport::Handshake, opts)
+}
+
+pub(crate) fn parse_from_record_with<'a>(
+	data: &'a [u8],
+	opts: &ParseOptions,
+) -> Result<ClientHello<'a>, Error> {
+	let handshake = framing::record_payload(data)?;
+	let oversized = handshake.len() > MAX_RECORD_PAYLOAD_LEN;
+	if oversized && opts.strict_record_framing {
+		return Err(Error::RecordTooLong {
+			len: handshake.len(),
+			max: MAX_RECORD_PAYLOAD_LEN,
 		});
 	}
-	let mut r = Reader::new(data);
-	let content_type = r.read_u8("record content type")?;
-	if content_type != 0x16 {
-		return Err(Error::NotHandshakeRecord(content_type));
+	let mut hello = parse_handshake(handshake, Transport::TlsRecord, opts)?;
+	// The body was read successfully, so the payload holds at least the
+	// 4-byte header plus the declared handshake length.
+	let handshake_len = framing::client_hello_body(handshake)?.len() + 4;
+	if handshake_len != handshake.len() {
+		if opts.strict_record_framing {
+			return Err(Error::HandshakeLengthMismatch {
+				handshake: handshake_len,
+				record: handshake.len(),
+			});
+		}
+		hello.diagnostics.handshake_length_mismatch = true;
+		hello
+			.diagnostics
+			.warnings
+			.push(Warning::HandshakeLengthMismatch {
+				handshake: handshake_len,
+				record: handshake.len(),
+			});
+	}
+	if oversized {
+		hello.diagnostics.oversized_record = true;
+		hello.diagnostics.warnings.push(Warning::OversizedRecord {
+			len: handshake.len(),
+		});
 	}
-	let _version = r.read_u16("record protocol version")?;
-	let record_len = r.read_u16("record length")? as usize;
-	let handshake = r.read_bytes(record_len, "record payload")?;
-	parse(handshake)
+	Ok(hello)
+}
+
+pub(crate) fn parse_any_with<'a>(
+	data: &'a [u8],
+	opts: &ParseOptions,
+) -> Result<(ClientHello<'a>, Transport), Error> {
+	let hello = match data {
+		[] => return Err(Error::BufferTooShort { need: 1, have: 0 }),
+		[CONTENT_TYPE_HANDSHAKE, 0xfe, ..] => parse_body(
+			framing::dtls_client_hello_body(data)?,
+			Transport::Dtls,
+			opts,
+		)?,
+		[CONTENT_TYPE_HANDSHAKE, ..] => parse_from_record_with(data, opts)?,
+		[HANDSHAKE_TYPE_CLIENT_HELLO, ..] => parse_with(data, opts)?,
+		[first, _, HANDSHAKE_TYPE_CLIENT_HELLO, ..] if first & 0x80 != 0 => {
+			parse_sslv2(framing::sslv2_record_payload(data)?, opts)?
+		}
+		[first, ..] => return Err(Error::NotHandshakeRecord(ContentType::from_u8(*first))),
+	};
+	let transport = hello.transport;
+	Ok((hello, transport))
+}
+
+fn parse_handshake<'a>(
+	data: &'a [u8],
+	transport: Transport,
+	opts: &ParseOptions,
+) -> Result<ClientHello<'a>, Error> {
+	let body = framing::client_hello_body(data)?;
+	parse_body(body, transport, opts)
 }
 
-fn parse_body<'a>(data: &'a [u8]) -> Result<ClientHello<'a>, Error> {
+fn parse_body<'a>(
+	data: &'a [u8],
+	transport: Transport,
+	opts: &ParseOptions,
+) -> Result<ClientHello<'a>, Error> {
 	let mut r = Reader::new(data);
 	let mut has_grease = false;
+	let mut grease = GreasePositions::default();
+	let mut reserved = ReservedValues::default();
+	let mut diagnostics = Diagnostics::default();
 
 	let legacy_version = r.read_u16("legacy version")?;
 	let random = r.read_bytes(32, "client random")?;
 
 	let sid_len = r.read_u8("session ID length")? as usize;
 	let session_id = r.read_bytes(sid_len, "session ID")?;
+	if transport == Transport::Dtls {
+		r.read_vec_u8("DTLS cookie")?;
+	}
 
-	let cipher_suites = parse_cipher_suites(&mut r, &mut has_grease)?;
+	let cipher_suites = parse_cipher_suites(
+		&mut r,
+		opts,
+		&mut grease.cipher_suites,
+		&mut reserved.cipher_suites,
+		&mut has_grease,
+		&mut diagnostics,
+	)?;
 
 	let comp_len = r.read_u8("compression methods length")? as usize;
 	let compression_methods = r.read_bytes(comp_len, "compression methods")?;
 
+	let mut extension_body_lengths = Vec::new();
 	let extensions = if r.remaining() >= 2 {
-		parse_extensions(&mut r, &mut has_grease)?
+		parse_extensions(
+			&mut r,
+			opts,
+			&mut grease,
+			&mut reserved,
+			&mut extension_body_lengths,
+			&mut has_grease,
+			&mut diagnostics,
+		)?
 	} else {
-		Vec::new()
+		Extensions::default()
 	};
+	if !r.is_empty() {
+		diagnostics
+			.warnings
+			.push(Warning::TrailingBodyBytes { len: r.remaining() });
+	}
 
 	Ok(ClientHello {
 		legacy_version,
@@ -114,11 +238,86 @@ fn parse_body<'a>(data: &'a [u8]) -> Result<ClientHello<'a>, Error> {
 		cipher_suites,
 		compression_methods,
 		extensions,
+		extension_body_lengths,
+		has_grease,
+		grease,
+		reserved,
+		diagnostics,
+		transport,
+	})
+}
+
+/// Parse an SSL 2.0 compatible CLIENT-HELLO (RFC 5246 Appendix E.2).
+///
+/// The challenge stands in for the client random, and cipher specs
+/// without a TLS equivalent (a non-zero first byte) are dropped.
+fn parse_sslv2<'a>(data: &'a [u8], opts: &ParseOptions) -> Result<ClientHello<'a>, Error> {
+	let mut r = Reader::new(data);
+	let msg_type = HandshakeType::from_u8(r.read_u8("handshake type")?);
+	if msg_type != HandshakeType::ClientHello {
+		return Err(Error::NotClientHello(msg_type));
+	}
+	let legacy_version = r.read_u16("legacy version")?;
+	let specs_len = usize::from(r.read_u16("cipher specs length")?);
+	let sid_len = usize::from(r.read_u16("session ID length")?);
+	let challenge_len = usize::from(r.read_u16("challenge length")?);
+	if !specs_len.is_multiple_of(3) {
+		return Err(Error::Truncated {
+			field: "cipher specs (length not a multiple of 3)",
+		});
+	}
+	check_limit(specs_len / 3, opts.max_cipher_suites, "cipher suites")?;
+	let specs = r.read_bytes(specs_len, "cipher specs")?;
+	let session_id = r.read_bytes(sid_len, "session ID")?;
+	let random = r.read_bytes(challenge_len, "challenge")?;
+
+	let mut has_grease = false;
+	let mut grease = GreasePositions::default();
+	let mut reserved = ReservedValues::default();
+	let mut cipher_suites = Vec::new();
+	for spec in specs.chunks_exact(3).filter(|spec| spec[0] == 0) {
+		let suite = u16::from_be_bytes([spec[1], spec[2]]);
+		if !opts.filter(
+			suite,
+			cipher_suites.len(),
+			&mut grease.cipher_suites,
+			&mut reserved.cipher_suites,
+			&mut has_grease,
+		) {
+			cipher_suites.push(suite);
+		}
+	}
+	let mut diagnostics = Diagnostics::default();
+	if !r.is_empty() {
+		diagnostics
+			.warnings
+			.push(Warning::TrailingBodyBytes { len: r.remaining() });
+	}
+
+	Ok(ClientHello {
+		legacy_version,
+		random,
+		session_id,
+		cipher_suites,
+		compression_methods: &[],
+		extensions: Extensions::default(),
+		extension_body_lengths: Vec::new(),
 		has_grease,
+		grease,
+		reserved,
+		diagnostics,
+		transport: Transport::SslV2,
 	})
 }
 
-fn parse_cipher_suites(r: &mut Reader<'_>, has_grease: &mut bool) -> Result<Vec<u16>, Error> {
+fn parse_cipher_suites(
+	r: &mut Reader<'_>,
+	opts: &ParseOptions,
+	grease: &mut Vec<usize>,
+	reserved: &mut Vec<(usize, u16)>,
+	has_grease: &mut bool,
+	diagnostics: &mut Diagnostics,
+) -> Result<Vec<u16>, Error> {
 	let len = r.read_u16("cipher suites length")? as usize;
 	if !len.is_multiple_of(2) {
 		return Err(Error::Truncated {
@@ -126,14 +325,21 @@ fn parse_cipher_suites(r: &mut Reader<'_>, has_grease: &mut bool) -> Result<Vec<
 		});
 	}
 	let cs_data = r.read_bytes(len, "cipher suites data")?;
+	check_limit(len / 2, opts.max_cipher_suites, "cipher suites")?;
 	let mut inner = Reader::new(cs_data);
-	let mut suites = Vec::new();
+	let mut suites = Vec::with_capacity(len / 2);
+	let mut seen_grease = Vec::new();
 	while inner.remaining() >= 2 {
 		let val = inner.read_u16("cipher suite")?;
-		if is_grease(val) {
-			*has_grease = true;
-		} else {
+		if !opts.filter(val, suites.len(), grease, reserved, has_grease) {
 			suites.push(val);
+		} else if is_grease(val) {
+			if seen_grease.contains(&val) {
+				diagnostics
+					.warnings
+					.push(Warning::RepeatedGreaseCipherSuite { value: val });
+			}
+			seen_grease.push(val);
 		}
 	}
 	Ok(suites)
@@ -141,21 +347,70 @@ fn parse_cipher_suites(r: &mut Reader<'_>, has_grease: &mut bool) -> Result<Vec<
 
 fn parse_extensions<'a>(
 	r: &mut Reader<'a>,
+	opts: &ParseOptions,
+	grease: &mut GreasePositions,
+	reserved: &mut ReservedValues,
+	lengths: &mut Vec<u16>,
 	has_grease: &mut bool,
-) -> Result<Vec<Extension<'a>>, Error> {
+	diagnostics: &mut Diagnostics,
+) -> Result<Extensions<'a>, Error> {
 	let len = r.read_u16("extensions length")? as usize;
 	let ext_data = r.read_bytes(len, "extensions data")?;
 	let mut inner = Reader::new(ext_data);
 	let mut extensions = Vec::new();
+	let mut seen_grease = Vec::new();
 	while inner.remaining() >= 4 {
+		let pos = extensions.len() + grease.extensions.len() + reserved.extensions.len();
+		check_limit(pos + 1, opts.max_extensions, "extensions")?;
 		let type_id = inner.read_u16("extension type")?;
-		let ext_len = inner.read_u16("extension length")? as usize;
-		let ext_body = inner.read_bytes(ext_len, "extension body")?;
-		if is_grease(type_id) {
-			*has_grease = true;
+		let ext_len = inner.read_u16("extension length")?;
+		let ext_body = inner.read_bytes(usize::from(ext_len), "extension body")?;
+		if opts.filter(
+			type_id,
+			extensions.len(),
+			&mut grease.extensions,
+			&mut reserved.extensions,
+			has_grease,
+		) {
+			if is_grease(type_id) {
+				if seen_grease.contains(&type_id) {
+					diagnostics
+						.warnings
+						.push(Warning::RepeatedGreaseExtension { value: type_id });
+				}
+				seen_grease.push(type_id);
+			}
 			continue;
 		}
-		extensions.push(parse_extension(type_id, ext_body, has_grease)?);
+		let ext = match parse_extension(
+			type_id,
+			ext_body,
+			opts,
+			has_grease,
+			grease,
+			reserved,
+			diagnostics,
+		) {
+			Err(error) if opts.recover_malformed_extensions => {
+				diagnostics
+					.warnings
+					.push(Warning::MalformedExtension { type_id });
+				Extension::Malformed {
+					type_id,
+					error: error.to_string(),
+					raw: ext_body,
+				}
+			}
+			result => result?,
+		};
+		extensions.push(ext);
+		lengths.push(ext_len);
+	}
+	if !inner.is_empty() {
+		diagnostics.warnings.push(Warning::PartialListEntry {
+			type_id: None,
+			len: inner.remaining(),
+		});
 	}
-	Ok(extensions)
+	Ok(Extensions::new(extensions))
 }
@@ -0,0 +1,581 @@
+/* src/pcap.rs */
+
+//! Offline analysis of packet captures.
+//!
+//! [`PcapReader`] reads classic libpcap files, and [`Pipeline`] turns
+//! one into an iterator of per-flow results: it follows TCP streams and
+//! DTLS datagrams, collects the first ClientHello of each flow, and runs
+//! a user function over batches of them on the rayon thread pool.
+//! Packets are read only as results are consumed, so at most one batch
+//! is held in memory however large the capture is.
+//!
+//! TCP segments must arrive in order; a flow whose ClientHello spans a
+//! lost or reordered segment never completes and is reported as
+//! truncated when the capture ends. QUIC Initial packets are encrypted
+//! and skipped; decrypt them and use [`Connection`](crate::Connection).
+//!
+//! ```no_run
+//! use clienthello::pcap::{PcapReader, Pipeline};
+//!
+//! let file = std::fs::File::open("capture.pcap").unwrap();
+//! let reader = PcapReader::new(std::io::BufReader::new(file)).unwrap();
+//! let flows = Pipeline::new(|hello| hello.server_name().map(String::from))
+//!     .on_progress(|p| eprintln!("{} packets, {} hellos", p.packets, p.hellos))
+//!     .on_error(|e| eprintln!("capture error: {e}"))
+//!     .run(reader);
+//! for flow in flows {
+//!     println!("{} -> {}: {:?}", flow.flow.src, flow.flow.dst, flow.result);
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{self, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use rayon::prelude::*;
+
+use crate::consts::{CONTENT_TYPE_HANDSHAKE, HANDSHAKE_TYPE_CLIENT_HELLO, RECORD_HEADER_LEN};
+use crate::{ClientHello, Error, parse_any};
+
+/// Largest packet a capture may declare, as in libpcap.
+const MAX_SNAPLEN: usize = 256 * 1024;
+
+/// Errors produced while reading a capture.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum PcapError {
+	/// Reading the capture failed.
+	#[error("io: {0}")]
+	Io(#[from] io::Error),
+
+	/// The file does not start with a pcap magic number.
+	#[error("not a pcap file (magic {0:#010x})")]
+	BadMagic(u32),
+
+	/// A packet record declares more bytes than any capture holds.
+	#[error("packet of {0} bytes exceeds the snapshot limit")]
+	OversizedPacket(usize),
+}
+
+/// One packet of a capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+	/// Capture time since the Unix epoch.
+	pub timestamp: Duration,
+	/// Captured bytes, starting at the link-layer header.
+	pub data: Vec<u8>,
+}
+
+/// Reader for classic libpcap capture files.
+///
+/// Both byte orders and microsecond and nanosecond timestamps are
+/// supported; pcapng is not.
+#[derive(Debug)]
+pub struct PcapReader<R> {
+	inner: R,
+	swapped: bool,
+	nanos: bool,
+	link_type: u32,
+}
+
+impl<R: Read> PcapReader<R> {
+	/// Read the file header.
+	///
+	/// # Errors
+	///
+	/// Returns [`PcapError::BadMagic`] for input that is not a pcap file,
+	/// or [`PcapError::Io`] when the header cannot be read.
+	pub fn new(mut inner: R) -> Result<Self, PcapError> {
+		let mut header = [0; 24];
+		inner.read_exact(&mut header)?;
+		let magic = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+		let (swapped, nanos) = match magic {
+			0xa1b2_c3d4 => (false, false),
+			0xa1b2_3c4d => (false, true),
+			0xd4c3_b2a1 => (true, false),
+			0x4d3c_b2a1 => (true, true),
+			other => return Err(PcapError::BadMagic(other)),
+		};
+		let mut reader = Self {
+			inner,
+			swapped,
+			nanos,
+			link_type: 0,
+		};
+		reader.link_type = reader.u32_at(&header, 20);
+		Ok(reader)
+	}
+
+	/// Link-layer header type of every packet, e.g. `1` for Ethernet.
+	#[must_use]
+	pub fn link_type(&self) -> u32 {
+		self.link_type
+	}
+
+	fn u32_at(&self, buf: &[u8], at: usize) -> u32 {
+		let bytes = [buf[at], buf[at + 1], buf[at + 2], buf[at + 3]];
+		if self.swapped {
+			u32::from_be_bytes(bytes)
+		} else {
+			u32::from_le_bytes(bytes)
+		}
+	}
+
+	fn read_packet(&mut self) -> Result<Option<Packet>, PcapError> {
+		let mut header = [0; 16];
+		match self.inner.read_exact(&mut header) {
+			Ok(()) => {}
+			Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+			Err(e) => return Err(e.into()),
+		}
+		let secs = self.u32_at(&header, 0);
+		let frac = self.u32_at(&header, 4);
+		let len = self.u32_at(&header, 8) as usize;
+		if len > MAX_SNAPLEN {
+			return Err(PcapError::OversizedPacket(len));
+		}
+		let mut data = vec![0; len];
+		self.inner.read_exact(&mut data)?;
+		let nanos = if self.nanos {
+			frac
+		} else {
+			frac.saturating_mul(1000)
+		};
+		Ok(Some(Packet {
+			timestamp: Duration::new(u64::from(secs), nanos),
+			data,
+		}))
+	}
+}
+
+impl<R: Read> Iterator for PcapReader<R> {
+	type Item = Result<Packet, PcapError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.read_packet().transpose()
+	}
+}
+
+/// Transport protocol of a [`FlowKey`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Protocol {
+	/// TCP, carrying TLS.
+	Tcp,
+	/// UDP, carrying DTLS.
+	Udp,
+}
+
+/// The 5-tuple of a flow, in the client-to-server direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FlowKey {
+	/// Transport protocol.
+	pub protocol: Protocol,
+	/// Client address.
+	pub src: SocketAddr,
+	/// Server address.
+	pub dst: SocketAddr,
+}
+
+/// The first ClientHello of one flow, as produced by [`Pipeline`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowResult<T> {
+	/// Flow the hello was sent on.
+	pub flow: FlowKey,
+	/// Capture time of the packet that completed the hello.
+	pub timestamp: Duration,
+	/// The pipeline function's output, or why the hello did not parse.
+	pub result: Result<T, Error>,
+}
+
+/// Counters passed to the progress callback of a [`Pipeline`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Progress {
+	/// Packets read.
+	pub packets: u64,
+	/// Captured bytes read.
+	pub bytes: u64,
+	/// ClientHellos collected, parsed or not.
+	pub hellos: u64,
+	/// Collected hellos that failed to parse.
+	pub errors: u64,
+}
+
+/// Capture-to-results pipeline; see the [module documentation](self).
+pub struct Pipeline<'a, F> {
+	map: F,
+	batch_size: usize,
+	max_flows: usize,
+	on_progress: Option<Box<dyn FnMut(&Progress) + 'a>>,
+	on_error: Option<Box<dyn FnMut(&PcapError) + 'a>>,
+}
+
+impl<F> core::fmt::Debug for Pipeline<'_, F> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("Pipeline")
+			.field("batch_size", &self.batch_size)
+			.field("max_flows", &self.max_flows)
+			.finish_non_exhaustive()
+	}
+}
+
+impl<'a, F, T> Pipeline<'a, F>
+where
+	F: Fn(&ClientHello<'_>) -> T + Sync,
+	T: Send,
+{
+	/// Create a pipeline that applies `map` to every parsed hello, with
+	/// batches of 256 hellos and up to 65536 flows being collected.
+	#[must_use]
+	pub fn new(map: F) -> Self {
+		Self {
+			map,
+			batch_size: 256,
+			max_flows: 65536,
+			on_progress: None,
+			on_error: None,
+		}
+	}
+
+	/// Set how many hellos are collected before a batch is parsed in
+	/// parallel (at least one). Larger batches use the thread pool
+	/// better and hold more memory.
+	#[must_use]
+	pub fn batch_size(mut self, hellos: usize) -> Self {
+		self.batch_size = hellos.max(1);
+		self
+	}
+
+	/// Set how many flows may be collecting a hello at once (at least
+	/// one); the flow seen least recently is dropped beyond that. Flows
+	/// whose hello was reported are remembered in as many slots again,
+	/// so retransmissions are not reported twice.
+	#[must_use]
+	pub fn max_flows(mut self, flows: usize) -> Self {
+		self.max_flows = flows.max(1);
+		self
+	}
+
+	/// Call `f` with the running counters after every batch.
+	#[must_use]
+	pub fn on_progress(mut self, f: impl FnMut(&Progress) + 'a) -> Self {
+		self.on_progress = Some(Box::new(f));
+		self
+	}
+
+	/// Call `f` when the capture cannot be read further. The hellos
+	/// collected so far are still reported, then iteration ends.
+	#[must_use]
+	pub fn on_error(mut self, f: impl FnMut(&PcapError) + 'a) -> Self {
+		self.on_error = Some(Box::new(f));
+		self
+	}
+
+	/// Start reading `reader`, returning the per-flow results in capture
+	/// order.
+	pub fn run<R: Read>(self, reader: PcapReader<R>) -> Flows<'a, R, F, T> {
+		Flows {
+			pipeline: self,
+			reader,
+			pending: HashMap::new(),
+			seq: 0,
+			finished: HashSet::new(),
+			finished_order: VecDeque::new(),
+			progress: Progress::default(),
+			ready: Vec::new().into_iter(),
+			done: false,
+		}
+	}
+}
+
+/// A TCP stream collecting its first record.
+struct Pending {
+	buf: Vec<u8>,
+	next_seq: u32,
+	/// Order the flow was last extended in, for eviction.
+	seq: u64,
+	/// Capture time of the latest segment.
+	timestamp: Duration,
+}
+
+/// Iterator over the results of a [`Pipeline`].
+pub struct Flows<'a, R, F, T> {
+	pipeline: Pipeline<'a, F>,
+	reader: PcapReader<R>,
+	pending: HashMap<FlowKey, Pending>,
+	seq: u64,
+	finished: HashSet<FlowKey>,
+	finished_order: VecDeque<FlowKey>,
+	progress: Progress,
+	ready: std::vec::IntoIter<FlowResult<T>>,
+	done: bool,
+}
+
+impl<R, F, T> core::fmt::Debug for Flows<'_, R, F, T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("Flows")
+			.field("progress", &self.progress)
+			.field("pending", &self.pending.len())
+			.finish_non_exhaustive()
+	}
+}
+
+impl<R, F, T> Flows<'_, R, F, T>
+where
+	R: Read,
+	F: Fn(&ClientHello<'_>) -> T + Sync,
+	T: Send,
+{
+	/// Counters so far.
+	#[must_use]
+	pub fn progress(&self) -> Progress {
+		self.progress
+	}
+
+	/// Read packets until a batch is full or the capture ends, then parse
+	/// the batch.
+	fn fill(&mut self) {
+		let mut batch = Vec::new();
+		while batch.len() < self.pipeline.batch_size {
+			match self.reader.next() {
+				Some(Ok(packet)) => {
+					self.progress.packets += 1;
+					self.progress.bytes += packet.data.len() as u64;
+					if let Some(hello) = self.collect(&packet) {
+						batch.push(hello);
+					}
+				}
+				Some(Err(e)) => {
+					if let Some(f) = &mut self.pipeline.on_error {
+						f(&e);
+					}
+					self.done = true;
+				}
+				None => self.done = true,
+			}
+			if self.done {
+				// Report streams that never completed; they fail to parse.
+				let mut rest: Vec<_> = self.pending.drain().collect();
+				rest.sort_by_key(|(_, p)| p.seq);
+				batch.extend(rest.into_iter().map(|(flow, p)| Collected {
+					flow,
+					timestamp: p.timestamp,
+					bytes: p.buf,
+				}));
+				break;
+			}
+		}
+		let map = &self.pipeline.map;
+		let results: Vec<FlowResult<T>> = batch
+			.into_par_iter()
+			.map(|c| FlowResult {
+				flow: c.flow,
+				timestamp: c.timestamp,
+				result: parse_any(&c.bytes).map(|(hello, _)| map(&hello)),
+			})
+			.collect();
+		self.progress.hellos += results.len() as u64;
+		self.progress.errors += results.iter().filter(|r| r.result.is_err()).count() as u64;
+		if let Some(f) = &mut self.pipeline.on_progress {
+			f(&self.progress);
+		}
+		self.ready = results.into_iter();
+	}
+
+	/// Feed one packet to its flow, returning the flow's hello once
+	/// complete.
+	fn collect(&mut self, packet: &Packet) -> Option<Collected> {
+		let segment = decode(self.reader.link_type, &packet.data)?;
+		if self.finished.contains(&segment.flow) {
+			return None;
+		}
+		let bytes = match segment.flow.protocol {
+			Protocol::Udp => {
+				// DTLS records carry the 0xfe major version.
+				if !segment.payload.starts_with(&[CONTENT_TYPE_HANDSHAKE, 0xfe]) {
+					return None;
+				}
+				segment.payload.to_vec()
+			}
+			Protocol::Tcp => self.push_tcp(&segment, packet.timestamp)?,
+		};
+		self.finish(segment.flow);
+		Some(Collected {
+			flow: segment.flow,
+			timestamp: packet.timestamp,
+			bytes,
+		})
+	}
+
+	fn push_tcp(&mut self, segment: &Segment<'_>, timestamp: Duration) -> Option<Vec<u8>> {
+		self.seq += 1;
+		let payload = segment.payload;
+		if let Some(pending) = self.pending.get(&segment.flow) {
+			// Retransmitted, reordered, or empty segments.
+			if pending.next_seq != segment.tcp_seq || payload.is_empty() {
+				return None;
+			}
+		} else {
+			let starts_hello = payload.first() == Some(&CONTENT_TYPE_HANDSHAKE)
+				&& payload.get(RECORD_HEADER_LEN) == Some(&HANDSHAKE_TYPE_CLIENT_HELLO);
+			if !starts_hello {
+				return None;
+			}
+			if self.pending.len() >= self.pipeline.max_flows {
+				self.evict_pending();
+			}
+		}
+		let pending = self.pending.entry(segment.flow).or_insert(Pending {
+			buf: Vec::new(),
+			next_seq: segment.tcp_seq,
+			seq: 0,
+			timestamp,
+		});
+		pending.buf.extend_from_slice(payload);
+		pending.next_seq = pending.next_seq.wrapping_add(payload.len() as u32);
+		pending.seq = self.seq;
+		pending.timestamp = timestamp;
+		let record_len = pending
+			.buf
+			.get(3..RECORD_HEADER_LEN)
+			.map(|len| RECORD_HEADER_LEN + usize::from(u16::from_be_bytes([len[0], len[1]])))?;
+		if pending.buf.len() < record_len {
+			return None;
+		}
+		let mut buf = self.pending.remove(&segment.flow)?.buf;
+		buf.truncate(record_len);
+		Some(buf)
+	}
+
+	fn evict_pending(&mut self) {
+		let oldest = self
+			.pending
+			.iter()
+			.min_by_key(|(_, p)| p.seq)
+			.map(|(flow, _)| *flow);
+		if let Some(flow) = oldest {
+			self.pending.remove(&flow);
+		}
+	}
+
+	fn finish(&mut self, flow: FlowKey) {
+		if self.finished_order.len() >= self.pipeline.max_flows
+			&& let Some(old) = self.finished_order.pop_front()
+		{
+			self.finished.remove(&old);
+		}
+		self.finished.insert(flow);
+		self.finished_order.push_back(flow);
+	}
+}
+
+impl<R, F, T> Iterator for Flows<'_, R, F, T>
+where
+	R: Read,
+	F: Fn(&ClientHello<'_>) -> T + Sync,
+	T: Send,
+{
+	type Item = FlowResult<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(result) = self.ready.next() {
+				return Some(result);
+			}
+			if self.done {
+				return None;
+			}
+			self.fill();
+		}
+	}
+}
+
+/// A complete hello waiting for its batch.
+struct Collected {
+	flow: FlowKey,
+	timestamp: Duration,
+	bytes: Vec<u8>,
+}
+
+/// The transport payload of a packet.
+struct Segment<'p> {
+	flow: FlowKey,
+	tcp_seq: u32,
+	payload: &'p [u8],
+}
+
+/// Decode the link, IP, and TCP or UDP headers of a packet.
+///
+/// Returns `None` for anything else, including IP fragments and IPv6
+/// extension headers.
+fn decode(link_type: u32, data: &[u8]) -> Option<Segment<'_>> {
+	const ETHERNET: u32 = 1;
+	const NULL: u32 = 0;
+	const RAW: u32 = 101;
+	const LINUX_SLL: u32 = 113;
+	let ip = match link_type {
+		ETHERNET => {
+			let mut ethertype = be16(data, 12)?;
+			let mut offset = 14;
+			// 802.1Q VLAN tags.
+			while ethertype == 0x8100 || ethertype == 0x88a8 {
+				ethertype = be16(data, offset + 2)?;
+				offset += 4;
+			}
+			matches!(ethertype, 0x0800 | 0x86dd).then_some(data.get(offset..)?)?
+		}
+		NULL => data.get(4..)?,
+		RAW => data,
+		LINUX_SLL => data.get(16..)?,
+		_ => return None,
+	};
+	let (src, dst, protocol, transport) = match ip.first()? >> 4 {
+		4 => {
+			let header_len = usize::from(ip[0] & 0x0f) * 4;
+			let total_len = usize::from(be16(ip, 2)?);
+			// Fragments other than an unfragmented first one.
+			if be16(ip, 6)? & 0x3fff != 0 {
+				return None;
+			}
+			let src = Ipv4Addr::from(<[u8; 4]>::try_from(ip.get(12..16)?).ok()?);
+			let dst = Ipv4Addr::from(<[u8; 4]>::try_from(ip.get(16..20)?).ok()?);
+			let body = ip.get(header_len..total_len.min(ip.len()))?;
+			(IpAddr::V4(src), IpAddr::V4(dst), ip.get(9).copied()?, body)
+		}
+		6 => {
+			let payload_len = usize::from(be16(ip, 4)?);
+			let src = Ipv6Addr::from(<[u8; 16]>::try_from(ip.get(8..24)?).ok()?);
+			let dst = Ipv6Addr::from(<[u8; 16]>::try_from(ip.get(24..40)?).ok()?);
+			let body = ip.get(40..(40 + payload_len).min(ip.len()))?;
+			(IpAddr::V6(src), IpAddr::V6(dst), ip.get(6).copied()?, body)
+		}
+		_ => return None,
+	};
+	let sport = be16(transport, 0)?;
+	let dport = be16(transport, 2)?;
+	let (protocol, tcp_seq, payload) = match protocol {
+		6 => {
+			let seq = u32::from_be_bytes(transport.get(4..8)?.try_into().ok()?);
+			let offset = usize::from(transport.get(12)? >> 4) * 4;
+			(Protocol::Tcp, seq, transport.get(offset..)?)
+		}
+		17 => (Protocol::Udp, 0, transport.get(8..)?),
+		_ => return None,
+	};
+	Some(Segment {
+		flow: FlowKey {
+			protocol,
+			src: SocketAddr::new(src, sport),
+			dst: SocketAddr::new(dst, dport),
+		},
+		tcp_seq,
+		payload,
+	})
+}
+
+fn be16(data: &[u8], at: usize) -> Option<u16> {
+	let bytes = data.get(at..at + 2)?;
+	Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
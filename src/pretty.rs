@@ -0,0 +1,164 @@
+/* src/pretty.rs */
+
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use crate::{ClientHello, Transport};
+
+const RESET: &str = "\x1b[0m";
+const FIELD: &str = "\x1b[1;36m";
+const GREASE: &str = "\x1b[33m";
+const ANOMALY: &str = "\x1b[1;31m";
+const DIM: &str = "\x1b[2m";
+
+/// Multi-line, optionally ANSI-colored rendering of a hello.
+///
+/// Returned by [`ClientHello::pretty`]. Field names are cyan, GREASE
+/// entries are shown in yellow at their wire positions, and cipher
+/// suites and extensions below the built-in rarity threshold, and any
+/// [`BotSignal`](crate::BotSignal)s that fired, are red. Colors can be
+/// turned off with [`Pretty::color`] for logs and files.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d
+/// # }
+/// # let data = build();
+/// let hello = clienthello::parse(&data).unwrap();
+/// let text = hello.pretty().color(false).to_string();
+/// assert!(text.contains("cipher_suites: 0x1301"));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Pretty<'h, 'a> {
+	hello: &'h ClientHello<'a>,
+	color: bool,
+}
+
+impl<'h, 'a> Pretty<'h, 'a> {
+	pub(crate) fn new(hello: &'h ClientHello<'a>) -> Self {
+		Self { hello, color: true }
+	}
+
+	/// Enable or disable ANSI escape codes (enabled by default).
+	#[must_use]
+	pub fn color(mut self, color: bool) -> Self {
+		self.color = color;
+		self
+	}
+
+	fn paint(&self, f: &mut Formatter<'_>, style: &str, text: impl Display) -> fmt::Result {
+		if self.color {
+			write!(f, "{style}{text}{RESET}")
+		} else {
+			write!(f, "{text}")
+		}
+	}
+
+	fn field(&self, f: &mut Formatter<'_>, name: &str) -> fmt::Result {
+		f.write_str("  ")?;
+		self.paint(f, FIELD, name)?;
+		f.write_str(": ")
+	}
+
+	/// Write a `u16` list with GREASE entries restored at their wire
+	/// positions and rare entries marked.
+	fn list(
+		&self,
+		f: &mut Formatter<'_>,
+		values: &[u16],
+		grease: &[usize],
+		rare: &[u16],
+	) -> fmt::Result {
+		let total = values.len() + grease.len();
+		let mut values = values.iter();
+		for i in 0..total {
+			if i > 0 {
+				f.write_str(", ")?;
+			}
+			if grease.contains(&i) {
+				self.paint(f, GREASE, "GREASE")?;
+			} else if let Some(&v) = values.next() {
+				let text = format_args!("{v:#06x}");
+				if rare.contains(&v) {
+					self.paint(f, ANOMALY, text)?;
+				} else {
+					write!(f, "{text}")?;
+				}
+			}
+		}
+		if total == 0 {
+			self.paint(f, DIM, "(none)")?;
+		}
+		writeln!(f)
+	}
+}
+
+impl Display for Pretty<'_, '_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let hello = self.hello;
+		let rarity = hello.rarity_score();
+		let transport = match hello.transport {
+			Transport::TlsRecord => "TLS record",
+			Transport::Handshake => "raw handshake",
+			Transport::Dtls => "DTLS record",
+			Transport::SslV2 => "SSLv2 record",
+		};
+		self.paint(f, FIELD, "ClientHello")?;
+		writeln!(f, " ({transport})")?;
+
+		self.field(f, "legacy_version")?;
+		writeln!(f, "{:#06x}", hello.legacy_version)?;
+		self.field(f, "session_id")?;
+		writeln!(f, "{} bytes", hello.session_id.len())?;
+		self.field(f, "cipher_suites")?;
+		self.list(
+			f,
+			&hello.cipher_suites,
+			&hello.grease.cipher_suites,
+			&rarity.rare_cipher_suites,
+		)?;
+		self.field(f, "compression_methods")?;
+		writeln!(f, "{:02x?}", hello.compression_methods)?;
+		self.field(f, "extensions")?;
+		let types: Vec<u16> = hello.extensions.type_ids().collect();
+		self.list(f, &types, &hello.grease.extensions, &rarity.rare_extensions)?;
+
+		self.field(f, "sni")?;
+		if let Some(name) = hello.server_name() {
+			write!(f, "{}", name.escape_debug())?;
+		} else {
+			self.paint(f, DIM, "(none)")?;
+		}
+		writeln!(f)?;
+		self.field(f, "alpn")?;
+		let alpn = hello.alpn_protocols();
+		for (i, proto) in alpn.iter().enumerate() {
+			if i > 0 {
+				f.write_str(", ")?;
+			}
+			write!(f, "{}", proto.escape_ascii())?;
+		}
+		if alpn.is_empty() {
+			self.paint(f, DIM, "(none)")?;
+		}
+		writeln!(f)?;
+		self.field(f, "supported_versions")?;
+		self.list(f, hello.supported_versions(), &[], &[])?;
+
+		self.field(f, "anomalies")?;
+		let bot = hello.bot_score();
+		for (i, signal) in bot.signals.iter().enumerate() {
+			if i > 0 {
+				f.write_str(", ")?;
+			}
+			self.paint(f, ANOMALY, signal)?;
+		}
+		if bot.signals.is_empty() {
+			self.paint(f, DIM, "(none)")?;
+		}
+		writeln!(f)
+	}
+}
@@ -0,0 +1,212 @@
+/* src/profile.rs */
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::ClientHello;
+use crate::consts::{EXT_ALPN, EXT_SERVER_NAME};
+use crate::fingerprint::PER_CONNECTION_EXTENSIONS;
+
+/// The hello a TLS library sends with its default configuration.
+///
+/// Built-in profiles cover widely used libraries; compare a hello against
+/// all of them with [`ClientHello::closest_profile`], or against one with
+/// [`Profile::compare`]. Custom profiles can be declared as constants.
+///
+/// Extensions are listed without GREASE, SNI, ALPN, padding, pre-shared
+/// key, and early data, which depend on the connection or the
+/// application rather than on the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Profile {
+	/// Library and configuration, e.g. `"rustls 0.23 (aws-lc-rs)"`.
+	pub name: &'static str,
+	/// Cipher suites in preference order.
+	pub cipher_suites: &'static [u16],
+	/// Extension types; order is not compared.
+	pub extensions: &'static [u16],
+	/// Supported groups in preference order.
+	pub supported_groups: &'static [u16],
+	/// Signature algorithms in preference order.
+	pub signature_algorithms: &'static [u16],
+	/// Supported versions in preference order.
+	pub supported_versions: &'static [u16],
+}
+
+/// A field of a [`Profile`] that a hello did not match exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ProfileField {
+	/// Cipher suites or their order differ.
+	CipherSuites,
+	/// The extension set differs.
+	Extensions,
+	/// Supported groups or their order differ.
+	SupportedGroups,
+	/// Signature algorithms or their order differ.
+	SignatureAlgorithms,
+	/// Supported versions or their order differ.
+	SupportedVersions,
+}
+
+/// Result of comparing a hello against a [`Profile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileMatch<'p> {
+	/// The profile compared against.
+	pub profile: &'p Profile,
+	/// Similarity from 0 to 100; 100 means every field holds the same
+	/// values, though possibly in a different order.
+	pub score: u8,
+	/// Fields that differ, in declaration order of [`ProfileField`].
+	pub mismatched: Vec<ProfileField>,
+}
+
+impl ProfileMatch<'_> {
+	/// Check whether the hello matched the profile in every field.
+	#[must_use]
+	pub fn is_exact(&self) -> bool {
+		self.mismatched.is_empty()
+	}
+}
+
+impl Profile {
+	/// rustls 0.23 with the default `aws-lc-rs` provider and resumption.
+	pub const RUSTLS: Self = Self {
+		name: "rustls 0.23 (aws-lc-rs)",
+		cipher_suites: &[
+			0x1302, 0x1301, 0x1303, 0xc02c, 0xc02b, 0xcca9, 0xc030, 0xc02f, 0xcca8, 0x00ff,
+		],
+		extensions: &[
+			0x0005, 0x000a, 0x000b, 0x000d, 0x0017, 0x0023, 0x002b, 0x002d, 0x0033,
+		],
+		supported_groups: &[0x11ec, 0x001d, 0x0017, 0x0018],
+		signature_algorithms: &[
+			0x0503, 0x0403, 0x0603, 0x0807, 0x0806, 0x0805, 0x0804, 0x0601, 0x0501, 0x0401,
+		],
+		supported_versions: &[0x0304, 0x0303],
+	};
+
+	/// `openssl s_client` from OpenSSL 3.2 to 3.4 without options.
+	pub const OPENSSL_S_CLIENT: Self = Self {
+		name: "OpenSSL 3.2 s_client",
+		cipher_suites: &[
+			0x1302, 0x1303, 0x1301, 0xc02c, 0xc030, 0x009f, 0xcca9, 0xcca8, 0xccaa, 0xc02b, 0xc02f,
+			0x009e, 0xc024, 0xc028, 0x006b, 0xc023, 0xc027, 0x0067, 0xc00a, 0xc014, 0x0039, 0xc009,
+			0xc013, 0x0033, 0x009d, 0x009c, 0x003d, 0x003c, 0x0035, 0x002f, 0x00ff,
+		],
+		extensions: &[
+			0x000a, 0x000b, 0x000d, 0x0016, 0x0017, 0x0023, 0x002b, 0x002d, 0x0033,
+		],
+		supported_groups: &[
+			0x001d, 0x0017, 0x001e, 0x0019, 0x0018, 0x0100, 0x0101, 0x0102, 0x0103, 0x0104,
+		],
+		signature_algorithms: &[
+			0x0403, 0x0503, 0x0603, 0x0807, 0x0808, 0x081a, 0x081b, 0x081c, 0x0809, 0x080a, 0x080b,
+			0x0804, 0x0805, 0x0806, 0x0401, 0x0501, 0x0601, 0x0303, 0x0301, 0x0302, 0x0402, 0x0502,
+			0x0602,
+		],
+		supported_versions: &[0x0304, 0x0303],
+	};
+
+	/// Go 1.22 `crypto/tls` with the default `tls.Config`.
+	pub const GO: Self = Self {
+		name: "Go 1.22 crypto/tls",
+		cipher_suites: &[
+			0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8, 0xc009, 0xc013,
+			0xc00a, 0xc014,
+		],
+		extensions: &[
+			0x0005, 0x000a, 0x000b, 0x000d, 0x0012, 0x0017, 0x002b, 0x0033, 0xff01,
+		],
+		supported_groups: &[0x001d, 0x0017, 0x0018, 0x0019],
+		signature_algorithms: &[
+			0x0804, 0x0403, 0x0807, 0x0805, 0x0806, 0x0401, 0x0501, 0x0601, 0x0503, 0x0603, 0x0201,
+			0x0203,
+		],
+		supported_versions: &[0x0304, 0x0303],
+	};
+
+	/// Every built-in profile.
+	pub const BUILTIN: &'static [Self] = &[Self::RUSTLS, Self::OPENSSL_S_CLIENT, Self::GO];
+
+	/// Compare `hello` against this profile.
+	///
+	/// Each field scores the share of values the two sides have in
+	/// common (intersection over union), and the score is the mean over
+	/// all five fields. A field counts as mismatched unless it is equal,
+	/// including order where the order is compared.
+	#[must_use]
+	pub fn compare(&self, hello: &ClientHello<'_>) -> ProfileMatch<'_> {
+		let extensions: Vec<u16> = hello
+			.extensions
+			.type_ids()
+			.filter(|&t| t != EXT_SERVER_NAME && t != EXT_ALPN && !PER_CONNECTION_EXTENSIONS.contains(&t))
+			.collect();
+		let fields = [
+			(
+				ProfileField::CipherSuites,
+				self.cipher_suites,
+				hello.cipher_suites.as_slice(),
+				true,
+			),
+			(
+				ProfileField::Extensions,
+				self.extensions,
+				&extensions,
+				false,
+			),
+			(
+				ProfileField::SupportedGroups,
+				self.supported_groups,
+				hello.supported_groups(),
+				true,
+			),
+			(
+				ProfileField::SignatureAlgorithms,
+				self.signature_algorithms,
+				hello.signature_algorithms(),
+				true,
+			),
+			(
+				ProfileField::SupportedVersions,
+				self.supported_versions,
+				hello.supported_versions(),
+				true,
+			),
+		];
+		let mut total = 0;
+		let mut mismatched = Vec::new();
+		for (field, expected, observed, ordered) in fields {
+			let expected_set: BTreeSet<u16> = expected.iter().copied().collect();
+			let observed_set: BTreeSet<u16> = observed.iter().copied().collect();
+			let equal = if ordered {
+				expected == observed
+			} else {
+				expected_set == observed_set
+			};
+			if !equal {
+				mismatched.push(field);
+			}
+			let union = expected_set.union(&observed_set).count();
+			let common = expected_set.intersection(&observed_set).count();
+			total += (common * 100).checked_div(union).unwrap_or(100);
+		}
+		// Five fields of at most 100 each.
+		let score = u8::try_from(total / fields.len()).unwrap_or(100);
+		ProfileMatch {
+			profile: self,
+			score,
+			mismatched,
+		}
+	}
+}
+
+pub(crate) fn closest(hello: &ClientHello<'_>) -> ProfileMatch<'static> {
+	let mut best = Profile::BUILTIN[0].compare(hello);
+	for profile in &Profile::BUILTIN[1..] {
+		let candidate = profile.compare(hello);
+		if candidate.score > best.score {
+			best = candidate;
+		}
+	}
+	best
+}
@@ -0,0 +1,259 @@
+/* src/proto.rs */
+
+//! Protobuf messages mirroring the parsed ClientHello.
+//!
+//! The schema ships as `proto/clienthello.proto` (package
+//! `clienthello.v1`); the types here are the matching [`prost`] messages,
+//! so telemetry pipelines can encode hellos without a `protoc` step.
+//!
+//! ```
+//! # fn build() -> Vec<u8> {
+//! #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+//! #     d.extend_from_slice(&[0u8; 32]);
+//! #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+//! #     d
+//! # }
+//! # let data = build();
+//! use prost::Message;
+//!
+//! let hello = clienthello::parse(&data).unwrap();
+//! let msg = clienthello::proto::ClientHello::from(&hello);
+//! let wire = msg.encode_to_vec();
+//! assert_eq!(clienthello::proto::ClientHello::decode(&*wire).unwrap(), msg);
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::Extension as ParsedExtension;
+
+/// Input framing, mirroring [`crate::Transport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Transport {
+	/// Not set.
+	Unspecified = 0,
+	/// TLS record layer.
+	TlsRecord = 1,
+	/// Raw handshake message.
+	Handshake = 2,
+	/// DTLS record layer.
+	Dtls = 3,
+	/// SSL 2.0 compatible CLIENT-HELLO.
+	SslV2 = 4,
+}
+
+/// Parsed ClientHello message.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ClientHello {
+	/// Legacy protocol version.
+	#[prost(uint32, tag = "1")]
+	pub legacy_version: u32,
+	/// 32-byte client random.
+	#[prost(bytes = "vec", tag = "2")]
+	pub random: Vec<u8>,
+	/// Session ID.
+	#[prost(bytes = "vec", tag = "3")]
+	pub session_id: Vec<u8>,
+	/// Cipher suites with GREASE values removed.
+	#[prost(uint32, repeated, tag = "4")]
+	pub cipher_suites: Vec<u32>,
+	/// Compression method bytes.
+	#[prost(bytes = "vec", tag = "5")]
+	pub compression_methods: Vec<u8>,
+	/// Parsed extensions in wire order.
+	#[prost(message, repeated, tag = "6")]
+	pub extensions: Vec<Extension>,
+	/// Whether any GREASE value was seen.
+	#[prost(bool, tag = "7")]
+	pub has_grease: bool,
+	/// Input framing.
+	#[prost(enumeration = "Transport", tag = "8")]
+	pub transport: i32,
+	/// Positions of filtered GREASE entries.
+	#[prost(message, optional, tag = "9")]
+	pub grease: Option<GreasePositions>,
+}
+
+/// Indices of filtered GREASE entries in the lists as sent.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GreasePositions {
+	/// Positions within the cipher suite list.
+	#[prost(uint32, repeated, tag = "1")]
+	pub cipher_suites: Vec<u32>,
+	/// Positions within the extension list.
+	#[prost(uint32, repeated, tag = "2")]
+	pub extensions: Vec<u32>,
+	/// Positions within the Supported Versions list.
+	#[prost(uint32, repeated, tag = "3")]
+	pub supported_versions: Vec<u32>,
+	/// Positions within the Supported Groups list.
+	#[prost(uint32, repeated, tag = "4")]
+	pub supported_groups: Vec<u32>,
+	/// Positions within the Key Share entries.
+	#[prost(uint32, repeated, tag = "5")]
+	pub key_share: Vec<u32>,
+}
+
+/// A single extension.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Extension {
+	/// TLS extension type identifier.
+	#[prost(uint32, tag = "1")]
+	pub type_id: u32,
+	/// Decoded extension body.
+	#[prost(oneof = "extension::Body", tags = "2, 3, 4, 5, 6, 7, 8, 9, 10")]
+	pub body: Option<extension::Body>,
+}
+
+/// Nested types for [`Extension`].
+pub mod extension {
+	use alloc::vec::Vec;
+
+	/// Decoded extension body.
+	#[derive(Clone, PartialEq, prost::Oneof)]
+	pub enum Body {
+		/// Server Name Indication.
+		#[prost(message, tag = "2")]
+		ServerName(super::ServerNameList),
+		/// ALPN protocol identifiers.
+		#[prost(message, tag = "3")]
+		Alpn(super::ProtocolList),
+		/// Supported versions.
+		#[prost(message, tag = "4")]
+		SupportedVersions(super::U16List),
+		/// Supported groups.
+		#[prost(message, tag = "5")]
+		SupportedGroups(super::U16List),
+		/// Signature algorithms.
+		#[prost(message, tag = "6")]
+		SignatureAlgorithms(super::U16List),
+		/// Key share groups.
+		#[prost(message, tag = "7")]
+		KeyShareGroups(super::U16List),
+		/// PSK key exchange modes.
+		#[prost(bytes, tag = "8")]
+		PskExchangeModes(Vec<u8>),
+		/// Renegotiation info.
+		#[prost(bytes, tag = "9")]
+		RenegotiationInfo(Vec<u8>),
+		/// Raw body of an extension without a structured form here,
+		/// Encrypted Client Hello included.
+		#[prost(bytes, tag = "10")]
+		Raw(Vec<u8>),
+	}
+}
+
+/// SNI entries.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ServerNameList {
+	/// Entries in wire order.
+	#[prost(message, repeated, tag = "1")]
+	pub names: Vec<ServerName>,
+}
+
+/// A single SNI entry.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ServerName {
+	/// Name type byte.
+	#[prost(uint32, tag = "1")]
+	pub name_type: u32,
+	/// Raw name bytes.
+	#[prost(bytes = "vec", tag = "2")]
+	pub name: Vec<u8>,
+}
+
+/// ALPN protocol identifiers.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ProtocolList {
+	/// Protocols in preference order.
+	#[prost(bytes = "vec", repeated, tag = "1")]
+	pub protocols: Vec<Vec<u8>>,
+}
+
+/// List of 16-bit code points.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct U16List {
+	/// Values in wire order.
+	#[prost(uint32, repeated, tag = "1")]
+	pub values: Vec<u32>,
+}
+
+impl From<&crate::ClientHello<'_>> for ClientHello {
+	fn from(hello: &crate::ClientHello<'_>) -> Self {
+		let transport = match hello.transport {
+			crate::Transport::TlsRecord => Transport::TlsRecord,
+			crate::Transport::Handshake => Transport::Handshake,
+			crate::Transport::Dtls => Transport::Dtls,
+			crate::Transport::SslV2 => Transport::SslV2,
+		};
+		Self {
+			legacy_version: u32::from(hello.legacy_version),
+			random: hello.random.to_vec(),
+			session_id: hello.session_id.to_vec(),
+			cipher_suites: widen(&hello.cipher_suites),
+			compression_methods: hello.compression_methods.to_vec(),
+			extensions: hello.extensions.iter().map(Extension::from).collect(),
+			has_grease: hello.has_grease,
+			transport: transport as i32,
+			grease: Some(GreasePositions {
+				cipher_suites: positions(&hello.grease.cipher_suites),
+				extensions: positions(&hello.grease.extensions),
+				supported_versions: positions(&hello.grease.supported_versions),
+				supported_groups: positions(&hello.grease.supported_groups),
+				key_share: positions(&hello.grease.key_share),
+			}),
+		}
+	}
+}
+
+impl From<&ParsedExtension<'_>> for Extension {
+	fn from(ext: &ParsedExtension<'_>) -> Self {
+		use extension::Body;
+
+		let body = match ext {
+			ParsedExtension::ServerName(names) => Body::ServerName(ServerNameList {
+				names: names
+					.iter()
+					.map(|sn| ServerName {
+						name_type: u32::from(sn.name_type),
+						name: sn.name.to_vec(),
+					})
+					.collect(),
+			}),
+			ParsedExtension::Alpn(protos) => Body::Alpn(ProtocolList {
+				protocols: protos.iter().map(|p| p.to_vec()).collect(),
+			}),
+			ParsedExtension::SupportedVersions(v) => Body::SupportedVersions(list(v)),
+			ParsedExtension::SupportedGroups(v) => Body::SupportedGroups(list(v)),
+			ParsedExtension::SignatureAlgorithms(v) => Body::SignatureAlgorithms(list(v)),
+			ParsedExtension::KeyShare { groups, .. } => Body::KeyShareGroups(list(groups)),
+			ParsedExtension::PskExchangeModes(data) => Body::PskExchangeModes(data.to_vec()),
+			ParsedExtension::RenegotiationInfo(data) => Body::RenegotiationInfo(data.to_vec()),
+			ParsedExtension::Malformed { raw: data, .. }
+			| ParsedExtension::EncryptedClientHello { raw: data, .. }
+			| ParsedExtension::Unknown { data, .. } => Body::Raw(data.to_vec()),
+		};
+		Self {
+			type_id: u32::from(ext.type_id()),
+			body: Some(body),
+		}
+	}
+}
+
+fn list(values: &[u16]) -> U16List {
+	U16List {
+		values: widen(values),
+	}
+}
+
+fn widen(values: &[u16]) -> Vec<u32> {
+	values.iter().map(|&v| u32::from(v)).collect()
+}
+
+fn positions(values: &[usize]) -> Vec<u32> {
+	// Positions index lists bounded by 16-bit lengths, so they fit.
+	values
+		.iter()
+		.map(|&p| u32::try_from(p).unwrap_or(u32::MAX))
+		.collect()
+}
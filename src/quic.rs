@@ -0,0 +1,134 @@
+/* src/quic.rs */
+
+use alloc::vec::Vec;
+
+use crate::ClientHello;
+use crate::consts::EXT_QUIC_TRANSPORT_PARAMETERS;
+
+/// Pre-RFC draft codepoint for QUIC transport parameters.
+const TRANSPORT_PARAMETERS_DRAFT: u16 = 0xffa5;
+/// `version_information` transport parameter (RFC 9368).
+const VERSION_INFORMATION: u64 = 0x11;
+
+/// QUIC version 1 (RFC 9000).
+pub(crate) const QUIC_V1: u32 = 0x0000_0001;
+/// Base value for IETF draft versions (`0xff0000NN`).
+const QUIC_DRAFT_BASE: u32 = 0xff00_0000;
+
+pub(crate) fn inferred_versions(hello: &ClientHello<'_>) -> Vec<u32> {
+	let mut versions = Vec::new();
+	let params = hello
+		.find_extension(EXT_QUIC_TRANSPORT_PARAMETERS)
+		.or_else(|| hello.find_extension(TRANSPORT_PARAMETERS_DRAFT));
+	if let Some(params) = params {
+		for v in version_information(params) {
+			push_unique(&mut versions, v);
+		}
+	}
+	for proto in hello.alpn_protocols() {
+		if let Some(v) = version_from_alpn(proto) {
+			push_unique(&mut versions, v);
+		}
+	}
+	versions
+}
+
+fn push_unique(versions: &mut Vec<u32>, v: u32) {
+	// Version 0 is reserved for version negotiation and never a target.
+	if v != 0 && !versions.contains(&v) {
+		versions.push(v);
+	}
+}
+
+/// Collect the chosen and available versions from a `version_information`
+/// transport parameter. Malformed parameter lists yield whatever was
+/// decoded before the error.
+fn version_information(params: &[u8]) -> Vec<u32> {
+	let mut versions = Vec::new();
+	let mut rest = params;
+	while !rest.is_empty() {
+		let Some((id, n)) = read_varint(rest) else {
+			break;
+		};
+		rest = rest.get(n..).unwrap_or_default();
+		let Some((len, n)) = read_varint(rest) else {
+			break;
+		};
+		rest = rest.get(n..).unwrap_or_default();
+		let Some((value, tail)) = usize::try_from(len)
+			.ok()
+			.and_then(|len| rest.split_at_checked(len))
+		else {
+			break;
+		};
+		rest = tail;
+		if id == VERSION_INFORMATION {
+			versions.extend(
+				value
+					.chunks_exact(4)
+					.filter_map(|c| <[u8; 4]>::try_from(c).ok())
+					.map(u32::from_be_bytes),
+			);
+		}
+	}
+	versions
+}
+
+/// Decode a QUIC variable-length integer (RFC 9000 §16), returning the
+/// value and the number of bytes consumed.
+pub(crate) fn read_varint(data: &[u8]) -> Option<(u64, usize)> {
+	let first = *data.first()?;
+	let len = 1usize << (first >> 6);
+	let bytes = data.get(1..len)?;
+	let mut val = u64::from(first & 0x3f);
+	for &b in bytes {
+		val = (val << 8) | u64::from(b);
+	}
+	Some((val, len))
+}
+
+/// Map an HTTP/3 ALPN identifier to the QUIC version it implies.
+///
+/// `h3` maps to version 1, `h3-NN` to IETF draft `NN`, and gQUIC-style
+/// `h3-Qxxx` / `h3-Txxx` to the ASCII-encoded Google version.
+fn version_from_alpn(proto: &[u8]) -> Option<u32> {
+	if proto == b"h3" {
+		return Some(QUIC_V1);
+	}
+	let suffix = proto.strip_prefix(b"h3-")?;
+	match suffix {
+		[kind @ (b'Q' | b'T'), d0, d1, d2] if [d0, d1, d2].iter().all(|d| d.is_ascii_digit()) => {
+			Some(u32::from_be_bytes([*kind, *d0, *d1, *d2]))
+		}
+		_ => {
+			let draft = core::str::from_utf8(suffix).ok()?.parse::<u8>().ok()?;
+			Some(QUIC_DRAFT_BASE | u32::from(draft))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn varint_lengths() {
+		assert_eq!(read_varint(&[0x25]), Some((37, 1)));
+		assert_eq!(read_varint(&[0x7b, 0xbd]), Some((15293, 2)));
+		assert_eq!(
+			read_varint(&[0x9d, 0x7f, 0x3e, 0x7d]),
+			Some((494_878_333, 4))
+		);
+		assert_eq!(read_varint(&[0x7b]), None);
+	}
+
+	#[test]
+	fn alpn_mapping() {
+		assert_eq!(version_from_alpn(b"h3"), Some(QUIC_V1));
+		assert_eq!(version_from_alpn(b"h3-29"), Some(0xff00_001d));
+		assert_eq!(version_from_alpn(b"h3-Q050"), Some(0x5130_3530));
+		assert_eq!(version_from_alpn(b"h3-T051"), Some(0x5430_3531));
+		assert_eq!(version_from_alpn(b"h2"), None);
+		assert_eq!(version_from_alpn(b"h3-"), None);
+	}
+}
@@ -0,0 +1,366 @@
+/* src/quic_initial.rs */
+
+//! Client Initial packet protection of QUIC version 1 (RFC 9001 §5):
+//! keys derived from the Destination Connection ID, header protection,
+//! and the AEAD, enough to recover the CRYPTO frames carrying the
+//! ClientHello.
+
+use alloc::vec::Vec;
+
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::aes::Aes128;
+use aes_gcm::aes::cipher::BlockEncrypt;
+use aes_gcm::aes::cipher::generic_array::GenericArray;
+use aes_gcm::{Aes128Gcm, KeyInit, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::ConnectionError;
+use crate::quic::{QUIC_V1, read_varint};
+
+/// Initial salt of QUIC version 1 (RFC 9001 §5.2).
+const INITIAL_SALT: [u8; 20] = [
+	0x38, 0x76, 0x2c, 0xf7, 0xf5, 0x59, 0x34, 0xb3, 0x4d, 0x17, 0x9a, 0xe6, 0xa4, 0xc8, 0x0c, 0xad,
+	0xcc, 0xbb, 0x7f, 0x0a,
+];
+
+/// Long header packet types of version 1 (RFC 9000 §17.2).
+const TYPE_INITIAL: u8 = 0x0;
+const TYPE_RETRY: u8 = 0x3;
+/// Longest connection ID version 1 allows.
+const MAX_CID_LEN: usize = 20;
+/// The header protection sample starts this far past the packet number
+/// offset, as if the packet number were 4 bytes long (RFC 9001 §5.4.2).
+const SAMPLE_OFFSET: usize = 4;
+const SAMPLE_LEN: usize = 16;
+
+/// Frame types a client may send in Initial packets (RFC 9000 §12.4).
+const FRAME_PADDING: u64 = 0x00;
+const FRAME_PING: u64 = 0x01;
+const FRAME_ACK: u64 = 0x02;
+const FRAME_ACK_ECN: u64 = 0x03;
+const FRAME_CRYPTO: u64 = 0x06;
+const FRAME_CONNECTION_CLOSE: u64 = 0x1c;
+
+/// Client Initial packet protection keys (RFC 9001 §5.2).
+struct Keys {
+	key: [u8; 16],
+	iv: [u8; 12],
+	hp: [u8; 16],
+}
+
+impl Keys {
+	/// Derive the client keys from the Destination Connection ID of the
+	/// client's first Initial packet.
+	fn client(dcid: &[u8]) -> Self {
+		let (initial_secret, _) = Hkdf::<Sha256>::extract(Some(&INITIAL_SALT), dcid);
+		let secret: [u8; 32] = expand_label(&initial_secret, b"client in");
+		Self {
+			key: expand_label(&secret, b"quic key"),
+			iv: expand_label(&secret, b"quic iv"),
+			hp: expand_label(&secret, b"quic hp"),
+		}
+	}
+}
+
+/// TLS 1.3 `HKDF-Expand-Label` with an empty context.
+fn expand_label<const N: usize>(secret: &[u8], label: &[u8]) -> [u8; N] {
+	let mut info = u16::try_from(N).unwrap_or(u16::MAX).to_be_bytes().to_vec();
+	info.push(u8::try_from(b"tls13 ".len() + label.len()).unwrap_or(u8::MAX));
+	info.extend_from_slice(b"tls13 ");
+	info.extend_from_slice(label);
+	info.push(0);
+	let mut out = [0u8; N];
+	// `secret` is always a full SHA-256 output and `N` at most 32, so
+	// neither call can fail.
+	if let Ok(hkdf) = Hkdf::<Sha256>::from_prk(secret) {
+		let _ = hkdf.expand(&info, &mut out);
+	}
+	out
+}
+
+const fn invalid(reason: &'static str) -> ConnectionError {
+	ConnectionError::InvalidQuicPacket { reason }
+}
+
+/// Long header fields needed to unprotect a packet.
+struct LongHeader<'a> {
+	packet_type: u8,
+	dcid: &'a [u8],
+	/// Offset of the protected packet number.
+	pn_offset: usize,
+	/// End of the packet within the datagram.
+	end: usize,
+}
+
+/// Parse the long header packet at the start of `data`.
+fn long_header(data: &[u8]) -> Result<LongHeader<'_>, ConnectionError> {
+	let truncated = || invalid("truncated long header");
+	let version = data
+		.get(1..5)
+		.and_then(|v| <[u8; 4]>::try_from(v).ok())
+		.map(u32::from_be_bytes)
+		.ok_or_else(truncated)?;
+	// Packet types and everything after the connection IDs are version
+	// specific.
+	if version != QUIC_V1 {
+		return Err(ConnectionError::UnsupportedQuicVersion { version });
+	}
+	let packet_type = (data[0] >> 4) & 0x03;
+	let dcid_len = usize::from(*data.get(5).ok_or_else(truncated)?);
+	let dcid = data.get(6..6 + dcid_len).ok_or_else(truncated)?;
+	let scid_len = usize::from(*data.get(6 + dcid_len).ok_or_else(truncated)?);
+	if dcid_len > MAX_CID_LEN || scid_len > MAX_CID_LEN {
+		return Err(invalid("connection ID longer than 20 bytes"));
+	}
+	if packet_type == TYPE_RETRY {
+		return Err(invalid("Retry packet from a client"));
+	}
+	let mut pos = 7 + dcid_len + scid_len;
+	let length_at = |pos: &mut usize| -> Result<usize, ConnectionError> {
+		let (value, n) = data
+			.get(*pos..)
+			.and_then(read_varint)
+			.ok_or_else(truncated)?;
+		*pos += n;
+		usize::try_from(value).map_err(|_| truncated())
+	};
+	if packet_type == TYPE_INITIAL {
+		let token_len = length_at(&mut pos)?;
+		pos = pos.saturating_add(token_len);
+	}
+	let len = length_at(&mut pos)?;
+	let end = pos
+		.checked_add(len)
+		.filter(|&end| end <= data.len())
+		.ok_or_else(|| invalid("packet extends past the datagram"))?;
+	Ok(LongHeader {
+		packet_type,
+		dcid,
+		pn_offset: pos,
+		end,
+	})
+}
+
+/// Remove header protection from and decrypt a client Initial packet,
+/// returning its frames.
+fn open(packet: &[u8], header: &LongHeader<'_>) -> Result<Vec<u8>, ConnectionError> {
+	let keys = Keys::client(header.dcid);
+	let sample_start = header.pn_offset + SAMPLE_OFFSET;
+	let sample = packet
+		.get(sample_start..sample_start + SAMPLE_LEN)
+		.ok_or_else(|| invalid("packet too short to sample"))?;
+	let mut mask = GenericArray::clone_from_slice(sample);
+	Aes128::new(&keys.hp.into()).encrypt_block(&mut mask);
+
+	let mut aad = packet.to_vec();
+	aad[0] ^= mask[0] & 0x0f;
+	let pn_len = usize::from(aad[0] & 0x03) + 1;
+	let mut payload = aad.split_off(header.pn_offset + pn_len);
+	let mut nonce = keys.iv;
+	let pn = &mut aad[header.pn_offset..];
+	for (i, (byte, mask)) in pn.iter_mut().zip(&mask[1..]).enumerate() {
+		*byte ^= mask;
+		// Early client packets have numbers below 2^32, so the truncated
+		// packet number is the full one (RFC 9000 Appendix A.3).
+		nonce[nonce.len() - pn_len + i] ^= *byte;
+	}
+	Aes128Gcm::new(&keys.key.into())
+		.decrypt_in_place(Nonce::from_slice(&nonce), &aad, &mut payload)
+		.map_err(|_| invalid("Initial packet failed to decrypt"))?;
+	Ok(payload)
+}
+
+/// Walk the frames of a decrypted Initial packet, passing each CRYPTO
+/// frame's offset and data to `f`.
+fn walk_frames<F>(mut payload: &[u8], f: &mut F) -> Result<(), ConnectionError>
+where
+	F: FnMut(u64, &[u8]) -> Result<(), ConnectionError>,
+{
+	while !payload.is_empty() {
+		match varint(&mut payload)? {
+			FRAME_PADDING | FRAME_PING => {}
+			frame @ (FRAME_ACK | FRAME_ACK_ECN) => {
+				// Largest acknowledged, delay, range count, first range,
+				// then a gap and a length per further range.
+				varint(&mut payload)?;
+				varint(&mut payload)?;
+				let ranges = varint(&mut payload)?;
+				varint(&mut payload)?;
+				for _ in 0..ranges {
+					varint(&mut payload)?;
+					varint(&mut payload)?;
+				}
+				if frame == FRAME_ACK_ECN {
+					for _ in 0..3 {
+						varint(&mut payload)?;
+					}
+				}
+			}
+			FRAME_CRYPTO => {
+				let offset = varint(&mut payload)?;
+				let len = varint(&mut payload)?;
+				f(offset, take(&mut payload, len)?)?;
+			}
+			FRAME_CONNECTION_CLOSE => {
+				// Error code, frame type, reason phrase.
+				varint(&mut payload)?;
+				varint(&mut payload)?;
+				let len = varint(&mut payload)?;
+				take(&mut payload, len)?;
+			}
+			_ => return Err(invalid("frame not allowed in an Initial packet")),
+		}
+	}
+	Ok(())
+}
+
+fn varint(payload: &mut &[u8]) -> Result<u64, ConnectionError> {
+	let (value, n) = read_varint(payload).ok_or(invalid("truncated frame"))?;
+	*payload = &payload[n..];
+	Ok(value)
+}
+
+fn take<'p>(payload: &mut &'p [u8], len: u64) -> Result<&'p [u8], ConnectionError> {
+	let (data, rest) = usize::try_from(len)
+		.ok()
+		.and_then(|len| payload.split_at_checked(len))
+		.ok_or(invalid("truncated frame"))?;
+	*payload = rest;
+	Ok(data)
+}
+
+/// Decrypt the client Initial packets of a UDP datagram and pass each
+/// CRYPTO frame's offset and data to `f`.
+///
+/// Coalesced 0-RTT and Handshake packets are skipped; a short header
+/// packet runs to the end of the datagram and ends the walk.
+pub(crate) fn crypto_frames<F>(datagram: &[u8], mut f: F) -> Result<(), ConnectionError>
+where
+	F: FnMut(u64, &[u8]) -> Result<(), ConnectionError>,
+{
+	let mut rest = datagram;
+	while let Some(&first) = rest.first() {
+		if first & 0x80 == 0 {
+			break;
+		}
+		let header = long_header(rest)?;
+		let (packet, tail) = rest.split_at(header.end);
+		if header.packet_type == TYPE_INITIAL {
+			walk_frames(&open(packet, &header)?, &mut f)?;
+		}
+		rest = tail;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec;
+
+	use super::*;
+	use crate::{Builder, Connection};
+
+	fn unhex<const N: usize>(s: &str) -> [u8; N] {
+		let mut out = [0u8; N];
+		for (i, b) in out.iter_mut().enumerate() {
+			*b = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+		}
+		out
+	}
+
+	/// Destination Connection ID of the RFC 9001 Appendix A examples.
+	const DCID: [u8; 8] = [0x83, 0x94, 0xc8, 0xf0, 0x3e, 0x51, 0x57, 0x08];
+
+	/// Protect a client Initial packet carrying `frames`, with packet
+	/// number 2 encoded in 4 bytes as in RFC 9001 Appendix A.2.
+	fn seal(frames: &[u8]) -> Vec<u8> {
+		let keys = Keys::client(&DCID);
+		let pn = [0x00, 0x00, 0x00, 0x02];
+		let len = u16::try_from(pn.len() + frames.len() + 16).unwrap() | 0x4000;
+		let mut packet = vec![0xc3, 0x00, 0x00, 0x00, 0x01, 0x08];
+		packet.extend_from_slice(&DCID);
+		packet.extend_from_slice(&[0x00, 0x00]);
+		packet.extend_from_slice(&len.to_be_bytes());
+		let pn_offset = packet.len();
+		packet.extend_from_slice(&pn);
+		let mut nonce = keys.iv;
+		nonce[11] ^= 2;
+		let mut payload = frames.to_vec();
+		Aes128Gcm::new(&keys.key.into())
+			.encrypt_in_place(Nonce::from_slice(&nonce), &packet, &mut payload)
+			.unwrap();
+		packet.extend_from_slice(&payload);
+
+		let sample = &packet[pn_offset + SAMPLE_OFFSET..][..SAMPLE_LEN];
+		let mut mask = GenericArray::clone_from_slice(sample);
+		Aes128::new(&keys.hp.into()).encrypt_block(&mut mask);
+		packet[0] ^= mask[0] & 0x0f;
+		for (byte, mask) in packet[pn_offset..pn_offset + 4].iter_mut().zip(&mask[1..]) {
+			*byte ^= mask;
+		}
+		packet
+	}
+
+	fn crypto_frame(offset: u8, data: &[u8]) -> Vec<u8> {
+		let len = u16::try_from(data.len()).unwrap() | 0x4000;
+		let mut frame = vec![0x06, offset];
+		frame.extend_from_slice(&len.to_be_bytes());
+		frame.extend_from_slice(data);
+		frame
+	}
+
+	/// RFC 9001 Appendix A.1: keys for the client's Initial packets.
+	#[test]
+	fn rfc9001_client_keys() {
+		let keys = Keys::client(&DCID);
+		assert_eq!(keys.key, unhex::<16>("1f369613dd76d5467730efcbe3b1a22d"));
+		assert_eq!(keys.iv, unhex::<12>("fa044b2f42a3fd3b46fb255c"));
+		assert_eq!(keys.hp, unhex::<16>("9f50449e04a0e810283a1e9933adedd2"));
+
+		// Appendix A.2: header protection mask of the client Initial.
+		let mut mask = GenericArray::from(unhex::<16>("d1b1c98dd7689fb8ec11d242b123dc9b"));
+		Aes128::new(&keys.hp.into()).encrypt_block(&mut mask);
+		assert_eq!(mask[..5], unhex::<5>("437b9aec36"));
+	}
+
+	#[test]
+	fn datagram_feeds_connection() {
+		let msg = Builder::new().server_name("example.com").build().unwrap();
+		let (head, tail) = msg.split_at(40);
+		// The second packet is coalesced behind the first, with its
+		// CRYPTO frame behind padding and an ACK.
+		let mut second = vec![0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00];
+		second.extend_from_slice(&crypto_frame(40, tail));
+		let mut datagram = seal(&crypto_frame(0, head));
+		datagram.extend_from_slice(&seal(&second));
+		datagram.resize(1200, 0);
+
+		let mut conn = Connection::new();
+		assert!(conn.push_quic_datagram(&datagram).unwrap());
+		assert_eq!(conn.as_bytes(), Some(msg.as_slice()));
+	}
+
+	#[test]
+	fn tampered_packet_is_rejected() {
+		let mut packet = seal(&crypto_frame(0, b"hello"));
+		let last = packet.len() - 1;
+		packet[last] ^= 1;
+		assert_eq!(
+			crypto_frames(&packet, |_, _| Ok(())),
+			Err(invalid("Initial packet failed to decrypt"))
+		);
+	}
+
+	#[test]
+	fn other_versions_are_rejected() {
+		let mut packet = seal(&crypto_frame(0, b"hello"));
+		packet[1..5].copy_from_slice(&0x6b33_43cf_u32.to_be_bytes());
+		assert_eq!(
+			crypto_frames(&packet, |_, _| Ok(())),
+			Err(ConnectionError::UnsupportedQuicVersion {
+				version: 0x6b33_43cf
+			})
+		);
+	}
+}
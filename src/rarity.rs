@@ -0,0 +1,196 @@
+/* src/rarity.rs */
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::ClientHello;
+
+/// Approximate share of hellos on browser-dominated web traffic that
+/// carry each extension.
+const BUILTIN_EXTENSIONS: &[(u16, f32)] = &[
+	(0x0000, 0.97), // server_name
+	(0x0005, 0.85), // status_request
+	(0x000a, 0.99), // supported_groups
+	(0x000b, 0.95), // ec_point_formats
+	(0x000d, 0.99), // signature_algorithms
+	(0x0010, 0.90), // application_layer_protocol_negotiation
+	(0x0012, 0.70), // signed_certificate_timestamp
+	(0x0015, 0.50), // padding
+	(0x0016, 0.10), // encrypt_then_mac
+	(0x0017, 0.95), // extended_master_secret
+	(0x001b, 0.70), // compress_certificate
+	(0x001c, 0.08), // record_size_limit
+	(0x0022, 0.08), // delegated_credentials
+	(0x0023, 0.90), // session_ticket
+	(0x0029, 0.20), // pre_shared_key
+	(0x002a, 0.02), // early_data
+	(0x002b, 0.92), // supported_versions
+	(0x002d, 0.90), // psk_key_exchange_modes
+	(0x0031, 0.08), // post_handshake_auth
+	(0x0033, 0.92), // key_share
+	(0x4469, 0.30), // application_settings (old codepoint)
+	(0x44cd, 0.30), // application_settings
+	(0xfe0d, 0.50), // encrypted_client_hello
+	(0xff01, 0.95), // renegotiation_info
+];
+
+/// Approximate share of hellos offering each cipher suite.
+const BUILTIN_CIPHER_SUITES: &[(u16, f32)] = &[
+	(0x000a, 0.30), // TLS_RSA_WITH_3DES_EDE_CBC_SHA
+	(0x002f, 0.85), // TLS_RSA_WITH_AES_128_CBC_SHA
+	(0x0035, 0.85), // TLS_RSA_WITH_AES_256_CBC_SHA
+	(0x003c, 0.10), // TLS_RSA_WITH_AES_128_CBC_SHA256
+	(0x003d, 0.10), // TLS_RSA_WITH_AES_256_CBC_SHA256
+	(0x009c, 0.85), // TLS_RSA_WITH_AES_128_GCM_SHA256
+	(0x009d, 0.85), // TLS_RSA_WITH_AES_256_GCM_SHA384
+	(0x009e, 0.15), // TLS_DHE_RSA_WITH_AES_128_GCM_SHA256
+	(0x009f, 0.15), // TLS_DHE_RSA_WITH_AES_256_GCM_SHA384
+	(0x00ff, 0.10), // TLS_EMPTY_RENEGOTIATION_INFO_SCSV
+	(0x1301, 0.95), // TLS_AES_128_GCM_SHA256
+	(0x1302, 0.95), // TLS_AES_256_GCM_SHA384
+	(0x1303, 0.93), // TLS_CHACHA20_POLY1305_SHA256
+	(0xc009, 0.50), // TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA
+	(0xc00a, 0.50), // TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA
+	(0xc013, 0.90), // TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA
+	(0xc014, 0.90), // TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA
+	(0xc023, 0.20), // TLS_ECDHE_ECDSA_WITH_AES_128_CBC_SHA256
+	(0xc024, 0.20), // TLS_ECDHE_ECDSA_WITH_AES_256_CBC_SHA384
+	(0xc027, 0.20), // TLS_ECDHE_RSA_WITH_AES_128_CBC_SHA256
+	(0xc028, 0.20), // TLS_ECDHE_RSA_WITH_AES_256_CBC_SHA384
+	(0xc02b, 0.97), // TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256
+	(0xc02c, 0.96), // TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384
+	(0xc02f, 0.98), // TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256
+	(0xc030, 0.97), // TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384
+	(0xcca8, 0.90), // TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256
+	(0xcca9, 0.90), // TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256
+];
+
+/// Result of [`FrequencyTable::rarity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rarity {
+	/// Share of the hello's extensions and cipher suites that are rare,
+	/// from 0 (all common) to 100 (all rare).
+	pub score: u8,
+	/// Rare extension type identifiers, in wire order.
+	pub rare_extensions: Vec<u16>,
+	/// Rare cipher suites, in wire order.
+	pub rare_cipher_suites: Vec<u16>,
+}
+
+/// How often extensions and cipher suites occur, for spotting unusual
+/// hellos.
+///
+/// Frequencies are the fraction of hellos, from 0.0 to 1.0, that carry an
+/// extension or offer a cipher suite. Values missing from the table
+/// count as never seen. An entry is rare when its frequency is below the
+/// threshold (1% by default).
+///
+/// [`FrequencyTable::builtin`] holds rough estimates for browser-heavy
+/// web traffic. Tables built from the traffic actually being triaged
+/// give far better results.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d
+/// # }
+/// # let data = build();
+/// use clienthello::FrequencyTable;
+///
+/// let hello = clienthello::parse(&data).unwrap();
+/// assert_eq!(FrequencyTable::builtin().rarity(&hello).score, 0);
+///
+/// let mut table = FrequencyTable::new();
+/// table.set_cipher_suite(0x1301, 0.001);
+/// assert_eq!(table.rarity(&hello).rare_cipher_suites, [0x1301]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyTable {
+	extensions: BTreeMap<u16, f32>,
+	cipher_suites: BTreeMap<u16, f32>,
+	threshold: f32,
+}
+
+impl Default for FrequencyTable {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl FrequencyTable {
+	/// Create an empty table with the default 1% threshold.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			extensions: BTreeMap::new(),
+			cipher_suites: BTreeMap::new(),
+			threshold: 0.01,
+		}
+	}
+
+	/// Create a table with the built-in frequency estimates.
+	#[must_use]
+	pub fn builtin() -> Self {
+		Self {
+			extensions: BUILTIN_EXTENSIONS.iter().copied().collect(),
+			cipher_suites: BUILTIN_CIPHER_SUITES.iter().copied().collect(),
+			..Self::new()
+		}
+	}
+
+	/// Set the frequency of an extension type.
+	pub fn set_extension(&mut self, type_id: u16, frequency: f32) {
+		self.extensions.insert(type_id, frequency);
+	}
+
+	/// Set the frequency of a cipher suite.
+	pub fn set_cipher_suite(&mut self, suite: u16, frequency: f32) {
+		self.cipher_suites.insert(suite, frequency);
+	}
+
+	/// Set the frequency below which an entry counts as rare.
+	pub fn set_threshold(&mut self, threshold: f32) {
+		self.threshold = threshold;
+	}
+
+	/// Frequency of an extension type, or 0.0 when unknown.
+	#[must_use]
+	pub fn extension(&self, type_id: u16) -> f32 {
+		self.extensions.get(&type_id).copied().unwrap_or(0.0)
+	}
+
+	/// Frequency of a cipher suite, or 0.0 when unknown.
+	#[must_use]
+	pub fn cipher_suite(&self, suite: u16) -> f32 {
+		self.cipher_suites.get(&suite).copied().unwrap_or(0.0)
+	}
+
+	/// Find the rare extensions and cipher suites in `hello`.
+	///
+	/// GREASE values are never counted, since they are filtered during
+	/// parsing.
+	#[must_use]
+	pub fn rarity(&self, hello: &ClientHello<'_>) -> Rarity {
+		let rare_extensions: Vec<u16> = hello
+			.extensions
+			.type_ids()
+			.filter(|&t| self.extension(t) < self.threshold)
+			.collect();
+		let rare_cipher_suites: Vec<u16> = hello
+			.cipher_suites
+			.iter()
+			.copied()
+			.filter(|&c| self.cipher_suite(c) < self.threshold)
+			.collect();
+		let total = hello.extensions.len() + hello.cipher_suites.len();
+		let rare = rare_extensions.len() + rare_cipher_suites.len();
+		let score = (rare * 100).checked_div(total).unwrap_or(0);
+		Rarity {
+			score: u8::try_from(score).unwrap_or(100),
+			rare_extensions,
+			rare_cipher_suites,
+		}
+	}
+}
@@ -0,0 +1,168 @@
+/* src/registry.rs */
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::fmt;
+
+use crate::{ClientHello, Error, Extension};
+
+type DecodeFn = dyn Fn(&[u8]) -> Result<Box<dyn Any + Send + Sync>, Error> + Send + Sync;
+
+/// User-installed decoders for extensions this crate keeps as raw bytes.
+///
+/// Register a function per extension type id; it receives the extension
+/// body and returns any `'static` type, so proprietary extensions from
+/// enterprise middleboxes or private protocols can be decoded without
+/// forking the crate. [`tls_reader::Reader`](crate::tls_reader::Reader)
+/// handles the usual length-prefixed fields.
+///
+/// Decoders run on [`Extension::Unknown`] and [`Extension::Malformed`]
+/// bodies, which includes extensions whose `ext-*` feature is disabled,
+/// and see bodies shortened by an
+/// [`UnknownExtensionPolicy`](crate::UnknownExtensionPolicy). One
+/// registry can be shared across threads and applied to any number of
+/// parsed hellos.
+///
+/// ```
+/// use clienthello::tls_reader::Reader;
+/// use clienthello::{Builder, DecoderRegistry};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct TenantId(u32);
+///
+/// let mut registry = DecoderRegistry::new();
+/// registry.register(0xfa00, |body| {
+///     let mut r = Reader::new(body);
+///     let hi = r.read_u16("tenant id")?;
+///     let lo = r.read_u16("tenant id")?;
+///     Ok(TenantId(u32::from(hi) << 16 | u32::from(lo)))
+/// });
+///
+/// let msg = Builder::new().extension(0xfa00, &[0, 0, 0, 42]).build().unwrap();
+/// let hello = clienthello::parse(&msg).unwrap();
+/// assert_eq!(registry.get::<TenantId>(&hello, 0xfa00), Some(Ok(TenantId(42))));
+/// ```
+#[derive(Default)]
+pub struct DecoderRegistry {
+	decoders: BTreeMap<u16, Box<DecodeFn>>,
+}
+
+/// One extension decoded by a [`DecoderRegistry`].
+pub struct DecodedExtension {
+	/// Extension type.
+	pub type_id: u16,
+	/// The decoder's output, or the error it returned.
+	pub value: Result<Box<dyn Any + Send + Sync>, Error>,
+}
+
+impl DecodedExtension {
+	/// Borrow the decoded value if decoding succeeded and produced a `T`.
+	#[must_use]
+	pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+		self.value.as_ref().ok()?.downcast_ref()
+	}
+}
+
+impl fmt::Debug for DecodedExtension {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DecodedExtension")
+			.field("type_id", &self.type_id)
+			.field("value", &self.value.as_ref().map(|_| ".."))
+			.finish()
+	}
+}
+
+impl DecoderRegistry {
+	/// Create a registry with no decoders.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Install `decode` for extensions of type `type_id`, replacing any
+	/// decoder registered for it before.
+	pub fn register<T, F>(&mut self, type_id: u16, decode: F) -> &mut Self
+	where
+		T: Any + Send + Sync,
+		F: Fn(&[u8]) -> Result<T, Error> + Send + Sync + 'static,
+	{
+		let erased = move |body: &[u8]| -> Result<Box<dyn Any + Send + Sync>, Error> {
+			Ok(Box::new(decode(body)?))
+		};
+		self.decoders.insert(type_id, Box::new(erased));
+		self
+	}
+
+	/// Remove the decoder for `type_id`, returning whether one was
+	/// installed.
+	pub fn unregister(&mut self, type_id: u16) -> bool {
+		self.decoders.remove(&type_id).is_some()
+	}
+
+	/// Check whether a decoder is installed for `type_id`.
+	#[must_use]
+	pub fn contains(&self, type_id: u16) -> bool {
+		self.decoders.contains_key(&type_id)
+	}
+
+	/// Iterate the type ids with an installed decoder, in ascending order.
+	pub fn type_ids(&self) -> impl Iterator<Item = u16> + '_ {
+		self.decoders.keys().copied()
+	}
+
+	/// Run the installed decoders over every raw extension of `hello`
+	/// that has one, in wire order.
+	#[must_use]
+	pub fn decode(&self, hello: &ClientHello<'_>) -> Vec<DecodedExtension> {
+		hello
+			.extensions
+			.iter()
+			.filter_map(|ext| {
+				let (type_id, body) = raw_body(ext)?;
+				let decode = self.decoders.get(&type_id)?;
+				Some(DecodedExtension {
+					type_id,
+					value: decode(body),
+				})
+			})
+			.collect()
+	}
+
+	/// Decode the first raw extension of type `type_id` in `hello` as a
+	/// `T`.
+	///
+	/// Returns `None` when no decoder producing a `T` is installed for
+	/// `type_id` or the hello has no raw extension of that type.
+	///
+	/// # Errors
+	///
+	/// The inner result carries the error returned by the decoder.
+	#[must_use]
+	pub fn get<T: Any>(&self, hello: &ClientHello<'_>, type_id: u16) -> Option<Result<T, Error>> {
+		let decode = self.decoders.get(&type_id)?;
+		let body = hello
+			.extensions
+			.all_by_type(type_id)
+			.find_map(|ext| raw_body(ext).map(|(_, body)| body))?;
+		match decode(body) {
+			Ok(value) => value.downcast().ok().map(|v| Ok(*v)),
+			Err(e) => Some(Err(e)),
+		}
+	}
+}
+
+impl fmt::Debug for DecoderRegistry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_set().entries(self.type_ids()).finish()
+	}
+}
+
+fn raw_body<'a>(ext: &Extension<'a>) -> Option<(u16, &'a [u8])> {
+	match ext {
+		Extension::Unknown { type_id, data } => Some((*type_id, *data)),
+		Extension::Malformed { type_id, raw, .. } => Some((*type_id, *raw)),
+		_ => None,
+	}
+}
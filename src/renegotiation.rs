@@ -0,0 +1,103 @@
+/* src/renegotiation.rs */
+
+use crate::consts::{EXT_RENEGOTIATION_INFO, TLS_EMPTY_RENEGOTIATION_INFO_SCSV};
+use crate::{ClientHello, Extension};
+
+/// How a hello signals secure renegotiation support (RFC 5746).
+///
+/// Part of [`Renegotiation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum RenegotiationSignal {
+	/// Neither the SCSV nor the extension. Legacy clients that predate
+	/// RFC 5746 send this, and so do TLS 1.3-only clients, which have
+	/// no renegotiation to protect.
+	Neither,
+	/// Only the SCSV cipher suite, as OpenSSL sends.
+	Scsv,
+	/// Only the extension, as browsers and Go send.
+	Extension,
+	/// Both, which RFC 5746 §3.3 recommends against.
+	Both,
+}
+
+/// Cross-check of the renegotiation SCSV against the
+/// `renegotiation_info` extension.
+///
+/// Returned by [`ClientHello::renegotiation`]. Which signal a client
+/// sends is fixed by its TLS stack, and the irregular combinations point
+/// at hand-built or misconfigured clients.
+///
+/// ```
+/// let msg = clienthello::Builder::new()
+///     .cipher_suites(&[0x1301, 0x00ff])
+///     .renegotiation_info(&[])
+///     .build()
+///     .unwrap();
+/// let hello = clienthello::parse(&msg).unwrap();
+/// let reneg = hello.renegotiation();
+/// assert_eq!(reneg.signal, clienthello::RenegotiationSignal::Both);
+/// assert!(!reneg.is_consistent());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Renegotiation {
+	/// Which of the two signals are present.
+	pub signal: RenegotiationSignal,
+	/// Times the SCSV appears in the cipher suite list.
+	pub scsv_count: usize,
+	/// Number of `renegotiation_info` extensions.
+	pub extension_count: usize,
+	/// Set when an extension carries renegotiation data. An initial
+	/// handshake must send it empty, so this only happens on
+	/// renegotiation or when the client is broken.
+	pub non_empty_extension: bool,
+}
+
+impl Renegotiation {
+	/// Check that the hello signals renegotiation the way RFC 5746
+	/// describes for an initial handshake: not both signals, neither one
+	/// repeated, and an empty extension.
+	///
+	/// Sending neither signal is consistent; see
+	/// [`RenegotiationSignal::Neither`].
+	#[must_use]
+	pub fn is_consistent(&self) -> bool {
+		self.signal != RenegotiationSignal::Both
+			&& self.scsv_count <= 1
+			&& self.extension_count <= 1
+			&& !self.non_empty_extension
+	}
+}
+
+pub(crate) fn check(hello: &ClientHello<'_>) -> Renegotiation {
+	let scsv_count = hello
+		.cipher_suites
+		.iter()
+		.filter(|&&cs| cs == TLS_EMPTY_RENEGOTIATION_INFO_SCSV)
+		.count();
+	let mut extension_count = 0;
+	let mut non_empty_extension = false;
+	for ext in hello.extensions_of_type(EXT_RENEGOTIATION_INFO) {
+		extension_count += 1;
+		non_empty_extension |= match ext {
+			Extension::RenegotiationInfo(data) => !data.is_empty(),
+			// Undecoded bodies still hold the one-byte length prefix.
+			Extension::Unknown { data: raw, .. } | Extension::Malformed { raw, .. } => *raw != [0x00],
+			_ => false,
+		};
+	}
+	let signal = match (scsv_count > 0, extension_count > 0) {
+		(false, false) => RenegotiationSignal::Neither,
+		(true, false) => RenegotiationSignal::Scsv,
+		(false, true) => RenegotiationSignal::Extension,
+		(true, true) => RenegotiationSignal::Both,
+	};
+	Renegotiation {
+		signal,
+		scsv_count,
+		extension_count,
+		non_empty_extension,
+	}
+}
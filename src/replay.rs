@@ -0,0 +1,268 @@
+/* src/replay.rs */
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Builder;
+
+/// Errors produced when reconstructing a hello from a fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum FingerprintError {
+	/// The fingerprint does not have the expected number of sections.
+	#[error("expected {expected} sections, found {found}")]
+	Sections {
+		/// Number of sections the format has.
+		expected: usize,
+		/// Number of sections found.
+		found: usize,
+	},
+
+	/// A section holds a value that cannot be decoded.
+	#[error("invalid {field}: {value:?}")]
+	Value {
+		/// Section the value was found in.
+		field: &'static str,
+		/// The offending text.
+		value: String,
+	},
+}
+
+/// Signature algorithms used when the fingerprint does not list them.
+const DEFAULT_SIG_ALGS: &[u16] = &[
+	0x0403, 0x0804, 0x0401, 0x0503, 0x0805, 0x0501, 0x0806, 0x0601,
+];
+/// Groups used when the fingerprint does not list them.
+const DEFAULT_GROUPS: &[u16] = &[0x001d, 0x0017, 0x0018];
+/// Host name sent when the fingerprint only says SNI is present.
+const DEFAULT_HOST: &str = "example.com";
+
+impl Builder {
+	/// Reconstruct a hello whose JA3 string is `fingerprint`.
+	///
+	/// `fingerprint` must be the full JA3 string
+	/// (`771,4865-4866-...,0-23-...,29-23-24,0`); an MD5 digest cannot be
+	/// reversed. The version, cipher suites, extension order, groups, and
+	/// point formats come from the string. Everything JA3 does not
+	/// record is filled with fixed defaults: SNI `example.com`, ALPN
+	/// `h2` and `http/1.1`, TLS 1.3 and 1.2 as supported versions, a
+	/// zeroed key share for the first group, and empty or minimal bodies
+	/// for other extensions. The output is deterministic, so the same
+	/// fingerprint always encodes to the same bytes.
+	///
+	/// ```
+	/// use clienthello::Builder;
+	///
+	/// let ja3 = "771,4865-4866,0-10-11-43-51,29-23,0";
+	/// let msg = Builder::from_ja3(ja3).unwrap().build().unwrap();
+	/// let hello = clienthello::parse(&msg).unwrap();
+	/// assert_eq!(hello.cipher_suites, [4865, 4866]);
+	/// assert_eq!(hello.supported_groups(), [29, 23]);
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Returns [`FingerprintError`] when the string does not have five
+	/// comma-separated sections or a value is not a decimal number in
+	/// range.
+	pub fn from_ja3(fingerprint: &str) -> Result<Self, FingerprintError> {
+		let sections: Vec<&str> = fingerprint.trim().split(',').collect();
+		let &[version, ciphers, extensions, groups, formats] = sections.as_slice() else {
+			return Err(FingerprintError::Sections {
+				expected: 5,
+				found: sections.len(),
+			});
+		};
+		let version = parse_dec::<u16>("version", version)?;
+		let ciphers = dec_list::<u16>("cipher suites", ciphers)?;
+		let extensions = dec_list::<u16>("extensions", extensions)?;
+		let groups = dec_list::<u16>("groups", groups)?;
+		let formats = dec_list::<u8>("point formats", formats)?;
+
+		let mut b = Self::new().legacy_version(version).cipher_suites(&ciphers);
+		for type_id in extensions {
+			b = match type_id {
+				0x000a => b.supported_groups(&groups),
+				0x000b => b.extension(0x000b, &point_formats(&formats)),
+				0x0033 => key_share(b, groups.first().copied()),
+				_ => with_default(b, type_id),
+			};
+		}
+		Ok(b)
+	}
+
+	/// Reconstruct a hello whose raw JA4 fingerprint (`JA4_r`) is
+	/// `fingerprint`.
+	///
+	/// The cipher suites, extensions, and signature algorithms come from
+	/// the unhashed sections, and the version, SNI flag, and ALPN from the
+	/// prefix. SNI and ALPN are placed first and the other extensions
+	/// follow in the listed order. Values JA4 does not record are filled
+	/// with the same defaults as [`from_ja3`](Self::from_ja3); an ALPN
+	/// code other than `h1`, `h2`, or `h3` is sent as the two characters
+	/// themselves. The `t` / `q` marker is not checked: encode with
+	/// [`build_record`](Self::build_record) for `t` and with
+	/// [`build`](Self::build) for `q`. Counts in the prefix are not
+	/// checked against the lists.
+	///
+	/// ```
+	/// use clienthello::Builder;
+	///
+	/// let ja4_r = "t13d0204h2_1301,1302_000d,002b_0403,0804";
+	/// let msg = Builder::from_ja4_r(ja4_r).unwrap().build().unwrap();
+	/// let hello = clienthello::parse(&msg).unwrap();
+	/// assert_eq!(hello.alpn_protocols(), [b"h2"]);
+	/// assert_eq!(hello.signature_algorithms(), [0x0403, 0x0804]);
+	/// ```
+	///
+	/// # Errors
+	///
+	/// Returns [`FingerprintError`] when the string does not have three
+	/// or four `_`-separated sections, the prefix is malformed, or a list
+	/// value is not a 4-digit hex number.
+	pub fn from_ja4_r(fingerprint: &str) -> Result<Self, FingerprintError> {
+		let sections: Vec<&str> = fingerprint.trim().split('_').collect();
+		let (prefix, ciphers, extensions, sig_algs) = match sections.as_slice() {
+			&[prefix, ciphers, extensions] => (prefix, ciphers, extensions, ""),
+			&[prefix, ciphers, extensions, sig_algs] => (prefix, ciphers, extensions, sig_algs),
+			other => {
+				return Err(FingerprintError::Sections {
+					expected: 4,
+					found: other.len(),
+				});
+			}
+		};
+		let bad_prefix = || FingerprintError::Value {
+			field: "prefix",
+			value: prefix.to_owned(),
+		};
+		if !prefix.is_ascii() || prefix.len() != 10 {
+			return Err(bad_prefix());
+		}
+		let (legacy_version, version) = match &prefix[1..3] {
+			"13" => (0x0303, Some(0x0304)),
+			"12" => (0x0303, None),
+			"11" => (0x0302, None),
+			"10" => (0x0301, None),
+			"s3" => (0x0300, None),
+			_ => return Err(bad_prefix()),
+		};
+		let has_sni = match &prefix[3..4] {
+			"d" => true,
+			"i" => false,
+			_ => return Err(bad_prefix()),
+		};
+		let alpn: &[u8] = match &prefix[8..10] {
+			"00" => b"",
+			"h1" => b"http/1.1",
+			code => code.as_bytes(),
+		};
+		let ciphers = hex_list("cipher suites", ciphers)?;
+		let extensions = hex_list("extensions", extensions)?;
+		let sig_algs = hex_list("signature algorithms", sig_algs)?;
+
+		let mut b = Self::new()
+			.legacy_version(legacy_version)
+			.cipher_suites(&ciphers);
+		if has_sni {
+			b = b.server_name(DEFAULT_HOST);
+		}
+		if !alpn.is_empty() {
+			b = b.alpn(&[alpn]);
+		}
+		for type_id in extensions {
+			b = match (type_id, version) {
+				(0x000d, _) if !sig_algs.is_empty() => b.signature_algorithms(&sig_algs),
+				(0x002b, Some(version)) => b.supported_versions(&[version, 0x0303]),
+				(0x002b, None) => b.supported_versions(&[legacy_version]),
+				_ => with_default(b, type_id),
+			};
+		}
+		Ok(b)
+	}
+}
+
+/// Add `type_id` with a default body.
+fn with_default(b: Builder, type_id: u16) -> Builder {
+	match type_id {
+		0x0000 => b.server_name(DEFAULT_HOST),
+		0x0005 => b.extension(type_id, &[0x01, 0x00, 0x00, 0x00, 0x00]),
+		0x000a => b.supported_groups(DEFAULT_GROUPS),
+		0x000b => b.extension(type_id, &point_formats(&[0x00])),
+		0x000d => b.signature_algorithms(DEFAULT_SIG_ALGS),
+		0x0010 => b.alpn(&[b"h2", b"http/1.1"]),
+		0x001b => b.extension(type_id, &[0x02, 0x00, 0x02]),
+		0x001c => b.extension(type_id, &[0x40, 0x01]),
+		0x002b => b.supported_versions(&[0x0304, 0x0303]),
+		0x002d => b.psk_exchange_modes(&[0x01]),
+		0x0033 => key_share(b, DEFAULT_GROUPS.first().copied()),
+		0x4469 | 0x44cd => b.extension(type_id, &[0x00, 0x03, 0x02, b'h', b'2']),
+		0xff01 => b.renegotiation_info(&[]),
+		_ => b.extension(type_id, &[]),
+	}
+}
+
+/// Add a key share with a zeroed key of the right size for `group`.
+fn key_share(b: Builder, group: Option<u16>) -> Builder {
+	let Some(group) = group else {
+		return b.key_share(&[]);
+	};
+	let len = match group {
+		0x0017 => 65,
+		0x0018 => 97,
+		0x0019 => 133,
+		0x001e => 56,
+		0x11eb => 1249,
+		0x11ec | 0x6399 => 1216,
+		0x11ed => 1665,
+		_ => 32,
+	};
+	b.key_share(&[(group, &alloc::vec![0u8; len])])
+}
+
+fn point_formats(formats: &[u8]) -> Vec<u8> {
+	let mut body = Vec::with_capacity(formats.len() + 1);
+	body.push(u8::try_from(formats.len()).unwrap_or(u8::MAX));
+	body.extend_from_slice(formats);
+	body
+}
+
+fn parse_dec<T: core::str::FromStr>(
+	field: &'static str,
+	value: &str,
+) -> Result<T, FingerprintError> {
+	value.parse().map_err(|_| FingerprintError::Value {
+		field,
+		value: value.to_owned(),
+	})
+}
+
+fn dec_list<T: core::str::FromStr>(
+	field: &'static str,
+	list: &str,
+) -> Result<Vec<T>, FingerprintError> {
+	if list.is_empty() {
+		return Ok(Vec::new());
+	}
+	list.split('-').map(|v| parse_dec(field, v)).collect()
+}
+
+fn hex_list(field: &'static str, list: &str) -> Result<Vec<u16>, FingerprintError> {
+	if list.is_empty() {
+		return Ok(Vec::new());
+	}
+	list
+		.split(',')
+		.map(|v| {
+			let valid = v.len() == 4 && v.bytes().all(|c| c.is_ascii_hexdigit());
+			valid
+				.then(|| u16::from_str_radix(v, 16).ok())
+				.flatten()
+				.ok_or_else(|| FingerprintError::Value {
+					field,
+					value: v.to_owned(),
+				})
+		})
+		.collect()
+}
@@ -0,0 +1,261 @@
+/* src/report.rs */
+
+//! Markdown and HTML reports of a parsed hello.
+//!
+//! [`markdown`] and [`html`] render the same content: an overview of the
+//! main fields, the available fingerprints, the heuristic analyses
+//! ([`infer_stack`](crate::ClientHello::infer_stack),
+//! [`extension_order`](crate::ClientHello::extension_order),
+//! [`bot_score`](crate::ClientHello::bot_score),
+//! [`rarity_score`](crate::ClientHello::rarity_score)), and the raw
+//! cipher suite and extension lists. Fingerprint rows appear when the
+//! `ja3`, `ja4`, and `known-clients` features are enabled.
+//!
+//! Every value taken from the hello is escaped, so reports of hostile
+//! hellos are safe to paste into tickets or serve as HTML.
+//!
+//! ```
+//! # fn build() -> Vec<u8> {
+//! #     let mut d = vec![0x01, 0x00, 0x00, 0x29, 0x03, 0x03];
+//! #     d.extend_from_slice(&[0u8; 32]);
+//! #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+//! #     d
+//! # }
+//! # let data = build();
+//! use clienthello::report;
+//!
+//! let hello = clienthello::parse(&data).unwrap();
+//! let md = report::markdown(&hello);
+//! assert!(md.starts_with("# TLS ClientHello report"));
+//! assert!(md.contains("| Cipher suites | 0x1301 |"));
+//! assert!(report::html(&hello).contains("<td>0x1301</td>"));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{ClientHello, Transport};
+
+/// A titled table of label / value rows.
+pub(crate) struct Section {
+	pub(crate) title: &'static str,
+	pub(crate) rows: Vec<(&'static str, String)>,
+}
+
+/// Render `hello` as a Markdown document.
+#[must_use]
+pub fn markdown(hello: &ClientHello<'_>) -> String {
+	let mut out = String::from("# TLS ClientHello report\n");
+	for section in sections(hello) {
+		let _ = write!(
+			out,
+			"\n## {}\n\n| Field | Value |\n| --- | --- |\n",
+			section.title
+		);
+		for (label, value) in &section.rows {
+			let _ = writeln!(out, "| {label} | {} |", escape_markdown(value));
+		}
+	}
+	out
+}
+
+/// Render `hello` as a standalone HTML document.
+#[must_use]
+pub fn html(hello: &ClientHello<'_>) -> String {
+	let mut out = String::from(
+		"<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+		 <title>TLS ClientHello report</title>\n</head>\n<body>\n\
+		 <h1>TLS ClientHello report</h1>\n",
+	);
+	for section in sections(hello) {
+		let _ = writeln!(out, "<h2>{}</h2>\n<table>", section.title);
+		for (label, value) in &section.rows {
+			let _ = writeln!(
+				out,
+				"<tr><th>{label}</th><td>{}</td></tr>",
+				escape_html(value)
+			);
+		}
+		out.push_str("</table>\n");
+	}
+	out.push_str("</body>\n</html>\n");
+	out
+}
+
+/// Gather the report content shared by every output format.
+pub(crate) fn sections(hello: &ClientHello<'_>) -> Vec<Section> {
+	#[cfg_attr(not(any(feature = "ja3", feature = "ja4")), allow(unused_mut))]
+	let mut sections = alloc::vec![overview(hello), analysis(hello), lists(hello)];
+	#[cfg(any(feature = "ja3", feature = "ja4"))]
+	sections.insert(1, fingerprints(hello));
+	sections
+}
+
+fn overview(hello: &ClientHello<'_>) -> Section {
+	let transport = match hello.transport {
+		Transport::TlsRecord => "TLS record",
+		Transport::Handshake => "raw handshake",
+		Transport::Dtls => "DTLS record",
+		Transport::SslV2 => "SSLv2 record",
+	};
+	let max_version = hello
+		.effective_versions()
+		.iter()
+		.copied()
+		.max()
+		.unwrap_or(hello.legacy_version);
+	let alpn: Vec<String> = hello
+		.alpn_protocols()
+		.iter()
+		.map(|p| String::from_utf8_lossy(p).into_owned())
+		.collect();
+	Section {
+		title: "Overview",
+		rows: alloc::vec![
+			("Transport", transport.into()),
+			("Legacy version", version_name(hello.legacy_version)),
+			("Highest version", version_name(max_version)),
+			("SNI", hello.server_name().unwrap_or("(none)").into()),
+			("ALPN", or_none(alpn.join(", "))),
+			("Session ID length", hello.session_id.len().to_string()),
+			("Cipher suites", hello.cipher_suites.len().to_string()),
+			("Extensions", hello.extensions.len().to_string()),
+			("GREASE", yes_no(hello.has_grease).into()),
+		],
+	}
+}
+
+#[cfg(any(feature = "ja3", feature = "ja4"))]
+fn fingerprints(hello: &ClientHello<'_>) -> Section {
+	let mut rows = Vec::new();
+	#[cfg(feature = "ja3")]
+	{
+		rows.push(("JA3", crate::ja3::ja3_string(hello)));
+		rows.push(("JA3 hash", crate::ja3::ja3_hash(hello)));
+	}
+	#[cfg(feature = "ja4")]
+	{
+		rows.push(("JA4", crate::ja4::ja4(hello)));
+		rows.push(("JA4_r", crate::ja4::ja4_raw(hello)));
+	}
+	#[cfg(feature = "known-clients")]
+	{
+		let known: Vec<String> = hello
+			.identify()
+			.iter()
+			.map(|c| alloc::format!("{} ({})", c.name, c.score))
+			.collect();
+		rows.push(("Known clients", or_none(known.join(", "))));
+	}
+	Section {
+		title: "Fingerprints",
+		rows,
+	}
+}
+
+fn analysis(hello: &ClientHello<'_>) -> Section {
+	let stack = hello
+		.infer_stack()
+		.map_or_else(|| "(inconclusive)".into(), |s| alloc::format!("{s:?}"));
+	let bot = hello.bot_score();
+	let signals: Vec<&str> = bot.signals.iter().map(|s| s.description()).collect();
+	let rarity = hello.rarity_score();
+	let rare_extensions = hex_list(rarity.rare_extensions.iter().copied());
+	let rare_suites = hex_list(rarity.rare_cipher_suites.iter().copied());
+	Section {
+		title: "Analysis",
+		rows: alloc::vec![
+			("Inferred stack", stack),
+			(
+				"Extension order",
+				alloc::format!("{:?}", hello.extension_order())
+			),
+			("Bot score", bot.score.to_string()),
+			("Bot signals", or_none(signals.join(", "))),
+			("Rarity score", rarity.score.to_string()),
+			("Rare extensions", or_none(rare_extensions)),
+			("Rare cipher suites", or_none(rare_suites)),
+		],
+	}
+}
+
+fn lists(hello: &ClientHello<'_>) -> Section {
+	Section {
+		title: "Lists",
+		rows: alloc::vec![
+			(
+				"Cipher suites",
+				hex_list(hello.cipher_suites.iter().copied())
+			),
+			("Extensions", hex_list(hello.extensions.type_ids()),),
+		],
+	}
+}
+
+fn version_name(version: u16) -> String {
+	let name = match version {
+		0x0304 => "TLS 1.3",
+		0x0303 => "TLS 1.2",
+		0x0302 => "TLS 1.1",
+		0x0301 => "TLS 1.0",
+		0x0300 => "SSL 3.0",
+		_ => return alloc::format!("{version:#06x}"),
+	};
+	alloc::format!("{name} ({version:#06x})")
+}
+
+fn hex_list(values: impl Iterator<Item = u16>) -> String {
+	let mut out = String::new();
+	for (i, v) in values.enumerate() {
+		if i > 0 {
+			out.push_str(", ");
+		}
+		let _ = write!(out, "{v:#06x}");
+	}
+	out
+}
+
+fn or_none(value: String) -> String {
+	if value.is_empty() {
+		"(none)".into()
+	} else {
+		value
+	}
+}
+
+fn yes_no(value: bool) -> &'static str {
+	if value { "yes" } else { "no" }
+}
+
+/// Escape text for a Markdown table cell.
+fn escape_markdown(value: &str) -> String {
+	let mut out = String::with_capacity(value.len());
+	for c in value.chars() {
+		match c {
+			'\\' | '|' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '!' => {
+				out.push('\\');
+				out.push(c);
+			}
+			c if c.is_control() => out.push(' '),
+			c => out.push(c),
+		}
+	}
+	out
+}
+
+/// Escape text for HTML element content.
+fn escape_html(value: &str) -> String {
+	let mut out = String::with_capacity(value.len());
+	for c in value.chars() {
+		match c {
+			'&' => out.push_str("&amp;"),
+			'<' => out.push_str("&lt;"),
+			'>' => out.push_str("&gt;"),
+			'"' => out.push_str("&quot;"),
+			'\'' => out.push_str("&#39;"),
+			c => out.push(c),
+		}
+	}
+	out
+}
@@ -0,0 +1,183 @@
+/* src/router.rs */
+
+//! Hostname-based routing on the SNI of a ClientHello.
+//!
+//! [`SniRouter`] maps hostname patterns to backends, the lookup table
+//! every SNI proxy needs. Patterns are either exact hostnames
+//! (`api.example.com`) or wildcards (`*.example.com`). A hostname is
+//! routed by the first rule that applies:
+//!
+//! 1. An exact pattern equal to the hostname.
+//! 2. The wildcard with the longest suffix the hostname ends with.
+//!    `*.example.com` matches `a.example.com` and `a.b.example.com` but
+//!    not `example.com`; `*.b.example.com` takes precedence over it for
+//!    `a.b.example.com`.
+//! 3. The default target, if set. Hellos without SNI also use it.
+//!
+//! Hostnames and patterns are compared case-insensitively and a
+//! trailing dot is ignored.
+//!
+//! ```
+//! # fn build() -> Vec<u8> {
+//! #     let mut d = vec![0x01, 0x00, 0x00, 0x3b, 0x03, 0x03];
+//! #     d.extend_from_slice(&[0u8; 32]);
+//! #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+//! #     d.extend_from_slice(&[0x00, 0x10, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x0a, 0x00]);
+//! #     d.extend_from_slice(&[0x00, 0x07]);
+//! #     d.extend_from_slice(b"example");
+//! #     d
+//! # }
+//! # let msg = build();
+//! use clienthello::router::SniRouter;
+//!
+//! let mut router = SniRouter::new();
+//! router.insert("example", "origin").unwrap();
+//! router.insert("*.example", "edge").unwrap();
+//! router.set_default("fallback");
+//!
+//! let hello = clienthello::parse(&msg).unwrap();
+//! assert_eq!(router.route(&hello), Some(&"origin"));
+//! assert_eq!(router.route_host("cdn.Example."), Some(&"edge"));
+//! assert_eq!(router.route_host("other.test"), Some(&"fallback"));
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+
+use crate::ClientHello;
+
+/// Errors produced when registering a pattern.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum PatternError {
+	/// The pattern, or the part after `*.`, is empty.
+	#[error("empty hostname pattern")]
+	Empty,
+
+	/// A `*` appears anywhere but as the whole first label.
+	#[error("wildcard must be the whole first label: {0}")]
+	Wildcard(String),
+}
+
+/// Routing table from hostname patterns to targets.
+///
+/// See the [module documentation](self) for the precedence rules.
+#[derive(Debug, Clone)]
+pub struct SniRouter<T> {
+	exact: BTreeMap<String, T>,
+	/// Wildcard targets keyed by the suffix after `*.`.
+	wildcard: BTreeMap<String, T>,
+	default: Option<T>,
+}
+
+impl<T> Default for SniRouter<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> SniRouter<T> {
+	/// Create an empty router.
+	#[must_use]
+	pub fn new() -> Self {
+		Self {
+			exact: BTreeMap::new(),
+			wildcard: BTreeMap::new(),
+			default: None,
+		}
+	}
+
+	/// Number of registered patterns, not counting the default.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.exact.len() + self.wildcard.len()
+	}
+
+	/// Check whether no patterns are registered.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.exact.is_empty() && self.wildcard.is_empty()
+	}
+
+	/// Register `target` for `pattern`, returning the target it replaces.
+	///
+	/// # Errors
+	///
+	/// Returns [`PatternError`] when the pattern is empty or has a `*`
+	/// anywhere but as the whole first label.
+	pub fn insert(&mut self, pattern: &str, target: T) -> Result<Option<T>, PatternError> {
+		let (table, key) = self.table_mut(pattern)?;
+		Ok(table.insert(key, target))
+	}
+
+	/// Remove the target registered for `pattern`.
+	///
+	/// # Errors
+	///
+	/// Returns [`PatternError`] for an invalid pattern, as
+	/// [`SniRouter::insert`] does.
+	pub fn remove(&mut self, pattern: &str) -> Result<Option<T>, PatternError> {
+		let (table, key) = self.table_mut(pattern)?;
+		Ok(table.remove(&key))
+	}
+
+	/// Set the target used when no pattern matches or SNI is absent,
+	/// returning the previous default.
+	pub fn set_default(&mut self, target: T) -> Option<T> {
+		self.default.replace(target)
+	}
+
+	/// Route a parsed hello by its SNI hostname.
+	#[must_use]
+	pub fn route(&self, hello: &ClientHello<'_>) -> Option<&T> {
+		match hello.server_name() {
+			Some(host) => self.route_host(host),
+			None => self.default.as_ref(),
+		}
+	}
+
+	/// Route a hostname, e.g. one returned by
+	/// [`extract_sni`](crate::extract_sni).
+	#[must_use]
+	pub fn route_host(&self, host: &str) -> Option<&T> {
+		let host = normalize(host);
+		if let Some(target) = self.exact.get(host.as_ref()) {
+			return Some(target);
+		}
+		// Suffixes after each dot, longest first.
+		host
+			.match_indices('.')
+			.find_map(|(i, _)| self.wildcard.get(host.get(i + 1..)?))
+			.or(self.default.as_ref())
+	}
+
+	fn table_mut(
+		&mut self,
+		pattern: &str,
+	) -> Result<(&mut BTreeMap<String, T>, String), PatternError> {
+		let pattern = normalize(pattern);
+		let (table, name) = match pattern.strip_prefix("*.") {
+			Some(suffix) => (&mut self.wildcard, suffix),
+			None => (&mut self.exact, pattern.as_ref()),
+		};
+		if name.is_empty() {
+			return Err(PatternError::Empty);
+		}
+		if name.contains('*') {
+			return Err(PatternError::Wildcard(pattern.into_owned()));
+		}
+		Ok((table, name.into()))
+	}
+}
+
+/// Lowercase and strip one trailing dot, borrowing when already
+/// normalized.
+fn normalize(host: &str) -> Cow<'_, str> {
+	let host = host.strip_suffix('.').unwrap_or(host);
+	if host.bytes().any(|b| b.is_ascii_uppercase()) {
+		Cow::Owned(host.to_ascii_lowercase())
+	} else {
+		Cow::Borrowed(host)
+	}
+}
@@ -0,0 +1,140 @@
+/* src/scan.rs */
+
+//! Bulk scanning of arbitrary byte streams for ClientHello records.
+//!
+//! For research pipelines that walk raw captures, reassembled TCP
+//! streams, or memory dumps without a packet dissector: [`records`]
+//! finds every plausible ClientHello record in a haystack,
+//! [`server_names`] pairs each with its SNI using the allocation-free
+//! [`extract_sni_from_record`](crate::extract_sni_from_record) path, and
+//! `client_hellos` (with `client-hello`) fully parses each one.
+//!
+//! Candidates are located with a SIMD-accelerated search from `memchr`
+//! for the `16 03` record prefix, then filtered by the record version,
+//! the handshake type, and the length fields before anything is parsed.
+//! Runtime CPU feature detection, and with it AVX2 on x86-64, needs the
+//! `std` feature; without it `memchr` uses the baseline instruction set.
+//!
+//! ```
+//! # fn build() -> Vec<u8> {
+//! #     let mut d = vec![0x16, 0x03, 0x01, 0x00, 0x3f];
+//! #     d.extend_from_slice(&[0x01, 0x00, 0x00, 0x3b, 0x03, 0x03]);
+//! #     d.extend_from_slice(&[0u8; 32]);
+//! #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+//! #     d.extend_from_slice(&[0x00, 0x10, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x0a, 0x00]);
+//! #     d.extend_from_slice(&[0x00, 0x07]);
+//! #     d.extend_from_slice(b"example");
+//! #     d
+//! # }
+//! # let record = build();
+//! use clienthello::scan;
+//!
+//! let mut haystack = vec![0x16, 0x03, 0xff];
+//! haystack.extend_from_slice(&record);
+//! haystack.extend_from_slice(b"trailing noise");
+//!
+//! let found: Vec<_> = scan::server_names(&haystack).collect();
+//! assert_eq!(found, [(3, "example")]);
+//! ```
+
+use memchr::memmem::Finder;
+
+use crate::tls_reader::Reader;
+
+/// Record header prefix: handshake content type and major version 3.
+const PREFIX: &[u8; 2] = b"\x16\x03";
+/// Largest record payload allowed for TLS 1.2 ciphertext (RFC 5246
+/// §6.2.3); ClientHello records are plaintext and smaller still.
+const MAX_RECORD_LEN: usize = (1 << 14) + 2048;
+
+/// Iterator over plausible ClientHello records in a haystack.
+///
+/// Created by [`records`]. Yields the offset of each record header and
+/// the record bytes, header included. Records only count when they fit
+/// entirely in the haystack; scanning resumes after each match.
+#[derive(Debug, Clone)]
+pub struct Records<'h> {
+	haystack: &'h [u8],
+	pos: usize,
+	finder: Finder<'static>,
+}
+
+/// Find the plausible ClientHello records in `haystack`.
+///
+/// A candidate has content type `0x16`, record version `0x0300` to
+/// `0x0304`, a record length between 4 and 18432, handshake type
+/// `0x01`, and a handshake length that fits in the record. Candidates
+/// are not parsed; feed them to
+/// [`parse_from_record`](crate::parse_from_record) or use
+/// `client_hellos`.
+#[must_use]
+pub fn records(haystack: &[u8]) -> Records<'_> {
+	Records {
+		haystack,
+		pos: 0,
+		finder: Finder::new(PREFIX),
+	}
+}
+
+impl<'h> Iterator for Records<'h> {
+	type Item = (usize, &'h [u8]);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while let Some(rest) = self.haystack.get(self.pos..) {
+			let offset = self.pos + self.finder.find(rest)?;
+			match candidate(self.haystack.get(offset..)?) {
+				Some(record) => {
+					self.pos = offset + record.len();
+					return Some((offset, record));
+				}
+				None => self.pos = offset + 1,
+			}
+		}
+		None
+	}
+}
+
+/// Check the headers at the start of `data` and return the record.
+fn candidate(data: &[u8]) -> Option<&[u8]> {
+	let mut r = Reader::new(data);
+	let prefix = r.read_bytes(2, "record prefix").ok()?;
+	let minor = r.read_u8("record minor version").ok()?;
+	let record_len = r.read_u16("record length").ok()? as usize;
+	let hs_type = r.read_u8("handshake type").ok()?;
+	let hs_len = r.read_u24("handshake length").ok()? as usize;
+	let plausible = prefix == PREFIX
+		&& minor <= 0x04
+		&& hs_type == 0x01
+		&& (4..=MAX_RECORD_LEN).contains(&record_len)
+		&& hs_len <= record_len - 4;
+	if plausible {
+		data.get(..5 + record_len)
+	} else {
+		None
+	}
+}
+
+/// Find every ClientHello record with a DNS SNI hostname in `haystack`.
+///
+/// Yields the record offset and the hostname. Records without SNI, or
+/// that fail [`extract_sni_from_record`](crate::extract_sni_from_record),
+/// are skipped.
+pub fn server_names(haystack: &[u8]) -> impl Iterator<Item = (usize, &str)> {
+	records(haystack).filter_map(|(offset, record)| {
+		let host = crate::extract_sni_from_record(record).ok()??;
+		Some((offset, host))
+	})
+}
+
+/// Find and parse every ClientHello record in `haystack`.
+///
+/// Yields the record offset and the parsed hello. Candidates that fail
+/// to parse are skipped.
+#[cfg(feature = "client-hello")]
+pub fn client_hellos(haystack: &[u8]) -> impl Iterator<Item = (usize, crate::ClientHello<'_>)> {
+	records(haystack).filter_map(|(offset, record)| {
+		crate::parse_from_record(record)
+			.ok()
+			.map(|hello| (offset, hello))
+	})
+}
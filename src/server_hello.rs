@@ -0,0 +1,248 @@
+/* src/server_hello.rs */
+
+#[cfg(any(feature = "ja3", feature = "ja4"))]
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::consts::{EXT_ALPN, EXT_KEY_SHARE, EXT_SUPPORTED_VERSIONS};
+use crate::framing;
+use crate::tls_reader::Reader;
+use crate::types::HandshakeType;
+use crate::{Error, Extension, Extensions, KeyShareEntry, Transport};
+
+/// The `random` of a HelloRetryRequest, SHA-256 of
+/// `"HelloRetryRequest"` (RFC 8446 §4.1.3).
+const HELLO_RETRY_REQUEST_RANDOM: [u8; 32] = [
+	0xcf, 0x21, 0xad, 0x74, 0xe5, 0x9a, 0x61, 0x11, 0xbe, 0x1d, 0x8c, 0x02, 0x1e, 0x65, 0xb8, 0x91,
+	0xc2, 0xa2, 0x11, 0x16, 0x7a, 0xbb, 0x8c, 0x5e, 0x07, 0x9e, 0x09, 0xe2, 0xc8, 0xa8, 0x33, 0x9c,
+];
+
+/// Parsed TLS ServerHello message holding zero-copy references into the
+/// original byte buffer.
+///
+/// Server extension bodies differ from the ClientHello ones, e.g.
+/// `supported_versions` holds one version rather than a list, so every
+/// entry of [`extensions`](Self::extensions) is kept as
+/// [`Extension::Unknown`] and the accessors here decode the ones that
+/// matter for fingerprinting and negotiation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServerHello<'a> {
+	/// Legacy protocol version (`0x0303` for TLS 1.2 and 1.3).
+	pub legacy_version: u16,
+	/// 32-byte server random.
+	pub random: &'a [u8],
+	/// Session ID echoed from the ClientHello, or a new one.
+	pub session_id: &'a [u8],
+	/// Selected cipher suite.
+	pub cipher_suite: u16,
+	/// Selected compression method.
+	pub compression_method: u8,
+	/// Extensions in wire order, all as [`Extension::Unknown`].
+	#[cfg_attr(feature = "serde", serde(borrow))]
+	pub extensions: Extensions<'a>,
+	/// Framing the message was parsed from.
+	pub transport: Transport,
+}
+
+impl<'a> ServerHello<'a> {
+	/// Return the body of the first extension with `type_id`.
+	#[must_use]
+	pub fn find_extension(&self, type_id: u16) -> Option<&'a [u8]> {
+		self.extensions.iter().find_map(|ext| match *ext {
+			Extension::Unknown { type_id: t, data } if t == type_id => Some(data),
+			_ => None,
+		})
+	}
+
+	/// Return the version from the `supported_versions` extension, which
+	/// TLS 1.3 servers send in place of `legacy_version`.
+	#[must_use]
+	pub fn supported_version(&self) -> Option<u16> {
+		let mut r = Reader::new(self.find_extension(EXT_SUPPORTED_VERSIONS)?);
+		r.read_u16("selected version").ok()
+	}
+
+	/// Return the negotiated version: the `supported_versions` value
+	/// when present, `legacy_version` otherwise.
+	#[must_use]
+	pub fn version(&self) -> u16 {
+		self.supported_version().unwrap_or(self.legacy_version)
+	}
+
+	/// Check whether this is a HelloRetryRequest, a ServerHello with the
+	/// special random of RFC 8446 §4.1.3.
+	#[must_use]
+	pub fn is_hello_retry_request(&self) -> bool {
+		self.random == HELLO_RETRY_REQUEST_RANDOM
+	}
+
+	/// Return the server's key share.
+	///
+	/// A HelloRetryRequest only names the group the client must retry
+	/// with, so its entry has an empty `key`.
+	#[must_use]
+	pub fn key_share(&self) -> Option<KeyShareEntry<'a>> {
+		let mut r = Reader::new(self.find_extension(EXT_KEY_SHARE)?);
+		let group = r.read_u16("key share group").ok()?;
+		let key = if self.is_hello_retry_request() {
+			&[]
+		} else {
+			r.read_vec_u16("key exchange").ok()?
+		};
+		Some(KeyShareEntry { group, key })
+	}
+
+	/// Return the ALPN protocol the server selected.
+	#[must_use]
+	pub fn alpn(&self) -> Option<&'a [u8]> {
+		let mut r = Reader::new(self.find_extension(EXT_ALPN)?);
+		let mut list = Reader::new(r.read_vec_u16("ALPN list").ok()?);
+		list.read_vec_u8("ALPN protocol").ok()
+	}
+
+	/// Return the JA3S string, `version,cipher,extensions`.
+	///
+	/// Values are decimal and extensions are joined with `-`, in wire
+	/// order, as Zeek and Suricata compute it.
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3s(&self) -> String {
+		crate::ja3::ja3s_string(self)
+	}
+
+	/// Return the lowercase hex MD5 of the JA3S string.
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3s_hash(&self) -> String {
+		crate::ja3::ja3s_hash(self)
+	}
+
+	/// Return the JA3S string as revision `version` of the algorithm
+	/// defines it; [`ja3s`](Self::ja3s) is pinned to
+	/// [`Ja3Version::V1`](crate::Ja3Version::V1).
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3s_with(&self, version: crate::Ja3Version) -> String {
+		crate::ja3::ja3s_string_with(self, version)
+	}
+
+	/// Return the lowercase hex MD5 of [`ja3s_with`](Self::ja3s_with).
+	#[cfg(feature = "ja3")]
+	#[must_use]
+	pub fn ja3s_hash_with(&self, version: crate::Ja3Version) -> String {
+		crate::ja3::md5_hex(&self.ja3s_with(version))
+	}
+
+	/// Return the JA4S fingerprint (`a_b_c`, FoxIO JA4S spec), e.g.
+	/// `t130200_1301_a56c5b993250`.
+	///
+	/// Pair it with the ClientHello's JA4; a server answers different
+	/// clients differently.
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn ja4s(&self) -> String {
+		crate::ja4::ja4s(self)
+	}
+
+	/// Return the raw JA4S fingerprint (`JA4S_r`) with the extension
+	/// list unhashed.
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn ja4s_raw(&self) -> String {
+		crate::ja4::ja4s_raw(self)
+	}
+
+	/// Return the JA4S fingerprint as revision `version` of the FoxIO
+	/// spec defines it; [`ja4s`](Self::ja4s) is pinned to
+	/// [`Ja4Version::V1`](crate::Ja4Version::V1).
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn ja4s_with(&self, version: crate::Ja4Version) -> String {
+		crate::ja4::ja4s_with(self, version)
+	}
+
+	/// Return the raw `JA4S_r` fingerprint as revision `version` of the
+	/// FoxIO spec defines it.
+	#[cfg(feature = "ja4")]
+	#[must_use]
+	pub fn ja4s_raw_with(&self, version: crate::Ja4Version) -> String {
+		crate::ja4::ja4s_raw_with(self, version)
+	}
+}
+
+/// Parse a TLS ServerHello from a raw Handshake message.
+///
+/// The input should begin with the handshake type byte `0x02`, as in
+/// QUIC CRYPTO frames. Bytes after the ServerHello, such as the next
+/// handshake message of the flight, are ignored.
+///
+/// # Errors
+///
+/// Returns [`Error::NotServerHello`] when the handshake type is not
+/// ServerHello, and an error when the data is truncated or any field
+/// cannot be decoded.
+///
+/// ```
+/// let mut msg = vec![0x02, 0x00, 0x00, 0x2e, 0x03, 0x03];
+/// msg.extend_from_slice(&[0x11; 32]);
+/// msg.extend_from_slice(&[0x00, 0x13, 0x01, 0x00]);
+/// msg.extend_from_slice(&[0x00, 0x06, 0x00, 0x2b, 0x00, 0x02, 0x03, 0x04]);
+///
+/// let hello = clienthello::parse_server_hello(&msg).unwrap();
+/// assert_eq!(hello.cipher_suite, 0x1301);
+/// assert_eq!(hello.version(), 0x0304);
+/// ```
+pub fn parse_server_hello(data: &[u8]) -> Result<ServerHello<'_>, Error> {
+	parse_handshake(data, Transport::Handshake)
+}
+
+/// Parse a TLS ServerHello from a TLS record-layer message.
+///
+/// The input should begin with the content type byte `0x16`
+/// (Handshake). The record may go on with the rest of the server's
+/// flight, which is ignored.
+///
+/// # Errors
+///
+/// Returns an error when the record layer is invalid, plus the errors of
+/// [`parse_server_hello`].
+pub fn parse_server_hello_from_record(data: &[u8]) -> Result<ServerHello<'_>, Error> {
+	parse_handshake(framing::record_payload(data)?, Transport::TlsRecord)
+}
+
+fn parse_handshake(data: &[u8], transport: Transport) -> Result<ServerHello<'_>, Error> {
+	let mut r = Reader::new(data);
+	let hs_type = HandshakeType::from_u8(r.read_u8("handshake type")?);
+	if hs_type != HandshakeType::ServerHello {
+		return Err(Error::NotServerHello(hs_type));
+	}
+	let body_len = r.read_u24("handshake length")? as usize;
+	let mut r = Reader::new(r.read_bytes(body_len, "handshake body")?);
+
+	let legacy_version = r.read_u16("legacy version")?;
+	let random = r.read_bytes(32, "server random")?;
+	let session_id = r.read_vec_u8("session ID")?;
+	let cipher_suite = r.read_u16("cipher suite")?;
+	let compression_method = r.read_u8("compression method")?;
+
+	let mut extensions = Vec::new();
+	if !r.is_empty() {
+		let mut list = Reader::new(r.read_vec_u16("extensions")?);
+		while !list.is_empty() {
+			let type_id = list.read_u16("extension type")?;
+			let data = list.read_vec_u16("extension data")?;
+			extensions.push(Extension::Unknown { type_id, data });
+		}
+	}
+
+	Ok(ServerHello {
+		legacy_version,
+		random,
+		session_id,
+		cipher_suite,
+		compression_method,
+		extensions: Extensions::new(extensions),
+		transport,
+	})
+}
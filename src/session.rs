@@ -0,0 +1,268 @@
+/* src/session.rs */
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::consts::{EXT_COOKIE, EXT_EARLY_DATA, EXT_KEY_SHARE, EXT_PADDING, EXT_PRE_SHARED_KEY};
+use crate::framing;
+use crate::grease::is_grease;
+use crate::tls_reader::Reader;
+use crate::{ClientHello, Error, Extension, ParseOptions, Transport};
+
+/// What changed between two ClientHellos of one handshake.
+///
+/// A server answers a first hello with a HelloRetryRequest (TLS 1.3) or
+/// a HelloVerifyRequest (DTLS), and the client sends a second hello that
+/// RFC 8446 §4.1.2 allows to differ only in a few ways. Returned by
+/// [`SessionTracker::observe`] and [`HelloChanges::between`].
+///
+/// ```
+/// use clienthello::{Builder, HelloChanges};
+///
+/// let first = Builder::new().cipher_suites(&[0x1301]).key_share(&[(29, &[0; 32])]);
+/// let second = first.clone().key_share(&[(23, &[0; 65])]).extension(0x002c, &[0, 1, 7]);
+/// let (a, b) = (first.build().unwrap(), second.build().unwrap());
+/// let changes = HelloChanges::between(
+///     &clienthello::parse(&a).unwrap(),
+///     &clienthello::parse(&b).unwrap(),
+/// );
+/// assert!(changes.cookie_added);
+/// assert_eq!(changes.key_share_groups, Some((vec![29], vec![23])));
+/// assert!(changes.is_valid_retry());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HelloChanges {
+	/// The second hello carries a cookie the first did not: the
+	/// `cookie` extension, or the DTLS cookie field.
+	pub cookie_added: bool,
+	/// Key share groups of the first and second hello, GREASE excluded,
+	/// when they differ.
+	pub key_share_groups: Option<(Vec<u16>, Vec<u16>)>,
+	/// Extension types only the second hello sends, in its wire order.
+	pub extensions_added: Vec<u16>,
+	/// Extension types only the first hello sent, in its wire order.
+	pub extensions_removed: Vec<u16>,
+	/// The client random differs.
+	pub random_changed: bool,
+	/// The legacy session ID differs.
+	pub session_id_changed: bool,
+	/// The cipher suites or their order differ.
+	pub cipher_suites_changed: bool,
+}
+
+impl HelloChanges {
+	/// Compare a first hello with the one that followed it.
+	///
+	/// Only the `cookie` extension is considered for
+	/// [`cookie_added`](Self::cookie_added); DTLS cookies live outside
+	/// [`ClientHello`] and are compared by [`SessionTracker`].
+	#[must_use]
+	pub fn between(first: &ClientHello<'_>, second: &ClientHello<'_>) -> Self {
+		let (before, after) = (key_share_groups(first), key_share_groups(second));
+		Self {
+			cookie_added: !first.extensions.contains(EXT_COOKIE)
+				&& second.extensions.contains(EXT_COOKIE),
+			key_share_groups: (before != after).then_some((before, after)),
+			extensions_added: only_in(second, first),
+			extensions_removed: only_in(first, second),
+			random_changed: first.random != second.random,
+			session_id_changed: first.session_id != second.session_id,
+			cipher_suites_changed: first.cipher_suites != second.cipher_suites,
+		}
+	}
+
+	/// Check whether the hellos are identical in every way compared.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		*self == Self::default()
+	}
+
+	/// Check whether the second hello is a permitted retry of the first.
+	///
+	/// RFC 8446 §4.1.2 allows the key shares to be replaced, a cookie to
+	/// be added, `early_data` and `pre_shared_key` to be dropped, and
+	/// padding to change; the random, session ID, and cipher suites must
+	/// stay the same.
+	#[must_use]
+	pub fn is_valid_retry(&self) -> bool {
+		!self.random_changed
+			&& !self.session_id_changed
+			&& !self.cipher_suites_changed
+			&& self
+				.extensions_added
+				.iter()
+				.all(|&t| t == EXT_COOKIE || t == EXT_PADDING)
+			&& self
+				.extensions_removed
+				.iter()
+				.all(|&t| matches!(t, EXT_EARLY_DATA | EXT_PRE_SHARED_KEY | EXT_PADDING))
+	}
+}
+
+/// Correlates successive ClientHellos of the same flow.
+///
+/// Feed every hello with a key identifying its flow, such as the
+/// 5-tuple; the first is remembered, and each later one is compared
+/// with the hello before it, so HelloRetryRequest flows and DTLS cookie
+/// exchanges can be inspected without keeping the hellos around. Input
+/// in any framing [`parse_any`](crate::parse_any) understands is
+/// accepted. At most `capacity` flows are tracked; the flow seen least
+/// recently is forgotten first.
+///
+/// ```
+/// use clienthello::{Builder, SessionTracker};
+///
+/// let first = Builder::new().cipher_suites(&[0x1301]).key_share(&[(29, &[0; 32])]);
+/// let second = first.clone().key_share(&[(23, &[0; 65])]);
+///
+/// let mut tracker = SessionTracker::new(1024);
+/// let flow = ("192.0.2.1", 40000, "198.51.100.7", 443);
+/// assert_eq!(tracker.observe(flow, &first.build_record().unwrap()), Ok(None));
+/// let changes = tracker.observe(flow, &second.build_record().unwrap()).unwrap().unwrap();
+/// assert_eq!(changes.key_share_groups, Some((vec![29], vec![23])));
+/// assert!(!changes.cookie_added);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionTracker<K> {
+	capacity: usize,
+	opts: ParseOptions,
+	flows: BTreeMap<K, Flow>,
+	/// Flow keys by the sequence number of their latest hello.
+	order: BTreeMap<u64, K>,
+	seq: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Flow {
+	hello: Vec<u8>,
+	seq: u64,
+}
+
+impl<K: Ord + Clone> SessionTracker<K> {
+	/// Create a tracker following at most `capacity` flows (at least one),
+	/// using the default [`ParseOptions`].
+	#[must_use]
+	pub fn new(capacity: usize) -> Self {
+		Self::with_options(capacity, ParseOptions::new())
+	}
+
+	/// Create a tracker that parses hellos with `opts`.
+	#[must_use]
+	pub fn with_options(capacity: usize, opts: ParseOptions) -> Self {
+		Self {
+			capacity: capacity.max(1),
+			opts,
+			flows: BTreeMap::new(),
+			order: BTreeMap::new(),
+			seq: 0,
+		}
+	}
+
+	/// Maximum number of flows tracked.
+	#[must_use]
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Number of flows tracked.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.flows.len()
+	}
+
+	/// Check whether no flows are tracked.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.flows.is_empty()
+	}
+
+	/// Record a hello of the flow `key`.
+	///
+	/// Returns `None` for the first hello of a flow, and otherwise what
+	/// changed since the flow's previous hello, which this one replaces.
+	///
+	/// # Errors
+	///
+	/// Returns the errors of [`parse_any`](crate::parse_any); a hello that
+	/// does not parse is not recorded.
+	pub fn observe(&mut self, key: K, data: &[u8]) -> Result<Option<HelloChanges>, Error> {
+		let (hello, transport) = self.opts.parse_any(data)?;
+		let seq = self.seq;
+		self.seq += 1;
+		let changes = if let Some(flow) = self.flows.get_mut(&key) {
+			let Ok((previous, _)) = self.opts.parse_any(&flow.hello) else {
+				unreachable!("hello was validated when it was recorded")
+			};
+			let mut changes = HelloChanges::between(&previous, &hello);
+			if transport == Transport::Dtls && previous.transport == Transport::Dtls {
+				changes.cookie_added |=
+					dtls_cookie(&flow.hello).is_empty() && !dtls_cookie(data).is_empty();
+			}
+			self.order.remove(&flow.seq);
+			flow.hello.clear();
+			flow.hello.extend_from_slice(data);
+			flow.seq = seq;
+			Some(changes)
+		} else {
+			if self.flows.len() == self.capacity
+				&& let Some((_, oldest)) = self.order.pop_first()
+			{
+				self.flows.remove(&oldest);
+			}
+			let flow = Flow {
+				hello: data.to_vec(),
+				seq,
+			};
+			self.flows.insert(key.clone(), flow);
+			None
+		};
+		self.order.insert(seq, key);
+		Ok(changes)
+	}
+
+	/// Stop tracking the flow `key`, e.g. once its handshake completed.
+	///
+	/// Returns whether the flow was tracked.
+	pub fn remove(&mut self, key: &K) -> bool {
+		let Some(flow) = self.flows.remove(key) else {
+			return false;
+		};
+		self.order.remove(&flow.seq);
+		true
+	}
+}
+
+fn dtls_cookie(data: &[u8]) -> &[u8] {
+	framing::dtls_cookie(data).unwrap_or_default()
+}
+
+/// Key share groups with or without the `ext-key-share` decoder.
+fn key_share_groups(hello: &ClientHello<'_>) -> Vec<u16> {
+	match hello.extensions.by_type(EXT_KEY_SHARE) {
+		Some(Extension::KeyShare { groups, .. }) => groups.clone(),
+		Some(Extension::Unknown { data: raw, .. } | Extension::Malformed { raw, .. }) => {
+			let mut r = Reader::new(raw);
+			let entries = r.read_vec_u16("key share entries").unwrap_or_default();
+			let mut r = Reader::new(entries);
+			let mut groups = Vec::new();
+			// Without the decoder no GREASE positions were recorded, so
+			// only RFC 8701 values can be told apart here.
+			while let (Ok(group), Ok(_)) = (r.read_u16("group"), r.read_vec_u16("key exchange")) {
+				if !is_grease(group) {
+					groups.push(group);
+				}
+			}
+			groups
+		}
+		_ => Vec::new(),
+	}
+}
+
+/// Extension types of `a` that `b` does not send.
+fn only_in(a: &ClientHello<'_>, b: &ClientHello<'_>) -> Vec<u16> {
+	a.extensions
+		.type_ids()
+		.filter(|&t| !b.extensions.contains(t))
+		.collect()
+}
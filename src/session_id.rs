@@ -0,0 +1,70 @@
+/* src/session_id.rs */
+
+use crate::consts::{EXT_SUPPORTED_VERSIONS, MAX_SESSION_ID_LEN};
+use crate::{ClientHello, Extension};
+
+/// How a hello uses its `legacy_session_id` field.
+///
+/// Returned by [`ClientHello::session_id_style`]. Whether a client sends
+/// a session ID on a fresh connection is fixed by its TLS stack, so the
+/// style is a cheap fingerprinting signal, and a resumption-style ID
+/// tells a returning client from a new one.
+///
+/// ```
+/// use clienthello::{Builder, SessionIdStyle};
+///
+/// let msg = Builder::new()
+///     .cipher_suites(&[0x1301])
+///     .session_id(&[0x5a; 32])
+///     .supported_versions(&[0x0304, 0x0303])
+///     .build()
+///     .unwrap();
+/// let hello = clienthello::parse(&msg).unwrap();
+/// assert_eq!(hello.session_id_style(), SessionIdStyle::Compatibility);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum SessionIdStyle {
+	/// No session ID. Sent by TLS 1.2 stacks on fresh connections and by
+	/// QUIC clients, which must not use compatibility mode.
+	Empty,
+	/// 32 bytes alongside a TLS 1.3 offer: the random value of
+	/// middlebox compatibility mode (RFC 8446 Appendix D.4), sent by
+	/// browsers, OpenSSL, and Go.
+	Compatibility,
+	/// 1 to 32 bytes without a TLS 1.3 offer: a session ID issued by the
+	/// server on an earlier connection, or one the client generated to
+	/// pair with a session ticket (RFC 5077 §3.4).
+	Resumption,
+	/// Longer than the 32 bytes TLS allows.
+	Oversized,
+}
+
+pub(crate) fn classify(hello: &ClientHello<'_>) -> SessionIdStyle {
+	match hello.session_id.len() {
+		0 => SessionIdStyle::Empty,
+		len if len > MAX_SESSION_ID_LEN => SessionIdStyle::Oversized,
+		MAX_SESSION_ID_LEN if offers_tls13(hello) => SessionIdStyle::Compatibility,
+		_ => SessionIdStyle::Resumption,
+	}
+}
+
+/// Check for TLS 1.3 in Supported Versions, with or without the
+/// `ext-supported-versions` decoder.
+pub(crate) fn offers_tls13(hello: &ClientHello<'_>) -> bool {
+	const TLS13: u16 = 0x0304;
+	hello
+		.extensions_of_type(EXT_SUPPORTED_VERSIONS)
+		.any(|ext| match ext {
+			#[cfg(feature = "ext-supported-versions")]
+			Extension::SupportedVersions(versions) => versions.contains(&TLS13),
+			// Undecoded bodies still hold the one-byte length prefix.
+			Extension::Unknown { data: raw, .. } | Extension::Malformed { raw, .. } => raw
+				.get(1..)
+				.unwrap_or_default()
+				.chunks_exact(2)
+				.any(|v| v == TLS13.to_be_bytes()),
+			_ => false,
+		})
+}
@@ -0,0 +1,79 @@
+/* src/sni.rs */
+
+use crate::Error;
+use crate::framing;
+use crate::tls_reader::Reader;
+
+/// Extract the first DNS hostname from a raw Handshake ClientHello.
+///
+/// Walks the message without allocating and without decoding any
+/// extension other than SNI. Returns `Ok(None)` when there is no SNI
+/// extension, no `host_name` entry, or the name is not valid UTF-8.
+///
+/// # Errors
+///
+/// Returns an error when the handshake header is invalid or a field on
+/// the way to the SNI extension is truncated.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x3b, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x10, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x0a, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x07]);
+/// #     d.extend_from_slice(b"example");
+/// #     d
+/// # }
+/// # let msg = build();
+/// assert_eq!(clienthello::extract_sni(&msg).unwrap(), Some("example"));
+/// ```
+pub fn extract_sni(data: &[u8]) -> Result<Option<&str>, Error> {
+	sni_from_body(framing::client_hello_body(data)?)
+}
+
+/// Extract the first DNS hostname from a TLS record containing a
+/// ClientHello.
+///
+/// See [`extract_sni`] for the return value semantics.
+///
+/// # Errors
+///
+/// Returns an error when the record or handshake header is invalid or a
+/// field on the way to the SNI extension is truncated.
+pub fn extract_sni_from_record(data: &[u8]) -> Result<Option<&str>, Error> {
+	extract_sni(framing::record_payload(data)?)
+}
+
+fn sni_from_body(body: &[u8]) -> Result<Option<&str>, Error> {
+	let mut r = Reader::new(body);
+	r.read_bytes(2 + 32, "legacy version and random")?;
+	r.read_vec_u8("session ID")?;
+	r.read_vec_u16("cipher suites")?;
+	r.read_vec_u8("compression methods")?;
+	if r.remaining() < 2 {
+		return Ok(None);
+	}
+	let mut exts = Reader::new(r.read_vec_u16("extensions data")?);
+	while exts.remaining() >= 4 {
+		let type_id = exts.read_u16("extension type")?;
+		let ext_body = exts.read_vec_u16("extension body")?;
+		if type_id == 0x0000 {
+			return host_name(ext_body);
+		}
+	}
+	Ok(None)
+}
+
+/// Return the first `host_name` entry of an SNI extension body.
+pub(crate) fn host_name(ext_body: &[u8]) -> Result<Option<&str>, Error> {
+	let mut list = Reader::new(Reader::new(ext_body).read_vec_u16("SNI list data")?);
+	while !list.is_empty() {
+		let name_type = list.read_u8("SNI name type")?;
+		let name = list.read_vec_u16("SNI name")?;
+		if name_type == 0x00 {
+			return Ok(core::str::from_utf8(name).ok());
+		}
+	}
+	Ok(None)
+}
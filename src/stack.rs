@@ -0,0 +1,336 @@
+/* src/stack.rs */
+
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::ClientHello;
+use crate::consts::{
+	EXT_ALPN, EXT_APPLICATION_SETTINGS, EXT_APPLICATION_SETTINGS_OLD, EXT_CHANNEL_ID,
+	EXT_COMPRESS_CERTIFICATE, EXT_COOKIE, EXT_DELEGATED_CREDENTIAL, EXT_EARLY_DATA,
+	EXT_EC_POINT_FORMATS, EXT_ENCRYPT_THEN_MAC, EXT_ENCRYPTED_CLIENT_HELLO,
+	EXT_EXTENDED_MASTER_SECRET, EXT_KEY_SHARE, EXT_NEXT_PROTOCOL_NEGOTIATION, EXT_PADDING,
+	EXT_POST_HANDSHAKE_AUTH, EXT_PRE_SHARED_KEY, EXT_PSK_KEY_EXCHANGE_MODES,
+	EXT_QUIC_TRANSPORT_PARAMETERS, EXT_QUIC_TRANSPORT_PARAMETERS_DRAFT, EXT_RECORD_SIZE_LIMIT,
+	EXT_RENEGOTIATION_INFO, EXT_SERVER_NAME, EXT_SESSION_TICKET, EXT_SIGNATURE_ALGORITHMS,
+	EXT_SIGNED_CERTIFICATE_TIMESTAMP, EXT_STATUS_REQUEST, EXT_SUPPORTED_GROUPS,
+	EXT_SUPPORTED_VERSIONS, EXT_USE_SRTP,
+};
+
+/// TLS implementation family a client hello was most likely produced by.
+///
+/// Returned by [`ClientHello::infer_stack`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum TlsStack {
+	/// BoringSSL and its derivatives (Chrome, Edge, Android, Safari).
+	BoringSsl,
+	/// Mozilla NSS (Firefox, Thunderbird).
+	Nss,
+	/// OpenSSL (curl, Python, most command-line tools on Linux).
+	OpenSsl,
+	/// Go `crypto/tls`.
+	GoCryptoTls,
+	/// Microsoft Schannel (Windows system TLS, .NET on Windows).
+	Schannel,
+}
+
+/// Whether a hello's extension order looks shuffled per connection.
+///
+/// Returned by [`ClientHello::extension_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum ExtensionOrder {
+	/// The order deviates from the one BoringSSL builds before shuffling,
+	/// as Chrome 110 and later send.
+	Randomized,
+	/// The order is one a stack sends on every connection.
+	Fixed,
+	/// Too few recognised extensions to tell.
+	Unknown,
+}
+
+impl ExtensionOrder {
+	/// Check whether order-sensitive fingerprints such as JA3 stay the
+	/// same across the client's connections.
+	///
+	/// Only a [`Fixed`](Self::Fixed) order does; for the others, group
+	/// hellos by fingerprints that sort extensions, such as JA3N or JA4.
+	#[must_use]
+	pub const fn is_stable(self) -> bool {
+		matches!(self, Self::Fixed)
+	}
+}
+
+const STACKS: [TlsStack; 5] = [
+	TlsStack::BoringSsl,
+	TlsStack::Nss,
+	TlsStack::OpenSsl,
+	TlsStack::GoCryptoTls,
+	TlsStack::Schannel,
+];
+
+/// Evidence needed before a stack is reported.
+const MIN_SCORE: u8 = 3;
+
+/// Certificate compression algorithms (RFC 8879).
+pub(crate) const ZLIB: u16 = 0x0001;
+pub(crate) const BROTLI: u16 = 0x0002;
+pub(crate) const ZSTD: u16 = 0x0003;
+
+/// Extensions Go `crypto/tls` sends first, after an optional SNI.
+const GO_ORDER: [u16; 5] = [
+	EXT_STATUS_REQUEST,
+	EXT_SUPPORTED_GROUPS,
+	EXT_EC_POINT_FORMATS,
+	EXT_SIGNATURE_ALGORITHMS,
+	EXT_RENEGOTIATION_INFO,
+];
+
+/// Evidence points per candidate, shared by stack inference and
+/// [`ClientHello::probable_client`].
+pub(crate) struct Tally<T, const N: usize> {
+	candidates: [T; N],
+	scores: [u8; N],
+}
+
+impl<T: Copy + PartialEq, const N: usize> Tally<T, N> {
+	pub(crate) const fn new(candidates: [T; N]) -> Self {
+		Self {
+			candidates,
+			scores: [0; N],
+		}
+	}
+
+	pub(crate) fn add(&mut self, candidate: T, points: u8) {
+		if let Some(i) = self.candidates.iter().position(|&c| c == candidate) {
+			self.scores[i] = self.scores[i].saturating_add(points);
+		}
+	}
+
+	/// Highest-scoring candidate, its score, and its lead over the
+	/// runner-up. Ties go to the candidate listed first.
+	pub(crate) fn leader(&self) -> Option<(T, u8, u8)> {
+		let mut ranked: [(u8, T); N] = core::array::from_fn(|i| (self.scores[i], self.candidates[i]));
+		ranked.sort_by_key(|&(score, _)| Reverse(score));
+		match ranked.as_slice() {
+			[(best, candidate), (runner_up, _), ..] => Some((*candidate, *best, best - runner_up)),
+			[(best, candidate)] => Some((*candidate, *best, *best)),
+			[] => None,
+		}
+	}
+}
+
+pub(crate) fn infer(hello: &ClientHello<'_>) -> Option<TlsStack> {
+	let mut tally = Tally::new(STACKS);
+	let has = |type_id: u16| hello.extensions.contains(type_id);
+
+	// GREASE and ALPS are only sent by BoringSSL-based clients.
+	if hello.has_grease {
+		tally.add(TlsStack::BoringSsl, 4);
+	}
+	if has(EXT_APPLICATION_SETTINGS_OLD) || has(EXT_APPLICATION_SETTINGS) {
+		tally.add(TlsStack::BoringSsl, 3);
+	}
+	tally.add(TlsStack::Nss, nss_extensions(hello));
+	tally.add(TlsStack::OpenSsl, openssl_defaults(hello));
+	if hello.cipher_suites.len() >= 30 {
+		tally.add(TlsStack::OpenSsl, 1);
+	}
+
+	let algorithms = cert_compression(hello);
+	let brotli = algorithms.contains(&BROTLI);
+	let others = algorithms.iter().any(|&alg| matches!(alg, ZLIB | ZSTD));
+	match (brotli, others) {
+		(true, false) => tally.add(TlsStack::BoringSsl, 2),
+		(_, true) => tally.add(TlsStack::Nss, 2),
+		(false, false) => {}
+	}
+
+	match tls13_cipher_order(hello) {
+		Some(TlsStack::Nss) => tally.add(TlsStack::Nss, 1),
+		Some(stack) => tally.add(stack, 2),
+		None => {}
+	}
+	// Schannel prefers ECDSA over RSA within each key size.
+	let mut tls12 = hello.cipher_suites.iter().filter(|&&c| c >> 8 != 0x13);
+	if tls12.next() == Some(&0xc02c) && tls12.next() == Some(&0xc02b) {
+		tally.add(TlsStack::Schannel, 2);
+	}
+
+	tally.add(TlsStack::GoCryptoTls, go_evidence(hello));
+	let order = types_after_sni(hello);
+	if order.starts_with(&[
+		EXT_STATUS_REQUEST,
+		EXT_SUPPORTED_GROUPS,
+		EXT_EC_POINT_FORMATS,
+		EXT_SIGNATURE_ALGORITHMS,
+		EXT_SESSION_TICKET,
+	]) || order
+		.windows(2)
+		.any(|w| w == [EXT_EXTENDED_MASTER_SECRET, EXT_RENEGOTIATION_INFO])
+	{
+		tally.add(TlsStack::Schannel, 2);
+	}
+
+	let (stack, best, lead) = tally.leader()?;
+	(best >= MIN_SCORE && lead > 0).then_some(stack)
+}
+
+/// Points for the extensions only Firefox sends.
+pub(crate) fn nss_extensions(hello: &ClientHello<'_>) -> u8 {
+	let mut points = 0;
+	if hello.extensions.contains(EXT_RECORD_SIZE_LIMIT) {
+		points += 3;
+	}
+	if hello.extensions.contains(EXT_DELEGATED_CREDENTIAL) {
+		points += 3;
+	}
+	points
+}
+
+/// Points for what OpenSSL enables by default and browsers and Go never
+/// send.
+pub(crate) fn openssl_defaults(hello: &ClientHello<'_>) -> u8 {
+	let mut points = 0;
+	if hello.extensions.contains(EXT_ENCRYPT_THEN_MAC) {
+		points += 3;
+	}
+	if hello.extensions.contains(EXT_POST_HANDSHAKE_AUTH) {
+		points += 2;
+	}
+	if point_format_count(hello) == Some(3) {
+		points += 2;
+	}
+	points
+}
+
+/// Points for Go's fixed extension order and for offering TLS 1.3
+/// without `psk_key_exchange_modes`.
+pub(crate) fn go_evidence(hello: &ClientHello<'_>) -> u8 {
+	let mut points = 0;
+	if types_after_sni(hello).starts_with(&GO_ORDER) {
+		points += 4;
+	}
+	if hello.extensions.contains(EXT_SUPPORTED_VERSIONS)
+		&& !hello.extensions.contains(EXT_PSK_KEY_EXCHANGE_MODES)
+	{
+		points += 1;
+	}
+	points
+}
+
+/// Stack whose default TLS 1.3 cipher suite preference the hello opens
+/// with.
+pub(crate) fn tls13_cipher_order(hello: &ClientHello<'_>) -> Option<TlsStack> {
+	match hello.cipher_suites.as_slice() {
+		[0x1302, 0x1303, 0x1301, ..] => Some(TlsStack::OpenSsl),
+		[0x1302, 0x1301, ..] => Some(TlsStack::Schannel),
+		[0x1301, 0x1303, 0x1302, ..] => Some(TlsStack::Nss),
+		_ => None,
+	}
+}
+
+/// Algorithms offered in `compress_certificate`, empty without it.
+pub(crate) fn cert_compression(hello: &ClientHello<'_>) -> Vec<u16> {
+	hello
+		.find_extension(EXT_COMPRESS_CERTIFICATE)
+		.map(|data| u16_list(data.get(1..).unwrap_or_default()).collect())
+		.unwrap_or_default()
+}
+
+/// Extension types in wire order, ignoring a leading SNI.
+fn types_after_sni(hello: &ClientHello<'_>) -> Vec<u16> {
+	hello
+		.extensions
+		.type_ids()
+		.filter(|&t| t != EXT_SERVER_NAME)
+		.collect()
+}
+
+/// Extension order BoringSSL builds before permuting it. Clients that
+/// never shuffle send known extensions as a subsequence of this list.
+const BORINGSSL_ORDER: &[u16] = &[
+	EXT_ENCRYPTED_CLIENT_HELLO,
+	EXT_SERVER_NAME,
+	EXT_EXTENDED_MASTER_SECRET,
+	EXT_RENEGOTIATION_INFO,
+	EXT_SUPPORTED_GROUPS,
+	EXT_EC_POINT_FORMATS,
+	EXT_SESSION_TICKET,
+	EXT_ALPN,
+	EXT_STATUS_REQUEST,
+	EXT_SIGNATURE_ALGORITHMS,
+	EXT_NEXT_PROTOCOL_NEGOTIATION,
+	EXT_SIGNED_CERTIFICATE_TIMESTAMP,
+	EXT_CHANNEL_ID,
+	EXT_USE_SRTP,
+	EXT_KEY_SHARE,
+	EXT_PSK_KEY_EXCHANGE_MODES,
+	EXT_EARLY_DATA,
+	EXT_SUPPORTED_VERSIONS,
+	EXT_COOKIE,
+	EXT_QUIC_TRANSPORT_PARAMETERS,
+	EXT_QUIC_TRANSPORT_PARAMETERS_DRAFT,
+	EXT_COMPRESS_CERTIFICATE,
+	EXT_DELEGATED_CREDENTIAL,
+	EXT_APPLICATION_SETTINGS_OLD,
+	EXT_APPLICATION_SETTINGS,
+];
+
+/// Minimum number of recognised extensions for an order verdict.
+const MIN_ORDERED: usize = 3;
+
+/// Extensions BoringSSL appends after the shuffled block, in this order.
+const BORINGSSL_TAIL: [u16; 2] = [EXT_PADDING, EXT_PRE_SHARED_KEY];
+
+pub(crate) fn extension_order(hello: &ClientHello<'_>) -> ExtensionOrder {
+	// Only BoringSSL shuffles, and it always sends GREASE extensions.
+	if hello.grease.extensions.is_empty() {
+		return ExtensionOrder::Fixed;
+	}
+	// BoringSSL never moves padding and pre_shared_key out of the tail, so
+	// a hello with either one earlier was ordered by another stack or by
+	// hand.
+	let types: Vec<u16> = hello.extensions.type_ids().collect();
+	let tail_start = types
+		.iter()
+		.position(|t| BORINGSSL_TAIL.contains(t))
+		.unwrap_or(types.len());
+	let tail_ranks: Vec<usize> = types[tail_start..]
+		.iter()
+		.filter_map(|t| BORINGSSL_TAIL.iter().position(|b| b == t))
+		.collect();
+	if tail_ranks.len() != types.len() - tail_start || !tail_ranks.is_sorted() {
+		return ExtensionOrder::Fixed;
+	}
+	// padding and pre_shared_key follow the shuffled block and are not
+	// listed, so they are skipped along with unrecognised types.
+	let ranks: Vec<usize> = hello
+		.extensions
+		.iter()
+		.filter_map(|e| BORINGSSL_ORDER.iter().position(|&t| t == e.type_id()))
+		.collect();
+	if ranks.len() < MIN_ORDERED {
+		ExtensionOrder::Unknown
+	} else if ranks.is_sorted() {
+		ExtensionOrder::Fixed
+	} else {
+		ExtensionOrder::Randomized
+	}
+}
+
+/// Number of entries in the EC point formats extension.
+fn point_format_count(hello: &ClientHello<'_>) -> Option<u8> {
+	hello
+		.find_extension(EXT_EC_POINT_FORMATS)
+		.and_then(|data| data.first().copied())
+}
+
+fn u16_list(data: &[u8]) -> impl Iterator<Item = u16> + '_ {
+	data
+		.chunks_exact(2)
+		.filter_map(|c| <[u8; 2]>::try_from(c).ok())
+		.map(u16::from_be_bytes)
+}
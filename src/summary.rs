@@ -0,0 +1,134 @@
+/* src/summary.rs */
+
+use crate::consts::{
+	EXT_ALPN, EXT_ENCRYPTED_CLIENT_HELLO, EXT_SERVER_NAME, EXT_SUPPORTED_VERSIONS,
+};
+use crate::tls_reader::Reader;
+use crate::{Error, ExtensionBitmap, framing, is_grease, sni};
+
+/// The handful of ClientHello fields most often logged, gathered by
+/// [`summarize`] in a single pass without allocating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Summary<'a> {
+	/// First DNS hostname from the SNI extension, if valid UTF-8.
+	pub sni: Option<&'a str>,
+	/// Raw ALPN protocol name list, without its length prefix; empty
+	/// when ALPN is absent. Iterate it with [`Summary::alpn_protocols`].
+	pub alpn: &'a [u8],
+	/// Highest non-GREASE version from Supported Versions, or the legacy
+	/// version field when that extension is absent.
+	pub max_version: u16,
+	/// Number of cipher suites, GREASE values excluded.
+	pub cipher_count: usize,
+	/// Whether an Encrypted Client Hello extension is present.
+	pub has_ech: bool,
+	/// Extension types present, GREASE excluded, for pre-filtering
+	/// before a full parse.
+	pub extensions: ExtensionBitmap,
+}
+
+impl<'a> Summary<'a> {
+	/// Iterate the offered ALPN protocol names in order.
+	///
+	/// Stops at the first entry that overruns the list.
+	pub fn alpn_protocols(&self) -> impl Iterator<Item = &'a [u8]> + use<'a> {
+		let mut r = Reader::new(self.alpn);
+		core::iter::from_fn(move || r.read_vec_u8("ALPN protocol").ok())
+	}
+}
+
+/// Summarize a raw Handshake ClientHello in one pass.
+///
+/// Sits between [`extract_sni`](crate::extract_sni) and a full
+/// [`parse`](crate::parse): it reads SNI, ALPN, the highest offered
+/// version, the cipher suite count, ECH presence, and an
+/// [`ExtensionBitmap`] of the extension types, borrowing from `data` and
+/// allocating nothing. Other extensions are skipped without being
+/// decoded.
+///
+/// # Errors
+///
+/// Returns an error when the handshake header is invalid or a field is
+/// truncated.
+///
+/// ```
+/// # fn build() -> Vec<u8> {
+/// #     let mut d = vec![0x01, 0x00, 0x00, 0x3b, 0x03, 0x03];
+/// #     d.extend_from_slice(&[0u8; 32]);
+/// #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x10, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x0a, 0x00]);
+/// #     d.extend_from_slice(&[0x00, 0x07]);
+/// #     d.extend_from_slice(b"example");
+/// #     d
+/// # }
+/// # let msg = build();
+/// let summary = clienthello::summarize(&msg).unwrap();
+/// assert_eq!(summary.sni, Some("example"));
+/// assert_eq!(summary.cipher_count, 1);
+/// assert_eq!(summary.alpn_protocols().count(), 0);
+/// ```
+pub fn summarize(data: &[u8]) -> Result<Summary<'_>, Error> {
+	summarize_body(framing::client_hello_body(data)?)
+}
+
+/// Summarize a TLS record containing a ClientHello in one pass.
+///
+/// See [`summarize`].
+///
+/// # Errors
+///
+/// Returns an error when the record or handshake header is invalid or a
+/// field is truncated.
+pub fn summarize_from_record(data: &[u8]) -> Result<Summary<'_>, Error> {
+	summarize(framing::record_payload(data)?)
+}
+
+fn summarize_body(body: &[u8]) -> Result<Summary<'_>, Error> {
+	let mut r = Reader::new(body);
+	let legacy_version = r.read_u16("legacy version")?;
+	r.read_bytes(32, "random")?;
+	r.read_vec_u8("session ID")?;
+	let suites = r.read_vec_u16("cipher suites data")?;
+	r.read_vec_u8("compression methods")?;
+
+	let mut summary = Summary {
+		max_version: legacy_version,
+		cipher_count: count_non_grease(suites),
+		..Summary::default()
+	};
+	if r.remaining() < 2 {
+		return Ok(summary);
+	}
+	let mut exts = Reader::new(r.read_vec_u16("extensions data")?);
+	while exts.remaining() >= 4 {
+		let type_id = exts.read_u16("extension type")?;
+		let ext_body = exts.read_vec_u16("extension body")?;
+		if !is_grease(type_id) {
+			summary.extensions.insert(type_id);
+		}
+		match type_id {
+			EXT_SERVER_NAME => summary.sni = sni::host_name(ext_body)?,
+			EXT_ALPN => summary.alpn = Reader::new(ext_body).read_vec_u16("ALPN list data")?,
+			EXT_SUPPORTED_VERSIONS => {
+				let list = Reader::new(ext_body).read_vec_u8("supported versions data")?;
+				if let Some(max) = u16s(list).filter(|&v| !is_grease(v)).max() {
+					summary.max_version = max;
+				}
+			}
+			EXT_ENCRYPTED_CLIENT_HELLO => summary.has_ech = true,
+			_ => {}
+		}
+	}
+	Ok(summary)
+}
+
+fn count_non_grease(list: &[u8]) -> usize {
+	u16s(list).filter(|&v| !is_grease(v)).count()
+}
+
+fn u16s(list: &[u8]) -> impl Iterator<Item = u16> + '_ {
+	list
+		.chunks_exact(2)
+		.filter_map(|c| <[u8; 2]>::try_from(c).ok())
+		.map(u16::from_be_bytes)
+}
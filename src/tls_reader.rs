@@ -0,0 +1,229 @@
+/* src/tls_reader.rs */
+
+//! Bounds-checked reader for TLS presentation-language structures.
+//!
+//! [`Reader`] is the cursor the ClientHello parser is built on. It is
+//! exposed so that code decoding adjacent TLS structures (session
+//! tickets, `ECHConfig`, `NewSessionTicket`, ...) can reuse the same
+//! big-endian integer and length-prefixed vector handling.
+//!
+//! ```
+//! use clienthello::tls_reader::Reader;
+//!
+//! let data = [0x00, 0x03, b'a', b'b', b'c', 0x07];
+//! let mut r = Reader::new(&data);
+//! assert_eq!(r.read_vec_u16("name").unwrap(), b"abc");
+//! assert_eq!(r.read_u8("flag").unwrap(), 0x07);
+//! assert!(r.is_empty());
+//! ```
+//!
+//! Nested vectors are decoded through closures that receive a reader
+//! over just the vector's body, so an entry can never read past it:
+//!
+//! ```
+//! use clienthello::tls_reader::Reader;
+//!
+//! // An ALPN extension body: a u16-prefixed list of u8-prefixed names.
+//! let body = [0x00, 0x06, 0x02, b'h', b'2', 0x02, b'h', b'3'];
+//! let protocols = Reader::new(&body)
+//!     .read_nested_u16("ALPN list", |r| {
+//!         let mut out = Vec::new();
+//!         while !r.is_empty() {
+//!             out.push(r.read_vec_u8("ALPN protocol")?);
+//!         }
+//!         Ok::<_, clienthello::Error>(out)
+//!     })
+//!     .unwrap();
+//! assert_eq!(protocols, [b"h2", b"h3"]);
+//! ```
+
+use crate::Error;
+
+/// Sequential byte reader with bounds checking.
+///
+/// Every read names the field being decoded; that name is reported in
+/// [`Error::Truncated`] when the input runs out.
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+	data: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> Reader<'a> {
+	/// Create a reader positioned at the start of `data`.
+	#[must_use]
+	pub fn new(data: &'a [u8]) -> Self {
+		Self { data, pos: 0 }
+	}
+
+	/// Number of unread bytes.
+	#[must_use]
+	pub fn remaining(&self) -> usize {
+		self.data.len().saturating_sub(self.pos)
+	}
+
+	/// Check whether all bytes have been consumed.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.remaining() == 0
+	}
+
+	/// Current offset from the start of the input.
+	#[must_use]
+	pub fn position(&self) -> usize {
+		self.pos
+	}
+
+	/// Return the unread bytes without consuming them.
+	#[must_use]
+	pub fn rest(&self) -> &'a [u8] {
+		self.data.get(self.pos..).unwrap_or_default()
+	}
+
+	/// Read a single byte.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when no bytes remain.
+	pub fn read_u8(&mut self, field: &'static str) -> Result<u8, Error> {
+		let [b0] = self.take_array(field)?;
+		Ok(b0)
+	}
+
+	/// Read a big-endian `u16`.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when fewer than 2 bytes remain.
+	pub fn read_u16(&mut self, field: &'static str) -> Result<u16, Error> {
+		self.take_array(field).map(u16::from_be_bytes)
+	}
+
+	/// Read a big-endian 24-bit integer.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when fewer than 3 bytes remain.
+	pub fn read_u24(&mut self, field: &'static str) -> Result<u32, Error> {
+		let [b0, b1, b2] = self.take_array(field)?;
+		Ok(u32::from_be_bytes([0, b0, b1, b2]))
+	}
+
+	/// Read a big-endian `u32`.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when fewer than 4 bytes remain.
+	pub fn read_u32(&mut self, field: &'static str) -> Result<u32, Error> {
+		self.take_array(field).map(u32::from_be_bytes)
+	}
+
+	/// Read exactly `n` bytes.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when fewer than `n` bytes remain.
+	pub fn read_bytes(&mut self, n: usize, field: &'static str) -> Result<&'a [u8], Error> {
+		// `checked_add` and `get` keep a hostile length from overflowing
+		// the cursor or indexing out of bounds.
+		let slice = self
+			.pos
+			.checked_add(n)
+			.and_then(|end| self.data.get(self.pos..end))
+			.ok_or(Error::Truncated { field })?;
+		self.pos += n;
+		Ok(slice)
+	}
+
+	fn take_array<const N: usize>(&mut self, field: &'static str) -> Result<[u8; N], Error> {
+		let bytes = self.read_bytes(N, field)?;
+		<[u8; N]>::try_from(bytes).map_err(|_| Error::Truncated { field })
+	}
+
+	/// Read an opaque vector with a `u8` length prefix (`opaque x<0..2^8-1>`).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when the prefix or the body is cut short.
+	pub fn read_vec_u8(&mut self, field: &'static str) -> Result<&'a [u8], Error> {
+		let len = self.read_u8(field)? as usize;
+		self.read_bytes(len, field)
+	}
+
+	/// Read an opaque vector with a `u16` length prefix (`opaque x<0..2^16-1>`).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when the prefix or the body is cut short.
+	pub fn read_vec_u16(&mut self, field: &'static str) -> Result<&'a [u8], Error> {
+		let len = self.read_u16(field)? as usize;
+		self.read_bytes(len, field)
+	}
+
+	/// Read an opaque vector with a 24-bit length prefix (`opaque x<0..2^24-1>`).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when the prefix or the body is cut short.
+	pub fn read_vec_u24(&mut self, field: &'static str) -> Result<&'a [u8], Error> {
+		let len = self.read_u24(field)? as usize;
+		self.read_bytes(len, field)
+	}
+
+	/// Read a vector with a `u8` length prefix and decode its body
+	/// with `f`.
+	///
+	/// `f` receives a reader over the body alone. Bytes it leaves unread
+	/// are skipped; check [`is_empty`](Self::is_empty) inside `f` to
+	/// reject them. `f` may return any error type that truncation errors
+	/// convert into, so decoders with their own error enum can nest too.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when the prefix or the body is cut
+	/// short, or any error returned by `f`.
+	pub fn read_nested_u8<T, E, F>(&mut self, field: &'static str, f: F) -> Result<T, E>
+	where
+		E: From<Error>,
+		F: FnOnce(&mut Self) -> Result<T, E>,
+	{
+		let body = self.read_vec_u8(field)?;
+		f(&mut Reader::new(body))
+	}
+
+	/// Read a vector with a `u16` length prefix and decode its body
+	/// with `f`.
+	///
+	/// See [`read_nested_u8`](Self::read_nested_u8).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when the prefix or the body is cut
+	/// short, or any error returned by `f`.
+	pub fn read_nested_u16<T, E, F>(&mut self, field: &'static str, f: F) -> Result<T, E>
+	where
+		E: From<Error>,
+		F: FnOnce(&mut Self) -> Result<T, E>,
+	{
+		let body = self.read_vec_u16(field)?;
+		f(&mut Reader::new(body))
+	}
+
+	/// Read a vector with a 24-bit length prefix and decode its body
+	/// with `f`.
+	///
+	/// See [`read_nested_u8`](Self::read_nested_u8).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::Truncated`] when the prefix or the body is cut
+	/// short, or any error returned by `f`.
+	pub fn read_nested_u24<T, E, F>(&mut self, field: &'static str, f: F) -> Result<T, E>
+	where
+		E: From<Error>,
+		F: FnOnce(&mut Self) -> Result<T, E>,
+	{
+		let body = self.read_vec_u24(field)?;
+		f(&mut Reader::new(body))
+	}
+}
@@ -0,0 +1,201 @@
+/* src/tls_writer.rs */
+
+//! Writer for TLS presentation-language structures.
+//!
+//! [`Writer`] is the counterpart of [`Reader`](crate::tls_reader::Reader)
+//! and backs [`Builder`](crate::Builder). Length-prefixed vectors are
+//! written through closures so the prefix is always patched to the
+//! number of bytes actually produced:
+//!
+//! ```
+//! use clienthello::tls_writer::Writer;
+//!
+//! let mut w = Writer::new();
+//! w.write_vec_u16(|w| {
+//!     w.write_u8(0x00);
+//!     w.write_opaque_u16(b"example.com")
+//! })
+//! .unwrap();
+//! assert_eq!(&w.as_bytes()[..5], &[0x00, 0x0e, 0x00, 0x00, 0x0b]);
+//! ```
+
+use alloc::vec::Vec;
+
+use crate::Error;
+
+/// Append-only byte writer with length-prefix bookkeeping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Writer {
+	buf: Vec<u8>,
+}
+
+impl Writer {
+	/// Create an empty writer.
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Create an empty writer with room for `capacity` bytes.
+	#[must_use]
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self {
+			buf: Vec::with_capacity(capacity),
+		}
+	}
+
+	/// Number of bytes written so far.
+	#[must_use]
+	pub fn len(&self) -> usize {
+		self.buf.len()
+	}
+
+	/// Check whether nothing has been written yet.
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.buf.is_empty()
+	}
+
+	/// Borrow the bytes written so far.
+	#[must_use]
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.buf
+	}
+
+	/// Consume the writer and return the encoded bytes.
+	#[must_use]
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.buf
+	}
+
+	/// Write a single byte.
+	pub fn write_u8(&mut self, val: u8) {
+		self.buf.push(val);
+	}
+
+	/// Write a big-endian `u16`.
+	pub fn write_u16(&mut self, val: u16) {
+		self.buf.extend_from_slice(&val.to_be_bytes());
+	}
+
+	/// Write a big-endian 24-bit integer.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when `val` does not fit in 24 bits.
+	pub fn write_u24(&mut self, val: u32) -> Result<(), Error> {
+		if val > U24_MAX {
+			return Err(Error::LengthOverflow {
+				len: val as usize,
+				max: U24_MAX as usize,
+			});
+		}
+		self.buf.extend_from_slice(&val.to_be_bytes()[1..]);
+		Ok(())
+	}
+
+	/// Write raw bytes.
+	pub fn write_bytes(&mut self, data: &[u8]) {
+		self.buf.extend_from_slice(data);
+	}
+
+	/// Write a vector with a `u8` length prefix whose contents are
+	/// produced by `f`.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when the contents exceed 255
+	/// bytes, or any error returned by `f`.
+	pub fn write_vec_u8<F>(&mut self, f: F) -> Result<(), Error>
+	where
+		F: FnOnce(&mut Self) -> Result<(), Error>,
+	{
+		self.write_prefixed(1, u8::MAX as usize, f)
+	}
+
+	/// Write a vector with a `u16` length prefix whose contents are
+	/// produced by `f`.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when the contents exceed 65535
+	/// bytes, or any error returned by `f`.
+	pub fn write_vec_u16<F>(&mut self, f: F) -> Result<(), Error>
+	where
+		F: FnOnce(&mut Self) -> Result<(), Error>,
+	{
+		self.write_prefixed(2, u16::MAX as usize, f)
+	}
+
+	/// Write a vector with a 24-bit length prefix whose contents are
+	/// produced by `f`.
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when the contents exceed
+	/// 2^24 - 1 bytes, or any error returned by `f`.
+	pub fn write_vec_u24<F>(&mut self, f: F) -> Result<(), Error>
+	where
+		F: FnOnce(&mut Self) -> Result<(), Error>,
+	{
+		self.write_prefixed(3, U24_MAX as usize, f)
+	}
+
+	/// Write `data` with a `u8` length prefix; the counterpart of
+	/// [`Reader::read_vec_u8`](crate::tls_reader::Reader::read_vec_u8).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when `data` exceeds 255 bytes.
+	pub fn write_opaque_u8(&mut self, data: &[u8]) -> Result<(), Error> {
+		self.write_vec_u8(|w| {
+			w.write_bytes(data);
+			Ok(())
+		})
+	}
+
+	/// Write `data` with a `u16` length prefix; the counterpart of
+	/// [`Reader::read_vec_u16`](crate::tls_reader::Reader::read_vec_u16).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when `data` exceeds 65535 bytes.
+	pub fn write_opaque_u16(&mut self, data: &[u8]) -> Result<(), Error> {
+		self.write_vec_u16(|w| {
+			w.write_bytes(data);
+			Ok(())
+		})
+	}
+
+	/// Write `data` with a 24-bit length prefix; the counterpart of
+	/// [`Reader::read_vec_u24`](crate::tls_reader::Reader::read_vec_u24).
+	///
+	/// # Errors
+	///
+	/// Returns [`Error::LengthOverflow`] when `data` exceeds 2^24 - 1
+	/// bytes.
+	pub fn write_opaque_u24(&mut self, data: &[u8]) -> Result<(), Error> {
+		self.write_vec_u24(|w| {
+			w.write_bytes(data);
+			Ok(())
+		})
+	}
+
+	fn write_prefixed<F>(&mut self, width: usize, max: usize, f: F) -> Result<(), Error>
+	where
+		F: FnOnce(&mut Self) -> Result<(), Error>,
+	{
+		let start = self.buf.len();
+		self.buf.resize(start + width, 0);
+		f(self)?;
+		let len = self.buf.len() - start - width;
+		if len > max {
+			return Err(Error::LengthOverflow { len, max });
+		}
+		let be = (len as u32).to_be_bytes();
+		self.buf[start..start + width].copy_from_slice(&be[4 - width..]);
+		Ok(())
+	}
+}
+
+const U24_MAX: u32 = 0x00FF_FFFF;
@@ -0,0 +1,120 @@
+/* src/transcript.rs */
+
+use digest::{Digest, Output};
+
+use crate::Error;
+use crate::consts::EXT_PRE_SHARED_KEY;
+use crate::framing;
+use crate::tls_reader::Reader;
+
+/// Hash a raw ClientHello handshake message the way the TLS 1.3 key
+/// schedule does.
+///
+/// The hash covers the handshake header and body exactly as received,
+/// i.e. `Transcript-Hash(ClientHello)` of RFC 8446 §4.4.1, so it can be
+/// compared with a peer's key schedule when debugging a mismatch. Bytes
+/// after the declared handshake length are not included.
+///
+/// # Errors
+///
+/// Returns an error when the handshake header is truncated, the message
+/// is not a ClientHello, or its body is shorter than declared.
+///
+/// ```
+/// use sha2::{Digest, Sha256};
+///
+/// let msg = clienthello::Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+/// let hash = clienthello::transcript_hash::<Sha256>(&msg).unwrap();
+/// assert_eq!(hash, Sha256::digest(&msg));
+/// ```
+pub fn transcript_hash<D: Digest>(data: &[u8]) -> Result<Output<D>, Error> {
+	Ok(D::digest(handshake_message(data)?))
+}
+
+/// Hash the ClientHello carried in a TLS record, as
+/// [`transcript_hash`] does; the record header is not included.
+///
+/// # Errors
+///
+/// Returns an error when the record layer is invalid or the payload is
+/// not a complete ClientHello.
+pub fn transcript_hash_from_record<D: Digest>(data: &[u8]) -> Result<Output<D>, Error> {
+	transcript_hash::<D>(framing::record_payload(data)?)
+}
+
+/// Hash a raw ClientHello handshake message up to its PSK binders,
+/// `Transcript-Hash(Truncate(ClientHello1))` of RFC 8446 §4.2.11.2.
+///
+/// This is the hash a PSK binder is an HMAC over, so a server-side tool
+/// can recompute binders for a known PSK. Returns `None` when the hello
+/// has no `pre_shared_key` extension.
+///
+/// # Errors
+///
+/// Returns an error when the message is not a complete ClientHello or
+/// its extensions up to and including `pre_shared_key` are truncated.
+///
+/// ```
+/// use sha2::{Digest, Sha256};
+///
+/// let mut partial = Vec::new();
+/// let msg = clienthello::Builder::new()
+///     .cipher_suites(&[0x1301])
+///     .pre_shared_key(&[(b"ticket", 0)], 32)
+///     .build_with_binders(|_, head, _| partial = head.to_vec())
+///     .unwrap();
+/// let hash = clienthello::binder_transcript_hash::<Sha256>(&msg).unwrap();
+/// assert_eq!(hash, Some(Sha256::digest(&partial)));
+/// ```
+pub fn binder_transcript_hash<D: Digest>(data: &[u8]) -> Result<Option<Output<D>>, Error> {
+	let msg = handshake_message(data)?;
+	let Some(offset) = binders_offset(&msg[4..])? else {
+		return Ok(None);
+	};
+	Ok(Some(D::digest(&msg[..4 + offset])))
+}
+
+/// Hash the ClientHello carried in a TLS record up to its PSK binders,
+/// as [`binder_transcript_hash`] does; the record header is not
+/// included.
+///
+/// # Errors
+///
+/// Returns an error when the record layer is invalid or the payload is
+/// not a ClientHello whose extensions can be walked.
+pub fn binder_transcript_hash_from_record<D: Digest>(
+	data: &[u8],
+) -> Result<Option<Output<D>>, Error> {
+	binder_transcript_hash::<D>(framing::record_payload(data)?)
+}
+
+/// Return the handshake header and body, without any trailing bytes.
+fn handshake_message(data: &[u8]) -> Result<&[u8], Error> {
+	let body = framing::client_hello_body(data)?;
+	Ok(&data[..4 + body.len()])
+}
+
+/// Find where the PSK binders list starts within a ClientHello body.
+fn binders_offset(body: &[u8]) -> Result<Option<usize>, Error> {
+	let mut r = Reader::new(body);
+	r.read_bytes(2 + 32, "client random")?;
+	r.read_vec_u8("session ID")?;
+	r.read_vec_u16("cipher suites data")?;
+	r.read_vec_u8("compression methods")?;
+	if r.is_empty() {
+		return Ok(None);
+	}
+	let base = r.position() + 2;
+	let mut exts = Reader::new(r.read_vec_u16("extensions data")?);
+	while !exts.is_empty() {
+		let type_id = exts.read_u16("extension type")?;
+		let data = exts.read_vec_u16("extension body")?;
+		if type_id == EXT_PRE_SHARED_KEY {
+			let identities = Reader::new(data).read_vec_u16("PSK identities")?;
+			// The binders follow the identities and their length prefix.
+			let start = base + exts.position() - data.len();
+			return Ok(Some(start + 2 + identities.len()));
+		}
+	}
+	Ok(None)
+}
@@ -0,0 +1,162 @@
+/* src/tunnel.rs */
+
+//! Locate ClientHellos tunneled inside other traffic.
+//!
+//! Decrypted proxy logs often hold a hello one or more layers deep: the
+//! payload of an HTTP `CONNECT` request, or the application data of an
+//! outer TLS session that was already decrypted, as with a client
+//! talking TLS to an HTTPS proxy. [`find_inner_hello`] peels those
+//! layers off and returns the innermost ClientHello record.
+//!
+//! ```
+//! use clienthello::Builder;
+//! use clienthello::tunnel::{Tunnel, find_inner_hello};
+//!
+//! let record = Builder::new()
+//!     .server_name("inner.example")
+//!     .build_record()
+//!     .unwrap();
+//! let mut log = b"CONNECT inner.example:443 HTTP/1.1\r\nHost: inner.example:443\r\n\r\n".to_vec();
+//! log.extend_from_slice(&record);
+//!
+//! let inner = find_inner_hello(&log).unwrap();
+//! assert_eq!(inner.layers, [Tunnel::HttpConnect]);
+//! assert_eq!(inner.parse().unwrap().server_name(), Some("inner.example"));
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::consts::{CONTENT_TYPE_HANDSHAKE, HANDSHAKE_TYPE_CLIENT_HELLO, RECORD_HEADER_LEN};
+use crate::tls_reader::Reader;
+use crate::{ClientHello, ContentType, Error};
+
+/// Layers peeled off before giving up, so hostile input cannot nest
+/// indefinitely.
+const MAX_LAYERS: usize = 4;
+
+/// A layer wrapped around a tunneled ClientHello.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Tunnel {
+	/// The payload following an HTTP/1.x `CONNECT` request, and the
+	/// proxy's response if the log interleaves it.
+	HttpConnect,
+	/// The application data records of a decrypted outer TLS session.
+	TlsApplicationData,
+}
+
+/// A ClientHello found inside one or more tunnel layers, returned by
+/// [`find_inner_hello`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InnerHello<'a> {
+	/// Layers peeled off, outermost first; never empty.
+	pub layers: Vec<Tunnel>,
+	/// The inner stream from the ClientHello record header on.
+	///
+	/// Borrowed from the input unless an outer TLS layer had to be
+	/// reassembled from several records.
+	pub record: Cow<'a, [u8]>,
+}
+
+impl InnerHello<'_> {
+	/// Parse the inner ClientHello record.
+	///
+	/// # Errors
+	///
+	/// Returns the errors of [`parse_from_record`](crate::parse_from_record).
+	pub fn parse(&self) -> Result<ClientHello<'_>, Error> {
+		crate::parse_from_record(&self.record)
+	}
+}
+
+/// Find a ClientHello tunneled inside `data`.
+///
+/// `data` is the client-to-server side of a connection. An HTTP
+/// `CONNECT` request is skipped up to the end of its headers, along
+/// with a following proxy response. A sequence of TLS records with
+/// application data is treated as a decrypted outer session: the
+/// application data payloads are joined, in order, and the others
+/// dropped. Payloads must be the plaintext application data, without a
+/// TLS 1.3 inner content type; a truncated last record contributes what
+/// was captured. Layers repeat, up to four deep, until a ClientHello
+/// record remains.
+///
+/// Returns `None` when no layer is found, so a plain ClientHello record
+/// is not reported, or when the innermost payload does not start with a
+/// ClientHello record.
+#[must_use]
+pub fn find_inner_hello(data: &[u8]) -> Option<InnerHello<'_>> {
+	let mut layers = Vec::new();
+	let mut stream = Cow::Borrowed(data);
+	while layers.len() < MAX_LAYERS {
+		if let Some(start) = connect_payload(&stream) {
+			layers.push(Tunnel::HttpConnect);
+			stream = match stream {
+				Cow::Borrowed(s) => Cow::Borrowed(s.get(start..)?),
+				Cow::Owned(mut s) => {
+					s.drain(..start);
+					Cow::Owned(s)
+				}
+			};
+		} else if let Some(inner) = application_data(&stream) {
+			layers.push(Tunnel::TlsApplicationData);
+			stream = Cow::Owned(inner);
+		} else {
+			break;
+		}
+	}
+	let is_hello = stream.starts_with(&[CONTENT_TYPE_HANDSHAKE, 0x03])
+		&& stream.get(RECORD_HEADER_LEN) == Some(&HANDSHAKE_TYPE_CLIENT_HELLO);
+	(!layers.is_empty() && is_hello).then_some(InnerHello {
+		layers,
+		record: stream,
+	})
+}
+
+/// Offset of the tunneled payload when `data` starts with an HTTP
+/// `CONNECT` request.
+fn connect_payload(data: &[u8]) -> Option<usize> {
+	if !data.starts_with(b"CONNECT ") {
+		return None;
+	}
+	let mut start = header_end(data)?;
+	let rest = data.get(start..)?;
+	if rest.starts_with(b"HTTP/1.") {
+		start += header_end(rest)?;
+	}
+	Some(start)
+}
+
+/// Offset just past the blank line ending an HTTP header block.
+fn header_end(data: &[u8]) -> Option<usize> {
+	data
+		.windows(4)
+		.position(|w| w == b"\r\n\r\n")
+		.map(|i| i + 4)
+}
+
+/// Join the application data payloads when `data` is a sequence of TLS
+/// records holding at least one.
+fn application_data(data: &[u8]) -> Option<Vec<u8>> {
+	let mut r = Reader::new(data);
+	let mut payload = Vec::new();
+	let mut found = false;
+	while r.remaining() >= RECORD_HEADER_LEN {
+		let content_type = ContentType::from_u8(r.read_u8("record content type").ok()?);
+		let [major, minor] = r.read_u16("record version").ok()?.to_be_bytes();
+		let len = r.read_u16("record length").ok()? as usize;
+		let known = !matches!(content_type, ContentType::Unknown(_));
+		if !known || major != 0x03 || minor > 0x04 {
+			break;
+		}
+		let body = r
+			.read_bytes(len.min(r.remaining()), "record payload")
+			.ok()?;
+		if content_type == ContentType::ApplicationData {
+			payload.extend_from_slice(body);
+			found = true;
+		}
+	}
+	found.then_some(payload)
+}
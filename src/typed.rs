@@ -0,0 +1,115 @@
+/* src/typed.rs */
+
+//! Strongly-typed extension lookup for [`ClientHello::get`].
+//!
+//! Each marker type names one extension kind and, through
+//! [`FromExtension`], the data it yields. Markers exist for every
+//! structured [`Extension`] variant whose `ext-*` feature is enabled.
+//! Implement [`FromExtension`] on your own marker to look up extensions
+//! this crate keeps as [`Extension::Unknown`].
+//!
+//! ```
+//! # fn build() -> Vec<u8> {
+//! #     let mut d = vec![0x01, 0x00, 0x00, 0x3b, 0x03, 0x03];
+//! #     d.extend_from_slice(&[0u8; 32]);
+//! #     d.extend_from_slice(&[0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00]);
+//! #     d.extend_from_slice(&[0x00, 0x10, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x0a, 0x00]);
+//! #     d.extend_from_slice(&[0x00, 0x07]);
+//! #     d.extend_from_slice(b"example");
+//! #     d
+//! # }
+//! # let msg = build();
+//! use clienthello::typed::{Alpn, ServerNames};
+//!
+//! let hello = clienthello::parse(&msg).unwrap();
+//! let names = hello.get::<ServerNames>().unwrap();
+//! assert_eq!(names[0].name, b"example");
+//! assert!(hello.get::<Alpn>().is_none());
+//! ```
+//!
+//! [`ClientHello::get`]: crate::ClientHello::get
+
+#[cfg(feature = "ext-key-share")]
+use crate::KeyShareEntry;
+#[cfg(feature = "ext-sni")]
+use crate::ServerName;
+#[cfg(feature = "ext-ech")]
+use crate::ech::EchClientHello;
+use crate::extension::Extension;
+
+/// Extract typed data from one extension.
+///
+/// [`ClientHello::get`](crate::ClientHello::get) calls
+/// [`from_extension`](FromExtension::from_extension) on each extension
+/// in wire order and returns the first `Some`.
+pub trait FromExtension {
+	/// Data borrowed from the extension, which may in turn borrow from
+	/// the parsed input.
+	type Output<'h, 'a: 'h>;
+
+	/// Return the data if `ext` is the extension this marker names.
+	fn from_extension<'h, 'a>(ext: &'h Extension<'a>) -> Option<Self::Output<'h, 'a>>;
+}
+
+/// Generate a marker for a structured extension variant.
+macro_rules! marker {
+	($(#[$meta:meta])* $feature:literal, $name:ident, $pat:pat => $get:expr, $out:ty) => {
+		$(#[$meta])*
+		#[cfg(feature = $feature)]
+		#[derive(Debug, Clone, Copy)]
+		pub enum $name {}
+
+		#[cfg(feature = $feature)]
+		impl FromExtension for $name {
+			type Output<'h, 'a: 'h> = $out;
+
+			fn from_extension<'h, 'a>(ext: &'h Extension<'a>) -> Option<Self::Output<'h, 'a>> {
+				match ext {
+					$pat => Some($get),
+					_ => None,
+				}
+			}
+		}
+	};
+}
+
+marker!(
+	/// Server Name Indication entries (type `0x0000`).
+	"ext-sni", ServerNames, Extension::ServerName(v) => v.as_slice(), &'h [ServerName<'a>]
+);
+marker!(
+	/// ALPN protocol identifiers (type `0x0010`).
+	"ext-alpn", Alpn, Extension::Alpn(v) => v.as_slice(), &'h [&'a [u8]]
+);
+marker!(
+	/// Supported Versions, GREASE excluded (type `0x002b`).
+	"ext-supported-versions", SupportedVersions, Extension::SupportedVersions(v) => v.as_slice(), &'h [u16]
+);
+marker!(
+	/// Supported Groups, GREASE excluded (type `0x000a`).
+	"ext-supported-groups", SupportedGroups, Extension::SupportedGroups(v) => v.as_slice(), &'h [u16]
+);
+marker!(
+	/// Signature Algorithms, GREASE excluded (type `0x000d`).
+	"ext-signature-algorithms", SignatureAlgorithms, Extension::SignatureAlgorithms(v) => v.as_slice(), &'h [u16]
+);
+marker!(
+	/// Key Share entry groups, GREASE excluded (type `0x0033`).
+	"ext-key-share", KeyShareGroups, Extension::KeyShare { groups: v, .. } => v.as_slice(), &'h [u16]
+);
+marker!(
+	/// Key Share entries with their keys, GREASE included (type `0x0033`).
+	"ext-key-share", KeyShareEntries, Extension::KeyShare { entries: v, .. } => v.as_slice(), &'h [KeyShareEntry<'a>]
+);
+marker!(
+	/// PSK Key Exchange Modes bytes (type `0x002d`).
+	"ext-psk-modes", PskExchangeModes, Extension::PskExchangeModes(v) => *v, &'a [u8]
+);
+marker!(
+	/// Renegotiation Info bytes (type `0xff01`).
+	"ext-renegotiation-info", RenegotiationInfo, Extension::RenegotiationInfo(v) => *v, &'a [u8]
+);
+marker!(
+	/// Encrypted Client Hello body (type `0xfe0d`).
+	"ext-ech", EncryptedClientHello, Extension::EncryptedClientHello { ech, .. } => ech, &'h EchClientHello<'a>
+);
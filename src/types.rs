@@ -0,0 +1,474 @@
+/* src/types.rs */
+
+use core::fmt;
+
+/// TLS record content type (RFC 8446 §5.1).
+///
+/// Build values with [`from_u8`](Self::from_u8) rather than
+/// [`Unknown`](Self::Unknown): `ContentType::Unknown(0x16)` does not
+/// compare equal to [`Handshake`](Self::Handshake). New named variants
+/// may be added as content types are assigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ContentType {
+	/// `change_cipher_spec` (`0x14`).
+	ChangeCipherSpec,
+	/// `alert` (`0x15`).
+	Alert,
+	/// `handshake` (`0x16`).
+	Handshake,
+	/// `application_data` (`0x17`).
+	ApplicationData,
+	/// `heartbeat` (`0x18`, RFC 6520).
+	Heartbeat,
+	/// Any other content type byte.
+	Unknown(u8),
+}
+
+impl ContentType {
+	/// Convert a raw content type byte.
+	#[must_use]
+	pub const fn from_u8(value: u8) -> Self {
+		match value {
+			0x14 => Self::ChangeCipherSpec,
+			0x15 => Self::Alert,
+			0x16 => Self::Handshake,
+			0x17 => Self::ApplicationData,
+			0x18 => Self::Heartbeat,
+			other => Self::Unknown(other),
+		}
+	}
+
+	/// Return the raw content type byte.
+	#[must_use]
+	pub const fn to_u8(self) -> u8 {
+		match self {
+			Self::ChangeCipherSpec => 0x14,
+			Self::Alert => 0x15,
+			Self::Handshake => 0x16,
+			Self::ApplicationData => 0x17,
+			Self::Heartbeat => 0x18,
+			Self::Unknown(other) => other,
+		}
+	}
+
+	const fn name(self) -> Option<&'static str> {
+		match self {
+			Self::ChangeCipherSpec => Some("ChangeCipherSpec"),
+			Self::Alert => Some("Alert"),
+			Self::Handshake => Some("Handshake"),
+			Self::ApplicationData => Some("ApplicationData"),
+			Self::Heartbeat => Some("Heartbeat"),
+			Self::Unknown(_) => None,
+		}
+	}
+}
+
+impl From<u8> for ContentType {
+	fn from(value: u8) -> Self {
+		Self::from_u8(value)
+	}
+}
+
+impl From<ContentType> for u8 {
+	fn from(value: ContentType) -> Self {
+		value.to_u8()
+	}
+}
+
+impl fmt::Display for ContentType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write_named(f, self.name(), format_args!("{:#04x}", self.to_u8()))
+	}
+}
+
+/// TLS handshake message type (RFC 8446 §4, RFC 5246 §7.4).
+///
+/// As with [`ContentType`], build values with
+/// [`from_u8`](Self::from_u8): `HandshakeType::Unknown(0x01)` does not
+/// compare equal to [`ClientHello`](Self::ClientHello). New named
+/// variants may be added as handshake types are assigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HandshakeType {
+	/// `hello_request` (`0x00`, TLS 1.2 and earlier).
+	HelloRequest,
+	/// `client_hello` (`0x01`).
+	ClientHello,
+	/// `server_hello` (`0x02`).
+	ServerHello,
+	/// `hello_verify_request` (`0x03`, DTLS).
+	HelloVerifyRequest,
+	/// `new_session_ticket` (`0x04`).
+	NewSessionTicket,
+	/// `end_of_early_data` (`0x05`).
+	EndOfEarlyData,
+	/// `encrypted_extensions` (`0x08`).
+	EncryptedExtensions,
+	/// `certificate` (`0x0b`).
+	Certificate,
+	/// `server_key_exchange` (`0x0c`, TLS 1.2 and earlier).
+	ServerKeyExchange,
+	/// `certificate_request` (`0x0d`).
+	CertificateRequest,
+	/// `server_hello_done` (`0x0e`, TLS 1.2 and earlier).
+	ServerHelloDone,
+	/// `certificate_verify` (`0x0f`).
+	CertificateVerify,
+	/// `client_key_exchange` (`0x10`, TLS 1.2 and earlier).
+	ClientKeyExchange,
+	/// `finished` (`0x14`).
+	Finished,
+	/// `certificate_status` (`0x16`, RFC 6066).
+	CertificateStatus,
+	/// `key_update` (`0x18`).
+	KeyUpdate,
+	/// `message_hash` (`0xfe`), used in the TLS 1.3 transcript.
+	MessageHash,
+	/// Any other handshake type byte.
+	Unknown(u8),
+}
+
+impl HandshakeType {
+	/// Convert a raw handshake type byte.
+	#[must_use]
+	pub const fn from_u8(value: u8) -> Self {
+		match value {
+			0x00 => Self::HelloRequest,
+			0x01 => Self::ClientHello,
+			0x02 => Self::ServerHello,
+			0x03 => Self::HelloVerifyRequest,
+			0x04 => Self::NewSessionTicket,
+			0x05 => Self::EndOfEarlyData,
+			0x08 => Self::EncryptedExtensions,
+			0x0b => Self::Certificate,
+			0x0c => Self::ServerKeyExchange,
+			0x0d => Self::CertificateRequest,
+			0x0e => Self::ServerHelloDone,
+			0x0f => Self::CertificateVerify,
+			0x10 => Self::ClientKeyExchange,
+			0x14 => Self::Finished,
+			0x16 => Self::CertificateStatus,
+			0x18 => Self::KeyUpdate,
+			0xfe => Self::MessageHash,
+			other => Self::Unknown(other),
+		}
+	}
+
+	/// Return the raw handshake type byte.
+	#[must_use]
+	pub const fn to_u8(self) -> u8 {
+		match self {
+			Self::HelloRequest => 0x00,
+			Self::ClientHello => 0x01,
+			Self::ServerHello => 0x02,
+			Self::HelloVerifyRequest => 0x03,
+			Self::NewSessionTicket => 0x04,
+			Self::EndOfEarlyData => 0x05,
+			Self::EncryptedExtensions => 0x08,
+			Self::Certificate => 0x0b,
+			Self::ServerKeyExchange => 0x0c,
+			Self::CertificateRequest => 0x0d,
+			Self::ServerHelloDone => 0x0e,
+			Self::CertificateVerify => 0x0f,
+			Self::ClientKeyExchange => 0x10,
+			Self::Finished => 0x14,
+			Self::CertificateStatus => 0x16,
+			Self::KeyUpdate => 0x18,
+			Self::MessageHash => 0xfe,
+			Self::Unknown(other) => other,
+		}
+	}
+
+	const fn name(self) -> Option<&'static str> {
+		match self {
+			Self::HelloRequest => Some("HelloRequest"),
+			Self::ClientHello => Some("ClientHello"),
+			Self::ServerHello => Some("ServerHello"),
+			Self::HelloVerifyRequest => Some("HelloVerifyRequest"),
+			Self::NewSessionTicket => Some("NewSessionTicket"),
+			Self::EndOfEarlyData => Some("EndOfEarlyData"),
+			Self::EncryptedExtensions => Some("EncryptedExtensions"),
+			Self::Certificate => Some("Certificate"),
+			Self::ServerKeyExchange => Some("ServerKeyExchange"),
+			Self::CertificateRequest => Some("CertificateRequest"),
+			Self::ServerHelloDone => Some("ServerHelloDone"),
+			Self::CertificateVerify => Some("CertificateVerify"),
+			Self::ClientKeyExchange => Some("ClientKeyExchange"),
+			Self::Finished => Some("Finished"),
+			Self::CertificateStatus => Some("CertificateStatus"),
+			Self::KeyUpdate => Some("KeyUpdate"),
+			Self::MessageHash => Some("MessageHash"),
+			Self::Unknown(_) => None,
+		}
+	}
+}
+
+impl From<u8> for HandshakeType {
+	fn from(value: u8) -> Self {
+		Self::from_u8(value)
+	}
+}
+
+impl From<HandshakeType> for u8 {
+	fn from(value: HandshakeType) -> Self {
+		value.to_u8()
+	}
+}
+
+impl fmt::Display for HandshakeType {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write_named(f, self.name(), format_args!("{:#04x}", self.to_u8()))
+	}
+}
+
+/// TLS signature scheme (RFC 8446 §4.2.3), as offered in the
+/// `signature_algorithms` extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureScheme {
+	/// `rsa_pkcs1_sha1` (`0x0201`, legacy).
+	RsaPkcs1Sha1,
+	/// `ecdsa_sha1` (`0x0203`, legacy).
+	EcdsaSha1,
+	/// `rsa_pkcs1_sha256` (`0x0401`).
+	RsaPkcs1Sha256,
+	/// `ecdsa_secp256r1_sha256` (`0x0403`).
+	EcdsaSecp256r1Sha256,
+	/// `rsa_pkcs1_sha384` (`0x0501`).
+	RsaPkcs1Sha384,
+	/// `ecdsa_secp384r1_sha384` (`0x0503`).
+	EcdsaSecp384r1Sha384,
+	/// `rsa_pkcs1_sha512` (`0x0601`).
+	RsaPkcs1Sha512,
+	/// `ecdsa_secp521r1_sha512` (`0x0603`).
+	EcdsaSecp521r1Sha512,
+	/// `rsa_pss_rsae_sha256` (`0x0804`).
+	RsaPssRsaeSha256,
+	/// `rsa_pss_rsae_sha384` (`0x0805`).
+	RsaPssRsaeSha384,
+	/// `rsa_pss_rsae_sha512` (`0x0806`).
+	RsaPssRsaeSha512,
+	/// `ed25519` (`0x0807`).
+	Ed25519,
+	/// `ed448` (`0x0808`).
+	Ed448,
+	/// `rsa_pss_pss_sha256` (`0x0809`).
+	RsaPssPssSha256,
+	/// `rsa_pss_pss_sha384` (`0x080a`).
+	RsaPssPssSha384,
+	/// `rsa_pss_pss_sha512` (`0x080b`).
+	RsaPssPssSha512,
+	/// Any other signature scheme code point.
+	Unknown(u16),
+}
+
+impl SignatureScheme {
+	/// Convert a raw signature scheme code point.
+	#[must_use]
+	pub const fn from_u16(value: u16) -> Self {
+		match value {
+			0x0201 => Self::RsaPkcs1Sha1,
+			0x0203 => Self::EcdsaSha1,
+			0x0401 => Self::RsaPkcs1Sha256,
+			0x0403 => Self::EcdsaSecp256r1Sha256,
+			0x0501 => Self::RsaPkcs1Sha384,
+			0x0503 => Self::EcdsaSecp384r1Sha384,
+			0x0601 => Self::RsaPkcs1Sha512,
+			0x0603 => Self::EcdsaSecp521r1Sha512,
+			0x0804 => Self::RsaPssRsaeSha256,
+			0x0805 => Self::RsaPssRsaeSha384,
+			0x0806 => Self::RsaPssRsaeSha512,
+			0x0807 => Self::Ed25519,
+			0x0808 => Self::Ed448,
+			0x0809 => Self::RsaPssPssSha256,
+			0x080a => Self::RsaPssPssSha384,
+			0x080b => Self::RsaPssPssSha512,
+			other => Self::Unknown(other),
+		}
+	}
+
+	/// Return the raw signature scheme code point.
+	#[must_use]
+	pub const fn to_u16(self) -> u16 {
+		match self {
+			Self::RsaPkcs1Sha1 => 0x0201,
+			Self::EcdsaSha1 => 0x0203,
+			Self::RsaPkcs1Sha256 => 0x0401,
+			Self::EcdsaSecp256r1Sha256 => 0x0403,
+			Self::RsaPkcs1Sha384 => 0x0501,
+			Self::EcdsaSecp384r1Sha384 => 0x0503,
+			Self::RsaPkcs1Sha512 => 0x0601,
+			Self::EcdsaSecp521r1Sha512 => 0x0603,
+			Self::RsaPssRsaeSha256 => 0x0804,
+			Self::RsaPssRsaeSha384 => 0x0805,
+			Self::RsaPssRsaeSha512 => 0x0806,
+			Self::Ed25519 => 0x0807,
+			Self::Ed448 => 0x0808,
+			Self::RsaPssPssSha256 => 0x0809,
+			Self::RsaPssPssSha384 => 0x080a,
+			Self::RsaPssPssSha512 => 0x080b,
+			Self::Unknown(other) => other,
+		}
+	}
+
+	/// Check whether this is an RSASSA-PSS scheme.
+	#[must_use]
+	pub const fn is_pss(self) -> bool {
+		matches!(self.to_u16(), 0x0804..=0x0806 | 0x0809..=0x080b)
+	}
+
+	/// Return the hash the scheme signs with.
+	///
+	/// Also covers the TLS 1.2 hash/signature pairs (RFC 5246 §7.4.1.4.1)
+	/// that have no TLS 1.3 name, such as `0x0101` (MD5 with RSA).
+	/// Returns `None` for EdDSA, which hashes internally, and for unknown
+	/// code points.
+	#[must_use]
+	pub const fn hash(self) -> Option<HashAlgorithm> {
+		let value = self.to_u16();
+		match value {
+			0x0804 | 0x0809 => Some(HashAlgorithm::Sha256),
+			0x0805 | 0x080a => Some(HashAlgorithm::Sha384),
+			0x0806 | 0x080b => Some(HashAlgorithm::Sha512),
+			_ if matches!(value & 0xff, 0x01..=0x03) => HashAlgorithm::from_u8((value >> 8) as u8),
+			_ => None,
+		}
+	}
+
+	const fn name(self) -> Option<&'static str> {
+		match self {
+			Self::RsaPkcs1Sha1 => Some("RsaPkcs1Sha1"),
+			Self::EcdsaSha1 => Some("EcdsaSha1"),
+			Self::RsaPkcs1Sha256 => Some("RsaPkcs1Sha256"),
+			Self::EcdsaSecp256r1Sha256 => Some("EcdsaSecp256r1Sha256"),
+			Self::RsaPkcs1Sha384 => Some("RsaPkcs1Sha384"),
+			Self::EcdsaSecp384r1Sha384 => Some("EcdsaSecp384r1Sha384"),
+			Self::RsaPkcs1Sha512 => Some("RsaPkcs1Sha512"),
+			Self::EcdsaSecp521r1Sha512 => Some("EcdsaSecp521r1Sha512"),
+			Self::RsaPssRsaeSha256 => Some("RsaPssRsaeSha256"),
+			Self::RsaPssRsaeSha384 => Some("RsaPssRsaeSha384"),
+			Self::RsaPssRsaeSha512 => Some("RsaPssRsaeSha512"),
+			Self::Ed25519 => Some("Ed25519"),
+			Self::Ed448 => Some("Ed448"),
+			Self::RsaPssPssSha256 => Some("RsaPssPssSha256"),
+			Self::RsaPssPssSha384 => Some("RsaPssPssSha384"),
+			Self::RsaPssPssSha512 => Some("RsaPssPssSha512"),
+			Self::Unknown(_) => None,
+		}
+	}
+}
+
+impl From<u16> for SignatureScheme {
+	fn from(value: u16) -> Self {
+		Self::from_u16(value)
+	}
+}
+
+impl From<SignatureScheme> for u16 {
+	fn from(value: SignatureScheme) -> Self {
+		value.to_u16()
+	}
+}
+
+impl fmt::Display for SignatureScheme {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write_named(f, self.name(), format_args!("{:#06x}", self.to_u16()))
+	}
+}
+
+/// Hash algorithm used by a [`SignatureScheme`], ordered from weakest to
+/// strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HashAlgorithm {
+	/// MD5 (`1` in the TLS 1.2 hash registry).
+	Md5,
+	/// SHA-1 (`2`).
+	Sha1,
+	/// SHA-224 (`3`).
+	Sha224,
+	/// SHA-256 (`4`).
+	Sha256,
+	/// SHA-384 (`5`).
+	Sha384,
+	/// SHA-512 (`6`).
+	Sha512,
+}
+
+impl HashAlgorithm {
+	/// Convert a TLS 1.2 `HashAlgorithm` registry value.
+	#[must_use]
+	pub const fn from_u8(value: u8) -> Option<Self> {
+		match value {
+			1 => Some(Self::Md5),
+			2 => Some(Self::Sha1),
+			3 => Some(Self::Sha224),
+			4 => Some(Self::Sha256),
+			5 => Some(Self::Sha384),
+			6 => Some(Self::Sha512),
+			_ => None,
+		}
+	}
+
+	/// Check whether the hash is broken for signatures (MD5 and SHA-1).
+	#[must_use]
+	pub const fn is_weak(self) -> bool {
+		matches!(self, Self::Md5 | Self::Sha1)
+	}
+}
+
+fn write_named(
+	f: &mut fmt::Formatter<'_>,
+	name: Option<&str>,
+	value: impl fmt::Display,
+) -> fmt::Result {
+	match name {
+		Some(name) => write!(f, "{name} ({value})"),
+		None => write!(f, "unknown ({value})"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn content_type_round_trip() {
+		for b in 0..=u8::MAX {
+			assert_eq!(ContentType::from_u8(b).to_u8(), b);
+		}
+	}
+
+	#[test]
+	fn handshake_type_round_trip() {
+		for b in 0..=u8::MAX {
+			assert_eq!(HandshakeType::from_u8(b).to_u8(), b);
+		}
+	}
+
+	#[test]
+	fn signature_scheme_round_trip() {
+		for v in 0..=u16::MAX {
+			assert_eq!(SignatureScheme::from_u16(v).to_u16(), v);
+		}
+	}
+
+	#[test]
+	fn signature_scheme_hash() {
+		assert_eq!(
+			SignatureScheme::RsaPkcs1Sha1.hash(),
+			Some(HashAlgorithm::Sha1)
+		);
+		assert_eq!(
+			SignatureScheme::from_u16(0x0101).hash(),
+			Some(HashAlgorithm::Md5)
+		);
+		assert_eq!(
+			SignatureScheme::RsaPssPssSha384.hash(),
+			Some(HashAlgorithm::Sha384)
+		);
+		assert_eq!(SignatureScheme::Ed25519.hash(), None);
+		assert_eq!(SignatureScheme::from_u16(0x0704).hash(), None);
+		assert!(SignatureScheme::RsaPssRsaeSha256.is_pss());
+		assert!(!SignatureScheme::Ed448.is_pss());
+	}
+}
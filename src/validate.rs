@@ -0,0 +1,269 @@
+/* src/validate.rs */
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::consts::{
+	EXT_EARLY_DATA, EXT_KEY_SHARE, EXT_PADDING, EXT_PRE_SHARED_KEY, EXT_PSK_KEY_EXCHANGE_MODES,
+	EXT_SIGNATURE_ALGORITHMS, EXT_SUPPORTED_GROUPS, MAX_SESSION_ID_LEN,
+};
+use crate::{ClientHello, Extension, Warning};
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+	/// Allowed by the specifications but unusual enough to point at a
+	/// broken or hand-built client.
+	Lint,
+	/// Breaks a MUST of RFC 8446, RFC 5246, or an extension's RFC.
+	Violation,
+}
+
+/// One problem found by [`ClientHello::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValidationIssue {
+	/// Something the parser tolerated; see [`Warning`].
+	Parse(Warning),
+	/// The cipher suite list is empty, or holds only GREASE values.
+	NoCipherSuites,
+	/// The session ID is longer than 32 bytes.
+	SessionIdTooLong(usize),
+	/// The compression methods omit `null`, or a TLS 1.3 offer lists
+	/// anything besides it (RFC 8446 §4.1.2).
+	CompressionMethods,
+	/// A TLS 1.3 offer with a `legacy_version` other than 0x0303.
+	LegacyVersion(u16),
+	/// An extension type appears more than once (RFC 8446 §4.2).
+	DuplicateExtension(u16),
+	/// `pre_shared_key` is not the last extension (RFC 8446 §4.2.11).
+	PreSharedKeyNotLast,
+	/// `pre_shared_key` without `psk_key_exchange_modes` (RFC 8446
+	/// §4.2.9).
+	PskWithoutKeyExchangeModes,
+	/// `early_data` without `pre_shared_key` (RFC 8446 §4.2.10).
+	EarlyDataWithoutPsk,
+	/// A TLS 1.3 offer without a PSK lacks this extension (RFC 8446
+	/// §9.2).
+	MissingTls13Extension(u16),
+	/// A key share for a group missing from Supported Groups (RFC 8446
+	/// §4.2.8).
+	KeyShareGroupNotOffered(u16),
+	/// Two key shares for the same group (RFC 8446 §4.2.8).
+	DuplicateKeyShareGroup(u16),
+	/// Key shares in a different order than their groups in Supported
+	/// Groups (RFC 8446 §4.2.8).
+	KeyShareOrder,
+	/// The SNI host name is a literal IP address (RFC 6066 §3).
+	ServerNameIpLiteral,
+	/// The SNI host name ends in a dot (RFC 6066 §3).
+	ServerNameTrailingDot,
+	/// The renegotiation SCSV and `renegotiation_info` disagree; see
+	/// [`Renegotiation::is_consistent`](crate::Renegotiation::is_consistent).
+	InconsistentRenegotiation,
+	/// The padding extension holds non-zero bytes (RFC 7685 §3), a sign
+	/// of a covert channel or a broken client.
+	NonZeroPadding,
+}
+
+impl ValidationIssue {
+	/// Return how serious the issue is.
+	#[must_use]
+	pub fn severity(&self) -> Severity {
+		match self {
+			Self::Parse(
+				Warning::RepeatedGreaseCipherSuite { .. }
+				| Warning::RepeatedGreaseExtension { .. }
+				| Warning::HandshakeLengthMismatch { .. },
+			)
+			| Self::KeyShareOrder
+			| Self::InconsistentRenegotiation => Severity::Lint,
+			_ => Severity::Violation,
+		}
+	}
+}
+
+impl fmt::Display for ValidationIssue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Parse(w) => write!(f, "tolerated while parsing: {w:?}"),
+			Self::NoCipherSuites => f.write_str("no cipher suites"),
+			Self::SessionIdTooLong(len) => write!(f, "session ID of {len} bytes"),
+			Self::CompressionMethods => f.write_str("invalid compression methods"),
+			Self::LegacyVersion(v) => write!(f, "TLS 1.3 offered with legacy version {v:#06x}"),
+			Self::DuplicateExtension(t) => write!(f, "duplicate extension {t:#06x}"),
+			Self::PreSharedKeyNotLast => f.write_str("pre_shared_key is not the last extension"),
+			Self::PskWithoutKeyExchangeModes => {
+				f.write_str("pre_shared_key without psk_key_exchange_modes")
+			}
+			Self::EarlyDataWithoutPsk => f.write_str("early_data without pre_shared_key"),
+			Self::MissingTls13Extension(t) => write!(f, "TLS 1.3 offered without extension {t:#06x}"),
+			Self::KeyShareGroupNotOffered(g) => {
+				write!(f, "key share for group {g:#06x} not in supported groups")
+			}
+			Self::DuplicateKeyShareGroup(g) => write!(f, "duplicate key share for group {g:#06x}"),
+			Self::KeyShareOrder => f.write_str("key shares not in supported groups order"),
+			Self::ServerNameIpLiteral => f.write_str("SNI host name is an IP address"),
+			Self::ServerNameTrailingDot => f.write_str("SNI host name ends in a dot"),
+			Self::InconsistentRenegotiation => f.write_str("inconsistent renegotiation signals"),
+			Self::NonZeroPadding => f.write_str("padding holds non-zero bytes"),
+		}
+	}
+}
+
+/// Result of [`ClientHello::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+	/// Issues found, parse warnings first, then in declaration order of
+	/// [`ValidationIssue`].
+	pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+	/// Check whether no [`Severity::Violation`] was found.
+	#[must_use]
+	pub fn is_valid(&self) -> bool {
+		self.violations().next().is_none()
+	}
+
+	/// Check whether nothing at all was found.
+	#[must_use]
+	pub fn is_clean(&self) -> bool {
+		self.issues.is_empty()
+	}
+
+	/// Iterate the issues of [`Severity::Violation`].
+	pub fn violations(&self) -> impl Iterator<Item = &ValidationIssue> {
+		self
+			.issues
+			.iter()
+			.filter(|i| i.severity() == Severity::Violation)
+	}
+
+	/// Iterate the issues of [`Severity::Lint`].
+	pub fn lints(&self) -> impl Iterator<Item = &ValidationIssue> {
+		self
+			.issues
+			.iter()
+			.filter(|i| i.severity() == Severity::Lint)
+	}
+}
+
+pub(crate) fn validate(hello: &ClientHello<'_>) -> ValidationReport {
+	let mut issues: Vec<ValidationIssue> = hello
+		.diagnostics
+		.warnings
+		.iter()
+		.copied()
+		.map(ValidationIssue::Parse)
+		.collect();
+	let has = |t| hello.extensions.contains(t);
+	let tls13 = hello.supported_versions().contains(&0x0304);
+
+	if hello.cipher_suites.is_empty() {
+		issues.push(ValidationIssue::NoCipherSuites);
+	}
+	if hello.session_id.len() > MAX_SESSION_ID_LEN {
+		issues.push(ValidationIssue::SessionIdTooLong(hello.session_id.len()));
+	}
+	let compression_ok = if tls13 {
+		hello.compression_methods == [0x00]
+	} else {
+		hello.compression_methods.contains(&0x00)
+	};
+	if !compression_ok {
+		issues.push(ValidationIssue::CompressionMethods);
+	}
+	if tls13 && hello.legacy_version != 0x0303 {
+		issues.push(ValidationIssue::LegacyVersion(hello.legacy_version));
+	}
+
+	let mut seen = Vec::new();
+	for t in hello.extensions.type_ids() {
+		if seen.contains(&t) && !issues.contains(&ValidationIssue::DuplicateExtension(t)) {
+			issues.push(ValidationIssue::DuplicateExtension(t));
+		}
+		seen.push(t);
+	}
+	let has_psk = has(EXT_PRE_SHARED_KEY);
+	if has_psk {
+		// GREASE positions leave reserved extensions out; reserved
+		// positions count everything.
+		let len = hello.extensions.len() + hello.grease.extensions.len();
+		let wire_len = len + hello.reserved.extensions.len();
+		let last = hello.extensions.last().map(crate::Extension::type_id);
+		if last != Some(EXT_PRE_SHARED_KEY)
+			|| hello.grease.extensions.contains(&(len - 1))
+			|| hello
+				.reserved
+				.extensions
+				.iter()
+				.any(|&(i, _)| i == wire_len - 1)
+		{
+			issues.push(ValidationIssue::PreSharedKeyNotLast);
+		}
+		if !has(EXT_PSK_KEY_EXCHANGE_MODES) {
+			issues.push(ValidationIssue::PskWithoutKeyExchangeModes);
+		}
+	}
+	if has(EXT_EARLY_DATA) && !has_psk {
+		issues.push(ValidationIssue::EarlyDataWithoutPsk);
+	}
+	if tls13 && !has_psk {
+		for t in [
+			EXT_SIGNATURE_ALGORITHMS,
+			EXT_SUPPORTED_GROUPS,
+			EXT_KEY_SHARE,
+		] {
+			if !has(t) {
+				issues.push(ValidationIssue::MissingTls13Extension(t));
+			}
+		}
+	}
+
+	check_key_shares(hello, &mut issues);
+	if let Some(name) = hello.server_name() {
+		if name.parse::<core::net::IpAddr>().is_ok() {
+			issues.push(ValidationIssue::ServerNameIpLiteral);
+		}
+		if name.ends_with('.') {
+			issues.push(ValidationIssue::ServerNameTrailingDot);
+		}
+	}
+	if !hello.renegotiation().is_consistent() {
+		issues.push(ValidationIssue::InconsistentRenegotiation);
+	}
+	let non_zero_padding = hello.extensions_of_type(EXT_PADDING).any(|ext| match ext {
+		Extension::Unknown { data: raw, .. } | Extension::Malformed { raw, .. } => {
+			raw.iter().any(|&b| b != 0)
+		}
+		_ => false,
+	});
+	if non_zero_padding {
+		issues.push(ValidationIssue::NonZeroPadding);
+	}
+	ValidationReport { issues }
+}
+
+fn check_key_shares(hello: &ClientHello<'_>, issues: &mut Vec<ValidationIssue>) {
+	let groups = hello.supported_groups();
+	let shares = hello.key_share_groups();
+	let mut last_index = None;
+	let mut out_of_order = false;
+	for (i, &g) in shares.iter().enumerate() {
+		if shares[..i].contains(&g) {
+			issues.push(ValidationIssue::DuplicateKeyShareGroup(g));
+			continue;
+		}
+		match groups.iter().position(|&o| o == g) {
+			None => issues.push(ValidationIssue::KeyShareGroupNotOffered(g)),
+			Some(index) => {
+				out_of_order |= last_index.is_some_and(|last| index < last);
+				last_index = Some(index);
+			}
+		}
+	}
+	if out_of_order {
+		issues.push(ValidationIssue::KeyShareOrder);
+	}
+}
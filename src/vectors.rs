@@ -0,0 +1,301 @@
+/* src/vectors.rs */
+
+//! Plain-text test vectors for sharing ClientHello regression cases.
+//!
+//! A vector file is an annotated hexdump that survives email, issue
+//! trackers, and code review. [`parse`] loads one at runtime and
+//! [`write()`] produces one, labelling each structural field so a reader
+//! can see where a hostile input goes wrong:
+//!
+//! ```text
+//! format: 1
+//!
+//! vector: no extensions
+//! expect: parses
+//! note: found by the fuzzer
+//! 01 00 00 29  # handshake header
+//! 03 03  # legacy version
+//! 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  # random
+//! 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+//! 00  # session ID
+//! 00 02 13 01  # cipher suites
+//! 01 00  # compression methods
+//! ```
+//!
+//! The rules:
+//!
+//! - `#` starts a comment on hex lines; lines that are blank after
+//!   removing comments are skipped.
+//! - `key: value` lines set the format version (`format`, optional, and
+//!   only before the first vector), start a vector (`vector`, followed
+//!   by its name), set its [`Expect`]ation (`expect`, `parses` by
+//!   default), or add a single-line note (`note`). Values are taken
+//!   verbatim, `#` included.
+//! - Any other line holds hex digits, whitespace ignored, appended to the
+//!   current vector. Bytes starting with `0x16` are a TLS record,
+//!   anything else a raw handshake message.
+//!
+//! The format is stable: files written by [`write()`] keep loading in
+//! later releases, and a change to the rules bumps [`FORMAT_VERSION`].
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+
+use crate::consts::CONTENT_TYPE_HANDSHAKE;
+use crate::tls_reader::Reader;
+use crate::{ClientHello, Error};
+
+/// Version of the text format written by [`write()`] and accepted by
+/// [`parse`].
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Bytes per line of hex in [`write()`] output.
+const BYTES_PER_LINE: usize = 16;
+
+/// Errors produced while loading a vector file.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}: {reason}")]
+pub struct VectorError {
+	/// 1-based line number.
+	pub line: usize,
+	/// What was wrong with the line.
+	pub reason: &'static str,
+}
+
+/// Whether a [`TestVector`] is expected to parse.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Expect {
+	/// [`TestVector::parse`] must succeed.
+	#[default]
+	Parses,
+	/// [`TestVector::parse`] must return an error.
+	Fails,
+}
+
+impl Expect {
+	/// Return the keyword used for this expectation in vector files.
+	#[must_use]
+	pub const fn as_str(self) -> &'static str {
+		match self {
+			Self::Parses => "parses",
+			Self::Fails => "fails",
+		}
+	}
+}
+
+/// One named input with the outcome it should produce.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestVector {
+	/// Name, unique within a file by convention.
+	pub name: String,
+	/// Expected outcome of parsing [`bytes`](Self::bytes).
+	pub expect: Expect,
+	/// Free-form single-line notes, e.g. where the input came from.
+	pub notes: Vec<String>,
+	/// A TLS record or a raw handshake message.
+	pub bytes: Vec<u8>,
+}
+
+impl TestVector {
+	/// Create a vector expected to parse.
+	#[must_use]
+	pub fn new(name: impl Into<String>, bytes: impl Into<Vec<u8>>) -> Self {
+		Self {
+			name: name.into(),
+			bytes: bytes.into(),
+			..Self::default()
+		}
+	}
+
+	/// Parse the vector's bytes, as a record when they start with `0x16`.
+	///
+	/// # Errors
+	///
+	/// Returns any error from [`crate::parse`] or
+	/// [`crate::parse_from_record`].
+	pub fn parse(&self) -> Result<ClientHello<'_>, Error> {
+		if self.bytes.first() == Some(&CONTENT_TYPE_HANDSHAKE) {
+			crate::parse_from_record(&self.bytes)
+		} else {
+			crate::parse(&self.bytes)
+		}
+	}
+
+	/// Check whether parsing produces the expected outcome.
+	#[must_use]
+	pub fn passes(&self) -> bool {
+		self.parse().is_ok() == (self.expect == Expect::Parses)
+	}
+}
+
+/// Load every vector in `text`.
+///
+/// # Errors
+///
+/// Returns a [`VectorError`] naming the first line that breaks the rules
+/// in the [module documentation](self).
+pub fn parse(text: &str) -> Result<Vec<TestVector>, VectorError> {
+	let mut vectors: Vec<TestVector> = Vec::new();
+	for (i, raw) in text.lines().enumerate() {
+		let err = |reason| VectorError {
+			line: i + 1,
+			reason,
+		};
+		let line = raw.trim();
+		if line.starts_with('#') {
+			continue;
+		}
+		if let Some((key, value)) = line.split_once(':')
+			&& !key.is_empty()
+			&& key
+				.bytes()
+				.all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+		{
+			let value = value.trim();
+			match key {
+				"format" if vectors.is_empty() => {
+					if value.parse() != Ok(FORMAT_VERSION) {
+						return Err(err("unsupported format version"));
+					}
+				}
+				"format" => return Err(err("format must precede the first vector")),
+				"vector" if value.is_empty() => return Err(err("missing vector name")),
+				"vector" => vectors.push(TestVector::new(value, Vec::new())),
+				"expect" | "note" => {
+					let current = vectors.last_mut().ok_or(err("key outside a vector"))?;
+					if key == "note" {
+						current.notes.push(value.into());
+					} else {
+						current.expect = match value {
+							"parses" => Expect::Parses,
+							"fails" => Expect::Fails,
+							_ => return Err(err("expect must be `parses` or `fails`")),
+						};
+					}
+				}
+				_ => return Err(err("unknown key")),
+			}
+			continue;
+		}
+		let digits: Vec<u8> = line
+			.split('#')
+			.next()
+			.unwrap_or_default()
+			.bytes()
+			.filter(|b| !b.is_ascii_whitespace())
+			.collect();
+		if digits.is_empty() {
+			continue;
+		}
+		let current = vectors.last_mut().ok_or(err("bytes outside a vector"))?;
+		if !digits.len().is_multiple_of(2) {
+			return Err(err("odd number of hex digits"));
+		}
+		for pair in digits.chunks_exact(2) {
+			let byte = hex_value(pair[0])
+				.zip(hex_value(pair[1]))
+				.ok_or(err("invalid hex digit"))?;
+			current.bytes.push(byte.0 << 4 | byte.1);
+		}
+	}
+	Ok(vectors)
+}
+
+/// Render `vectors` as a vector file, annotating the bytes of each
+/// with the fields they encode.
+///
+/// Annotation stops at the first field that does not decode; the rest
+/// is labelled `unparsed`. Notes spanning several lines are written as
+/// one `note` per line.
+#[must_use]
+pub fn write(vectors: &[TestVector]) -> String {
+	let mut out = format!("format: {FORMAT_VERSION}\n");
+	for vector in vectors {
+		let _ = write!(
+			out,
+			"\nvector: {}\nexpect: {}\n",
+			vector.name,
+			vector.expect.as_str()
+		);
+		for line in vector.notes.iter().flat_map(|n| n.lines()) {
+			let _ = writeln!(out, "note: {line}");
+		}
+		for (start, end, label) in annotate(&vector.bytes) {
+			for (i, chunk) in vector.bytes[start..end].chunks(BYTES_PER_LINE).enumerate() {
+				for (j, byte) in chunk.iter().enumerate() {
+					let sep = if j == 0 { "" } else { " " };
+					let _ = write!(out, "{sep}{byte:02x}");
+				}
+				if i == 0 {
+					let _ = write!(out, "  # {label}");
+				}
+				out.push('\n');
+			}
+		}
+	}
+	out
+}
+
+/// A labelled `start..end` range of a vector's bytes.
+type Span = (usize, usize, String);
+
+/// Split `bytes` into labelled field spans covering all of it.
+fn annotate(bytes: &[u8]) -> Vec<Span> {
+	let mut spans = Vec::new();
+	let mut r = Reader::new(bytes);
+	let _ = annotate_fields(&mut r, &mut spans);
+	let done = spans.last().map_or(0, |&(_, end, _)| end);
+	if done < bytes.len() {
+		spans.push((done, bytes.len(), "unparsed".into()));
+	}
+	spans
+}
+
+/// How a field's length is determined.
+#[derive(Clone, Copy)]
+enum Len {
+	Fixed(usize),
+	PrefixU8,
+	PrefixU16,
+}
+
+fn annotate_fields(r: &mut Reader<'_>, spans: &mut Vec<Span>) -> Result<(), Error> {
+	let mut field = |r: &mut Reader<'_>, label: &str, len: Len| -> Result<(), Error> {
+		let start = r.position();
+		match len {
+			Len::Fixed(n) => r.read_bytes(n, "field")?,
+			Len::PrefixU8 => r.read_vec_u8("field")?,
+			Len::PrefixU16 => r.read_vec_u16("field")?,
+		};
+		spans.push((start, r.position(), label.into()));
+		Ok(())
+	};
+	if r.rest().first() == Some(&CONTENT_TYPE_HANDSHAKE) {
+		field(r, "record header", Len::Fixed(5))?;
+	}
+	field(r, "handshake header", Len::Fixed(4))?;
+	field(r, "legacy version", Len::Fixed(2))?;
+	field(r, "random", Len::Fixed(32))?;
+	field(r, "session ID", Len::PrefixU8)?;
+	field(r, "cipher suites", Len::PrefixU16)?;
+	field(r, "compression methods", Len::PrefixU8)?;
+	if r.is_empty() {
+		return Ok(());
+	}
+	field(r, "extensions length", Len::Fixed(2))?;
+	while !r.is_empty() {
+		let start = r.position();
+		let type_id = r.read_u16("field")?;
+		r.read_vec_u16("field")?;
+		spans.push((start, r.position(), format!("extension {type_id:#06x}")));
+	}
+	Ok(())
+}
+
+fn hex_value(digit: u8) -> Option<u8> {
+	char::from(digit)
+		.to_digit(16)
+		.and_then(|d| u8::try_from(d).ok())
+}
@@ -0,0 +1,56 @@
+/* tests/alpn.rs */
+#![allow(missing_docs)]
+
+use clienthello::{AlpnSet, Builder, parse};
+
+static HTTP: AlpnSet<'static> = AlpnSet::new(&[b"h2", b"http/1.1"]);
+
+fn hello_bytes(alpn: &[&[u8]]) -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(&[0x1301])
+		.alpn(alpn)
+		.build()
+		.unwrap()
+}
+
+#[test]
+fn matches_any_offered_member() {
+	let data = hello_bytes(&[b"spdy/3", b"http/1.1"]);
+	let hello = parse(&data).unwrap();
+	assert!(HTTP.contains_any(&hello));
+	assert_eq!(HTTP.first_match(&hello), Some(&b"http/1.1"[..]));
+}
+
+#[test]
+fn first_match_follows_client_order() {
+	let data = hello_bytes(&[b"http/1.1", b"h2"]);
+	let hello = parse(&data).unwrap();
+	assert_eq!(HTTP.first_match(&hello), Some(&b"http/1.1"[..]));
+}
+
+#[test]
+fn no_match_without_members_or_alpn() {
+	let data = hello_bytes(&[b"h3", b"http/1.0"]);
+	assert!(!HTTP.contains_any(&parse(&data).unwrap()));
+	let data = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	assert!(!HTTP.contains_any(&parse(&data).unwrap()));
+}
+
+#[test]
+fn contains_checks_exact_bytes() {
+	assert!(HTTP.contains(b"h2"));
+	assert!(!HTTP.contains(b"h3"));
+	assert!(!HTTP.contains(b"H2"));
+	assert!(!HTTP.contains(b""));
+	assert!(!AlpnSet::new(&[]).contains(b"h2"));
+	assert_eq!(HTTP.protocols().len(), 2);
+}
+
+#[test]
+fn unmatchable_members_are_ignored() {
+	let long = [b'a'; 300];
+	let protocols: [&[u8]; 2] = [b"", &long];
+	let set = AlpnSet::new(&protocols);
+	assert!(!set.contains(b""));
+	assert!(!set.contains(&long));
+}
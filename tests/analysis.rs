@@ -0,0 +1,94 @@
+/* tests/analysis.rs */
+#![allow(missing_docs)]
+
+use clienthello::analysis::{InterceptionSignal, interception_score};
+use clienthello::{Builder, parse};
+
+fn score(builder: &Builder) -> clienthello::analysis::InterceptionScore {
+	let msg = builder.build().unwrap();
+	interception_score(&parse(&msg).unwrap())
+}
+
+#[test]
+fn chrome_like_hello_scores_zero() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x0a0a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc013])
+		.extension(0x1a1a, &[])
+		.server_name("example.com")
+		.supported_versions(&[0x0304, 0x0303])
+		.supported_groups(&[0x001d, 0x0017])
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.alpn(&[b"h2", b"http/1.1"]);
+	let result = score(&builder);
+	assert_eq!(result.score, 0);
+	assert!(result.signals.is_empty());
+}
+
+#[test]
+fn firefox_like_hello_only_misses_grease() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1301, 0x1303, 0x1302, 0xc02b, 0xc02f])
+		.server_name("example.com")
+		.supported_versions(&[0x0304, 0x0303])
+		.supported_groups(&[0x001d, 0x0017])
+		.key_share(&[(0x001d, &[0x11; 32]), (0x0017, &[0x04; 65])]);
+	let result = score(&builder);
+	assert_eq!(result.signals, [InterceptionSignal::MissingGrease]);
+	assert_eq!(result.score, 10);
+}
+
+#[test]
+fn bolted_on_tls13_is_flagged() {
+	// An old CBC-first cipher list with TLS 1.3 suites appended, heartbeat,
+	// and no key share.
+	let builder = Builder::new()
+		.cipher_suites(&[0xc014, 0xc013, 0x002f, 0x0035, 0x1302, 0x1301])
+		.server_name("example.com")
+		.supported_versions(&[0x0304, 0x0303])
+		.supported_groups(&[0x0017])
+		.extension(0x000f, &[0x01]);
+	let result = score(&builder);
+	assert_eq!(
+		result.signals,
+		[
+			InterceptionSignal::LegacyExtensionsWithTls13,
+			InterceptionSignal::Tls13WithoutKeyShare,
+			InterceptionSignal::Tls13SuitesNotFirst,
+			InterceptionSignal::CbcPreferred,
+			InterceptionSignal::MissingGrease,
+		]
+	);
+	assert_eq!(result.score, 100);
+}
+
+#[test]
+fn tls12_only_legacy_client_scores_low() {
+	let builder = Builder::new()
+		.cipher_suites(&[0xc013, 0x0005, 0x000a])
+		.server_name("example.com");
+	let result = score(&builder);
+	assert_eq!(result.signals, [InterceptionSignal::CbcPreferred]);
+	assert_eq!(result.score, 20);
+}
+
+#[test]
+fn method_matches_free_function() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301, 0x0004])
+		.supported_versions(&[0x0304])
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	let result = hello.interception_score();
+	assert_eq!(result, interception_score(&hello));
+	assert!(
+		result
+			.signals
+			.contains(&InterceptionSignal::ObsoleteCiphersWithTls13)
+	);
+	assert_eq!(
+		InterceptionSignal::ObsoleteCiphersWithTls13.to_string(),
+		"obsolete cipher suites next to TLS 1.3"
+	);
+}
@@ -0,0 +1,133 @@
+/* tests/bot.rs */
+#![allow(missing_docs)]
+
+use clienthello::{BotScorer, BotSignal, Builder, parse_from_record};
+
+fn browser() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x3a3a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c])
+		.extension(0x4a4a, &[])
+		.supported_versions(&[0x0304, 0x0303])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_groups(&[0x001d, 0x0017])
+}
+
+#[test]
+fn browser_scores_zero() {
+	let msg = browser().build_record().unwrap();
+	let result = parse_from_record(&msg).unwrap().bot_score();
+	assert_eq!(result.score, 0);
+	assert!(result.signals.is_empty());
+}
+
+#[test]
+fn scripted_client_fires_every_signal() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x002f, 0x000a])
+		.server_name("example.com")
+		.supported_groups(&[0x0017])
+		.extension(0x000b, &[0x01, 0x00])
+		.signature_algorithms(&[0x0401])
+		.build_record()
+		.unwrap();
+	let result = parse_from_record(&msg).unwrap().bot_score();
+	assert_eq!(result.score, 100);
+	assert_eq!(
+		result.signals,
+		[
+			BotSignal::MissingGrease,
+			BotSignal::StaticExtensionOrder,
+			BotSignal::RareCipherSet,
+			BotSignal::NoAlpn,
+			BotSignal::Tls12OnlyWithSni,
+		]
+	);
+	assert_eq!(result.signals[3].to_string(), "no ALPN extension");
+}
+
+#[test]
+fn tls12_only_supported_versions_fires() {
+	let msg = browser()
+		.supported_versions(&[0x0303])
+		.build_record()
+		.unwrap();
+	let result = parse_from_record(&msg).unwrap().bot_score();
+	assert_eq!(result.signals, [BotSignal::Tls12OnlyWithSni]);
+}
+
+#[test]
+fn weights_are_tunable() {
+	// Firefox-like: everything a browser sends except GREASE.
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301, 0x1303, 0x1302, 0xc02b])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_groups(&[0x001d, 0x0017])
+		.supported_versions(&[0x0304, 0x0303])
+		.build_record()
+		.unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert_eq!(hello.bot_score().score, 25);
+
+	let result = BotScorer::new().missing_grease(0).no_alpn(90).score(&hello);
+	assert_eq!(result.score, 0);
+	assert!(result.signals.is_empty());
+
+	let result = BotScorer::new().missing_grease(200).score(&hello);
+	assert_eq!(result.score, 100);
+	assert_eq!(result.signals, [BotSignal::MissingGrease]);
+}
+
+#[test]
+fn size_anomalies() {
+	let host = "a".repeat(1100);
+	let msg = browser()
+		.server_name(&host)
+		.extension(0x7777, &[0x55; 5000])
+		.build_record()
+		.unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	let result = hello.bot_score();
+	assert_eq!(result.score, 100);
+	assert_eq!(
+		result.signals,
+		[
+			BotSignal::OversizedServerName,
+			BotSignal::OversizedExtension
+		]
+	);
+	assert_eq!(result.signals[1].to_string(), "oversized extension");
+
+	let result = BotScorer::new()
+		.max_server_name_len(2048)
+		.max_extension_len(8192)
+		.score(&hello);
+	assert_eq!(result.score, 0);
+
+	let result = BotScorer::new()
+		.oversized_server_name(0)
+		.oversized_extension(30)
+		.score(&hello);
+	assert_eq!(result.score, 30);
+	assert_eq!(result.signals, [BotSignal::OversizedExtension]);
+}
+
+#[test]
+fn excessive_extension_count() {
+	let mut builder = browser();
+	for t in 0x1000..0x1040 {
+		builder = builder.extension(t, &[]);
+	}
+	let msg = builder.build_record().unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert_eq!(hello.bot_score().signals, [BotSignal::ExcessiveExtensions]);
+	assert_eq!(hello.bot_score().score, 50);
+	assert!(
+		BotScorer::new()
+			.max_extensions(80)
+			.score(&hello)
+			.signals
+			.is_empty()
+	);
+}
@@ -0,0 +1,269 @@
+/* tests/builder.rs */
+#![allow(missing_docs)]
+
+use clienthello::tls_writer::Writer;
+use clienthello::{Builder, Error, Extension, parse, parse_from_record};
+
+#[test]
+fn round_trip_raw() {
+	let msg = Builder::new()
+		.random([0x11; 32])
+		.session_id(&[0x22; 32])
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0304, 0x0303])
+		.supported_groups(&[0x001d, 0x0017])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.key_share(&[(0x001d, &[0xEE; 32])])
+		.psk_exchange_modes(&[0x01])
+		.renegotiation_info(&[])
+		.extension(0x0042, &[0xDE, 0xAD])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.random, &[0x11; 32]);
+	assert_eq!(hello.session_id, &[0x22; 32]);
+	assert_eq!(hello.cipher_suites, vec![0x1301, 0x1302]);
+	assert_eq!(hello.server_name(), Some("example.com"));
+	assert_eq!(
+		hello.alpn_protocols(),
+		&[b"h2".as_slice(), b"http/1.1".as_slice()]
+	);
+	assert_eq!(hello.supported_versions(), &[0x0304, 0x0303]);
+	assert_eq!(hello.supported_groups(), &[0x001d, 0x0017]);
+	assert_eq!(hello.signature_algorithms(), &[0x0403, 0x0804]);
+	assert_eq!(hello.key_share_groups(), &[0x001d]);
+	assert!(hello.has_renegotiation_info());
+	assert_eq!(hello.find_extension(0x0042), Some([0xDE, 0xAD].as_slice()));
+	assert_eq!(hello.extensions.len(), 9);
+}
+
+#[test]
+fn round_trip_record() {
+	let rec = Builder::new()
+		.cipher_suites(&[0x1301])
+		.build_record()
+		.unwrap();
+	assert_eq!(&rec[..3], &[0x16, 0x03, 0x01]);
+	let hello = parse_from_record(&rec).unwrap();
+	assert_eq!(hello.cipher_suites, vec![0x1301]);
+	assert!(hello.extensions.is_empty());
+}
+
+#[test]
+fn record_version_override() {
+	let b = Builder::new()
+		.cipher_suites(&[0x1301])
+		.record_version(0x0303);
+	let rec = b.build_record().unwrap();
+	assert_eq!(&rec[..3], &[0x16, 0x03, 0x03]);
+	let hello = parse_from_record(&rec).unwrap();
+	assert_eq!(hello.legacy_version, 0x0303);
+	assert_eq!(
+		b.build().unwrap(),
+		Builder::new().cipher_suites(&[0x1301]).build().unwrap()
+	);
+}
+
+#[test]
+fn setting_extension_twice_replaces_in_place() {
+	let msg = Builder::new()
+		.server_name("a.example")
+		.alpn(&[b"h2"])
+		.server_name("b.example")
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.extensions.len(), 2);
+	assert!(matches!(hello.extensions[0], Extension::ServerName(_)));
+	assert_eq!(hello.server_name(), Some("b.example"));
+}
+
+#[test]
+fn writer_nested_vectors() {
+	let mut w = Writer::new();
+	w.write_vec_u24(|w| {
+		w.write_vec_u8(|w| {
+			w.write_bytes(&[1, 2, 3]);
+			Ok(())
+		})
+	})
+	.unwrap();
+	assert_eq!(w.into_bytes(), vec![0x00, 0x00, 0x04, 0x03, 1, 2, 3]);
+}
+
+#[test]
+fn writer_opaque_vectors() {
+	let mut w = Writer::new();
+	w.write_opaque_u8(b"ab").unwrap();
+	w.write_opaque_u16(b"c").unwrap();
+	w.write_opaque_u24(&[]).unwrap();
+	assert_eq!(
+		w.into_bytes(),
+		vec![0x02, b'a', b'b', 0x00, 0x01, b'c', 0x00, 0x00, 0x00]
+	);
+	assert_eq!(
+		Writer::new().write_opaque_u8(&[0u8; 256]),
+		Err(Error::LengthOverflow { len: 256, max: 255 })
+	);
+}
+
+#[test]
+fn writer_overflow() {
+	let mut w = Writer::new();
+	let err = w
+		.write_vec_u8(|w| {
+			w.write_bytes(&[0u8; 256]);
+			Ok(())
+		})
+		.unwrap_err();
+	assert_eq!(err, Error::LengthOverflow { len: 256, max: 255 });
+	assert_eq!(
+		w.write_u24(0x0100_0000).unwrap_err(),
+		Error::LengthOverflow {
+			len: 0x0100_0000,
+			max: 0x00FF_FFFF
+		}
+	);
+}
+
+#[test]
+fn estimated_size_matches_build() {
+	let b = Builder::new()
+		.session_id(&[0x22; 32])
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0304, 0x0303])
+		.key_share(&[(0x001d, &[0xEE; 32])])
+		.psk_exchange_modes(&[0x01])
+		.extension(0x0042, &[0xDE, 0xAD]);
+	assert_eq!(b.estimated_size(), b.build().unwrap().len());
+	assert_eq!(b.estimated_size() + 5, b.build_record().unwrap().len());
+	assert_eq!(
+		Builder::new().estimated_size(),
+		Builder::new().build().unwrap().len()
+	);
+}
+
+#[test]
+fn pad_to_reaches_target() {
+	let b = Builder::new()
+		.cipher_suites(&[0x1301])
+		.pad_to(512)
+		.server_name("example.com");
+	let msg = b.build().unwrap();
+	assert_eq!(msg.len(), 512);
+	assert_eq!(b.estimated_size(), 512);
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.server_name(), Some("example.com"));
+	assert_eq!(
+		hello.find_extension(0x0015).unwrap().len(),
+		512 - 4 - 41 - 2 - 4 - 16 - 4
+	);
+}
+
+#[test]
+fn pad_to_below_current_size_is_empty() {
+	let msg = Builder::new()
+		.server_name("example.com")
+		.pad_to(16)
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.find_extension(0x0015), Some(&[][..]));
+}
+
+#[test]
+fn session_ticket_and_psk_offer() {
+	let b = Builder::new()
+		.cipher_suites(&[0x1301])
+		.pre_shared_key(&[(b"ticket-1", 1000), (b"ticket-2", 2000)], 32)
+		.session_ticket(b"opaque")
+		.psk_exchange_modes(&[0x01]);
+	let msg = b.build().unwrap();
+	assert_eq!(msg.len(), b.estimated_size());
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.find_extension(0x0023), Some(&b"opaque"[..]));
+	// pre_shared_key is moved to the end.
+	assert_eq!(hello.extensions.type_ids().last(), Some(0x0029));
+	let psk = hello.find_extension(0x0029).unwrap();
+	let identities_len = 2 + (2 + 8 + 4) * 2;
+	assert_eq!(&psk[..2], &[0x00, 0x1c]);
+	assert_eq!(&psk[2..4], &[0x00, 0x08]);
+	assert_eq!(&psk[12..16], &1000u32.to_be_bytes());
+	let mut binders = vec![0x00, 66, 32];
+	binders.extend_from_slice(&[0; 32]);
+	binders.push(32);
+	binders.extend_from_slice(&[0; 32]);
+	assert_eq!(&psk[identities_len..], binders.as_slice());
+}
+
+#[test]
+fn build_with_binders_fills_each_binder() {
+	let b = Builder::new()
+		.cipher_suites(&[0x1301])
+		.pre_shared_key(&[(b"a", 0), (b"bb", 0)], 4);
+	let placeholder = b.build().unwrap();
+	let binders_len = 2 + 2 * 5;
+	let mut calls = Vec::new();
+	let msg = b
+		.build_with_binders(|i, partial, out| {
+			calls.push((i, partial.to_vec()));
+			out.fill(u8::try_from(i).unwrap() + 1);
+		})
+		.unwrap();
+	assert_eq!(calls.len(), 2);
+	let partial = &placeholder[..placeholder.len() - binders_len];
+	assert!(calls.iter().all(|(_, p)| p == partial));
+	assert_eq!(&msg[msg.len() - 10..], &[4, 1, 1, 1, 1, 4, 2, 2, 2, 2]);
+
+	let rec = b
+		.build_record_with_binders(|_, partial, out| {
+			assert_eq!(partial[0], 0x01);
+			out.fill(0xaa);
+		})
+		.unwrap();
+	assert_eq!(rec[rec.len() - 1], 0xaa);
+	assert!(parse_from_record(&rec).is_ok());
+
+	let mut called = false;
+	Builder::new()
+		.build_with_binders(|_, _, _| called = true)
+		.unwrap();
+	assert!(!called);
+}
+
+#[test]
+fn ech_grease_offer_shape() {
+	let mut calls = 0u8;
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.ech_grease(|buf| {
+			calls += 1;
+			buf.fill(calls);
+		})
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	let body = hello.find_extension(0xfe0d).unwrap();
+	// outer, HKDF-SHA256, AES-128-GCM, config ID from the first fill
+	assert_eq!(&body[..6], &[0x00, 0x00, 0x01, 0x00, 0x01, 0x01]);
+	assert_eq!(&body[6..8], &[0x00, 0x20]);
+	assert!(body[8..40].iter().all(|&b| b == 2));
+	// size byte 1 selects the second payload size
+	assert_eq!(&body[40..42], &[0x00, 176]);
+	assert_eq!(body.len(), 42 + 176);
+	assert!(body[42..].iter().all(|&b| b == 3));
+
+	for size in 0..4u8 {
+		let msg = Builder::new()
+			.ech_grease(|buf| buf.fill(size))
+			.build()
+			.unwrap();
+		let hello = parse(&msg).unwrap();
+		let body = hello.find_extension(0xfe0d).unwrap();
+		assert_eq!(body.len(), 42 + 144 + 32 * usize::from(size));
+	}
+}
@@ -0,0 +1,84 @@
+/* tests/cache.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, Error, HelloCache, parse_from_record};
+
+fn hello(sni: &str) -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name(sni)
+		.build_record()
+		.unwrap()
+}
+
+fn server_name(data: &[u8]) -> Result<String, Error> {
+	parse_from_record(data).map(|h| h.server_name().unwrap_or_default().to_owned())
+}
+
+#[test]
+fn repeated_hello_is_computed_once() {
+	let mut cache = HelloCache::new(4);
+	let data = hello("a.example");
+	let mut calls = 0;
+	for _ in 0..3 {
+		let name = cache.get_or_insert_with(&data, |d| {
+			calls += 1;
+			server_name(d)
+		});
+		assert_eq!(name.as_deref(), Ok("a.example"));
+	}
+	assert_eq!(calls, 1);
+	assert_eq!((cache.hits(), cache.misses()), (2, 1));
+	assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn errors_are_cached() {
+	let mut cache = HelloCache::new(4);
+	let data = [0x16, 0x03, 0x01];
+	assert!(cache.get_or_insert_with(&data, server_name).is_err());
+	assert!(cache.get(&data).unwrap().is_err());
+}
+
+#[test]
+fn least_recently_used_is_evicted() {
+	let mut cache = HelloCache::new(2);
+	let (a, b, c) = (hello("a.example"), hello("b.example"), hello("c.example"));
+	cache.get_or_insert_with(&a, server_name);
+	cache.get_or_insert_with(&b, server_name);
+	// Touch `a` so `b` becomes the oldest entry.
+	assert!(cache.get(&a).is_some());
+	cache.get_or_insert_with(&c, server_name);
+
+	assert_eq!(cache.len(), 2);
+	assert!(cache.get(&b).is_none());
+	assert_eq!(cache.get(&a).unwrap().as_deref(), Ok("a.example"));
+	assert_eq!(cache.get(&c).unwrap().as_deref(), Ok("c.example"));
+}
+
+#[test]
+fn churn_keeps_capacity() {
+	let mut cache = HelloCache::new(8);
+	let hellos: Vec<_> = (0..32).map(|i| hello(&format!("{i}.example"))).collect();
+	for data in hellos.iter().chain(&hellos) {
+		cache.get_or_insert_with(data, server_name);
+		assert!(cache.len() <= cache.capacity());
+	}
+	for data in &hellos[24..] {
+		assert!(cache.get(data).is_some());
+	}
+	for data in &hellos[..24] {
+		assert!(cache.get(data).is_none());
+	}
+	cache.clear();
+	assert!(cache.is_empty());
+	assert_eq!(cache.hits(), 0);
+}
+
+#[test]
+fn zero_capacity_holds_one() {
+	let mut cache = HelloCache::new(0);
+	assert_eq!(cache.capacity(), 1);
+	assert_eq!(*cache.get_or_insert_with(b"x", <[u8]>::len), 1);
+	assert_eq!(cache.get(b"x"), Some(&1));
+}
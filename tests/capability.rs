@@ -0,0 +1,90 @@
+/* tests/capability.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, Capabilities, Capability, parse};
+
+fn chrome_like() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x0a0a, 0x1301, 0x1302])
+		.extension(0x1a1a, &[])
+		.supported_versions(&[0x0304, 0x0303])
+		.supported_groups(&[0x11ec, 0x001d])
+		.key_share(&[(0x001d, &[0; 32])])
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.extension(0x0012, &[])
+		.extension(0x0017, &[])
+		.extension(0x0023, &[])
+		.extension(0x001b, &[0x02, 0x00, 0x02])
+		.extension(0x44cd, &[0x00, 0x03, 0x02, b'h', b'2'])
+		.extension(0xfe0d, &[0x00; 8])
+}
+
+#[test]
+fn detects_chrome_like_capabilities() {
+	let msg = chrome_like().build().unwrap();
+	let caps = parse(&msg).unwrap().capabilities();
+	let found: Vec<_> = caps.iter().collect();
+	assert_eq!(
+		found,
+		[
+			Capability::Tls13,
+			Capability::PostQuantum,
+			Capability::Ech,
+			Capability::Alps,
+			Capability::SessionTickets,
+			Capability::Ocsp,
+			Capability::Sct,
+			Capability::ExtendedMasterSecret,
+			Capability::CertificateCompression,
+			Capability::Grease,
+		]
+	);
+}
+
+#[test]
+fn legacy_hello_has_no_tls13() {
+	let msg = Builder::new()
+		.legacy_version(0x0303)
+		.cipher_suites(&[0xc02f])
+		.extension(0x0016, &[])
+		.build()
+		.unwrap();
+	let caps = parse(&msg).unwrap().capabilities();
+	assert!(!caps.contains(Capability::Tls13));
+	assert!(!caps.contains(Capability::Grease));
+	assert_eq!(
+		caps.iter().collect::<Vec<_>>(),
+		[Capability::EncryptThenMac]
+	);
+}
+
+#[test]
+fn csv_matrix() {
+	let chrome = chrome_like().build().unwrap();
+	let plain = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	let csv = Capabilities::to_csv([
+		("chrome", parse(&chrome).unwrap().capabilities()),
+		("a,b", parse(&plain).unwrap().capabilities()),
+	]);
+	let lines: Vec<_> = csv.split("\r\n").collect();
+	assert_eq!(
+		lines[0],
+		"label,tls13,post_quantum,ech,alps,session_tickets,psk,early_data,ocsp,sct,\
+		 extended_master_secret,encrypt_then_mac,certificate_compression,\
+		 delegated_credentials,record_size_limit,post_handshake_auth,grease"
+	);
+	assert_eq!(lines[1], "chrome,1,1,1,1,1,0,0,1,1,1,0,1,0,0,0,1");
+	assert_eq!(lines[2], "\"a,b\",0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0");
+	assert_eq!(lines[3], "");
+	assert_eq!(lines.len(), 4);
+}
+
+#[test]
+fn collects_from_capabilities() {
+	let caps: Capabilities = [Capability::Psk, Capability::EarlyData]
+		.into_iter()
+		.collect();
+	assert!(caps.contains(Capability::Psk));
+	assert!(!caps.contains(Capability::Tls13));
+	assert_eq!(Capability::EarlyData.to_string(), "early_data");
+}
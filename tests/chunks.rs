@@ -0,0 +1,93 @@
+/* tests/chunks.rs */
+#![allow(missing_docs)]
+
+use std::io::IoSlice;
+
+use clienthello::{
+	Builder, Error, Transport, parse, parse_from_chunks, parse_from_record_chunks,
+	parse_from_record_vectored, parse_vectored,
+};
+
+fn sample() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("chunked.example")
+		.alpn(&[b"h2"])
+}
+
+#[test]
+fn single_chunk_is_zero_copy() {
+	let msg = sample().build().unwrap();
+	let mut scratch = Vec::new();
+	let hello = parse_from_chunks([msg.as_slice()], &mut scratch).unwrap();
+	assert_eq!(hello.server_name(), Some("chunked.example"));
+	assert!(msg.as_ptr_range().contains(&hello.random.as_ptr()));
+	assert!(scratch.is_empty());
+}
+
+#[test]
+fn every_split_point_matches_contiguous_parse() {
+	let msg = sample().build().unwrap();
+	let expected = parse(&msg).unwrap();
+	for at in 0..=msg.len() {
+		let (a, b) = msg.split_at(at);
+		let mut scratch = Vec::new();
+		let hello = parse_from_chunks([a, b], &mut scratch).unwrap();
+		assert_eq!(hello, expected, "split at {at}");
+	}
+}
+
+#[test]
+fn many_small_chunks_with_trailing_data() {
+	let mut msg = sample().build().unwrap();
+	let len = msg.len();
+	msg.extend_from_slice(&[0xFF; 16]);
+	let mut scratch = Vec::new();
+	let hello = parse_from_chunks(msg.chunks(3), &mut scratch).unwrap();
+	assert_eq!(hello.server_name(), Some("chunked.example"));
+	assert!(scratch.len() >= len && scratch.len() < len + 3);
+}
+
+#[test]
+fn record_chunks() {
+	let rec = sample().build_record().unwrap();
+	let mut scratch = Vec::new();
+	let hello = parse_from_record_chunks(rec.chunks(7), &mut scratch).unwrap();
+	assert_eq!(hello.transport, Transport::TlsRecord);
+	assert_eq!(hello.cipher_suites, vec![0x1301, 0x1302]);
+}
+
+#[test]
+fn incomplete_chunks_report_truncation() {
+	let msg = sample().build().unwrap();
+	let mut scratch = Vec::new();
+	let err = parse_from_chunks(msg[..20].chunks(5), &mut scratch).unwrap_err();
+	assert_eq!(
+		err,
+		Error::Truncated {
+			field: "handshake body"
+		}
+	);
+	let mut scratch = Vec::new();
+	let err = parse_from_chunks(core::iter::empty(), &mut scratch).unwrap_err();
+	assert_eq!(err, Error::BufferTooShort { need: 1, have: 0 });
+}
+
+#[test]
+fn vectored_io_slices() {
+	let msg = sample().build().unwrap();
+	let bufs: Vec<IoSlice<'_>> = msg.chunks(11).map(IoSlice::new).collect();
+	let mut scratch = Vec::new();
+	let hello = parse_vectored(&bufs, &mut scratch).unwrap();
+	assert_eq!(hello.server_name(), Some("chunked.example"));
+}
+
+#[test]
+fn vectored_byte_slices_record() {
+	let rec = sample().build_record().unwrap();
+	let (a, b) = rec.split_at(4);
+	let bufs: [&[u8]; 2] = [a, b];
+	let mut scratch = Vec::new();
+	let hello = parse_from_record_vectored(&bufs, &mut scratch).unwrap();
+	assert_eq!(hello.transport, Transport::TlsRecord);
+}
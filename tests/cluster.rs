@@ -0,0 +1,92 @@
+/* tests/cluster.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, ClientHello, cluster};
+
+fn browser(host: &str, suites: &[u16]) -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(suites)
+		.server_name(host)
+		.alpn(&[b"h2"])
+		.supported_groups(&[0x001d, 0x0017])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.supported_versions(&[0x0304, 0x0303])
+		.extension(0x0017, &[])
+		.build()
+		.unwrap()
+}
+
+fn legacy() -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(&[0x002f, 0x0035, 0x000a])
+		.supported_groups(&[0x0017])
+		.build()
+		.unwrap()
+}
+
+fn parse_all(msgs: &[Vec<u8>]) -> Vec<ClientHello<'_>> {
+	msgs
+		.iter()
+		.map(|m| clienthello::parse(m).unwrap())
+		.collect()
+}
+
+#[test]
+fn similarity_ignores_connection_details() {
+	let msgs = [
+		browser("a.example", &[0x1301, 0x1302]),
+		Builder::new()
+			.cipher_suites(&[0x1302, 0x1301])
+			.server_name("b.example")
+			.extension(0x0017, &[])
+			.supported_versions(&[0x0303, 0x0304])
+			.signature_algorithms(&[0x0804, 0x0403])
+			.supported_groups(&[0x0017, 0x001d])
+			.extension(0x0015, &[0; 16])
+			.build()
+			.unwrap(),
+		legacy(),
+	];
+	let hellos = parse_all(&msgs);
+	assert_eq!(hellos[0].similarity(&hellos[1]), 100);
+	assert_eq!(hellos[0].similarity(&hellos[0]), 100);
+	assert!(hellos[0].similarity(&hellos[2]) < 30);
+	assert_eq!(
+		hellos[0].similarity(&hellos[2]),
+		hellos[2].similarity(&hellos[0])
+	);
+}
+
+#[test]
+fn cluster_groups_families() {
+	let msgs = [
+		browser("a.example", &[0x1301, 0x1302, 0x1303]),
+		legacy(),
+		browser("b.example", &[0x1301, 0x1302]),
+		legacy(),
+		browser("c.example", &[0x1301, 0x1302, 0x1303]),
+	];
+	let hellos = parse_all(&msgs);
+	let clusters = cluster(&hellos, 80);
+	assert_eq!(clusters.len(), 2);
+	assert_eq!(clusters[0].representative, 0);
+	assert_eq!(clusters[0].members, [0, 2, 4]);
+	assert_eq!(clusters[0].fingerprints.len(), 2);
+	assert!(clusters[0].fingerprints.contains(&clusters[0].fingerprint));
+	assert!(clusters[0].fingerprint.starts_with("q13d0306h2_"));
+	assert_eq!(clusters[1].members, [1, 3]);
+	assert_eq!(clusters[1].fingerprints, [clusters[1].fingerprint.clone()]);
+}
+
+#[test]
+fn cluster_threshold_bounds() {
+	let msgs = [
+		browser("a.example", &[0x1301, 0x1302, 0x1303]),
+		browser("b.example", &[0x1301, 0x1302]),
+		legacy(),
+	];
+	let hellos = parse_all(&msgs);
+	assert_eq!(cluster(&hellos, 0).len(), 1);
+	assert_eq!(cluster(&hellos, 100).len(), 3);
+	assert!(cluster(&[], 50).is_empty());
+}
@@ -0,0 +1,74 @@
+/* tests/codec.rs */
+#![allow(missing_docs)]
+
+use bytes::BytesMut;
+use clienthello::codec::{ClientHelloCodec, CodecError, TlsFrame};
+use clienthello::{Builder, Error, ParseOptions};
+use tokio_util::codec::Decoder;
+
+fn hello_record() -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("example.com")
+		.build_record()
+		.unwrap()
+}
+
+const APP_DATA: [u8; 8] = [0x17, 0x03, 0x03, 0x00, 0x03, 0xaa, 0xbb, 0xcc];
+
+#[test]
+fn hello_then_raw_records() {
+	let hello = hello_record();
+	let mut buf = BytesMut::new();
+	buf.extend_from_slice(&hello);
+	buf.extend_from_slice(&APP_DATA);
+	buf.extend_from_slice(&APP_DATA);
+
+	let mut codec = ClientHelloCodec::new();
+	let Some(TlsFrame::ClientHello(frame)) = codec.decode(&mut buf).unwrap() else {
+		panic!("expected a ClientHello frame");
+	};
+	assert_eq!(frame.client_hello().server_name(), Some("example.com"));
+	assert_eq!(frame.as_bytes(), hello);
+	for _ in 0..2 {
+		assert_eq!(
+			codec.decode(&mut buf).unwrap(),
+			Some(TlsFrame::Record(APP_DATA[..].into()))
+		);
+	}
+	assert_eq!(codec.decode(&mut buf).unwrap(), None);
+	assert!(buf.is_empty());
+}
+
+#[test]
+fn waits_for_complete_record() {
+	let hello = hello_record();
+	let mut codec = ClientHelloCodec::new();
+	let mut buf = BytesMut::new();
+	for (i, &b) in hello.iter().enumerate() {
+		buf.extend_from_slice(&[b]);
+		let frame = codec.decode(&mut buf).unwrap();
+		assert_eq!(frame.is_some(), i == hello.len() - 1);
+	}
+}
+
+#[test]
+fn invalid_first_record_is_an_error() {
+	let mut buf = BytesMut::from(&APP_DATA[..]);
+	let err = ClientHelloCodec::new().decode(&mut buf).unwrap_err();
+	assert!(matches!(
+		err,
+		CodecError::Parse(Error::NotHandshakeRecord(_))
+	));
+}
+
+#[test]
+fn options_apply_to_hello() {
+	let mut buf = BytesMut::from(&hello_record()[..]);
+	let mut codec = ClientHelloCodec::with_options(ParseOptions::new().max_cipher_suites(1));
+	let err = codec.decode(&mut buf).unwrap_err();
+	assert!(matches!(
+		err,
+		CodecError::Parse(Error::LimitExceeded { .. })
+	));
+}
@@ -0,0 +1,174 @@
+/* tests/codegen.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, ParseOptions, codegen};
+
+fn capture() -> Builder {
+	Builder::new()
+		.session_id(&[0; 32])
+		.cipher_suites(&[0x0a0a, 0x1301, 0x1302, 0xc02b])
+		.extension(0x0a0a, &[])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.supported_groups(&[0x0a0a, 0x001d, 0x0017])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.alpn(&[b"h2", b"http/1.1"])
+		.key_share(&[(0x0a0a, &[0; 1]), (0x001d, &[0; 32])])
+		.psk_exchange_modes(&[0x01])
+		.supported_versions(&[0x0a0a, 0x0304, 0x0303])
+		.renegotiation_info(&[])
+		.extension(0x4469, &[0x00, 0x03, 0x02, 0x68, 0x32])
+		.extension(0x1a1a, &[])
+		.extension(0x0015, &[0; 7])
+}
+
+const RUST: &str = "\
+clienthello::Builder::new()
+	.session_id(&[0; 32])
+	.cipher_suites(&[0x0a0a, 0x1301, 0x1302, 0xc02b])
+	.extension(0x0a0a, &[])
+	.server_name(\"example.com\")
+	.extension(0x0017, &[])
+	.supported_groups(&[0x0a0a, 0x001d, 0x0017])
+	.signature_algorithms(&[0x0403, 0x0804])
+	.alpn(&[b\"h2\", b\"http/1.1\"])
+	.key_share(&[(0x0a0a, &[0; 1]), (0x001d, &[0; 32])])
+	.psk_exchange_modes(&[0x01])
+	.supported_versions(&[0x0a0a, 0x0304, 0x0303])
+	.renegotiation_info(&[])
+	.extension(0x4469, &[0x00, 0x03, 0x02, 0x68, 0x32])
+	.extension(0x1a1a, &[])
+	.extension(0x0015, &[0; 7])
+";
+
+#[test]
+fn rust_snippet_reproduces_capture() {
+	let msg = capture().build().unwrap();
+	let hello = clienthello::parse(&msg).unwrap();
+	assert_eq!(codegen::rust(&hello), RUST);
+}
+
+#[test]
+fn rust_snippet_replays_reserved_values() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x0a0a, 0xff42, 0x1301])
+		.extension(0xff43, &[])
+		.supported_groups(&[0x0a0a, 0xff45, 0x001d])
+		.signature_algorithms(&[0xff46, 0x0403])
+		.key_share(&[(0x0a0a, &[0; 1]), (0xff47, &[0; 1]), (0x001d, &[0; 32])])
+		.supported_versions(&[0xff44, 0x0304])
+		.build()
+		.unwrap();
+	let hello = ParseOptions::new()
+		.reserved_values(|v| v & 0xff00 == 0xff00)
+		.parse(&msg)
+		.unwrap();
+	assert_eq!(
+		codegen::rust(&hello),
+		"clienthello::Builder::new()\n\
+		\t.cipher_suites(&[0x0a0a, 0xff42, 0x1301])\n\
+		\t.extension(0xff43, &[])\n\
+		\t.supported_groups(&[0x0a0a, 0xff45, 0x001d])\n\
+		\t.signature_algorithms(&[0xff46, 0x0403])\n\
+		\t.key_share(&[(0x0a0a, &[0; 1]), (0xff47, &[0; 1]), (0x001d, &[0; 32])])\n\
+		\t.supported_versions(&[0xff44, 0x0304])\n"
+	);
+	let spec = hello.to_spec();
+	assert_eq!(spec.cipher_suites, [None, Some(0xff42), Some(0x1301)]);
+}
+
+#[test]
+fn rust_snippet_keeps_non_default_fields() {
+	let msg = Builder::new()
+		.legacy_version(0x0301)
+		.cipher_suites(&[0x002f])
+		.compression_methods(&[0x01, 0x00])
+		.build()
+		.unwrap();
+	let hello = clienthello::parse(&msg).unwrap();
+	assert_eq!(
+		codegen::rust(&hello),
+		"clienthello::Builder::new()\n\
+		\t.legacy_version(0x0301)\n\
+		\t.cipher_suites(&[0x002f])\n\
+		\t.compression_methods(&[0x01, 0x00])\n"
+	);
+}
+
+#[test]
+fn rust_snippet_escapes_odd_names() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.alpn(&[b"a\"b\\\xff"])
+		.build()
+		.unwrap();
+	let hello = clienthello::parse(&msg).unwrap();
+	assert!(
+		codegen::rust(&hello).contains(r#".alpn(&[b"a\"b\\\xff"])"#),
+		"{}",
+		codegen::rust(&hello)
+	);
+}
+
+#[test]
+fn utls_spec_uses_typed_extensions() {
+	let msg = capture().build().unwrap();
+	let hello = clienthello::parse(&msg).unwrap();
+	let spec = codegen::utls(&hello);
+	assert!(spec.starts_with(
+		"&tls.ClientHelloSpec{\n\tCipherSuites: []uint16{\n\t\ttls.GREASE_PLACEHOLDER,\n\t\t0x1301,\n"
+	));
+	for line in [
+		"\tCompressionMethods: []byte{0x00},\n",
+		"\t\t&tls.UtlsGREASEExtension{},\n\t\t&tls.SNIExtension{},\n\t\t&tls.ExtendedMasterSecretExtension{},\n",
+		"\t\t&tls.SupportedCurvesExtension{Curves: []tls.CurveID{tls.GREASE_PLACEHOLDER, 0x001d, 0x0017}},\n",
+		"\t\t&tls.SignatureAlgorithmsExtension{SupportedSignatureAlgorithms: []tls.SignatureScheme{0x0403, 0x0804}},\n",
+		"\t\t&tls.ALPNExtension{AlpnProtocols: []string{\"h2\", \"http/1.1\"}},\n",
+		"\t\t&tls.KeyShareExtension{KeyShares: []tls.KeyShare{{Group: tls.CurveID(tls.GREASE_PLACEHOLDER), Data: []byte{0}}, {Group: 0x001d}}},\n",
+		"\t\t&tls.PSKKeyExchangeModesExtension{Modes: []uint8{0x01}},\n",
+		"\t\t&tls.SupportedVersionsExtension{Versions: []uint16{tls.GREASE_PLACEHOLDER, 0x0304, 0x0303}},\n",
+		"\t\t&tls.RenegotiationInfoExtension{Renegotiation: tls.RenegotiateOnceAsClient},\n",
+		"\t\t&tls.GenericExtension{Id: 0x4469, Data: []byte{0x00, 0x03, 0x02, 0x68, 0x32}},\n\t\t&tls.UtlsGREASEExtension{},\n",
+		"\t\t&tls.UtlsPaddingExtension{GetPaddingLen: tls.BoringPaddingStyle},\n\t},\n}\n",
+	] {
+		assert!(spec.contains(line), "{line} missing from\n{spec}");
+	}
+}
+
+#[test]
+fn spec_describes_capture() {
+	use clienthello::codegen::ExtensionSpec;
+
+	let msg = capture().build().unwrap();
+	let spec = clienthello::parse(&msg).unwrap().to_spec();
+	assert_eq!(
+		spec.cipher_suites,
+		[None, Some(0x1301), Some(0x1302), Some(0xc02b)]
+	);
+	assert_eq!(spec.compression_methods, [0x00]);
+	assert_eq!(
+		spec.extensions,
+		[
+			ExtensionSpec::Grease,
+			ExtensionSpec::ServerName,
+			ExtensionSpec::ExtendedMasterSecret,
+			ExtensionSpec::SupportedGroups(vec![None, Some(0x001d), Some(0x0017)]),
+			ExtensionSpec::SignatureAlgorithms(vec![0x0403, 0x0804]),
+			ExtensionSpec::Alpn(vec![b"h2".to_vec(), b"http/1.1".to_vec()]),
+			ExtensionSpec::KeyShare(vec![None, Some(0x001d)]),
+			ExtensionSpec::PskKeyExchangeModes(vec![0x01]),
+			ExtensionSpec::SupportedVersions(vec![None, Some(0x0304), Some(0x0303)]),
+			ExtensionSpec::RenegotiationInfo,
+			ExtensionSpec::Generic {
+				id: 0x4469,
+				data: vec![0x00, 0x03, 0x02, 0x68, 0x32],
+			},
+			ExtensionSpec::Grease,
+			ExtensionSpec::Padding,
+		]
+	);
+	assert_eq!(
+		spec.to_utls(),
+		codegen::utls(&clienthello::parse(&msg).unwrap())
+	);
+}
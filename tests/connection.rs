@@ -0,0 +1,122 @@
+/* tests/connection.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, Connection, ConnectionError, Error, Transport};
+
+fn hello() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("example.com")
+		.alpn(&[b"h3"])
+}
+
+#[test]
+fn tcp_byte_at_a_time() {
+	let record = hello().build_record().unwrap();
+	let mut stream = record.clone();
+	stream.extend_from_slice(&[0x17, 0x03, 0x03, 0x00, 0x01, 0xaa]);
+
+	let mut conn = Connection::new();
+	let mut done = Vec::new();
+	for b in &stream {
+		done.push(conn.push_tcp(core::slice::from_ref(b)).unwrap());
+	}
+	assert_eq!(done.iter().position(|&d| d), Some(record.len() - 1));
+	assert!(conn.is_complete());
+	assert_eq!(conn.transport(), Some(Transport::TlsRecord));
+	assert_eq!(conn.as_bytes(), Some(record.as_slice()));
+	let parsed = conn.client_hello().unwrap();
+	assert_eq!(parsed.server_name(), Some("example.com"));
+	assert_eq!(parsed.transport, Transport::TlsRecord);
+}
+
+#[test]
+fn tcp_single_push_with_trailing_records() {
+	let mut stream = hello().build_record().unwrap();
+	let len = stream.len();
+	stream.extend_from_slice(&[0x14, 0x03, 0x03, 0x00, 0x01, 0x01]);
+	let mut conn = Connection::new();
+	assert!(conn.push_tcp(&stream).unwrap());
+	assert_eq!(conn.as_bytes().unwrap().len(), len);
+	assert!(conn.push_tcp(&[0xff; 8]).unwrap());
+}
+
+#[test]
+fn quic_reordered_and_retransmitted_frames() {
+	let msg = hello().pad_to(600).build().unwrap();
+	let frames: Vec<(usize, &[u8])> = msg
+		.chunks(100)
+		.enumerate()
+		.map(|(i, c)| (i * 100, c))
+		.rev()
+		.collect();
+
+	let mut conn = Connection::new();
+	assert_eq!(conn.client_hello(), None);
+	for &(offset, data) in &frames[..frames.len() - 1] {
+		assert!(!conn.push_quic_crypto(offset as u64, data).unwrap());
+		// A retransmission of the same bytes is accepted.
+		assert!(!conn.push_quic_crypto(offset as u64, data).unwrap());
+	}
+	let (offset, data) = frames[frames.len() - 1];
+	assert!(conn.push_quic_crypto(offset as u64, data).unwrap());
+	assert_eq!(conn.transport(), Some(Transport::Handshake));
+	assert_eq!(conn.as_bytes(), Some(msg.as_slice()));
+	let parsed = conn.client_hello().unwrap();
+	assert_eq!(parsed.alpn_protocols(), [b"h3"]);
+	assert_eq!(parsed.transport, Transport::Handshake);
+}
+
+#[test]
+fn quic_overlapping_frames() {
+	let msg = hello().build().unwrap();
+	let mut conn = Connection::new();
+	assert!(!conn.push_quic_crypto(10, &msg[10..40]).unwrap());
+	assert!(!conn.push_quic_crypto(0, &msg[..20]).unwrap());
+	assert!(conn.push_quic_crypto(30, &msg[30..]).unwrap());
+	assert_eq!(conn.as_bytes(), Some(msg.as_slice()));
+}
+
+#[test]
+fn errors() {
+	let msg = hello().build().unwrap();
+
+	let mut conn = Connection::new();
+	conn.push_quic_crypto(0, &msg[..10]).unwrap();
+	let mut changed = msg[5..15].to_vec();
+	changed[3] ^= 0xff;
+	assert_eq!(
+		conn.push_quic_crypto(5, &changed),
+		Err(ConnectionError::ConflictingCrypto { offset: 8 })
+	);
+	assert_eq!(conn.push_tcp(&msg), Err(ConnectionError::MixedTransports));
+	assert_eq!(
+		conn.push_quic_crypto(Connection::MAX_LEN as u64, &[0]),
+		Err(ConnectionError::TooLarge {
+			len: Connection::MAX_LEN + 1,
+			max: Connection::MAX_LEN
+		})
+	);
+
+	let mut conn = Connection::new();
+	assert_eq!(
+		conn.push_quic_crypto(0, &[0x01, 0xff, 0xff, 0xff]),
+		Err(ConnectionError::TooLarge {
+			len: 4 + 0xff_ffff,
+			max: Connection::MAX_LEN
+		})
+	);
+
+	let mut conn = Connection::new();
+	conn.push_tcp(&[0x16, 0x03, 0x01]).unwrap();
+	assert_eq!(
+		conn.push_quic_crypto(0, &msg),
+		Err(ConnectionError::MixedTransports)
+	);
+	assert_eq!(
+		conn.push_tcp(&[0x00, 0x01, 0x02]),
+		Err(ConnectionError::Parse(Error::NotClientHello(0x02.into())))
+	);
+	assert!(!conn.is_complete());
+	assert_eq!(conn.as_bytes(), None);
+}
@@ -0,0 +1,98 @@
+/* tests/db.rs */
+#![allow(missing_docs)]
+
+use clienthello::db::{DbError, FingerprintDb};
+use clienthello::{Builder, parse_from_record};
+
+fn sample() -> Vec<u8> {
+	Builder::new()
+		.legacy_version(0x0301)
+		.cipher_suites(&[47, 53, 5, 10, 49161, 49162, 49171, 49172, 50, 56, 19, 4])
+		.server_name("example.com")
+		.supported_groups(&[23, 24, 25])
+		.extension(0x000b, &[0x01, 0x00])
+		.build_record()
+		.unwrap()
+}
+
+const JA3_HASH: &str = "ada70206e40642a3e4461f35503241d5";
+const JA3_STRING: &str = "769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0";
+
+#[test]
+fn csv_headerless() {
+	let csv = format!("{JA3_HASH},legacy client\n\n# comment\n\"{JA3_STRING}\",\"other, quoted\"\n");
+	let db = FingerprintDb::from_csv(&csv).unwrap();
+	assert_eq!(db.len(), 2);
+	let msg = sample();
+	let hello = parse_from_record(&msg).unwrap();
+	assert_eq!(db.lookup(&hello), ["legacy client", "other, quoted"]);
+}
+
+#[test]
+fn csv_with_header_columns() {
+	let csv = format!(
+		"count,User-Agent,md5\r\n7,Agent A,{}\r\n3,Agent B,ffffffffffffffffffffffffffffffff\r\n",
+		JA3_HASH.to_uppercase()
+	);
+	let db = FingerprintDb::from_csv(&csv).unwrap();
+	let msg = sample();
+	assert_eq!(db.lookup(&parse_from_record(&msg).unwrap()), ["Agent A"]);
+}
+
+#[test]
+fn csv_errors() {
+	assert!(matches!(
+		FingerprintDb::from_csv("\"unterminated,label\n"),
+		Err(DbError::Csv { line: 1, .. })
+	));
+	assert!(matches!(
+		FingerprintDb::from_csv("abc,label\nonly-one-column\n"),
+		Err(DbError::Csv { line: 2, .. })
+	));
+}
+
+#[test]
+fn json_ja3er_and_ja4db() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.build_record()
+		.unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	let json = r#"[
+		{"User-Agent": "curl/8.0", "md5": "unused", "Count": 4},
+		{"application": null, "library": "tiny", "ja4_fingerprint": "T12I010000_0F2CB44170F4_000000000000"},
+		{"application": "ja4r app", "ja4_fingerprint_string": "t12i010000_1301_"},
+		{"notes": "no label", "ja4_fingerprint": "t12i010000_1301_"}
+	]"#;
+	let db = FingerprintDb::from_json(json).unwrap();
+	assert_eq!(db.len(), 3);
+	assert_eq!(db.lookup(&hello), ["tiny", "ja4r app"]);
+}
+
+#[test]
+fn json_errors() {
+	assert!(matches!(
+		FingerprintDb::from_json("{}"),
+		Err(DbError::JsonShape)
+	));
+	assert!(matches!(
+		FingerprintDb::from_json("[1]"),
+		Err(DbError::JsonShape)
+	));
+	assert!(matches!(
+		FingerprintDb::from_json("[{"),
+		Err(DbError::Json(_))
+	));
+}
+
+#[test]
+fn duplicate_labels_collapse() {
+	let mut db = FingerprintDb::new();
+	db.insert(JA3_HASH, "client");
+	db.insert(&JA3_HASH.to_uppercase(), "client");
+	db.insert(JA3_STRING, "client");
+	db.insert("", "ignored");
+	let msg = sample();
+	assert_eq!(db.lookup(&parse_from_record(&msg).unwrap()), ["client"]);
+	assert_eq!(db.len(), 2);
+}
@@ -0,0 +1,119 @@
+/* tests/early_data.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, EarlyDataRejection, PskIdentity, TicketContext, parse};
+
+const TICKET: TicketContext<'static> = TicketContext {
+	max_early_data_size: 0x4000,
+	cipher_suite: 0x1301,
+	alpn: Some(b"h2"),
+};
+
+fn resuming() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1302, 0x1301])
+		.alpn(&[b"h3", b"h2"])
+		.extension(0x002a, &[])
+		.pre_shared_key(&[(b"first", 0x0102_0304), (b"second", 7)], 32)
+}
+
+fn predict(b: &Builder, ticket: &TicketContext<'_>) -> Result<(Vec<u8>, u32), EarlyDataRejection> {
+	let msg = b.build().unwrap();
+	let hello = parse(&msg).unwrap();
+	hello
+		.early_data(ticket)
+		.map(|e| (e.alpn.unwrap_or_default().to_vec(), e.max_size))
+}
+
+#[test]
+fn psk_identities_in_wire_order() {
+	let msg = resuming().build().unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(
+		hello.psk_identities(),
+		[
+			PskIdentity {
+				identity: b"first",
+				obfuscated_ticket_age: 0x0102_0304
+			},
+			PskIdentity {
+				identity: b"second",
+				obfuscated_ticket_age: 7
+			},
+		]
+	);
+	let bare = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	assert!(parse(&bare).unwrap().psk_identities().is_empty());
+}
+
+#[test]
+fn accepted_under_first_identity() {
+	let msg = resuming().build().unwrap();
+	let hello = parse(&msg).unwrap();
+	let early = hello.early_data(&TICKET).unwrap();
+	assert_eq!(early.identity.identity, b"first");
+	assert_eq!(early.alpn, Some(&b"h2"[..]));
+	assert_eq!(early.max_size, 0x4000);
+	assert_eq!(early.cipher_suite, 0x1301);
+
+	let no_alpn = Builder::new()
+		.cipher_suites(&[0x1301])
+		.extension(0x002a, &[])
+		.pre_shared_key(&[(b"t", 0)], 32);
+	let ticket = TicketContext {
+		alpn: None,
+		..TICKET
+	};
+	assert_eq!(predict(&no_alpn, &ticket), Ok((Vec::new(), 0x4000)));
+}
+
+#[test]
+fn rejection_reasons() {
+	let not_offered = Builder::new()
+		.cipher_suites(&[0x1301])
+		.pre_shared_key(&[(b"t", 0)], 32);
+	assert_eq!(
+		predict(&not_offered, &TICKET),
+		Err(EarlyDataRejection::NotOffered)
+	);
+
+	let no_psk = Builder::new()
+		.cipher_suites(&[0x1301])
+		.extension(0x002a, &[]);
+	assert_eq!(predict(&no_psk, &TICKET), Err(EarlyDataRejection::NoPsk));
+
+	let disallowed = TicketContext {
+		max_early_data_size: 0,
+		..TICKET
+	};
+	assert_eq!(
+		predict(&resuming(), &disallowed),
+		Err(EarlyDataRejection::NotAllowedByTicket)
+	);
+
+	let other_suite = TicketContext {
+		cipher_suite: 0x1303,
+		..TICKET
+	};
+	assert_eq!(
+		predict(&resuming(), &other_suite),
+		Err(EarlyDataRejection::CipherSuite(0x1303))
+	);
+
+	let other_alpn = TicketContext {
+		alpn: Some(b"http/1.1"),
+		..TICKET
+	};
+	assert_eq!(
+		predict(&resuming(), &other_alpn),
+		Err(EarlyDataRejection::Alpn)
+	);
+	let no_ticket_alpn = TicketContext {
+		alpn: None,
+		..TICKET
+	};
+	assert_eq!(
+		predict(&resuming(), &no_ticket_alpn),
+		Err(EarlyDataRejection::Alpn)
+	);
+}
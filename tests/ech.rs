@@ -0,0 +1,318 @@
+/* tests/ech.rs */
+#![allow(missing_docs)]
+
+use clienthello::ech::{EchClientHello, EchConfig, EchError, HpkeSuite, parse_config_list};
+use clienthello::tls_reader::Reader;
+use clienthello::{Builder, Error, Extension, ParseOptions, parse, parse_from_record};
+
+/// Server public key; offers are only built here, never opened.
+const PUBLIC_KEY: [u8; 32] = [
+	0x4a, 0xba, 0x44, 0x6e, 0x2d, 0x86, 0xb5, 0x5d, 0x5b, 0x37, 0xe0, 0x9b, 0x36, 0x80, 0x57, 0x2b,
+	0x31, 0xaf, 0x2e, 0x54, 0x12, 0xe1, 0x27, 0x29, 0xe3, 0x2c, 0x3e, 0x3a, 0x1e, 0xcb, 0xde, 0x1e,
+];
+
+fn config(version: u16, kem_id: u16, suites: &[(u16, u16)], public_name: &[u8]) -> Vec<u8> {
+	let mut contents = vec![0x2a];
+	contents.extend_from_slice(&kem_id.to_be_bytes());
+	contents.extend_from_slice(&[0x00, 0x20]);
+	contents.extend_from_slice(&PUBLIC_KEY);
+	contents.extend_from_slice(&u16::try_from(suites.len() * 4).unwrap().to_be_bytes());
+	for (kdf, aead) in suites {
+		contents.extend_from_slice(&kdf.to_be_bytes());
+		contents.extend_from_slice(&aead.to_be_bytes());
+	}
+	contents.push(64);
+	contents.push(u8::try_from(public_name.len()).unwrap());
+	contents.extend_from_slice(public_name);
+	contents.extend_from_slice(&[0x00, 0x00]);
+	let mut out = version.to_be_bytes().to_vec();
+	out.extend_from_slice(&u16::try_from(contents.len()).unwrap().to_be_bytes());
+	out.extend_from_slice(&contents);
+	out
+}
+
+fn inner() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("private.example")
+		.alpn(&[b"h2"])
+		.supported_versions(&[0x0304])
+		.key_share(&[(0x001d, &[0x11; 32])])
+}
+
+fn outer() -> Builder {
+	Builder::new()
+		.random([0x33; 32])
+		.session_id(&[0x44; 32])
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("placeholder")
+		.supported_versions(&[0x0304])
+		.key_share(&[(0x001d, &[0x22; 32])])
+}
+
+#[test]
+fn parse_config() {
+	let raw = config(0xfe0d, 0x0020, &[(1, 1), (1, 3)], b"cover.example");
+	let cfg = EchConfig::parse(&raw).unwrap();
+	assert_eq!(cfg.config_id, 0x2a);
+	assert_eq!(cfg.kem_id, 0x0020);
+	assert_eq!(cfg.public_key, PUBLIC_KEY);
+	assert_eq!(
+		cfg.cipher_suites,
+		[
+			HpkeSuite {
+				kdf_id: 1,
+				aead_id: 1
+			},
+			HpkeSuite {
+				kdf_id: 1,
+				aead_id: 3
+			},
+		]
+	);
+	assert_eq!(cfg.maximum_name_length, 64);
+	assert_eq!(cfg.public_name, "cover.example");
+	assert!(cfg.extensions.is_empty());
+	assert_eq!(cfg.as_bytes(), raw.as_slice());
+}
+
+#[test]
+fn parse_config_errors() {
+	let raw = config(0xfe0a, 0x0020, &[(1, 1)], b"cover.example");
+	assert_eq!(
+		EchConfig::parse(&raw),
+		Err(EchError::UnsupportedVersion(0xfe0a))
+	);
+
+	let raw = config(0xfe0d, 0x0020, &[(1, 1)], b"");
+	assert!(matches!(
+		EchConfig::parse(&raw),
+		Err(EchError::Malformed(_))
+	));
+
+	let mut raw = config(0xfe0d, 0x0020, &[(1, 1)], b"cover.example");
+	raw.push(0);
+	assert!(matches!(
+		EchConfig::parse(&raw),
+		Err(EchError::Malformed(_))
+	));
+
+	let raw = config(0xfe0d, 0x0020, &[(1, 1)], b"cover.example");
+	assert!(matches!(
+		EchConfig::parse(&raw[..raw.len() - 1]),
+		Err(EchError::Tls(Error::Truncated { .. }))
+	));
+}
+
+fn list(configs: &[&[u8]]) -> Vec<u8> {
+	let len: usize = configs.iter().map(|c| c.len()).sum();
+	let mut out = u16::try_from(len).unwrap().to_be_bytes().to_vec();
+	configs.iter().for_each(|c| out.extend_from_slice(c));
+	out
+}
+
+#[test]
+fn parse_config_list_skips_unknown_versions() {
+	let draft = config(0xfe0a, 0x0020, &[(1, 1)], b"old.example");
+	let first = config(0xfe0d, 0x0020, &[(1, 1)], b"first.example");
+	let second = config(0xfe0d, 0x0010, &[(1, 2)], b"second.example");
+	let data = list(&[&draft, &first, &second]);
+	let configs = parse_config_list(&data).unwrap();
+	let names: Vec<&str> = configs.iter().map(|c| c.public_name).collect();
+	assert_eq!(names, ["first.example", "second.example"]);
+	assert_eq!(configs[0].as_bytes(), first.as_slice());
+	assert_eq!(configs[1].kem_id, 0x0010);
+
+	let data = list(&[&draft]);
+	assert_eq!(parse_config_list(&data), Ok(Vec::new()));
+}
+
+#[test]
+fn parse_config_list_errors() {
+	assert!(matches!(
+		parse_config_list(&[0x00, 0x00]),
+		Err(EchError::Malformed(_))
+	));
+	assert!(matches!(
+		parse_config_list(&[0x00]),
+		Err(EchError::Tls(Error::Truncated { .. }))
+	));
+
+	let good = config(0xfe0d, 0x0020, &[(1, 1)], b"cover.example");
+	let mut data = list(&[&good]);
+	data.push(0);
+	assert!(matches!(
+		parse_config_list(&data),
+		Err(EchError::Malformed(_))
+	));
+
+	let bad = config(0xfe0d, 0x0020, &[(1, 1)], b"");
+	let data = list(&[&good, &bad]);
+	assert!(matches!(
+		parse_config_list(&data),
+		Err(EchError::Malformed(_))
+	));
+
+	// the list length overruns into a truncated config
+	let mut data = list(&[&good]);
+	data.truncate(data.len() - 1);
+	data[1] -= 1;
+	assert!(matches!(
+		parse_config_list(&data),
+		Err(EchError::Tls(Error::Truncated { .. }))
+	));
+}
+
+#[test]
+fn build_ech_outer_shape() {
+	let raw = config(0xfe0d, 0x0020, &[(1, 3), (1, 2)], b"cover.example");
+	let cfg = EchConfig::parse(&raw).unwrap();
+	let msg = outer().build_ech(&inner(), &cfg, [0x55; 32]).unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.server_name(), Some("cover.example"));
+	assert_eq!(hello.session_id, &[0x44; 32]);
+	assert_eq!(hello.key_share_groups(), [0x001d]);
+
+	let mut r = Reader::new(hello.find_extension(0xfe0d).unwrap());
+	assert_eq!(r.read_u8("type").unwrap(), 0x00);
+	// ChaCha20-Poly1305 is skipped for AES-256-GCM
+	assert_eq!(r.read_u16("kdf").unwrap(), 0x0001);
+	assert_eq!(r.read_u16("aead").unwrap(), 0x0002);
+	assert_eq!(r.read_u8("config_id").unwrap(), 0x2a);
+	assert_eq!(r.read_vec_u16("enc").unwrap().len(), 32);
+	let payload = r.read_vec_u16("payload").unwrap();
+	assert!(r.is_empty());
+	// padded to a multiple of 32, plus the AEAD tag
+	assert_eq!(payload.len() % 32, 16);
+
+	// deterministic for a fixed ephemeral key
+	let again = outer().build_ech(&inner(), &cfg, [0x55; 32]).unwrap();
+	assert_eq!(msg, again);
+	let other = outer().build_ech(&inner(), &cfg, [0x56; 32]).unwrap();
+	assert_ne!(msg, other);
+
+	let rec = outer()
+		.build_ech_record(&inner(), &cfg, [0x55; 32])
+		.unwrap();
+	assert_eq!(&rec[5..], msg.as_slice());
+	assert!(parse_from_record(&rec).is_ok());
+}
+
+#[test]
+fn build_ech_padding_hides_name_length() {
+	let raw = config(0xfe0d, 0x0020, &[(1, 1)], b"cover.example");
+	let cfg = EchConfig::parse(&raw).unwrap();
+	let payload_len = |host: &str| {
+		let msg = outer()
+			.build_ech(&inner().server_name(host), &cfg, [0x55; 32])
+			.unwrap();
+		parse(&msg).unwrap().find_extension(0xfe0d).unwrap().len()
+	};
+	assert_eq!(
+		payload_len("a.example"),
+		payload_len("a-longer-name.example")
+	);
+}
+
+#[test]
+fn build_ech_unsupported_config() {
+	let raw = config(0xfe0d, 0x0010, &[(1, 1)], b"cover.example");
+	let cfg = EchConfig::parse(&raw).unwrap();
+	assert_eq!(
+		outer().build_ech(&inner(), &cfg, [0x55; 32]),
+		Err(EchError::UnsupportedKem(0x0010))
+	);
+
+	let raw = config(0xfe0d, 0x0020, &[(1, 3), (2, 1)], b"cover.example");
+	let cfg = EchConfig::parse(&raw).unwrap();
+	assert_eq!(
+		outer().build_ech(&inner(), &cfg, [0x55; 32]),
+		Err(EchError::UnsupportedSuite)
+	);
+}
+
+#[test]
+fn ech_extension_decodes_outer_offer() {
+	let raw = config(0xfe0d, 0x0020, &[(1, 1)], b"cover.example");
+	let cfg = EchConfig::parse(&raw).unwrap();
+	let msg = outer().build_ech(&inner(), &cfg, [0x55; 32]).unwrap();
+	let hello = parse(&msg).unwrap();
+	let Some(EchClientHello::Outer {
+		cipher_suite,
+		config_id,
+		enc,
+		payload,
+	}) = hello.encrypted_client_hello()
+	else {
+		panic!("expected an outer offer");
+	};
+	assert_eq!(
+		*cipher_suite,
+		HpkeSuite {
+			kdf_id: 0x0001,
+			aead_id: 0x0001
+		}
+	);
+	assert_eq!(*config_id, 0x2a);
+	assert_eq!(enc.len(), 32);
+	assert_eq!(payload.len() % 32, 16);
+
+	// the raw body stays reachable and round-trips through the owned form
+	let body = hello.find_extension(0xfe0d).unwrap();
+	assert_eq!(
+		EchClientHello::parse(body).as_ref(),
+		Ok(hello.encrypted_client_hello().unwrap())
+	);
+	let ext = hello.extensions.by_type(0xfe0d).unwrap();
+	assert_eq!(ext.clone().into_owned().as_extension(), *ext);
+}
+
+#[test]
+fn ech_extension_inner_and_unknown_types() {
+	let msg = Builder::new().extension(0xfe0d, &[0x01]).build().unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.encrypted_client_hello(), Some(&EchClientHello::Inner));
+
+	let msg = Builder::new()
+		.extension(0xfe0d, &[0x07, 0xaa])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.encrypted_client_hello(), None);
+	assert_eq!(
+		hello.extensions.by_type(0xfe0d),
+		Some(&Extension::Unknown {
+			type_id: 0xfe0d,
+			data: &[0x07, 0xaa]
+		})
+	);
+	assert_eq!(
+		EchClientHello::parse(&[0x07]),
+		Err(EchError::Malformed("ECHClientHello type"))
+	);
+}
+
+#[test]
+fn ech_extension_undecodable_offer_kept_raw() {
+	let body = [0x00, 0x00, 0x01, 0x00, 0x01, 0x2a, 0x00, 0x20, 0x00];
+	let msg = Builder::new().extension(0xfe0d, &body).build().unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.encrypted_client_hello(), None);
+	assert_eq!(
+		hello.extensions.by_type(0xfe0d),
+		Some(&Extension::Unknown {
+			type_id: 0xfe0d,
+			data: &body
+		})
+	);
+
+	let hello = ParseOptions::new()
+		.recover_malformed_extensions(true)
+		.parse(&msg)
+		.unwrap();
+	assert!(matches!(
+		hello.extensions.by_type(0xfe0d),
+		Some(Extension::Malformed { raw, .. }) if *raw == body
+	));
+	assert_eq!(hello.find_extension(0xfe0d), Some(&body[..]));
+}
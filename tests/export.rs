@@ -0,0 +1,72 @@
+/* tests/export.rs */
+#![allow(missing_docs)]
+
+use clienthello::export::{self, ExportError, SCHEMA_VERSION};
+use clienthello::{Builder, Extension, ParseOptions, parse};
+
+#[test]
+fn round_trip_full_hello() {
+	let msg = Builder::new()
+		.random([0x42; 32])
+		.cipher_suites(&[0x0A0A, 0x1301, 0x1302])
+		.server_name("example.com")
+		.alpn(&[b"h2"])
+		.supported_versions(&[0x0304])
+		.key_share(&[(0x001d, &[0xEE; 32])])
+		.extension(0x0042, &[0xDE, 0xAD])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	let bytes = export::to_bincode(&hello).unwrap();
+	let back = export::from_bincode(&bytes).unwrap();
+	assert_eq!(back, hello);
+	assert_eq!(back.server_name(), Some("example.com"));
+}
+
+#[test]
+fn round_trip_recovered_hello() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.extension(0x0010, &[0x00, 0x05, 0x02, b'h'])
+		.server_name("example.com")
+		.build()
+		.unwrap();
+	let hello = ParseOptions::new()
+		.recover_malformed_extensions(true)
+		.parse(&msg)
+		.unwrap();
+	assert!(matches!(hello.extensions[0], Extension::Malformed { .. }));
+	let bytes = export::to_bincode(&hello).unwrap();
+	assert_eq!(export::from_bincode(&bytes).unwrap(), hello);
+}
+
+#[test]
+fn envelope_starts_with_schema_version() {
+	let msg = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	let bytes = export::to_bincode(&parse(&msg).unwrap()).unwrap();
+	assert_eq!(&bytes[..2], &SCHEMA_VERSION.to_le_bytes());
+}
+
+#[test]
+fn rejects_other_schema_version() {
+	let msg = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	let mut bytes = export::to_bincode(&parse(&msg).unwrap()).unwrap();
+	bytes[..2].copy_from_slice(&99u16.to_le_bytes());
+	assert!(matches!(
+		export::from_bincode(&bytes),
+		Err(ExportError::SchemaVersion {
+			found: 99,
+			expected: SCHEMA_VERSION
+		})
+	));
+}
+
+#[test]
+fn rejects_truncated_input() {
+	let msg = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	let bytes = export::to_bincode(&parse(&msg).unwrap()).unwrap();
+	assert!(matches!(
+		export::from_bincode(&bytes[..bytes.len() - 1]),
+		Err(ExportError::Bincode(_))
+	));
+}
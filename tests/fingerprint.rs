@@ -0,0 +1,148 @@
+/* tests/fingerprint.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, MultiFingerprint, ParseOptions, parse};
+use sha2::{Sha256, Sha512};
+
+fn chrome_like() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x0A0A, 0x1301, 0x1302, 0x1303])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x3A3A, 0x0304, 0x0303])
+		.supported_groups(&[0x001d, 0x0017])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.key_share(&[(0x001d, &[0xEE; 32])])
+}
+
+#[test]
+fn fingerprint_ignores_per_connection_fields() {
+	let a = chrome_like().random([0x01; 32]).build().unwrap();
+	let b = chrome_like()
+		.random([0x02; 32])
+		.session_id(&[0xCD; 32])
+		.server_name("other.example")
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.build()
+		.unwrap();
+	let a = parse(&a).unwrap();
+	let b = parse(&b).unwrap();
+	assert_eq!(
+		a.fingerprint_with::<Sha256>(),
+		b.fingerprint_with::<Sha256>()
+	);
+}
+
+#[test]
+fn fingerprint_changes_with_cipher_suites() {
+	let a = chrome_like().build().unwrap();
+	let b = chrome_like().cipher_suites(&[0x1301]).build().unwrap();
+	let a = parse(&a).unwrap();
+	let b = parse(&b).unwrap();
+	assert_ne!(
+		a.fingerprint_with::<Sha256>(),
+		b.fingerprint_with::<Sha256>()
+	);
+}
+
+#[test]
+fn fingerprint_output_size_follows_digest() {
+	let msg = chrome_like().build().unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.fingerprint_with::<Sha512>().len(), 64);
+}
+
+#[test]
+fn canonicalize_ignores_order_and_volatile_fields() {
+	let a = chrome_like()
+		.random([0x01; 32])
+		.session_id(&[0xCD; 32])
+		.pre_shared_key(&[(b"ticket", 7)], 32)
+		.build_with_binders(|_, _, binder| binder.fill(0xAA))
+		.unwrap();
+	let b = Builder::new()
+		.cipher_suites(&[0x0A0A, 0x1301, 0x1302, 0x1303])
+		.random([0x02; 32])
+		.session_id(&[0x5A; 32])
+		.key_share(&[(0x2A2A, &[0]), (0x001d, &[0x11; 32])])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.supported_groups(&[0x001d, 0x0017])
+		.supported_versions(&[0x3A3A, 0x0304, 0x0303])
+		.alpn(&[b"h2", b"http/1.1"])
+		.server_name("example.com")
+		.extension(0x1A1A, &[])
+		.pre_shared_key(&[(b"ticket", 7)], 32)
+		.build_with_binders(|_, _, binder| binder.fill(0xBB))
+		.unwrap();
+	let a = parse(&a).unwrap().canonicalize();
+	assert_eq!(a, parse(&b).unwrap().canonicalize());
+
+	let canonical = parse(&a).unwrap();
+	assert_eq!(canonical.random, [0; 32]);
+	assert_eq!(canonical.session_id, [0; 32]);
+	assert!(!canonical.has_grease);
+	assert_eq!(
+		canonical.extensions.type_ids().collect::<Vec<_>>(),
+		[0x0000, 0x000a, 0x000d, 0x0010, 0x0029, 0x002b, 0x0033]
+	);
+	assert_eq!(canonical.key_share_entries()[0].key, [0; 32]);
+	let psk = canonical.find_extension(0x0029).unwrap();
+	assert_eq!(&psk[psk.len() - 32..], [0; 32]);
+	assert!(psk.windows(6).any(|w| w == b"ticket"));
+}
+
+#[test]
+fn canonicalize_keeps_cipher_suite_order() {
+	let a = chrome_like().build().unwrap();
+	let b = chrome_like()
+		.cipher_suites(&[0x1303, 0x1302, 0x1301])
+		.build()
+		.unwrap();
+	assert_ne!(
+		parse(&a).unwrap().canonicalize(),
+		parse(&b).unwrap().canonicalize()
+	);
+}
+
+#[test]
+fn canonicalize_drops_reserved_key_shares() {
+	let opts = ParseOptions::new().reserved_values(|v| v & 0xff00 == 0xff00);
+	let a = chrome_like()
+		.supported_groups(&[0xff47, 0x001d, 0x0017])
+		.key_share(&[(0xff47, &[0x00]), (0x001d, &[0xEE; 32])])
+		.build()
+		.unwrap();
+	let b = chrome_like().build().unwrap();
+	let a = opts.parse(&a).unwrap().canonicalize();
+	assert_eq!(a, opts.parse(&b).unwrap().canonicalize());
+
+	let canonical = parse(&a).unwrap();
+	let groups: Vec<u16> = canonical
+		.key_share_entries()
+		.iter()
+		.map(|e| e.group)
+		.collect();
+	assert_eq!(groups, [0x001d]);
+}
+
+#[test]
+fn multi_fingerprint_hash_ignores_order() {
+	let shuffled = Builder::new()
+		.cipher_suites(&[0x0A0A, 0x1301, 0x1302, 0x1303])
+		.key_share(&[(0x001d, &[0xEE; 32])])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.supported_groups(&[0x001d, 0x0017])
+		.supported_versions(&[0x3A3A, 0x0304, 0x0303])
+		.alpn(&[b"h2", b"http/1.1"])
+		.server_name("example.com");
+	let mut a = MultiFingerprint::new();
+	assert!(a.fingerprint_with::<Sha256>().is_none());
+	a.observe(&parse(&chrome_like().build().unwrap()).unwrap());
+	let mut b = MultiFingerprint::new();
+	b.observe(&parse(&shuffled.build().unwrap()).unwrap());
+	assert_eq!(
+		a.fingerprint_with::<Sha256>(),
+		b.fingerprint_with::<Sha256>()
+	);
+	assert!(a.fingerprint_with::<Sha256>().is_some());
+}
@@ -0,0 +1,69 @@
+/* tests/fixed.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, ClientHelloFixed, Error, parse};
+
+fn browser_like() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x2a2a, 0x1301, 0x1302, 0xc02b])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0a0a, 0x0304, 0x0303])
+		.extension(0x0042, &[0xde, 0xad])
+}
+
+#[test]
+fn matches_full_parse() {
+	let msg = browser_like().build().unwrap();
+	let full = parse(&msg).unwrap();
+	let fixed = ClientHelloFixed::<8, 8>::parse(&msg).unwrap();
+	assert_eq!(fixed.legacy_version, full.legacy_version);
+	assert_eq!(fixed.random, full.random);
+	assert_eq!(
+		fixed.cipher_suites.as_slice(),
+		full.cipher_suites.as_slice()
+	);
+	assert_eq!(fixed.has_grease, full.has_grease);
+	let ids: Vec<u16> = fixed.extensions.iter().map(|e| e.type_id).collect();
+	assert_eq!(ids, full.extensions.type_ids().collect::<Vec<_>>());
+	assert_eq!(fixed.server_name(), full.server_name());
+	assert!(
+		fixed
+			.alpn_protocols()
+			.eq(full.alpn_protocols().iter().copied())
+	);
+	assert!(fixed.supported_versions().eq([0x0304, 0x0303]));
+	assert_eq!(fixed.find_extension(0x0042), Some([0xde, 0xad].as_slice()));
+	assert!(!fixed.extensions_overflowed);
+	assert!(!fixed.cipher_suites_overflowed);
+}
+
+#[test]
+fn record_input() {
+	let msg = browser_like().build_record().unwrap();
+	let fixed = ClientHelloFixed::<8, 8>::parse_from_record(&msg).unwrap();
+	assert_eq!(fixed.server_name(), Some("example.com"));
+}
+
+#[test]
+fn overflow_is_flagged_not_fatal() {
+	let msg = browser_like().build().unwrap();
+	let fixed = ClientHelloFixed::<2, 1>::parse(&msg).unwrap();
+	assert_eq!(fixed.cipher_suites.as_slice(), [0x1301]);
+	assert!(fixed.cipher_suites_overflowed);
+	assert_eq!(fixed.extensions.len(), 2);
+	assert!(fixed.extensions_overflowed);
+	// SNI came first, so it is still available.
+	assert_eq!(fixed.server_name(), Some("example.com"));
+	assert!(fixed.supported_versions().next().is_none());
+}
+
+#[test]
+fn truncated_input_errors() {
+	let msg = browser_like().build().unwrap();
+	let result = ClientHelloFixed::<8, 8>::parse(&msg[..msg.len() - 1]);
+	assert!(matches!(
+		result,
+		Err(Error::Truncated { .. } | Error::BufferTooShort { .. })
+	));
+}
@@ -0,0 +1,128 @@
+/* tests/heuristics.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, ClientFamily, Confidence, ProbableClient, parse_from_record};
+
+fn guess(builder: &Builder) -> ProbableClient {
+	let msg = builder.build_record().unwrap();
+	parse_from_record(&msg).unwrap().probable_client()
+}
+
+#[test]
+fn chromium_shuffled_with_alps() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x2a2a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f])
+		.extension(0x0a0a, &[])
+		.extension(0x44cd, &[0x00, 0x03, 0x02, b'h', b'2'])
+		.supported_versions(&[0x0304, 0x0303])
+		.extension(0x001b, &[0x02, 0x00, 0x02])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.psk_exchange_modes(&[0x01])
+		.supported_groups(&[0x11ec, 0x001d, 0x0017]);
+	assert_eq!(
+		guess(&builder),
+		ProbableClient {
+			family: ClientFamily::Chromium,
+			confidence: Confidence::High,
+		}
+	);
+}
+
+#[test]
+fn safari_fixed_order_with_grease() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x4a4a, 0x1301, 0x1302, 0x1303, 0xc02c, 0xc02b, 0xcca9])
+		.extension(0x1a1a, &[])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.renegotiation_info(&[])
+		.supported_groups(&[0x001d, 0x0017, 0x0018])
+		.extension(0x000b, &[0x01, 0x00])
+		.alpn(&[b"h2", b"http/1.1"])
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.extension(0x0012, &[])
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.psk_exchange_modes(&[0x01])
+		.supported_versions(&[0x0304, 0x0303])
+		.extension(0x001b, &[0x02, 0x00, 0x01]);
+	assert_eq!(
+		guess(&builder),
+		ProbableClient {
+			family: ClientFamily::Safari,
+			confidence: Confidence::High,
+		}
+	);
+}
+
+#[test]
+fn firefox_key_shares_and_extensions() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1301, 0x1303, 0x1302, 0xc02b, 0xc02f])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.supported_groups(&[0x001d, 0x0017, 0x0018])
+		.key_share(&[(0x001d, &[0x11; 32]), (0x0017, &[0x04; 65])])
+		.supported_versions(&[0x0304, 0x0303])
+		.psk_exchange_modes(&[0x01])
+		.extension(0x001c, &[0x40, 0x01]);
+	assert_eq!(
+		guess(&builder),
+		ProbableClient {
+			family: ClientFamily::Firefox,
+			confidence: Confidence::Medium,
+		}
+	);
+	let builder = builder.extension(0x0022, &[0x00, 0x02, 0x04, 0x03]);
+	assert_eq!(guess(&builder).confidence, Confidence::High);
+}
+
+#[test]
+fn go_extension_order() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f])
+		.server_name("example.com")
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.supported_groups(&[0x001d, 0x0017, 0x0018, 0x0019])
+		.extension(0x000b, &[0x01, 0x00])
+		.signature_algorithms(&[0x0804, 0x0403])
+		.renegotiation_info(&[])
+		.supported_versions(&[0x0304, 0x0303])
+		.key_share(&[(0x001d, &[0x22; 32])]);
+	assert_eq!(guess(&builder).family, ClientFamily::Go);
+}
+
+#[test]
+fn openssl_defaults() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1302, 0x1303, 0x1301, 0xc02c, 0xc030, 0x009f])
+		.server_name("example.com")
+		.extension(0x000b, &[0x03, 0x00, 0x01, 0x02])
+		.extension(0x0016, &[])
+		.extension(0x0017, &[])
+		.supported_versions(&[0x0304, 0x0303])
+		.psk_exchange_modes(&[0x01])
+		.extension(0x0031, &[]);
+	assert_eq!(
+		guess(&builder),
+		ProbableClient {
+			family: ClientFamily::OpenSsl,
+			confidence: Confidence::High,
+		}
+	);
+}
+
+#[test]
+fn weak_evidence_is_unknown() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name("example.com");
+	assert_eq!(
+		guess(&builder),
+		ProbableClient {
+			family: ClientFamily::Unknown,
+			confidence: Confidence::Low,
+		}
+	);
+}
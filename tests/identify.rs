@@ -0,0 +1,207 @@
+/* tests/identify.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, Candidate, ClientKind, parse, parse_from_record};
+
+/// Chrome hello with the extensions behind `..._e5627efa2ab1`.
+fn chrome() -> Builder {
+	Builder::new()
+		.cipher_suites(&[
+			0x1a1a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8, 0xc013,
+			0xc014, 0x009c, 0x009d, 0x002f, 0x0035,
+		])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.renegotiation_info(&[])
+		.supported_groups(&[0x001d, 0x0017, 0x0018])
+		.extension(0x000b, &[0x01, 0x00])
+		.extension(0x0023, &[])
+		.alpn(&[b"h2", b"http/1.1"])
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.signature_algorithms(&[
+			0x0403, 0x0804, 0x0401, 0x0503, 0x0805, 0x0501, 0x0806, 0x0601,
+		])
+		.extension(0x0012, &[])
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.psk_exchange_modes(&[0x01])
+		.supported_versions(&[0x0304, 0x0303])
+		.extension(0x001b, &[0x02, 0x00, 0x02])
+		.extension(0x4469, &[0x00, 0x03, 0x02, b'h', b'2'])
+		.extension(0x0015, &[0x00; 4])
+}
+
+/// Go `crypto/tls` client hello with its default configuration.
+fn go() -> Builder {
+	Builder::new()
+		.cipher_suites(&[
+			0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8, 0xc009, 0xc013,
+			0xc00a, 0xc014,
+		])
+		.server_name("example.com")
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.supported_groups(&[0x001d, 0x0017, 0x0018, 0x0019])
+		.extension(0x000b, &[0x01, 0x00])
+		.signature_algorithms(&[
+			0x0804, 0x0403, 0x0807, 0x0805, 0x0806, 0x0401, 0x0501, 0x0601, 0x0503, 0x0603, 0x0201,
+			0x0203,
+		])
+		.renegotiation_info(&[])
+		.alpn(&[b"h2", b"http/1.1"])
+		.extension(0x0012, &[])
+		.extension(0x0017, &[])
+		.supported_versions(&[0x0304, 0x0303])
+		.key_share(&[(0x001d, &[0x22; 32])])
+}
+
+/// Java 17 `HttpClient` hello with its default JSSE configuration.
+fn java() -> Builder {
+	Builder::new()
+		.cipher_suites(&[
+			0x1302, 0x1301, 0x1303, 0xc02c, 0xc02b, 0xcca9, 0xc030, 0xcca8, 0xc02f, 0x009f, 0xccaa,
+			0x00a3, 0x009e, 0x00a2, 0xc024, 0xc028, 0xc023, 0xc027, 0x006b, 0x006a, 0x0067, 0x0040,
+			0xc00a, 0xc014, 0xc009, 0xc013, 0x0039, 0x0038, 0x0033, 0x0032, 0x009d, 0x009c, 0x003d,
+			0x003c, 0x0035, 0x002f, 0x00ff,
+		])
+		.server_name("example.com")
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.supported_groups(&[
+			0x001d, 0x0017, 0x0018, 0x0019, 0x001e, 0x0100, 0x0101, 0x0102, 0x0103, 0x0104,
+		])
+		.extension(0x000b, &[0x01, 0x00])
+		.alpn(&[b"h2", b"http/1.1"])
+		.extension(
+			0x0011,
+			&[0x00, 0x07, 0x02, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00],
+		)
+		.extension(0x0017, &[])
+		.extension(0x0023, &[])
+		.signature_algorithms(&[
+			0x0403, 0x0503, 0x0603, 0x0807, 0x0808, 0x0804, 0x0805, 0x0806, 0x0809, 0x080a, 0x080b,
+			0x0401, 0x0501, 0x0601, 0x0402, 0x0303, 0x0301, 0x0302, 0x0203, 0x0201, 0x0202,
+		])
+		.supported_versions(&[0x0304, 0x0303])
+		.psk_exchange_modes(&[0x01])
+		.extension(0x0032, &[0x00, 0x02, 0x04, 0x03])
+		.key_share(&[(0x001d, &[0x33; 32])])
+}
+
+#[test]
+fn exact_match_scores_100() {
+	let msg = chrome().build_record().unwrap();
+	let candidates = parse_from_record(&msg).unwrap().identify();
+	assert_eq!(
+		candidates[0],
+		Candidate {
+			name: "Chrome",
+			kind: ClientKind::Browser,
+			score: 100
+		}
+	);
+	assert_eq!(candidates.len(), 1);
+}
+
+#[test]
+fn quic_hello_matches_tcp_entry() {
+	let msg = chrome().build().unwrap();
+	let candidates = parse(&msg).unwrap().identify();
+	assert_eq!(candidates[0].name, "Chrome");
+	assert_eq!(candidates[0].score, 100);
+}
+
+#[test]
+fn partial_matches_are_ranked() {
+	let msg = go().build_record().unwrap();
+	let candidates = parse_from_record(&msg).unwrap().identify();
+	let ranked: Vec<_> = candidates.iter().map(|c| (c.name, c.score)).collect();
+	assert_eq!(
+		ranked,
+		[
+			("Go crypto/tls", 100),
+			("Go crypto/tls (pre-1.22 cipher suites)", 30),
+		]
+	);
+	assert_eq!(candidates[0].kind, ClientKind::Library);
+}
+
+#[test]
+fn java_matches_jsse_entry() {
+	let msg = java().build_record().unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert_eq!(
+		hello.identify(),
+		[Candidate {
+			name: "Java JSSE",
+			kind: ClientKind::Library,
+			score: 100
+		}]
+	);
+	// Without ALPN only the prefix differs from the HttpClient entry.
+	let msg = java().alpn(&[]).build_record().unwrap();
+	assert_eq!(
+		parse_from_record(&msg).unwrap().identify()[0].name,
+		"Java JSSE"
+	);
+}
+
+#[test]
+fn changed_extensions_keep_cipher_match() {
+	let msg = chrome().extension(0x0042, &[]).build_record().unwrap();
+	let candidates = parse_from_record(&msg).unwrap().identify();
+	assert_eq!(candidates[0].name, "Chrome");
+	assert_eq!(candidates[0].score, 50);
+}
+
+#[test]
+fn unknown_client_has_no_candidates() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.build_record()
+		.unwrap();
+	assert!(parse_from_record(&msg).unwrap().identify().is_empty());
+}
+
+#[test]
+fn lookup_prefers_exact_match() {
+	let msg = go().build_record().unwrap();
+	let best = parse_from_record(&msg).unwrap().lookup_fingerprint();
+	assert_eq!(
+		best,
+		Some(Candidate {
+			name: "Go crypto/tls",
+			kind: ClientKind::Library,
+			score: 100
+		})
+	);
+}
+
+#[test]
+fn lookup_falls_back_to_ja3n() {
+	// Other signature algorithms change the JA4 but not the JA3N.
+	let msg = chrome()
+		.signature_algorithms(&[0x0403, 0x0804])
+		.build_record()
+		.unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert_eq!(hello.identify()[0].score, 70);
+	let best = hello.lookup_fingerprint().unwrap();
+	assert_eq!((best.name, best.score), ("Chrome", 100));
+}
+
+#[test]
+fn lookup_returns_best_partial_match() {
+	let msg = chrome().extension(0x0042, &[]).build_record().unwrap();
+	let best = parse_from_record(&msg)
+		.unwrap()
+		.lookup_fingerprint()
+		.unwrap();
+	assert_eq!((best.name, best.score), ("Chrome", 50));
+}
+
+#[test]
+fn lookup_unknown_client() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.build_record()
+		.unwrap();
+	assert_eq!(parse_from_record(&msg).unwrap().lookup_fingerprint(), None);
+}
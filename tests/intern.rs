@@ -0,0 +1,65 @@
+/* tests/intern.rs */
+#![allow(missing_docs)]
+
+use std::sync::Arc;
+
+use clienthello::{Builder, Interner, parse};
+
+fn hello_bytes(sni: &str) -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name(sni)
+		.alpn(&[b"h2", b"http/1.1"])
+		.build()
+		.unwrap()
+}
+
+#[test]
+fn names_are_shared_across_hellos() {
+	let mut interner = Interner::new();
+	let a = hello_bytes("example.com");
+	let b = hello_bytes("example.com");
+	let first = interner.server_name(&parse(&a).unwrap()).unwrap();
+	let second = interner.server_name(&parse(&b).unwrap()).unwrap();
+	assert!(Arc::ptr_eq(&first, &second));
+
+	let alpn_a = interner.alpn_protocols(&parse(&a).unwrap());
+	let alpn_b = interner.alpn_protocols(&parse(&b).unwrap());
+	assert_eq!(alpn_a.len(), 2);
+	assert_eq!(&*alpn_a[1], b"http/1.1");
+	assert!(alpn_a.iter().zip(&alpn_b).all(|(x, y)| Arc::ptr_eq(x, y)));
+	assert_eq!(interner.len(), 3);
+}
+
+#[test]
+fn distinct_values_are_kept_apart() {
+	let mut interner = Interner::new();
+	let a = interner.intern_str("a.example");
+	let b = interner.intern_str("b.example");
+	assert!(!Arc::ptr_eq(&a, &b));
+	assert_eq!(interner.len(), 2);
+}
+
+#[test]
+fn purge_drops_unreferenced_values() {
+	let mut interner = Interner::new();
+	let kept = interner.intern_str("kept.example");
+	drop(interner.intern_str("dropped.example"));
+	drop(interner.intern_bytes(b"h2"));
+	interner.purge();
+	assert_eq!(interner.len(), 1);
+	assert!(Arc::ptr_eq(&kept, &interner.intern_str("kept.example")));
+	interner.clear();
+	assert!(interner.is_empty());
+	assert_eq!(&*kept, "kept.example");
+}
+
+#[test]
+fn hello_without_names() {
+	let msg = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	let mut interner = Interner::new();
+	let hello = parse(&msg).unwrap();
+	assert!(interner.server_name(&hello).is_none());
+	assert!(interner.alpn_protocols(&hello).is_empty());
+	assert!(interner.is_empty());
+}
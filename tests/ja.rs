@@ -0,0 +1,305 @@
+/* tests/ja.rs */
+#![allow(missing_docs)]
+
+use clienthello::{
+	Builder, FingerprintError, Ja3Version, Ja4Version, fingerprints, fingerprints_from_record,
+	fingerprints_from_record_with, parse, parse_from_record,
+};
+
+/// Hello matching the JA3 reference example from the salesforce/ja3
+/// README.
+fn ja3_reference() -> Vec<u8> {
+	Builder::new()
+		.legacy_version(0x0301)
+		.cipher_suites(&[47, 53, 5, 10, 49161, 49162, 49171, 49172, 50, 56, 19, 4])
+		.server_name("example.com")
+		.supported_groups(&[0x0a0a, 23, 24, 25])
+		.extension(0x000b, &[0x01, 0x00])
+		.build_record()
+		.unwrap()
+}
+
+/// Hello matching the Chrome example in the FoxIO JA4 documentation.
+fn ja4_reference() -> Builder {
+	Builder::new()
+		.cipher_suites(&[
+			0x1a1a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f, 0xc02c, 0xc030, 0xcca9, 0xcca8, 0xc013,
+			0xc014, 0x009c, 0x009d, 0x002f, 0x0035,
+		])
+		.extension(0x2a2a, &[])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.renegotiation_info(&[])
+		.supported_groups(&[0x001d, 0x0017, 0x0018])
+		.extension(0x000b, &[0x01, 0x00])
+		.extension(0x0023, &[])
+		.alpn(&[b"h2", b"http/1.1"])
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.signature_algorithms(&[
+			0x0403, 0x0804, 0x0401, 0x0503, 0x0805, 0x0501, 0x0806, 0x0601,
+		])
+		.extension(0x0012, &[])
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.psk_exchange_modes(&[0x01])
+		.supported_versions(&[0x0304, 0x0303])
+		.extension(0x001b, &[0x02, 0x00, 0x02])
+		.extension(0x4469, &[0x00, 0x03, 0x02, b'h', b'2'])
+		.extension(0x0015, &[0x00; 4])
+}
+
+#[test]
+fn ja3_string_and_hash() {
+	let msg = ja3_reference();
+	let hello = parse_from_record(&msg).unwrap();
+	assert!(
+		hello.matches_ja3("769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0")
+	);
+	assert!(hello.matches_ja3("ada70206e40642a3e4461f35503241d5"));
+	assert_eq!(
+		hello.ja3(),
+		"769,47-53-5-10-49161-49162-49171-49172-50-56-19-4,0-10-11,23-24-25,0"
+	);
+	assert_eq!(hello.ja3_hash(), "ada70206e40642a3e4461f35503241d5");
+	assert_eq!(hello.ec_point_formats(), [0x00]);
+	assert!(hello.matches_ja3("  ADA70206E40642A3E4461F35503241D5\n"));
+	assert!(!hello.matches_ja3("769,47-53,0-10-11,23-24-25,0"));
+	assert!(!hello.matches_ja3("00000000000000000000000000000000"));
+}
+
+#[test]
+fn ja3n_ignores_extension_order() {
+	let hello = Builder::new()
+		.cipher_suites(&[0x1301, 0x1302])
+		.supported_versions(&[0x0304])
+		.server_name("example.com")
+		.supported_groups(&[29, 23]);
+	let shuffled = Builder::new()
+		.cipher_suites(&[0x1301, 0x1302])
+		.supported_groups(&[29, 23])
+		.server_name("example.com")
+		.supported_versions(&[0x0304]);
+	let a = hello.build().unwrap();
+	let b = shuffled.build().unwrap();
+	let (a, b) = (parse(&a).unwrap(), parse(&b).unwrap());
+	assert_ne!(a.ja3(), b.ja3());
+	assert_eq!(a.ja3n(), "771,4865-4866,0-10-43,29-23,");
+	assert_eq!(a.ja3n(), b.ja3n());
+	assert_eq!(a.ja3n_hash(), b.ja3n_hash());
+	assert_eq!(a.ja3n_hash().len(), 32);
+	assert_ne!(a.ja3n_hash(), a.ja3_hash());
+}
+
+#[test]
+fn ja3_empty_fields() {
+	let msg = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	let hello = parse(&msg).unwrap();
+	assert!(hello.matches_ja3("771,4865,,,"));
+	assert_eq!(hello.ja3(), "771,4865,,,");
+	assert!(hello.ec_point_formats().is_empty());
+}
+
+#[test]
+fn ja4_reference_hashed_and_raw() {
+	let msg = ja4_reference().build_record().unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert!(hello.matches_ja4("t13d1516h2_8daaf6152771_e5627efa2ab1"));
+	assert_eq!(hello.ja4(), "t13d1516h2_8daaf6152771_e5627efa2ab1");
+	let raw = "t13d1516h2_002f,0035,009c,009d,1301,1302,1303,c013,c014,c02b,c02c,c02f,c030,cca8,cca9_\
+	           0005,000a,000b,000d,0012,0015,0017,001b,0023,002b,002d,0033,4469,ff01_\
+	           0403,0804,0401,0503,0805,0501,0806,0601";
+	assert!(hello.matches_ja4(raw));
+	assert_eq!(hello.ja4_raw(), raw);
+	assert!(!hello.matches_ja4("t13d1516h2_8daaf6152771_000000000000"));
+}
+
+#[test]
+fn ja4_quic_marker() {
+	let msg = ja4_reference().build().unwrap();
+	let hello = parse(&msg).unwrap();
+	assert!(hello.matches_ja4("q13d1516h2_8daaf6152771_e5627efa2ab1"));
+	assert_eq!(hello.ja4(), "q13d1516h2_8daaf6152771_e5627efa2ab1");
+	assert!(!hello.matches_ja4("t13d1516h2_8daaf6152771_e5627efa2ab1"));
+}
+
+#[test]
+fn ja4_minimal_hello() {
+	let msg = Builder::new().build_record().unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert!(hello.matches_ja4("t12i000000_000000000000_000000000000"));
+	assert_eq!(hello.ja4(), "t12i000000_000000000000_000000000000");
+}
+
+#[test]
+fn ja4_non_alphanumeric_alpn() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.alpn(&[&[0xab, 0x01, 0xcd]])
+		.build_record()
+		.unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert!(hello.matches_ja4("t12i0101ad_1301_"));
+	assert_eq!(hello.ja4_raw(), "t12i0101ad_1301_");
+}
+
+#[test]
+fn builder_from_ja3_round_trip() {
+	let ja3 =
+		"771,4865-4866-4867-49195-49199,0-23-65281-10-11-35-16-5-13-18-51-45-43-27-21,29-23-24,0";
+	let b = Builder::from_ja3(ja3).unwrap();
+	let hello_bytes = b.build_record().unwrap();
+	let hello = parse_from_record(&hello_bytes).unwrap();
+	assert!(hello.matches_ja3(ja3));
+	assert_eq!(hello.server_name(), Some("example.com"));
+	assert_eq!(hello.key_share_entries()[0].key.len(), 32);
+	assert_eq!(Builder::from_ja3(ja3).unwrap(), b);
+
+	let minimal = "769,47,,,";
+	let msg = Builder::from_ja3(minimal).unwrap().build().unwrap();
+	assert!(parse(&msg).unwrap().matches_ja3(minimal));
+}
+
+#[test]
+fn builder_from_ja4_r_round_trip() {
+	let ja4_r = "t13d1516h2_002f,0035,009c,009d,1301,1302,1303,c013,c014,c02b,c02c,c02f,c030,cca8,cca9_\
+	             0005,000a,000b,000d,0012,0015,0017,001b,0023,002b,002d,0033,4469,ff01_\
+	             0403,0804,0401,0503,0805,0501,0806,0601";
+	let msg = Builder::from_ja4_r(ja4_r).unwrap().build_record().unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert!(hello.matches_ja4(ja4_r));
+	assert!(hello.matches_ja4("t13d1516h2_8daaf6152771_e5627efa2ab1"));
+
+	for ja4_r in ["t12i000000__", "t12i0101ad_1301_"] {
+		let msg = Builder::from_ja4_r(ja4_r).unwrap().build_record().unwrap();
+		assert!(
+			parse_from_record(&msg).unwrap().matches_ja4(ja4_r),
+			"{ja4_r}"
+		);
+	}
+}
+
+#[test]
+fn builder_from_fingerprint_errors() {
+	assert_eq!(
+		Builder::from_ja3("771,4865"),
+		Err(FingerprintError::Sections {
+			expected: 5,
+			found: 2
+		})
+	);
+	assert!(matches!(
+		Builder::from_ja3("771,4865-x,,,"),
+		Err(FingerprintError::Value {
+			field: "cipher suites",
+			..
+		})
+	));
+	assert!(matches!(
+		Builder::from_ja3("70000,,,,"),
+		Err(FingerprintError::Value {
+			field: "version",
+			..
+		})
+	));
+	assert!(matches!(
+		Builder::from_ja4_r("x99d0000_1301_"),
+		Err(FingerprintError::Value {
+			field: "prefix",
+			..
+		})
+	));
+	assert!(matches!(
+		Builder::from_ja4_r("t13d0100h2_+301_"),
+		Err(FingerprintError::Value {
+			field: "cipher suites",
+			..
+		})
+	));
+	assert!(Builder::from_ja4_r("8daaf6152771").is_err());
+}
+
+#[test]
+fn single_pass_fingerprints_match_full_parse() {
+	let record = ja3_reference();
+	let hello = parse_from_record(&record).unwrap();
+	let fp = fingerprints_from_record(&record).unwrap();
+	assert_eq!(fp.ja3, hello.ja3());
+	assert_eq!(fp.ja3_hash, "ada70206e40642a3e4461f35503241d5");
+	assert_eq!(fp.ja4, hello.ja4());
+	assert_eq!(fp.extension_order_hash, hello.extension_order_hash());
+
+	let msg = ja4_reference().build().unwrap();
+	let hello = parse(&msg).unwrap();
+	let fp = fingerprints(&msg).unwrap();
+	assert_eq!(fp.ja3, hello.ja3());
+	assert_eq!(fp.ja3_hash, hello.ja3_hash());
+	assert_eq!(fp.ja4, hello.ja4());
+	assert!(fp.ja4.starts_with("q13d1516h2_"));
+	assert_eq!(fp.extension_order_hash, hello.extension_order_hash());
+}
+
+#[test]
+fn single_pass_fingerprints_skip_undecoded_extensions() {
+	let msg = Builder::new()
+		.extension(0x0000, &[0xff])
+		.supported_versions(&[0x0304])
+		.build()
+		.unwrap();
+	assert!(parse(&msg).is_err());
+	let fp = fingerprints(&msg).unwrap();
+	assert!(fp.ja4.starts_with("q13d"));
+
+	let odd = Builder::new()
+		.extension(0x000a, &[0x00, 0x03, 0x00, 0x1d, 0x00])
+		.build()
+		.unwrap();
+	assert!(fingerprints(&odd).is_err());
+}
+
+#[test]
+fn extension_order_hash_tracks_wire_order() {
+	let a = Builder::new()
+		.server_name("example.com")
+		.supported_groups(&[0x001d])
+		.build()
+		.unwrap();
+	let b = Builder::new()
+		.supported_groups(&[0x001d])
+		.server_name("example.com")
+		.build()
+		.unwrap();
+	let (a, b) = (fingerprints(&a).unwrap(), fingerprints(&b).unwrap());
+	assert_eq!(a.ja4, b.ja4);
+	assert_ne!(a.ja3, b.ja3);
+	assert_ne!(a.extension_order_hash, b.extension_order_hash);
+	assert_eq!(a.extension_order_hash.len(), 12);
+}
+
+#[test]
+fn versioned_fingerprints_pin_v1() {
+	let msg = ja4_reference().build_record().unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert_eq!(Ja3Version::default(), Ja3Version::V1);
+	assert_eq!(Ja4Version::default(), Ja4Version::V1);
+	assert_eq!(hello.ja3_with(Ja3Version::V1), hello.ja3());
+	assert_eq!(hello.ja3_hash_with(Ja3Version::V1), hello.ja3_hash());
+	assert_eq!(hello.ja4_with(Ja4Version::V1), hello.ja4());
+	assert_eq!(hello.ja4_raw_with(Ja4Version::V1), hello.ja4_raw());
+	assert_eq!(
+		hello.ja4_with(Ja4Version::V1),
+		"t13d1516h2_8daaf6152771_e5627efa2ab1"
+	);
+	assert_eq!(hello.ja4_with(Ja4Version::LATEST), hello.ja4());
+	assert_eq!(hello.ja3_with(Ja3Version::LATEST), hello.ja3());
+	assert_eq!(hello.ja3n_with(Ja3Version::V1), hello.ja3n());
+	assert_eq!(hello.ja3n_hash_with(Ja3Version::V1), hello.ja3n_hash());
+	assert_eq!(
+		hello.extension_order_hash_with(Ja4Version::V1),
+		hello.extension_order_hash()
+	);
+	assert!(hello.matches_ja3_with(&hello.ja3_hash(), Ja3Version::V1));
+	assert!(hello.matches_ja4_with(&hello.ja4_raw(), Ja4Version::V1));
+	assert_eq!(
+		fingerprints_from_record_with(&msg, Ja3Version::V1, Ja4Version::V1).unwrap(),
+		fingerprints_from_record(&msg).unwrap()
+	);
+}
@@ -0,0 +1,84 @@
+/* tests/json.rs */
+#![allow(missing_docs)]
+
+use clienthello::json::{self, ExtensionRecord, HelloRecord, JSON_SCHEMA_VERSION, JsonError};
+use clienthello::{Builder, parse};
+
+fn sample() -> Vec<u8> {
+	Builder::new()
+		.random([0x42; 32])
+		.session_id(&[0xab, 0xcd])
+		.cipher_suites(&[0x0a0a, 0x1301])
+		.server_name("example.com")
+		.alpn(&[b"h2"])
+		.supported_versions(&[0x0304])
+		.key_share(&[(29, &[1; 32])])
+		.extension(0x0017, &[])
+		.extension(0x0042, &[0xde, 0xad])
+		.build()
+		.unwrap()
+}
+
+/// The version 1 layout; changing this string means bumping
+/// `JSON_SCHEMA_VERSION`, or adding fields only.
+const SAMPLE_V1: &str = concat!(
+	r#"{"schema_version":1,"transport":"handshake","legacy_version":771,"#,
+	r#""random":"4242424242424242424242424242424242424242424242424242424242424242","#,
+	r#""session_id":"abcd","cipher_suites":[4865],"compression_methods":[0],"#,
+	r#""extensions":[{"type":0,"length":16},{"type":16,"length":5},{"type":43,"length":3},"#,
+	r#"{"type":51,"length":38},{"type":23,"length":0,"data":""},"#,
+	r#"{"type":66,"length":2,"data":"dead"}],"#,
+	r#""grease":{"cipher_suites":[0],"extensions":[],"supported_versions":[],"#,
+	r#""supported_groups":[],"key_share":[]},"server_name":"example.com","alpn":["h2"],"#,
+	r#""supported_versions":[772],"supported_groups":[],"signature_algorithms":[],"#,
+	r#""key_share_groups":[29]}"#,
+);
+
+#[test]
+fn version_1_layout_is_stable() {
+	let msg = sample();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(JSON_SCHEMA_VERSION, 1);
+	assert_eq!(json::to_json(&hello), SAMPLE_V1);
+	assert_eq!(
+		json::from_json(SAMPLE_V1).unwrap(),
+		HelloRecord::from(&hello)
+	);
+}
+
+#[test]
+fn reads_records_with_unknown_and_missing_fields() {
+	let text = r#"{"schema_version":1,"legacy_version":771,"future_field":{"a":1},
+		"extensions":[{"type":66,"length":2,"data":"dead","future":true}]}"#;
+	let record = json::from_json(text).unwrap();
+	assert_eq!(record.legacy_version, 771);
+	assert_eq!(record.server_name, None);
+	assert!(record.cipher_suites.is_empty());
+	let mut ext = ExtensionRecord::default();
+	ext.type_id = 0x42;
+	ext.length = 2;
+	ext.data = Some("dead".into());
+	assert_eq!(record.extensions, [ext]);
+}
+
+#[test]
+fn rejects_newer_or_missing_schema_version() {
+	for (text, found) in [
+		(r#"{"schema_version":2,"cipher_suites":"changed"}"#, 2),
+		(r#"{"legacy_version":771}"#, 0),
+	] {
+		assert!(matches!(
+			json::from_json(text),
+			Err(JsonError::SchemaVersion { found: f, supported: 1 }) if f == found
+		));
+	}
+	assert!(matches!(json::from_json("{"), Err(JsonError::Json(_))));
+}
+
+#[test]
+fn ech_body_kept_as_data() {
+	let msg = Builder::new().extension(0xfe0d, &[0x01]).build().unwrap();
+	let record = HelloRecord::from(&parse(&msg).unwrap());
+	assert_eq!(record.extensions[0].type_id, 0xfe0d);
+	assert_eq!(record.extensions[0].data.as_deref(), Some("01"));
+}
@@ -0,0 +1,72 @@
+/* tests/logging.rs */
+#![allow(missing_docs)]
+
+use std::sync::Mutex;
+
+use clienthello::{Builder, LOG_TARGET, log_summary, parse};
+use log::kv::{Error, Key, Value, VisitSource};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Records every log call as `target message key=value...`.
+struct Capture(Mutex<Vec<String>>);
+
+struct Pairs<'s>(&'s mut String);
+
+impl<'kvs> VisitSource<'kvs> for Pairs<'_> {
+	fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), Error> {
+		self.0.push_str(&format!(" {key}={value}"));
+		Ok(())
+	}
+}
+
+impl Log for Capture {
+	fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+		metadata.level() <= Level::Info
+	}
+
+	fn log(&self, record: &Record<'_>) {
+		let mut line = format!("{} {}", record.target(), record.args());
+		record.key_values().visit(&mut Pairs(&mut line)).unwrap();
+		self.0.lock().unwrap().push(line);
+	}
+
+	fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+#[test]
+fn emits_structured_summary() {
+	log::set_logger(&LOGGER).unwrap();
+	log::set_max_level(LevelFilter::Trace);
+
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0304, 0x0303])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	log_summary(&hello, Level::Info);
+	// Filtered out by the logger.
+	log_summary(&hello, Level::Debug);
+
+	let bare = Builder::new().cipher_suites(&[0x002f]).build().unwrap();
+	log_summary(&parse(&bare).unwrap(), Level::Warn);
+
+	let lines = LOGGER.0.lock().unwrap();
+	assert_eq!(
+		*lines,
+		[
+			format!(
+				"{LOG_TARGET} client hello sni=example.com ja3={} version=0x0304 alpn=h2,http/1.1",
+				hello.ja3_hash()
+			),
+			format!(
+				"{LOG_TARGET} client hello sni= ja3={} version=0x0303 alpn=",
+				parse(&bare).unwrap().ja3_hash()
+			),
+		]
+	);
+}
@@ -0,0 +1,89 @@
+/* tests/multi.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, MultiFingerprint, parse};
+
+fn chrome_like() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302, 0xc02b])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0304, 0x0303])
+		.supported_groups(&[0x001d, 0x0017])
+		.extension(0x0a0a, &[])
+}
+
+fn observe(multi: &mut MultiFingerprint, builder: &Builder) -> bool {
+	let msg = builder.build().unwrap();
+	multi.observe(&parse(&msg).unwrap())
+}
+
+#[test]
+fn empty_has_no_core() {
+	let multi = MultiFingerprint::new();
+	assert_eq!(multi.observations(), 0);
+	assert!(multi.core().is_none());
+	assert!(!multi.is_stable());
+	assert!(!multi.is_order_randomized());
+}
+
+#[test]
+fn fixed_order_client() {
+	let mut multi = MultiFingerprint::new();
+	for host in ["a.example", "b.example", "c.example"] {
+		assert!(observe(&mut multi, &chrome_like().server_name(host)));
+	}
+	assert_eq!(multi.observations(), 3);
+	assert!(multi.is_stable());
+	assert!(!multi.is_order_randomized());
+	let orders: Vec<_> = multi.orders().collect();
+	assert_eq!(orders, [(&[0x0000, 0x0010, 0x002b, 0x000a][..], 3)]);
+}
+
+#[test]
+fn shuffled_orders_share_a_core() {
+	let shuffled = Builder::new()
+		.cipher_suites(&[0x1301, 0x1302, 0xc02b])
+		.supported_groups(&[0x001d, 0x0017])
+		.supported_versions(&[0x0304, 0x0303])
+		.alpn(&[b"h2", b"http/1.1"])
+		.server_name("example.com");
+	let mut multi = MultiFingerprint::new();
+	assert!(observe(&mut multi, &chrome_like()));
+	assert!(observe(&mut multi, &shuffled));
+	assert!(observe(&mut multi, &chrome_like()));
+	assert!(multi.is_stable());
+	assert!(multi.is_order_randomized());
+	let counts: Vec<usize> = multi.orders().map(|(_, n)| n).collect();
+	assert_eq!(counts.iter().sum::<usize>(), 3);
+	assert_eq!(counts.len(), 2);
+}
+
+#[test]
+fn per_connection_extensions_are_ignored() {
+	let mut multi = MultiFingerprint::new();
+	assert!(observe(&mut multi, &chrome_like()));
+	assert!(observe(&mut multi, &chrome_like().pad_to(512)));
+	assert!(observe(
+		&mut multi,
+		&chrome_like().pre_shared_key(&[(b"ticket", 7)], 32)
+	));
+	assert!(multi.is_stable());
+	assert!(!multi.is_order_randomized());
+}
+
+#[test]
+fn different_offer_is_a_mismatch() {
+	let mut multi = MultiFingerprint::new();
+	assert!(observe(&mut multi, &chrome_like()));
+	let core = multi.core().unwrap().to_vec();
+	assert!(!observe(
+		&mut multi,
+		&chrome_like().cipher_suites(&[0x1301])
+	));
+	assert!(!observe(&mut multi, &chrome_like().alpn(&[b"h2"])));
+	assert_eq!(multi.observations(), 1);
+	assert_eq!(multi.mismatches(), 2);
+	assert!(!multi.is_stable());
+	assert_eq!(multi.core(), Some(core.as_slice()));
+}
@@ -0,0 +1,121 @@
+/* tests/negotiate.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, NegotiationError, ServerConfig, parse};
+
+const SERVER: ServerConfig<'static> = ServerConfig {
+	versions: &[0x0304, 0x0303],
+	cipher_suites: &[0x1302, 0x1301, 0xc030, 0xc02f],
+	groups: &[0x0017, 0x001d],
+	signature_algorithms: &[0x0804, 0x0403],
+	alpn: &[b"h2", b"http/1.1"],
+};
+
+fn tls13() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302, 0xc02f])
+		.supported_versions(&[0x0304, 0x0303])
+		.supported_groups(&[0x001d, 0x0017])
+		.key_share(&[(0x001d, &[0; 32])])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.alpn(&[b"http/1.1", b"h2"])
+}
+
+#[test]
+fn server_preference_wins() {
+	let msg = tls13().build().unwrap();
+	let negotiated = parse(&msg).unwrap().negotiate(&SERVER).unwrap();
+	assert_eq!(negotiated.version, 0x0304);
+	assert_eq!(negotiated.cipher_suite, 0x1302);
+	assert_eq!(negotiated.signature_algorithm, Some(0x0804));
+	assert_eq!(negotiated.alpn, Some(&b"h2"[..]));
+}
+
+#[test]
+fn key_share_avoids_retry() {
+	let msg = tls13().build().unwrap();
+	let negotiated = parse(&msg).unwrap().negotiate(&SERVER).unwrap();
+	// The server prefers secp256r1 but the client only sent x25519.
+	assert_eq!(negotiated.group, Some(0x001d));
+	assert!(!negotiated.hello_retry);
+	assert!(negotiated.explain()[2].contains("key share"));
+
+	let server = ServerConfig {
+		groups: &[0x0017],
+		..SERVER
+	};
+	let negotiated = parse(&msg).unwrap().negotiate(&server).unwrap();
+	assert_eq!(negotiated.group, Some(0x0017));
+	assert!(negotiated.hello_retry);
+	assert!(negotiated.explain()[2].contains("HelloRetryRequest"));
+}
+
+#[test]
+fn explains_every_parameter() {
+	let msg = tls13().build().unwrap();
+	let negotiated = parse(&msg).unwrap().negotiate(&SERVER).unwrap();
+	let explanation = negotiated.explain();
+	assert_eq!(explanation.len(), 5);
+	assert!(explanation[0].starts_with("version 0x0304"));
+	assert!(explanation[1].starts_with("cipher suite 0x1302"));
+	assert!(explanation[3].starts_with("signature algorithm RsaPssRsaeSha256"));
+	assert!(explanation[4].starts_with("ALPN \"h2\""));
+}
+
+#[test]
+fn legacy_hello_negotiates_tls12() {
+	let msg = Builder::new()
+		.cipher_suites(&[0xc02f, 0x1301])
+		.supported_groups(&[0x001d])
+		.build()
+		.unwrap();
+	let negotiated = parse(&msg).unwrap().negotiate(&SERVER).unwrap();
+	assert_eq!(negotiated.version, 0x0303);
+	// TLS 1.3 suites are not usable with TLS 1.2.
+	assert_eq!(negotiated.cipher_suite, 0xc02f);
+	assert_eq!(negotiated.group, Some(0x001d));
+	assert_eq!(negotiated.signature_algorithm, None);
+	assert_eq!(negotiated.alpn, None);
+	assert!(negotiated.explain()[3].contains("SHA-1"));
+}
+
+#[test]
+fn no_common_cipher_suite() {
+	let msg = tls13().cipher_suites(&[0x1303]).build().unwrap();
+	let err = parse(&msg).unwrap().negotiate(&SERVER).unwrap_err();
+	assert_eq!(
+		err,
+		NegotiationError::NoCommonCipherSuite {
+			version: 0x0304,
+			client: vec![0x1303],
+		}
+	);
+	assert_eq!(
+		err.to_string(),
+		"no common cipher suite: client offers 0x1303 for 0x0304"
+	);
+}
+
+#[test]
+fn no_common_group() {
+	let msg = tls13().supported_groups(&[0x0018]).build().unwrap();
+	let err = parse(&msg).unwrap().negotiate(&SERVER).unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		"no common group: client offers 0x0018, TLS 1.3 requires one"
+	);
+}
+
+#[test]
+fn no_common_alpn() {
+	let msg = tls13().alpn(&[b"spdy/3"]).build().unwrap();
+	let err = parse(&msg).unwrap().negotiate(&SERVER).unwrap_err();
+	assert_eq!(err, NegotiationError::NoCommonAlpn);
+
+	let server = ServerConfig {
+		alpn: &[],
+		..SERVER
+	};
+	let negotiated = parse(&msg).unwrap().negotiate(&server).unwrap();
+	assert_eq!(negotiated.alpn, None);
+}
@@ -3,7 +3,16 @@
 
 mod helpers;
 
-use clienthello::{Error, Extension, is_grease, parse, parse_from_record};
+use clienthello::alps::{AlpsSettings, Http2Setting};
+use clienthello::tls_reader::Reader;
+use clienthello::typed;
+use clienthello::{
+	Builder, ContentType, Error, Extension, ExtensionBitmap, ExtensionOwned, FromExtension,
+	GREASE_PLACEHOLDER, GreasePositions, HandshakeType, HashAlgorithm, ParseOptions,
+	RenegotiationSignal, ReservedValues, SessionIdStyle, SignatureScheme, SniPolicy, Transport,
+	UnknownExtensionPolicy, Warning, extract_sni, extract_sni_from_record, is_grease, parse,
+	parse_any, parse_from_record, summarize, summarize_from_record,
+};
 
 // Happy path
 
@@ -29,6 +38,88 @@ fn minimal_record_layer() {
 	assert_eq!(hello.cipher_suites, vec![0x1301]);
 }
 
+fn dtls_record(cookie: &[u8], fragment_offset: u8) -> Vec<u8> {
+	let mut body = vec![0xfe, 0xfd];
+	body.extend_from_slice(&[0x11; 32]);
+	body.push(0);
+	body.push(cookie.len() as u8);
+	body.extend_from_slice(cookie);
+	body.extend_from_slice(&[0x00, 0x02, 0xc0, 0x2b, 0x01, 0x00]);
+	let len = body.len() as u8;
+	let mut handshake = vec![0x01, 0, 0, len, 0, 0, 0, 0, fragment_offset, 0, 0, len];
+	handshake.extend_from_slice(&body);
+	let mut record = vec![0x16, 0xfe, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+	record.push(handshake.len() as u8);
+	record.extend_from_slice(&handshake);
+	record
+}
+
+#[test]
+fn parse_any_detects_framing() {
+	let raw = helpers::full_raw();
+	let record = helpers::wrap_record(&raw);
+	let (hello, framing) = parse_any(&raw).unwrap();
+	assert_eq!(framing, Transport::Handshake);
+	assert_eq!(hello, parse(&raw).unwrap());
+	let (hello, framing) = parse_any(&record).unwrap();
+	assert_eq!(framing, Transport::TlsRecord);
+	assert_eq!(hello, parse_from_record(&record).unwrap());
+
+	let dtls = dtls_record(b"cookie", 0);
+	let (hello, framing) = parse_any(&dtls).unwrap();
+	assert_eq!(framing, Transport::Dtls);
+	assert_eq!(hello.transport, Transport::Dtls);
+	assert_eq!(hello.legacy_version, 0xfefd);
+	assert_eq!(hello.random, &[0x11; 32]);
+	assert_eq!(hello.cipher_suites, [0xc02b]);
+	assert_eq!(hello.compression_methods, &[0]);
+}
+
+#[test]
+fn parse_any_sslv2() {
+	let mut data = vec![
+		0x80, 0, 0x01, 0x03, 0x01, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x10,
+	];
+	// A TLS suite, an SSL 2.0-only spec, GREASE, and the renegotiation SCSV.
+	data.extend_from_slice(&[
+		0x00, 0x00, 0x2f, 0x07, 0x00, 0xc0, 0x00, 0x0a, 0x0a, 0x00, 0x00, 0xff,
+	]);
+	data.extend_from_slice(&[0x22; 16]);
+	data[1] = (data.len() - 2) as u8;
+	let (hello, framing) = parse_any(&data).unwrap();
+	assert_eq!(framing, Transport::SslV2);
+	assert_eq!(hello.legacy_version, 0x0301);
+	assert_eq!(hello.cipher_suites, [0x002f, 0x00ff]);
+	assert_eq!(hello.grease.cipher_suites, [1]);
+	assert!(hello.has_grease);
+	assert_eq!(hello.random, &[0x22; 16]);
+	assert!(hello.session_id.is_empty());
+	assert!(hello.compression_methods.is_empty());
+	assert!(hello.extensions.is_empty());
+	assert!(hello.diagnostics.warnings.is_empty());
+
+	data.truncate(data.len() - 1);
+	assert!(matches!(parse_any(&data), Err(Error::Truncated { .. })));
+}
+
+#[test]
+fn parse_any_rejects_unknown_framing() {
+	assert_eq!(
+		parse_any(&[]),
+		Err(Error::BufferTooShort { need: 1, have: 0 })
+	);
+	assert_eq!(
+		parse_any(&[0x17, 0x03, 0x03, 0x00, 0x00]),
+		Err(Error::NotHandshakeRecord(ContentType::ApplicationData))
+	);
+	assert_eq!(
+		parse_any(&dtls_record(b"", 1)),
+		Err(Error::Truncated {
+			field: "DTLS handshake fragment"
+		})
+	);
+}
+
 #[test]
 fn full_parse_raw() {
 	let data = helpers::full_raw();
@@ -182,6 +273,19 @@ fn full_session_id() {
 	assert_eq!(hello.session_id, &[0xCD; 32]);
 }
 
+#[test]
+fn session_id_span_points_into_input() {
+	let data = helpers::full_raw();
+	let hello = parse(&data).unwrap();
+	// Handshake header, version, random, then the length prefix.
+	assert_eq!(hello.session_id_span(&data), Some((39, 32)));
+
+	let record = helpers::wrap_record(&helpers::minimal_raw());
+	let hello = parse_from_record(&record).unwrap();
+	assert_eq!(hello.session_id_span(&record), Some((44, 0)));
+	assert_eq!(hello.session_id_span(&data), None);
+}
+
 #[test]
 fn psk_exchange_modes() {
 	let data = helpers::full_raw();
@@ -230,7 +334,7 @@ fn error_not_client_hello() {
 	let mut data = helpers::minimal_raw();
 	data[0] = 0x02;
 	let err = parse(&data).unwrap_err();
-	assert_eq!(err, Error::NotClientHello(0x02));
+	assert_eq!(err, Error::NotClientHello(HandshakeType::ServerHello));
 }
 
 #[test]
@@ -240,7 +344,7 @@ fn error_not_handshake_record() {
 	let mut record = helpers::wrap_record(&raw);
 	record[0] = 0x17;
 	let err = parse_from_record(&record).unwrap_err();
-	assert_eq!(err, Error::NotHandshakeRecord(0x17));
+	assert_eq!(err, Error::NotHandshakeRecord(ContentType::ApplicationData));
 }
 
 #[test]
@@ -396,6 +500,172 @@ fn sni_invalid_utf8_returns_none() {
 	assert_eq!(hello.server_name(), None);
 }
 
+#[test]
+fn server_name_span_points_into_input() {
+	let sni_body = helpers::build_sni_body(&[(0x01, b"other"), (0x00, &[0xFF, 0xFE, 0xFD])]);
+	let ext = helpers::build_ext(0x0000, &sni_body);
+	let mut data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	let (offset, len) = hello.server_name_span(&data).unwrap();
+	assert_eq!(&data[offset..offset + len], [0xFF, 0xFE, 0xFD]);
+
+	// The span is stable, so the name can be rewritten in place.
+	data[offset..offset + len].copy_from_slice(b"a.b");
+	assert_eq!(parse(&data).unwrap().server_name(), Some("a.b"));
+	let copy = data.clone();
+	assert_eq!(parse(&copy).unwrap().server_name_span(&data), None);
+}
+
+fn multi_sni_hello() -> Vec<u8> {
+	let sni_body =
+		helpers::build_sni_body(&[(0x00, b"first.com"), (0x01, b"other"), (0x00, b"last.com")]);
+	helpers::raw_with_extensions(&helpers::build_ext(0x0000, &sni_body))
+}
+
+#[test]
+fn sni_multiple_host_names_first_wins_by_default() {
+	let data = multi_sni_hello();
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.server_name(), Some("first.com"));
+	assert!(hello.diagnostics.multiple_server_names);
+	let names = hello.get::<typed::ServerNames>().unwrap();
+	assert_eq!(names.len(), 2);
+	assert_eq!(names[1].name, b"other");
+}
+
+#[test]
+fn sni_multiple_host_names_last_wins() {
+	let data = multi_sni_hello();
+	let opts = ParseOptions::new().sni_policy(SniPolicy::Last);
+	let hello = opts.parse(&data).unwrap();
+	assert_eq!(hello.server_name(), Some("last.com"));
+	assert!(hello.diagnostics.multiple_server_names);
+}
+
+#[test]
+fn sni_multiple_host_names_rejected() {
+	let data = multi_sni_hello();
+	let opts = ParseOptions::new().sni_policy(SniPolicy::Reject);
+	assert_eq!(
+		opts.parse(&data),
+		Err(Error::MultipleServerNames { count: 2 })
+	);
+}
+
+#[test]
+fn sni_single_host_name_not_flagged() {
+	let data = helpers::full_raw();
+	let hello = parse(&data).unwrap();
+	assert!(!hello.diagnostics.multiple_server_names);
+	let opts = ParseOptions::new().sni_policy(SniPolicy::Reject);
+	assert!(opts.parse(&data).is_ok());
+}
+
+#[test]
+fn unknown_extension_bodies_dropped_or_truncated() {
+	let mut exts = helpers::build_ext(0x0042, &[0xaa; 300]);
+	exts.extend(helpers::build_ext(
+		0x0010,
+		&helpers::build_alpn_body(&[b"h2"]),
+	));
+	let data = helpers::raw_with_extensions(&exts);
+
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.find_extension(0x0042).unwrap().len(), 300);
+
+	let opts = ParseOptions::new().unknown_extensions(UnknownExtensionPolicy::Drop);
+	let hello = opts.parse(&data).unwrap();
+	assert_eq!(hello.find_extension(0x0042), Some(&[][..]));
+	assert_eq!(hello.extension_lengths()[0], (0x0042, 300));
+	assert_eq!(hello.alpn_protocols(), &[b"h2".as_slice()]);
+
+	let opts = ParseOptions::new().unknown_extensions(UnknownExtensionPolicy::Truncate(16));
+	let hello = opts.parse(&data).unwrap();
+	assert_eq!(hello.find_extension(0x0042), Some(&[0xaa; 16][..]));
+}
+
+fn is_private(value: u16) -> bool {
+	value & 0xff00 == 0xff00 && value != 0xff01
+}
+
+#[test]
+fn reserved_values_filtered_like_grease() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301, 0xff42, 0x0a0a])
+		.extension(0xff43, &[])
+		.supported_versions(&[0xff44, 0x0304])
+		.supported_groups(&[0x001d, 0xff45])
+		.signature_algorithms(&[0xff46, 0x0403])
+		.key_share(&[(0xff47, &[0x00]), (0x001d, &[0x11; 32])])
+		.extension(0xff01, &[0x00])
+		.build()
+		.unwrap();
+
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.cipher_suites, [0x1301, 0xff42]);
+
+	let hello = ParseOptions::new()
+		.reserved_values(is_private)
+		.parse(&msg)
+		.unwrap();
+	assert_eq!(hello.cipher_suites, [0x1301]);
+	assert_eq!(hello.supported_versions(), [0x0304]);
+	assert_eq!(hello.supported_groups(), [0x001d]);
+	assert_eq!(hello.signature_algorithms(), [0x0403]);
+	assert_eq!(hello.key_share_groups(), [0x001d]);
+	assert_eq!(hello.key_share_entries().len(), 1);
+	assert!(hello.has_renegotiation_info());
+	assert_eq!(
+		hello.reserved,
+		ReservedValues {
+			cipher_suites: vec![(1, 0xff42)],
+			extensions: vec![(0, 0xff43)],
+			supported_versions: vec![(0, 0xff44)],
+			supported_groups: vec![(1, 0xff45)],
+			signature_algorithms: vec![(0, 0xff46)],
+			key_share: vec![(0, 0xff47)],
+		}
+	);
+}
+
+#[test]
+fn reserved_values_are_not_grease() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301, 0xff42, 0x0a0a])
+		.extension(0xff43, &[])
+		.server_name("example.com")
+		.build()
+		.unwrap();
+	let hello = ParseOptions::new()
+		.reserved_values(is_private)
+		.parse(&msg)
+		.unwrap();
+	// The GREASE suite indexes the list with the reserved one left out.
+	assert!(hello.has_grease);
+	assert_eq!(hello.grease.cipher_suites, [1]);
+	assert!(hello.grease.extensions.is_empty());
+
+	let msg = Builder::new()
+		.cipher_suites(&[0xff42, 0x1301])
+		.supported_groups(&[0xff45, 0x001d])
+		.build()
+		.unwrap();
+	let hello = ParseOptions::new()
+		.reserved_values(is_private)
+		.parse(&msg)
+		.unwrap();
+	assert!(!hello.has_grease);
+	assert_eq!(hello.grease, GreasePositions::default());
+	assert_eq!(hello.wire_extension_types().collect::<Vec<_>>(), [0x000a]);
+}
+
+#[test]
+fn reserved_values_compare_by_predicate() {
+	let opts = ParseOptions::new().reserved_values(is_private);
+	assert_eq!(opts, opts);
+	assert_ne!(opts, ParseOptions::new());
+}
+
 // Happy path: extension accessor edge cases
 
 #[test]
@@ -416,6 +686,248 @@ fn supported_versions_single() {
 	assert_eq!(hello.supported_versions(), &[0x0304]);
 }
 
+#[test]
+fn effective_versions_fall_back_to_legacy() {
+	let data = helpers::minimal_raw();
+	let hello = parse(&data).unwrap();
+	assert!(hello.supported_versions().is_empty());
+	assert_eq!(hello.effective_versions(), &[0x0303]);
+
+	let sv_body = helpers::build_supported_versions_body(&[0x0304, 0x0303]);
+	let ext = helpers::build_ext(0x002B, &sv_body);
+	let data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.effective_versions(), &[0x0304, 0x0303]);
+}
+
+#[test]
+fn group_preference_order() {
+	let body = helpers::build_u16_list_body(&[0x0a0a, 0x11ec, 0x001d, 0x0017]);
+	let ext = helpers::build_ext(0x000A, &body);
+	let data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.top_group(), Some(0x11ec));
+	assert_eq!(hello.group_rank(0x001d), Some(1));
+	assert_eq!(hello.group_rank(0x0018), None);
+	assert!(hello.prefers_group(0x11ec, 0x001d));
+	assert!(!hello.prefers_group(0x0017, 0x001d));
+	assert!(hello.prefers_group(0x0017, 0x0018));
+	assert!(!hello.prefers_group(0x0018, 0x0017));
+	assert!(!hello.prefers_group(0x001d, 0x001d));
+
+	let data = helpers::minimal_raw();
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.top_group(), None);
+	assert!(!hello.prefers_group(0x001d, 0x0017));
+}
+
+#[test]
+fn signature_algorithm_helpers() {
+	let body = helpers::build_u16_list_body(&[0x0403, 0x0804, 0x0201, 0x0807]);
+	let ext = helpers::build_ext(0x000D, &body);
+	let data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	assert!(hello.supports_pss());
+	assert!(hello.supports_ed25519());
+	assert_eq!(hello.weakest_hash_offered(), Some(HashAlgorithm::Sha1));
+	assert_eq!(
+		hello.signature_schemes().next(),
+		Some(SignatureScheme::EcdsaSecp256r1Sha256)
+	);
+
+	let body = helpers::build_u16_list_body(&[0x0403, 0x0808]);
+	let ext = helpers::build_ext(0x000D, &body);
+	let data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	assert!(!hello.supports_pss());
+	assert!(!hello.supports_ed25519());
+	assert_eq!(hello.weakest_hash_offered(), Some(HashAlgorithm::Sha256));
+
+	let data = helpers::minimal_raw();
+	assert_eq!(parse(&data).unwrap().weakest_hash_offered(), None);
+}
+
+#[test]
+fn key_share_entries_and_sizes() {
+	let mlkem = [0x11u8; 1216];
+	let ks_body =
+		helpers::build_key_share_body(&[(0x2a2a, &[0x00]), (0x11ec, &mlkem), (0x001d, &[0x22; 32])]);
+	let ext = helpers::build_ext(0x0033, &ks_body);
+	let data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.key_share_groups(), &[0x11ec, 0x001d]);
+	let entries = hello.key_share_entries();
+	assert_eq!(entries.len(), 3);
+	assert_eq!(entries[0].group, 0x2a2a);
+	assert_eq!(entries[1].key.len(), 1216);
+	assert_eq!(hello.key_share_size(), 1 + 1216 + 32);
+	assert!(hello.hybrid_key_exchange_offered());
+
+	let ks_body = helpers::build_key_share_body(&[(0x001d, &[0x22; 32])]);
+	let ext = helpers::build_ext(0x0033, &ks_body);
+	let data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.key_share_size(), 32);
+	assert!(!hello.hybrid_key_exchange_offered());
+}
+
+#[test]
+fn alps_protocols_either_codepoint() {
+	for type_id in [0x44cd, 0x4469] {
+		let ext = helpers::build_ext(type_id, &helpers::build_alpn_body(&[b"h2", b"h3"]));
+		let data = helpers::raw_with_extensions(&ext);
+		let hello = parse(&data).unwrap();
+		assert_eq!(hello.alps_protocols(), [b"h2", b"h3"]);
+	}
+
+	let data = helpers::minimal_raw();
+	assert!(parse(&data).unwrap().alps_protocols().is_empty());
+}
+
+#[test]
+fn alps_http2_settings() {
+	let payload = [
+		0x00, 0x01, 0x00, 0x01, 0x00, 0x00, // HEADER_TABLE_SIZE 65536
+		0x00, 0x04, 0x00, 0x60, 0x00, 0x00, // INITIAL_WINDOW_SIZE 6291456
+		0x0a, 0x0a, 0x00, 0x00, 0x00, 0x07, // GREASE
+	];
+	let settings = AlpsSettings::new(b"h2", &payload).http2().unwrap().unwrap();
+	assert_eq!(
+		settings,
+		[
+			Http2Setting {
+				id: 0x1,
+				value: 65536
+			},
+			Http2Setting {
+				id: 0x4,
+				value: 6_291_456
+			},
+			Http2Setting {
+				id: 0x0a0a,
+				value: 7
+			},
+		]
+	);
+	assert_eq!(settings[1].name(), Some("INITIAL_WINDOW_SIZE"));
+	assert_eq!(settings[2].name(), None);
+
+	assert!(AlpsSettings::new(b"h3", &payload).http2().is_none());
+	assert!(matches!(
+		AlpsSettings::new(b"h2", &payload[..5]).http2(),
+		Some(Err(Error::Truncated { .. }))
+	));
+	assert_eq!(AlpsSettings::new(b"h2", &[]).http2(), Some(Ok(Vec::new())));
+}
+
+#[test]
+fn renegotiation_signals() {
+	let check = |b: Builder| {
+		let msg = b.build().unwrap();
+		parse(&msg).unwrap().renegotiation()
+	};
+	let base = || Builder::new().cipher_suites(&[0x1301]);
+
+	let r = check(base());
+	assert_eq!(r.signal, RenegotiationSignal::Neither);
+	assert!(r.is_consistent());
+
+	let r = check(base().cipher_suites(&[0x1301, 0x00ff]));
+	assert_eq!(r.signal, RenegotiationSignal::Scsv);
+	assert_eq!(r.scsv_count, 1);
+	assert!(r.is_consistent());
+
+	let r = check(base().renegotiation_info(&[]));
+	assert_eq!(r.signal, RenegotiationSignal::Extension);
+	assert_eq!(r.extension_count, 1);
+	assert!(r.is_consistent());
+
+	let r = check(
+		base()
+			.cipher_suites(&[0x00ff, 0x1301])
+			.renegotiation_info(&[]),
+	);
+	assert_eq!(r.signal, RenegotiationSignal::Both);
+	assert!(!r.is_consistent());
+
+	let r = check(base().cipher_suites(&[0x00ff, 0x1301, 0x00ff]));
+	assert_eq!(r.scsv_count, 2);
+	assert!(!r.is_consistent());
+
+	let r = check(base().renegotiation_info(&[0xab; 12]));
+	assert!(r.non_empty_extension);
+	assert!(!r.is_consistent());
+}
+
+#[test]
+fn renegotiation_duplicate_extension() {
+	let ext = helpers::build_ext(0xff01, &[0x00]);
+	let mut exts = ext.clone();
+	exts.extend_from_slice(&ext);
+	let data = helpers::raw_with_extensions(&exts);
+	let r = parse(&data).unwrap().renegotiation();
+	assert_eq!(r.signal, RenegotiationSignal::Extension);
+	assert_eq!(r.extension_count, 2);
+	assert!(!r.non_empty_extension);
+	assert!(!r.is_consistent());
+}
+
+#[test]
+fn session_id_styles() {
+	let style = |b: Builder| {
+		let msg = b.build().unwrap();
+		parse(&msg).unwrap().session_id_style()
+	};
+	let tls13 = || {
+		Builder::new()
+			.cipher_suites(&[0x1301])
+			.supported_versions(&[0x0304, 0x0303])
+	};
+	let tls12 = || Builder::new().cipher_suites(&[0xc02f]);
+
+	assert_eq!(style(tls13()), SessionIdStyle::Empty);
+	assert_eq!(
+		style(tls13().session_id(&[0x11; 32])),
+		SessionIdStyle::Compatibility
+	);
+	assert_eq!(
+		style(tls13().session_id(&[0x11; 16])),
+		SessionIdStyle::Resumption
+	);
+	assert_eq!(
+		style(tls12().session_id(&[0x11; 32])),
+		SessionIdStyle::Resumption
+	);
+	assert_eq!(
+		style(tls12().session_id(&[0x11; 33])),
+		SessionIdStyle::Oversized
+	);
+}
+
+#[test]
+fn extension_lengths_match_wire() {
+	let mut exts = helpers::build_ext(0x2a2a, &[0x00]);
+	exts.extend(helpers::build_ext(
+		0x002b,
+		&helpers::build_supported_versions_body(&[0x3a3a, 0x0304]),
+	));
+	// ALPN body with a trailing byte, tolerated outside strict mode
+	let mut alpn = helpers::build_alpn_body(&[b"h2"]);
+	alpn.push(0x00);
+	exts.extend(helpers::build_ext(0x0010, &alpn));
+	exts.extend(helpers::build_ext(0x0042, &[]));
+	let data = helpers::raw_with_extensions(&exts);
+	let hello = parse(&data).unwrap();
+	assert_eq!(
+		hello.extension_lengths(),
+		[(0x002b, 5), (0x0010, 6), (0x0042, 0)]
+	);
+	assert_eq!(hello.extension_body_lengths.len(), hello.extensions.len());
+
+	let data = helpers::minimal_raw();
+	assert!(parse(&data).unwrap().extension_lengths().is_empty());
+}
+
 #[test]
 fn key_share_empty_list() {
 	let ks_body = helpers::build_key_share_body(&[]);
@@ -451,6 +963,416 @@ fn accessors_default_without_extensions() {
 	assert!(hello.find_extension(0x0042).is_none());
 }
 
+#[test]
+fn extensions_of_type_returns_duplicates() {
+	let mut exts = helpers::build_ext(0x0010, &helpers::build_alpn_body(&[b"h2"]));
+	exts.extend(helpers::build_ext(0x0042, &[0x01]));
+	exts.extend(helpers::build_ext(
+		0x0010,
+		&helpers::build_alpn_body(&[b"h3"]),
+	));
+	let data = helpers::raw_with_extensions(&exts);
+	let hello = parse(&data).unwrap();
+	let alpn: Vec<_> = hello.extensions_of_type(0x0010).collect();
+	assert_eq!(
+		alpn,
+		[
+			&Extension::Alpn(vec![b"h2".as_slice()]),
+			&Extension::Alpn(vec![b"h3".as_slice()]),
+		]
+	);
+	assert_eq!(hello.alpn_protocols(), &[b"h2".as_slice()]);
+	assert_eq!(hello.extensions_of_type(0x0042).count(), 1);
+	assert_eq!(hello.extensions_of_type(0x0000).count(), 0);
+}
+
+#[test]
+fn extensions_sorted_is_order_independent() {
+	let mut first = helpers::build_ext(0x0042, &[0x01]);
+	first.extend(helpers::build_ext(
+		0x0010,
+		&helpers::build_alpn_body(&[b"h2"]),
+	));
+	first.extend(helpers::build_ext(0x0017, &[]));
+	first.extend(helpers::build_ext(
+		0x0010,
+		&helpers::build_alpn_body(&[b"h3"]),
+	));
+	let mut second = helpers::build_ext(0x0010, &helpers::build_alpn_body(&[b"h2"]));
+	second.extend(helpers::build_ext(0x0017, &[]));
+	second.extend(helpers::build_ext(
+		0x0010,
+		&helpers::build_alpn_body(&[b"h3"]),
+	));
+	second.extend(helpers::build_ext(0x0042, &[0x01]));
+	let first = helpers::raw_with_extensions(&first);
+	let second = helpers::raw_with_extensions(&second);
+	let first = parse(&first).unwrap();
+	let second = parse(&second).unwrap();
+
+	assert_ne!(first.extensions, second.extensions);
+	assert_eq!(first.extensions_sorted(), second.extensions_sorted());
+	let sorted = first.extensions_sorted();
+	assert_eq!(
+		sorted.iter().map(|e| e.type_id()).collect::<Vec<_>>(),
+		[0x0010, 0x0010, 0x0017, 0x0042]
+	);
+	// Duplicates keep their wire order.
+	assert_eq!(sorted[0], &Extension::Alpn(vec![b"h2".as_slice()]));
+	assert_eq!(sorted[1], &Extension::Alpn(vec![b"h3".as_slice()]));
+}
+
+#[test]
+fn extension_into_owned_outlives_buffer() {
+	let (owned, borrowed_types) = {
+		let data = helpers::full_raw();
+		let hello = parse(&data).unwrap();
+		let types: Vec<u16> = hello.extensions.type_ids().collect();
+		let owned: Vec<ExtensionOwned> = hello
+			.extensions
+			.iter()
+			.cloned()
+			.map(Extension::into_owned)
+			.collect();
+		for (o, b) in owned.iter().zip(hello.extensions.iter()) {
+			assert_eq!(&o.as_extension(), b);
+		}
+		(owned, types)
+	};
+	assert_eq!(
+		owned
+			.iter()
+			.map(ExtensionOwned::type_id)
+			.collect::<Vec<_>>(),
+		borrowed_types
+	);
+	assert!(owned.iter().any(|e| matches!(
+		e,
+		ExtensionOwned::ServerName(names) if names[0].name == b"example.com"
+	)));
+}
+
+#[test]
+fn extensions_wrapper_queries() {
+	let data = helpers::full_raw();
+	let hello = parse(&data).unwrap();
+	let exts = &hello.extensions;
+	assert!(exts.contains(0x0000));
+	assert!(!exts.contains(0x0099));
+	assert!(matches!(exts.by_type(0x0010), Some(Extension::Alpn(_))));
+	assert!(exts.by_type(0x0099).is_none());
+	let ids: Vec<u16> = exts.type_ids().collect();
+	assert_eq!(ids.len(), exts.len());
+	assert_eq!(ids[0], 0x0000);
+	assert_eq!(exts.all_by_type(0x0010).count(), 1);
+	assert_eq!(exts.iter().count(), exts.clone().into_vec().len());
+}
+
+// Malformed extension recovery
+
+fn exts_with_bad_alpn() -> Vec<u8> {
+	let mut exts = helpers::build_ext(0x0010, &[0x00, 0x05, 0x02, b'h']);
+	exts.extend(helpers::build_ext(
+		0x0000,
+		&helpers::build_sni_body(&[(0x00, b"example.com")]),
+	));
+	exts
+}
+
+#[test]
+fn malformed_extension_aborts_by_default() {
+	let data = helpers::raw_with_extensions(&exts_with_bad_alpn());
+	assert!(matches!(parse(&data), Err(Error::Truncated { .. })));
+}
+
+#[test]
+fn malformed_extension_recovered() {
+	let exts = exts_with_bad_alpn();
+	let data = helpers::raw_with_extensions(&exts);
+	let hello = ParseOptions::new()
+		.recover_malformed_extensions(true)
+		.parse(&data)
+		.unwrap();
+	assert_eq!(hello.extensions.len(), 2);
+	let Extension::Malformed {
+		type_id,
+		error,
+		raw,
+	} = &hello.extensions[0]
+	else {
+		panic!("expected malformed ALPN, got {:?}", hello.extensions[0]);
+	};
+	assert_eq!(*type_id, 0x0010);
+	assert!(error.starts_with("truncated "), "{error}");
+	assert_eq!(*raw, &[0x00, 0x05, 0x02, b'h']);
+	assert_eq!(hello.extensions[0].type_id(), 0x0010);
+	assert_eq!(hello.find_extension(0x0010), Some(*raw));
+	assert_eq!(hello.server_name(), Some("example.com"));
+	assert!(hello.alpn_protocols().is_empty());
+}
+
+#[test]
+fn recovery_does_not_mask_list_framing_errors() {
+	// Extension length runs past the end of the extension list.
+	let data = helpers::raw_with_extensions(&[0x00, 0x10, 0x00, 0x09, 0x00]);
+	let opts = ParseOptions::new().recover_malformed_extensions(true);
+	assert!(matches!(opts.parse(&data), Err(Error::Truncated { .. })));
+}
+
+// Extension body trailing bytes
+
+fn exts_with_padded_sni() -> Vec<u8> {
+	let mut sni = helpers::build_sni_body(&[(0x00, b"example.com")]);
+	sni.extend_from_slice(&[0xde, 0xad]);
+	helpers::build_ext(0x0000, &sni)
+}
+
+#[test]
+fn trailing_bytes_recorded_by_default() {
+	let data = helpers::raw_with_extensions(&exts_with_padded_sni());
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.server_name(), Some("example.com"));
+	assert_eq!(hello.diagnostics.trailing_bytes, [0x0000]);
+	assert_eq!(
+		hello.diagnostics.warnings[..],
+		[Warning::TrailingBytes {
+			type_id: 0x0000,
+			len: 2
+		}]
+	);
+	assert!(!hello.diagnostics.is_clean());
+}
+
+#[test]
+fn trailing_bytes_rejected_in_strict_mode() {
+	let data = helpers::raw_with_extensions(&exts_with_padded_sni());
+	let opts = ParseOptions::new().strict_extension_bodies(true);
+	assert_eq!(
+		opts.parse(&data),
+		Err(Error::TrailingBytes {
+			type_id: 0x0000,
+			len: 2
+		})
+	);
+}
+
+#[test]
+fn strict_trailing_bytes_recoverable_as_malformed() {
+	let data = helpers::raw_with_extensions(&exts_with_padded_sni());
+	let hello = ParseOptions::new()
+		.strict_extension_bodies(true)
+		.recover_malformed_extensions(true)
+		.parse(&data)
+		.unwrap();
+	let Extension::Malformed { error, .. } = &hello.extensions[0] else {
+		panic!("expected malformed SNI, got {:?}", hello.extensions[0]);
+	};
+	assert_eq!(
+		*error,
+		Error::TrailingBytes {
+			type_id: 0x0000,
+			len: 2
+		}
+		.to_string()
+	);
+	assert_eq!(
+		hello.diagnostics.warnings[..],
+		[Warning::MalformedExtension { type_id: 0x0000 }]
+	);
+}
+
+#[test]
+fn well_formed_hello_has_clean_diagnostics() {
+	let data = helpers::full_raw();
+	let opts = ParseOptions::new().strict_extension_bodies(true);
+	assert!(opts.parse(&data).unwrap().diagnostics.is_clean());
+}
+
+// Record framing bounds
+
+fn record_with_extra_payload() -> Vec<u8> {
+	let mut record = helpers::wrap_record(&helpers::minimal_raw());
+	record.extend_from_slice(&[0x00, 0x00]);
+	let len = u16::try_from(record.len() - 5).unwrap();
+	record[3..5].copy_from_slice(&len.to_be_bytes());
+	record
+}
+
+#[test]
+fn handshake_length_mismatch_flagged() {
+	let record = record_with_extra_payload();
+	let hello = parse_from_record(&record).unwrap();
+	assert!(hello.diagnostics.handshake_length_mismatch);
+	assert!(!hello.diagnostics.oversized_record);
+	assert_eq!(
+		hello.diagnostics.warnings[..],
+		[Warning::HandshakeLengthMismatch {
+			handshake: 0x2d,
+			record: 0x2f
+		}]
+	);
+
+	let opts = ParseOptions::new().strict_record_framing(true);
+	assert_eq!(
+		opts.parse_from_record(&record),
+		Err(Error::HandshakeLengthMismatch {
+			handshake: 0x2d,
+			record: 0x2f
+		})
+	);
+}
+
+#[test]
+fn oversized_record_flagged() {
+	let record = Builder::new()
+		.cipher_suites(&[0x1301])
+		.extension(0x0042, &[0u8; 17_000])
+		.build_record()
+		.unwrap();
+	let hello = parse_from_record(&record).unwrap();
+	assert!(hello.diagnostics.oversized_record);
+	assert!(!hello.diagnostics.handshake_length_mismatch);
+
+	let opts = ParseOptions::new().strict_record_framing(true);
+	assert!(matches!(
+		opts.parse_from_record(&record),
+		Err(Error::RecordTooLong { max: 16384, .. })
+	));
+}
+
+#[test]
+fn strict_framing_accepts_well_formed_record() {
+	let record = helpers::wrap_record(&helpers::full_raw());
+	let opts = ParseOptions::new().strict_record_framing(true);
+	assert!(
+		opts
+			.parse_from_record(&record)
+			.unwrap()
+			.diagnostics
+			.is_clean()
+	);
+}
+
+// Warnings
+
+#[test]
+fn partial_list_entries_warned() {
+	let mut key_share = helpers::build_key_share_body(&[(0x001d, &[0x11; 32])]);
+	key_share.extend_from_slice(&[0x00, 0x1d]);
+	let list_len = u16::try_from(key_share.len() - 2).unwrap();
+	key_share[..2].copy_from_slice(&list_len.to_be_bytes());
+	let mut exts = helpers::build_ext(0x0033, &key_share);
+	exts.extend_from_slice(&[0xff, 0x01, 0x00]);
+	let data = helpers::raw_with_extensions(&exts);
+
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.key_share_groups(), &[0x001d]);
+	assert_eq!(
+		hello.diagnostics.warnings[..],
+		[
+			Warning::PartialListEntry {
+				type_id: Some(0x0033),
+				len: 2
+			},
+			Warning::PartialListEntry {
+				type_id: None,
+				len: 3
+			},
+		]
+	);
+}
+
+#[test]
+fn empty_required_lists_warned() {
+	let mut exts = helpers::build_ext(0x0010, &[0x00, 0x00]);
+	exts.extend(helpers::build_ext(0x002b, &[0x00]));
+	exts.extend(helpers::build_ext(0x0033, &[0x00, 0x00]));
+	let data = helpers::raw_with_extensions(&exts);
+	let hello = parse(&data).unwrap();
+	assert_eq!(
+		hello.diagnostics.warnings[..],
+		[
+			Warning::EmptyList { type_id: 0x0010 },
+			Warning::EmptyList { type_id: 0x002b },
+		]
+	);
+}
+
+#[test]
+fn repeated_grease_warned() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x0a0a, 0x1301, 0x0a0a])
+		.extension(0x1a1a, &[])
+		.extension(0x2a2a, &[0x00])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(
+		hello.diagnostics.warnings[..],
+		[Warning::RepeatedGreaseCipherSuite { value: 0x0a0a }]
+	);
+	assert!(
+		hello
+			.diagnostics
+			.warnings
+			.any(|w| matches!(w, Warning::RepeatedGreaseCipherSuite { .. }))
+	);
+}
+
+// Typed extension lookup
+
+#[test]
+fn get_typed_extensions() {
+	let data = helpers::full_raw();
+	let hello = parse(&data).unwrap();
+	let names = hello.get::<typed::ServerNames>().unwrap();
+	assert_eq!(names[0].name, b"example.com");
+	assert_eq!(hello.get::<typed::Alpn>(), Some(hello.alpn_protocols()));
+	assert_eq!(
+		hello.get::<typed::SupportedVersions>(),
+		Some(hello.supported_versions())
+	);
+	assert_eq!(
+		hello.get::<typed::KeyShareGroups>(),
+		Some(hello.key_share_groups())
+	);
+}
+
+#[test]
+fn get_absent_extension() {
+	let data = helpers::minimal_raw();
+	let hello = parse(&data).unwrap();
+	assert!(hello.get::<typed::ServerNames>().is_none());
+	assert!(hello.get::<typed::PskExchangeModes>().is_none());
+}
+
+#[test]
+fn get_with_custom_marker() {
+	enum CompressCertificate {}
+
+	impl FromExtension for CompressCertificate {
+		type Output<'h, 'a: 'h> = &'a [u8];
+
+		fn from_extension<'a>(ext: &Extension<'a>) -> Option<&'a [u8]> {
+			match ext {
+				Extension::Unknown {
+					type_id: 0x001b,
+					data,
+				} => Some(*data),
+				_ => None,
+			}
+		}
+	}
+
+	let ext = helpers::build_ext(0x001b, &[0x02, 0x00, 0x02]);
+	let data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	assert_eq!(
+		hello.get::<CompressCertificate>(),
+		Some([0x02, 0x00, 0x02].as_slice())
+	);
+}
+
 // Error path: handshake header
 
 #[test]
@@ -469,28 +1391,40 @@ fn error_parse_only_type_byte() {
 fn error_not_client_hello_server_hello() {
 	let mut data = helpers::minimal_raw();
 	data[0] = 0x02; // ServerHello
-	assert_eq!(parse(&data).unwrap_err(), Error::NotClientHello(0x02));
+	assert_eq!(
+		parse(&data).unwrap_err(),
+		Error::NotClientHello(HandshakeType::ServerHello)
+	);
 }
 
 #[test]
 fn error_not_client_hello_certificate() {
 	let mut data = helpers::minimal_raw();
 	data[0] = 0x0B; // Certificate
-	assert_eq!(parse(&data).unwrap_err(), Error::NotClientHello(0x0B));
+	assert_eq!(
+		parse(&data).unwrap_err(),
+		Error::NotClientHello(HandshakeType::Certificate)
+	);
 }
 
 #[test]
 fn error_not_client_hello_zero() {
 	let mut data = helpers::minimal_raw();
 	data[0] = 0x00; // HelloRequest
-	assert_eq!(parse(&data).unwrap_err(), Error::NotClientHello(0x00));
+	assert_eq!(
+		parse(&data).unwrap_err(),
+		Error::NotClientHello(HandshakeType::HelloRequest)
+	);
 }
 
 #[test]
 fn error_not_client_hello_0xff() {
 	let mut data = helpers::minimal_raw();
 	data[0] = 0xFF;
-	assert_eq!(parse(&data).unwrap_err(), Error::NotClientHello(0xFF));
+	assert_eq!(
+		parse(&data).unwrap_err(),
+		Error::NotClientHello(HandshakeType::Unknown(0xFF))
+	);
 }
 
 // Error path: body field truncation
@@ -550,7 +1484,12 @@ fn error_truncated_session_id() {
 	body.extend_from_slice(&[0u8; 10]); // only 10 bytes
 	let data = helpers::wrap_handshake(&body);
 	let err = parse(&data).unwrap_err();
-	assert_eq!(err, Error::Truncated { field: "session ID" });
+	assert_eq!(
+		err,
+		Error::Truncated {
+			field: "session ID"
+		}
+	);
 }
 
 #[test]
@@ -913,7 +1852,7 @@ fn error_record_content_type_alert() {
 	rec[0] = 0x15; // Alert
 	assert_eq!(
 		parse_from_record(&rec).unwrap_err(),
-		Error::NotHandshakeRecord(0x15)
+		Error::NotHandshakeRecord(ContentType::Alert)
 	);
 }
 
@@ -924,7 +1863,7 @@ fn error_record_content_type_ccs() {
 	rec[0] = 0x14; // ChangeCipherSpec
 	assert_eq!(
 		parse_from_record(&rec).unwrap_err(),
-		Error::NotHandshakeRecord(0x14)
+		Error::NotHandshakeRecord(ContentType::ChangeCipherSpec)
 	);
 }
 
@@ -935,7 +1874,7 @@ fn error_record_content_type_0xff() {
 	rec[0] = 0xFF;
 	assert_eq!(
 		parse_from_record(&rec).unwrap_err(),
-		Error::NotHandshakeRecord(0xFF)
+		Error::NotHandshakeRecord(ContentType::Unknown(0xFF))
 	);
 }
 
@@ -957,3 +1896,544 @@ fn error_record_four_bytes() {
 	let err = parse_from_record(&[0x16, 0x03, 0x01, 0x00]).unwrap_err();
 	assert_eq!(err, Error::BufferTooShort { need: 5, have: 4 });
 }
+
+// Happy path: QUIC version inference
+
+#[test]
+fn quic_versions_from_alpn() {
+	let alpn_body = helpers::build_alpn_body(&[b"h3", b"h3-29", b"h2"]);
+	let ext = helpers::build_ext(0x0010, &alpn_body);
+	let data = helpers::raw_with_extensions(&ext);
+	let hello = parse(&data).unwrap();
+	assert_eq!(
+		hello.inferred_quic_versions(),
+		vec![0x0000_0001, 0xff00_001d]
+	);
+}
+
+#[test]
+fn quic_versions_from_transport_parameters() {
+	// version_information (0x11): chosen = v2, available = [v2, v1]
+	let mut params = vec![0x11, 12];
+	params.extend_from_slice(&0x6b33_43cfu32.to_be_bytes());
+	params.extend_from_slice(&0x6b33_43cfu32.to_be_bytes());
+	params.extend_from_slice(&0x0000_0001u32.to_be_bytes());
+	let mut exts = helpers::build_ext(0x0039, &params);
+	exts.extend_from_slice(&helpers::build_ext(
+		0x0010,
+		&helpers::build_alpn_body(&[b"h3"]),
+	));
+	let data = helpers::raw_with_extensions(&exts);
+	let hello = parse(&data).unwrap();
+	assert_eq!(
+		hello.inferred_quic_versions(),
+		vec![0x6b33_43cf, 0x0000_0001]
+	);
+}
+
+#[test]
+fn quic_versions_empty_for_tcp_hello() {
+	let data = helpers::full_raw();
+	let hello = parse(&data).unwrap();
+	assert!(hello.inferred_quic_versions().is_empty());
+}
+
+// Happy path: transport tagging
+
+#[test]
+fn transport_raw_handshake() {
+	let data = helpers::minimal_raw();
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.transport, Transport::Handshake);
+}
+
+#[test]
+fn transport_record_layer() {
+	let record = helpers::wrap_record(&helpers::minimal_raw());
+	let hello = parse_from_record(&record).unwrap();
+	assert_eq!(hello.transport, Transport::TlsRecord);
+}
+
+// Error display
+
+#[test]
+fn error_display_uses_type_names() {
+	let err = Error::NotClientHello(HandshakeType::ServerHello);
+	assert_eq!(
+		err.to_string(),
+		"unexpected handshake type: expected ClientHello (0x01), got ServerHello (0x02)"
+	);
+	let err = Error::NotHandshakeRecord(ContentType::Unknown(0xFF));
+	assert_eq!(
+		err.to_string(),
+		"unexpected content type: expected Handshake (0x16), got unknown (0xff)"
+	);
+}
+
+// Public TLS reader
+
+#[test]
+fn tls_reader_vectors() {
+	let data = [0x02, 0xAA, 0xBB, 0x00, 0x00, 0x01, 0xCC, 0x00, 0x01, 0xDD];
+	let mut r = Reader::new(&data);
+	assert_eq!(r.read_vec_u8("a").unwrap(), &[0xAA, 0xBB]);
+	assert_eq!(r.read_vec_u24("b").unwrap(), &[0xCC]);
+	assert_eq!(r.position(), 7);
+	assert_eq!(r.read_vec_u16("c").unwrap(), &[0xDD]);
+	assert!(r.is_empty());
+}
+
+#[test]
+fn tls_reader_truncated_vector() {
+	let mut r = Reader::new(&[0x00, 0x05, 0x01]);
+	assert_eq!(
+		r.read_vec_u16("ticket").unwrap_err(),
+		Error::Truncated { field: "ticket" }
+	);
+}
+
+#[test]
+fn tls_reader_nested_vectors() {
+	// u24 list of u8-prefixed entries, then a trailing u16.
+	let data = [0x00, 0x00, 0x05, 0x01, 0xAA, 0x02, 0xBB, 0xCC, 0x12, 0x34];
+	let mut r = Reader::new(&data);
+	let entries = r
+		.read_nested_u24("list", |inner| {
+			let mut entries = Vec::new();
+			while !inner.is_empty() {
+				entries.push(inner.read_vec_u8("entry")?);
+			}
+			Ok::<_, Error>(entries)
+		})
+		.unwrap();
+	assert_eq!(entries, [&[0xAA][..], &[0xBB, 0xCC]]);
+	assert_eq!(r.read_u16("tail").unwrap(), 0x1234);
+
+	// An entry may not read past its enclosing vector.
+	let mut r = Reader::new(&[0x02, 0x00, 0x05, 0x01, 0x02, 0x03]);
+	assert_eq!(
+		r.read_nested_u8("outer", |inner| inner.read_vec_u16("inner")),
+		Err(Error::Truncated { field: "inner" })
+	);
+}
+
+#[derive(Debug, PartialEq)]
+enum CustomError {
+	Tls(Error),
+	Empty,
+}
+
+impl From<Error> for CustomError {
+	fn from(e: Error) -> Self {
+		Self::Tls(e)
+	}
+}
+
+fn decode_names(data: &[u8]) -> Result<&[u8], CustomError> {
+	Reader::new(data).read_nested_u16("names", |r| {
+		if r.is_empty() {
+			return Err(CustomError::Empty);
+		}
+		Ok(r.read_vec_u8("name")?)
+	})
+}
+
+#[test]
+fn tls_reader_nested_custom_error() {
+	assert_eq!(decode_names(&[0x00, 0x00]), Err(CustomError::Empty));
+	assert_eq!(
+		decode_names(&[0x00]),
+		Err(CustomError::Tls(Error::Truncated { field: "names" }))
+	);
+	assert_eq!(decode_names(&[0x00, 0x02, 0x01, 0x61]), Ok(&b"a"[..]));
+}
+
+#[test]
+fn error_implements_core_error() {
+	let err: Box<dyn core::error::Error + Send + Sync> = Box::new(parse(&[]).unwrap_err());
+	assert_eq!(err.to_string(), "buffer too short: need 1 bytes, have 0");
+	assert!(err.source().is_none());
+}
+
+// SNI fast path
+
+#[test]
+fn extract_sni_raw_and_record() {
+	let raw = helpers::full_raw();
+	assert_eq!(extract_sni(&raw).unwrap(), Some("example.com"));
+	let record = helpers::wrap_record(&raw);
+	assert_eq!(
+		extract_sni_from_record(&record).unwrap(),
+		Some("example.com")
+	);
+}
+
+#[test]
+fn extract_sni_absent() {
+	assert_eq!(extract_sni(&helpers::minimal_raw()).unwrap(), None);
+	let ext = helpers::build_ext(0x0042, &[0x01]);
+	assert_eq!(
+		extract_sni(&helpers::raw_with_extensions(&ext)).unwrap(),
+		None
+	);
+}
+
+#[test]
+fn extract_sni_matches_full_parse() {
+	let sni_body = helpers::build_sni_body(&[(0x01, b"wrong.com"), (0x00, b"correct.com")]);
+	let ext = helpers::build_ext(0x0000, &sni_body);
+	let data = helpers::raw_with_extensions(&ext);
+	assert_eq!(
+		extract_sni(&data).unwrap(),
+		parse(&data).unwrap().server_name()
+	);
+}
+
+#[test]
+fn extract_sni_errors() {
+	let mut data = helpers::minimal_raw();
+	data[0] = 0x02;
+	assert_eq!(
+		extract_sni(&data).unwrap_err(),
+		Error::NotClientHello(HandshakeType::ServerHello)
+	);
+	let sni_body = [0x00, 0x20];
+	let ext = helpers::build_ext(0x0000, &sni_body);
+	assert_eq!(
+		extract_sni(&helpers::raw_with_extensions(&ext)).unwrap_err(),
+		Error::Truncated {
+			field: "SNI list data"
+		}
+	);
+}
+
+// One-pass summary
+
+#[test]
+fn summarize_matches_full_parse() {
+	let raw = helpers::full_raw();
+	let hello = parse(&raw).unwrap();
+	let summary = summarize(&raw).unwrap();
+	assert_eq!(summary.sni, hello.server_name());
+	assert_eq!(
+		summary.alpn_protocols().collect::<Vec<_>>(),
+		hello.alpn_protocols()
+	);
+	assert_eq!(
+		Some(summary.max_version),
+		hello.supported_versions().iter().copied().max()
+	);
+	assert_eq!(summary.cipher_count, hello.cipher_suites.len());
+	assert!(!summary.has_ech);
+	assert_eq!(
+		summarize_from_record(&helpers::wrap_record(&raw)).unwrap(),
+		summary
+	);
+}
+
+#[test]
+fn summarize_grease_and_ech() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x0a0a, 0x1301, 0x1302])
+		.supported_versions(&[0x7a7a, 0x0304, 0x0303])
+		.extension(0xfe0d, &[0x00])
+		.build()
+		.unwrap();
+	let summary = summarize(&msg).unwrap();
+	assert_eq!(summary.cipher_count, 2);
+	assert_eq!(summary.max_version, 0x0304);
+	assert!(summary.has_ech);
+	assert_eq!(summary.sni, None);
+	assert!(summary.alpn.is_empty());
+	assert_eq!(
+		summary.extensions,
+		ExtensionBitmap::from_types(&[0x002b, 0xfe0d])
+	);
+	assert_eq!(summary.extensions, parse(&msg).unwrap().extension_bitmap());
+}
+
+#[test]
+fn extension_bitmap_filters() {
+	let high = [0x4469, 0x44cd, 0xfe0d, 0xff01, 0x7550, 0xfe02];
+	let mut all = ExtensionBitmap::new();
+	all.extend((0..128).chain(high));
+	for t in (0..128).chain(high) {
+		assert!(all.contains(t));
+	}
+	// Low types are exact and the common high types do not collide.
+	for (i, &t) in high.iter().enumerate() {
+		let single = ExtensionBitmap::new().with(t);
+		assert!(!single.contains(0x0000));
+		for &other in &high[i + 1..] {
+			assert!(!single.contains(other), "{t:#06x} and {other:#06x}");
+		}
+	}
+	let tls13 = ExtensionBitmap::from_types(&[0x002b, 0x0033]);
+	let seen = ExtensionBitmap::from_types(&[0x0000, 0x002b]);
+	assert!(!seen.contains_all(&tls13));
+	assert!(seen.intersects(&tls13));
+	assert!(!seen.intersects(&ExtensionBitmap::from_types(&[0xff01])));
+	assert!(ExtensionBitmap::default().is_empty());
+	assert!(seen.contains_all(&ExtensionBitmap::new()));
+}
+
+#[test]
+fn summarize_without_extensions() {
+	let data = helpers::minimal_raw();
+	let summary = summarize(&data).unwrap();
+	assert_eq!(summary.max_version, 0x0303);
+	assert_eq!(summary.sni, None);
+}
+
+#[test]
+fn summarize_errors() {
+	let ext = helpers::build_ext(0x0010, &[0x00, 0x20]);
+	assert_eq!(
+		summarize(&helpers::raw_with_extensions(&ext)).unwrap_err(),
+		Error::Truncated {
+			field: "ALPN list data"
+		}
+	);
+	assert_eq!(
+		summarize_from_record(&[0x17, 0x03, 0x03, 0x00, 0x00]).unwrap_err(),
+		Error::NotHandshakeRecord(ContentType::ApplicationData)
+	);
+}
+
+// Parse limits
+
+#[test]
+fn limits_default_accepts_full_hello() {
+	let data = helpers::full_raw();
+	assert_eq!(
+		ParseOptions::new().parse(&data).unwrap(),
+		parse(&data).unwrap()
+	);
+	let record = helpers::wrap_record(&data);
+	assert_eq!(
+		ParseOptions::default().parse_from_record(&record).unwrap(),
+		parse_from_record(&record).unwrap()
+	);
+}
+
+#[test]
+fn limit_cipher_suites() {
+	let mut body = helpers::minimal_body();
+	body.truncate(35);
+	body.extend_from_slice(&[0x00, 0x06, 0x0a, 0x0a, 0x13, 0x01, 0x13, 0x02, 0x01, 0x00]);
+	let data = helpers::wrap_handshake(&body);
+	// GREASE entries count toward the limit.
+	assert_eq!(
+		ParseOptions::new().max_cipher_suites(2).parse(&data),
+		Err(Error::LimitExceeded {
+			field: "cipher suites",
+			max: 2
+		})
+	);
+	assert!(
+		ParseOptions::new()
+			.max_cipher_suites(3)
+			.parse(&data)
+			.is_ok()
+	);
+}
+
+#[test]
+fn limit_extensions() {
+	let mut exts = helpers::build_ext(0x0042, &[]);
+	exts.extend(helpers::build_ext(0x0043, &[]));
+	let data = helpers::raw_with_extensions(&exts);
+	assert_eq!(
+		ParseOptions::new().max_extensions(1).parse(&data),
+		Err(Error::LimitExceeded {
+			field: "extensions",
+			max: 1
+		})
+	);
+	assert!(ParseOptions::new().max_extensions(2).parse(&data).is_ok());
+}
+
+#[test]
+fn limit_list_entries() {
+	let opts = ParseOptions::new().max_list_entries(1);
+	let cases = [
+		(
+			helpers::build_ext(0x0000, &helpers::build_sni_body(&[(0, b"a"), (0, b"b")])),
+			"SNI list",
+		),
+		(
+			helpers::build_ext(0x0010, &helpers::build_alpn_body(&[b"h2", b"h3"])),
+			"ALPN list",
+		),
+		(
+			helpers::build_ext(
+				0x002b,
+				&helpers::build_supported_versions_body(&[0x0304, 0x0303]),
+			),
+			"supported versions",
+		),
+		(
+			helpers::build_ext(0x000a, &helpers::build_u16_list_body(&[0x001d, 0x0017])),
+			"u16 list",
+		),
+		(
+			helpers::build_ext(0x000d, &helpers::build_u16_list_body(&[0x0403, 0x0804])),
+			"signature algorithms",
+		),
+		(
+			helpers::build_ext(
+				0x0033,
+				&helpers::build_key_share_body(&[(0x001d, &[1]), (0x0017, &[2])]),
+			),
+			"key share list",
+		),
+	];
+	for (ext, field) in cases {
+		let data = helpers::raw_with_extensions(&ext);
+		assert_eq!(
+			opts.parse(&data),
+			Err(Error::LimitExceeded { field, max: 1 })
+		);
+		assert!(parse(&data).is_ok());
+	}
+}
+
+#[test]
+fn limit_error_display() {
+	let err = Error::LimitExceeded {
+		field: "extensions",
+		max: 8,
+	};
+	assert_eq!(err.to_string(), "extensions exceeds limit of 8 entries");
+}
+
+// GREASE positions and GREASE-insensitive equality
+
+fn greased(cs_grease: u16, ext_grease: u16, grease_first: bool) -> Vec<u8> {
+	let suites = if grease_first {
+		[cs_grease, 0x1301, 0x1302]
+	} else {
+		[0x1301, cs_grease, 0x1302]
+	};
+	Builder::new()
+		.cipher_suites(&suites)
+		.extension(ext_grease, &[])
+		.server_name("example.com")
+		.extension(0xfafa, &[0x00])
+		.build()
+		.unwrap()
+}
+
+#[test]
+fn grease_positions_recorded() {
+	let data = greased(0x0a0a, 0x1a1a, false);
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.cipher_suites, vec![0x1301, 0x1302]);
+	assert_eq!(hello.grease.cipher_suites, vec![1]);
+	assert_eq!(hello.grease.extensions, vec![0, 2]);
+	assert_eq!(hello.extensions.len(), 1);
+}
+
+#[test]
+fn grease_positions_within_extensions() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.supported_versions(&[0x4a4a, 0x0304, 0x0303])
+		.supported_groups(&[0x001d, 0x0017, 0x8a8a])
+		.key_share(&[(0x001d, &[0x11; 32]), (0x2a2a, &[0x00])])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(hello.grease.supported_versions, [0]);
+	assert_eq!(hello.grease.supported_groups, [2]);
+	assert_eq!(hello.grease.key_share, [1]);
+	assert_eq!(hello.supported_versions(), [0x0304, 0x0303]);
+	assert!(hello.grease.cipher_suites.is_empty());
+}
+
+#[test]
+fn wire_extension_types_restore_grease() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.extension(0x0a0a, &[])
+		.extension(0x1a1a, &[])
+		.alpn(&[b"h2"])
+		.extension(0xfafa, &[0x00])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(
+		hello.wire_extension_types().collect::<Vec<_>>(),
+		[
+			GREASE_PLACEHOLDER,
+			GREASE_PLACEHOLDER,
+			0x0010,
+			GREASE_PLACEHOLDER
+		]
+	);
+
+	let data = helpers::full_raw();
+	let hello = parse(&data).unwrap();
+	assert!(hello.wire_extension_types().eq(hello.extensions.type_ids()));
+}
+
+#[test]
+fn grease_positions_empty_without_grease() {
+	let data = helpers::minimal_raw();
+	let hello = parse(&data).unwrap();
+	assert_eq!(hello.grease, GreasePositions::default());
+}
+
+#[test]
+fn eq_ignoring_grease_values() {
+	let a = greased(0x0a0a, 0x1a1a, false);
+	let b = greased(0xdada, 0x3a3a, false);
+	assert_ne!(a, b);
+	assert!(parse(&a).unwrap().eq_ignoring_grease(&parse(&b).unwrap()));
+}
+
+#[test]
+fn eq_ignoring_grease_matches_live_traffic() {
+	let hello = |grease: u16, random: u8, key: u8, host: &str| {
+		Builder::new()
+			.random([random; 32])
+			.session_id(&[random; 32])
+			.cipher_suites(&[grease, 0x1301, 0x1302])
+			.extension(grease, &[])
+			.server_name(host)
+			.supported_versions(&[grease, 0x0304, 0x0303])
+			.supported_groups(&[grease, 0x001d])
+			.key_share(&[(grease, &[0x00]), (0x001d, &[key; 32])])
+			.build()
+			.unwrap()
+	};
+	let template = hello(0x0a0a, 0x01, 0x11, "template.example");
+	let live = hello(0x9a9a, 0x02, 0x22, "live.example");
+	let (template, live) = (parse(&template).unwrap(), parse(&live).unwrap());
+	assert_ne!(template, live);
+	assert!(template.eq_ignoring_grease(&live));
+
+	let other_groups = Builder::new()
+		.random([0x02; 32])
+		.session_id(&[0x02; 32])
+		.cipher_suites(&[0x9a9a, 0x1301, 0x1302])
+		.extension(0x9a9a, &[])
+		.server_name("live.example")
+		.supported_versions(&[0x9a9a, 0x0304, 0x0303])
+		.supported_groups(&[0x9a9a, 0x0017])
+		.key_share(&[(0x9a9a, &[0x00]), (0x001d, &[0x22; 32])])
+		.build()
+		.unwrap();
+	assert!(!template.eq_ignoring_grease(&parse(&other_groups).unwrap()));
+}
+
+#[test]
+fn eq_ignoring_grease_checks_positions() {
+	let a = greased(0x0a0a, 0x1a1a, false);
+	let moved = greased(0x0a0a, 0x1a1a, true);
+	assert!(
+		!parse(&a)
+			.unwrap()
+			.eq_ignoring_grease(&parse(&moved).unwrap())
+	);
+}
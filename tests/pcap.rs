@@ -0,0 +1,170 @@
+/* tests/pcap.rs */
+#![allow(missing_docs)]
+
+use std::cell::Cell;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clienthello::pcap::{PcapError, PcapReader, Pipeline, Protocol};
+use clienthello::{Builder, Error};
+
+fn global_header() -> Vec<u8> {
+	let mut out = Vec::new();
+	out.extend_from_slice(&0xa1b2_c3d4u32.to_le_bytes());
+	out.extend_from_slice(&[2, 0, 4, 0]);
+	out.extend_from_slice(&[0; 8]);
+	out.extend_from_slice(&65535u32.to_le_bytes());
+	out.extend_from_slice(&1u32.to_le_bytes());
+	out
+}
+
+fn push_packet(pcap: &mut Vec<u8>, secs: u32, frame: &[u8]) {
+	pcap.extend_from_slice(&secs.to_le_bytes());
+	pcap.extend_from_slice(&500u32.to_le_bytes());
+	pcap.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+	pcap.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+	pcap.extend_from_slice(frame);
+}
+
+/// An Ethernet/IPv4 frame from 10.0.0.`host`:`sport` to 10.0.0.1:443.
+fn frame(host: u8, sport: u16, tcp_seq: Option<u32>, payload: &[u8]) -> Vec<u8> {
+	let mut transport = Vec::new();
+	transport.extend_from_slice(&sport.to_be_bytes());
+	transport.extend_from_slice(&443u16.to_be_bytes());
+	let protocol = if let Some(seq) = tcp_seq {
+		transport.extend_from_slice(&seq.to_be_bytes());
+		transport.extend_from_slice(&[0, 0, 0, 0, 0x50, 0x18, 0xff, 0xff, 0, 0, 0, 0]);
+		6
+	} else {
+		transport.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+		transport.extend_from_slice(&[0, 0]);
+		17
+	};
+	transport.extend_from_slice(payload);
+	let mut out = vec![0; 12];
+	out.extend_from_slice(&[0x08, 0x00]);
+	out.extend_from_slice(&[0x45, 0]);
+	out.extend_from_slice(&((20 + transport.len()) as u16).to_be_bytes());
+	out.extend_from_slice(&[0, 0, 0x40, 0, 64, protocol, 0, 0]);
+	out.extend_from_slice(&[10, 0, 0, host, 10, 0, 0, 1]);
+	out.extend_from_slice(&transport);
+	out
+}
+
+fn dtls_record() -> Vec<u8> {
+	let msg = Builder::new()
+		.legacy_version(0xfefd)
+		.cipher_suites(&[0xc02b])
+		.build()
+		.unwrap();
+	let mut body = msg[4..4 + 35].to_vec();
+	body.push(0);
+	body.extend_from_slice(&msg[4 + 35..]);
+	let len = (body.len() as u32).to_be_bytes();
+	let mut handshake = vec![0x01, len[1], len[2], len[3], 0, 0, 0, 0, 0];
+	handshake.extend_from_slice(&len[1..]);
+	handshake.extend_from_slice(&body);
+	let mut record = vec![0x16, 0xfe, 0xfd, 0, 0, 0, 0, 0, 0, 0, 0];
+	record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+	record.extend_from_slice(&handshake);
+	record
+}
+
+fn capture() -> Vec<u8> {
+	let record = Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name("split.example")
+		.build_record()
+		.unwrap();
+	let other = Builder::new()
+		.cipher_suites(&[0x1302])
+		.server_name("whole.example")
+		.build_record()
+		.unwrap();
+	let (head, tail) = record.split_at(20);
+	let mut pcap = global_header();
+	push_packet(&mut pcap, 1, &frame(2, 40000, Some(1000), head));
+	// Retransmission of the first segment, then the rest.
+	push_packet(&mut pcap, 2, &frame(2, 40000, Some(1000), head));
+	push_packet(&mut pcap, 3, &frame(3, 40001, Some(7), &other));
+	push_packet(&mut pcap, 4, &frame(2, 40000, Some(1020), tail));
+	// Later records on a finished flow and non-TLS traffic are ignored.
+	push_packet(&mut pcap, 5, &frame(3, 40001, Some(7), &other));
+	push_packet(&mut pcap, 6, &frame(4, 5353, None, b"not dtls"));
+	push_packet(&mut pcap, 7, &frame(5, 40002, None, &dtls_record()));
+	// A stream that never completes.
+	push_packet(&mut pcap, 8, &frame(6, 40003, Some(1), &record[..30]));
+	pcap
+}
+
+#[test]
+fn pipeline_reports_each_flow() {
+	let data = capture();
+	let reader = PcapReader::new(&data[..]).unwrap();
+	assert_eq!(reader.link_type(), 1);
+	let batches = Cell::new(0);
+	let flows: Vec<_> = Pipeline::new(|hello| hello.server_name().map(String::from))
+		.batch_size(2)
+		.on_progress(|_| batches.set(batches.get() + 1))
+		.run(reader)
+		.collect();
+	assert_eq!(flows.len(), 4);
+
+	assert_eq!(
+		flows[0].flow.src,
+		"10.0.0.3:40001".parse::<SocketAddr>().unwrap()
+	);
+	assert_eq!(flows[0].result, Ok(Some("whole.example".into())));
+	assert_eq!(
+		flows[1].flow.src,
+		"10.0.0.2:40000".parse::<SocketAddr>().unwrap()
+	);
+	assert_eq!(
+		flows[1].flow.dst,
+		"10.0.0.1:443".parse::<SocketAddr>().unwrap()
+	);
+	assert_eq!(flows[1].flow.protocol, Protocol::Tcp);
+	assert_eq!(flows[1].timestamp, Duration::new(4, 500_000));
+	assert_eq!(flows[1].result, Ok(Some("split.example".into())));
+	assert_eq!(flows[2].flow.protocol, Protocol::Udp);
+	assert_eq!(flows[2].result, Ok(None));
+	assert!(matches!(flows[3].result, Err(Error::Truncated { .. })));
+	assert_eq!(batches.get(), 2);
+}
+
+#[test]
+fn pipeline_progress_and_errors() {
+	let mut data = capture();
+	// A packet record cut short.
+	data.extend_from_slice(&[9, 0, 0, 0, 0, 0, 0, 0, 50, 0, 0, 0, 50, 0, 0, 0, 1, 2]);
+	let reader = PcapReader::new(&data[..]).unwrap();
+	let last = Cell::new(None);
+	let errors = Cell::new(0);
+	let mut flows = Pipeline::new(|hello| hello.cipher_suites.len())
+		.on_progress(|p| last.set(Some(*p)))
+		.on_error(|e| {
+			assert!(matches!(e, PcapError::Io(_)));
+			errors.set(errors.get() + 1);
+		})
+		.run(reader);
+	assert_eq!(flows.by_ref().count(), 4);
+	let progress = flows.progress();
+	assert_eq!(progress.packets, 8);
+	assert_eq!(progress.hellos, 4);
+	assert_eq!(progress.errors, 1);
+	drop(flows);
+	assert_eq!(last.get(), Some(progress));
+	assert_eq!(errors.get(), 1);
+}
+
+#[test]
+fn rejects_non_pcap_input() {
+	assert!(matches!(
+		PcapReader::new(&[0u8; 24][..]),
+		Err(PcapError::BadMagic(0))
+	));
+	assert!(matches!(
+		PcapReader::new(&[0u8; 3][..]),
+		Err(PcapError::Io(_))
+	));
+}
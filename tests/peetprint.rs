@@ -0,0 +1,61 @@
+/* tests/peetprint.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, parse};
+
+fn chrome_like() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x3a3a, 0x1301, 0x1302, 0xc02b])
+		.extension(0xdada, &[])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.supported_groups(&[0x2a2a, 29, 23])
+		.alpn(&[b"h2", b"http/1.1"])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.psk_exchange_modes(&[1])
+		.extension(0x001b, &[2, 0, 2])
+		.supported_versions(&[0x1a1a, 0x0304, 0x0303])
+		.extension(0x4469, &[0, 3, 2, b'h', b'2'])
+		.extension(0xfafa, &[0])
+}
+
+#[test]
+fn peetprint_fields() {
+	let msg = chrome_like().build().unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(
+		hello.peetprint(),
+		"GREASE-772-771|2-1.1|GREASE-29-23|1027-2052|1|2|GREASE-4865-4866-49195|\
+		 0-10-13-16-17513-23-27-43-45-GREASE-GREASE"
+	);
+	assert_eq!(hello.peetprint_hash().len(), 32);
+}
+
+#[test]
+fn peetprint_ignores_extension_order() {
+	let a = chrome_like().build().unwrap();
+	let b = Builder::new()
+		.cipher_suites(&[0x3a3a, 0x1301, 0x1302, 0xc02b])
+		.supported_versions(&[0x1a1a, 0x0304, 0x0303])
+		.extension(0x2a2a, &[])
+		.extension(0x001b, &[2, 0, 2])
+		.psk_exchange_modes(&[1])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_groups(&[0x2a2a, 29, 23])
+		.extension(0x0017, &[])
+		.server_name("example.org")
+		.extension(0x4469, &[0, 3, 2, b'h', b'2'])
+		.extension(0x0a0a, &[0])
+		.build()
+		.unwrap();
+	let (a, b) = (parse(&a).unwrap(), parse(&b).unwrap());
+	assert_eq!(a.peetprint(), b.peetprint());
+	assert_eq!(a.peetprint_hash(), b.peetprint_hash());
+}
+
+#[test]
+fn peetprint_empty_fields() {
+	let msg = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	assert_eq!(parse(&msg).unwrap().peetprint(), "||||||4865|");
+}
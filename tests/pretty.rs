@@ -0,0 +1,49 @@
+/* tests/pretty.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, parse_from_record};
+
+fn hello_record(builder: &Builder) -> Vec<u8> {
+	builder.build_record().unwrap()
+}
+
+fn browser_like() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1a1a, 0x1301, 0xc02b])
+		.server_name("example.com")
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0304, 0x0303])
+}
+
+#[test]
+fn plain_layout() {
+	let msg = hello_record(&browser_like());
+	let hello = parse_from_record(&msg).unwrap();
+	let text = hello.pretty().color(false).to_string();
+	assert!(text.starts_with("ClientHello (TLS record)\n"));
+	assert!(text.contains("  cipher_suites: GREASE, 0x1301, 0xc02b\n"));
+	assert!(text.contains("  sni: example.com\n"));
+	assert!(text.contains("  alpn: h2, http/1.1\n"));
+	assert!(text.contains("  supported_versions: 0x0304, 0x0303\n"));
+	assert!(!text.contains('\x1b'));
+}
+
+#[test]
+fn colors_grease_and_anomalies() {
+	let msg = hello_record(&browser_like().cipher_suites(&[0x1a1a, 0x1301, 0x0099]));
+	let hello = parse_from_record(&msg).unwrap();
+	let text = hello.pretty().to_string();
+	assert!(text.contains("\x1b[1;36mcipher_suites\x1b[0m"));
+	assert!(text.contains("\x1b[33mGREASE\x1b[0m"));
+	assert!(text.contains("\x1b[1;31m0x0099\x1b[0m"));
+	assert!(!text.contains("\x1b[1;31m0x1301"));
+}
+
+#[test]
+fn hostile_values_cannot_inject_escapes() {
+	let msg = hello_record(&browser_like().server_name("a\x1b[2Jb"));
+	let hello = parse_from_record(&msg).unwrap();
+	let text = hello.pretty().color(false).to_string();
+	assert!(!text.contains('\x1b'));
+	assert!(text.contains("  sni: a\\u{1b}[2Jb\n"));
+}
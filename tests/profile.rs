@@ -0,0 +1,81 @@
+/* tests/profile.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, Profile, ProfileField, parse};
+
+/// `openssl s_client -connect example.com:443` from OpenSSL 3.2.
+fn s_client() -> Builder {
+	let p = Profile::OPENSSL_S_CLIENT;
+	Builder::new()
+		.cipher_suites(p.cipher_suites)
+		.server_name("example.com")
+		.extension(0x000b, &[0x03, 0x00, 0x01, 0x02])
+		.supported_groups(p.supported_groups)
+		.extension(0x0023, &[])
+		.extension(0x0016, &[])
+		.extension(0x0017, &[])
+		.signature_algorithms(p.signature_algorithms)
+		.supported_versions(p.supported_versions)
+		.psk_exchange_modes(&[0x01])
+		.key_share(&[(0x001d, &[0x11; 32])])
+}
+
+/// rustls client with its default configuration.
+fn rustls() -> Builder {
+	let p = Profile::RUSTLS;
+	Builder::new()
+		.cipher_suites(p.cipher_suites)
+		.supported_versions(p.supported_versions)
+		.extension(0x000b, &[0x01, 0x00])
+		.supported_groups(p.supported_groups)
+		.signature_algorithms(p.signature_algorithms)
+		.extension(0x0017, &[])
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.server_name("example.com")
+		.extension(0x0023, &[])
+		.key_share(&[(0x11ec, &[0x33; 1216]), (0x001d, &[0x22; 32])])
+		.psk_exchange_modes(&[0x01])
+}
+
+#[test]
+fn s_client_matches_exactly() {
+	let msg = s_client().build().unwrap();
+	let m = parse(&msg).unwrap().closest_profile();
+	assert_eq!(m.profile.name, "OpenSSL 3.2 s_client");
+	assert_eq!(m.score, 100);
+	assert!(m.is_exact());
+}
+
+#[test]
+fn rustls_matches_regardless_of_sni_alpn_and_padding() {
+	let msg = rustls()
+		.alpn(&[b"h2"])
+		.extension(0x0015, &[0x00; 40])
+		.build()
+		.unwrap();
+	let m = parse(&msg).unwrap().closest_profile();
+	assert_eq!(m.profile.name, "rustls 0.23 (aws-lc-rs)");
+	assert!(m.is_exact());
+}
+
+#[test]
+fn reordered_ciphers_reported_as_mismatch() {
+	let mut suites = Profile::RUSTLS.cipher_suites.to_vec();
+	suites.swap(0, 1);
+	let msg = rustls().cipher_suites(&suites).build().unwrap();
+	let m = parse(&msg).unwrap().closest_profile();
+	assert_eq!(m.profile.name, "rustls 0.23 (aws-lc-rs)");
+	assert_eq!(m.mismatched, [ProfileField::CipherSuites]);
+	// Same values in another order still count as shared.
+	assert_eq!(m.score, 100);
+}
+
+#[test]
+fn missing_extension_lowers_score() {
+	let msg = s_client().extension(0x0031, &[]).build().unwrap();
+	let hello = parse(&msg).unwrap();
+	let m = Profile::OPENSSL_S_CLIENT.compare(&hello);
+	assert_eq!(m.mismatched, [ProfileField::Extensions]);
+	assert_eq!(m.score, 98);
+	assert!(Profile::GO.compare(&hello).score < m.score);
+}
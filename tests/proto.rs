@@ -0,0 +1,46 @@
+/* tests/proto.rs */
+#![allow(missing_docs)]
+
+use clienthello::proto::{self, extension::Body};
+use clienthello::{Builder, parse};
+use prost::Message;
+
+#[test]
+fn converts_parsed_hello() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x0A0A, 0x1301])
+		.server_name("example.com")
+		.alpn(&[b"h2"])
+		.supported_groups(&[0x001d])
+		.extension(0x0042, &[0xDE, 0xAD])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	let pb = proto::ClientHello::from(&hello);
+	assert_eq!(pb.legacy_version, 0x0303);
+	assert_eq!(pb.cipher_suites, vec![0x1301]);
+	assert!(pb.has_grease);
+	assert_eq!(pb.grease.as_ref().unwrap().cipher_suites, vec![0]);
+	assert_eq!(pb.transport, proto::Transport::Handshake as i32);
+	assert_eq!(pb.extensions.len(), 4);
+	let Some(Body::ServerName(names)) = &pb.extensions[0].body else {
+		panic!("expected server name body");
+	};
+	assert_eq!(names.names[0].name, b"example.com");
+	assert_eq!(pb.extensions[3].type_id, 0x0042);
+	assert_eq!(pb.extensions[3].body, Some(Body::Raw(vec![0xDE, 0xAD])));
+}
+
+#[test]
+fn wire_round_trip() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.supported_versions(&[0x0304])
+		.build_record()
+		.unwrap();
+	let hello = clienthello::parse_from_record(&msg).unwrap();
+	let pb = proto::ClientHello::from(&hello);
+	let decoded = proto::ClientHello::decode(&*pb.encode_to_vec()).unwrap();
+	assert_eq!(decoded, pb);
+	assert_eq!(decoded.transport(), proto::Transport::TlsRecord);
+}
@@ -0,0 +1,53 @@
+/* tests/rarity.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, FrequencyTable, parse_from_record};
+
+fn common() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302, 0xc02b, 0xc02f])
+		.server_name("example.com")
+		.supported_groups(&[0x001d])
+		.alpn(&[b"h2"])
+		.supported_versions(&[0x0304])
+}
+
+#[test]
+fn common_hello_is_not_rare() {
+	let msg = common().build_record().unwrap();
+	let rarity = parse_from_record(&msg).unwrap().rarity_score();
+	assert_eq!(rarity.score, 0);
+	assert!(rarity.rare_extensions.is_empty());
+	assert!(rarity.rare_cipher_suites.is_empty());
+}
+
+#[test]
+fn unknown_entries_are_rare() {
+	let msg = common()
+		.cipher_suites(&[0x1301, 0x0a0a, 0xc02b, 0x0017, 0x0018])
+		.extension(0x5500, &[])
+		.build_record()
+		.unwrap();
+	let rarity = parse_from_record(&msg).unwrap().rarity_score();
+	assert_eq!(rarity.rare_extensions, [0x5500]);
+	assert_eq!(rarity.rare_cipher_suites, [0x0017, 0x0018]);
+	// 2 of 4 cipher suites and 1 of 5 extensions are rare.
+	assert_eq!(rarity.score, 33);
+}
+
+#[test]
+fn custom_table_and_threshold() {
+	let msg = common().build_record().unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+
+	let mut table = FrequencyTable::builtin();
+	table.set_extension(0x0010, 0.2);
+	assert!(table.rarity(&hello).rare_extensions.is_empty());
+	table.set_threshold(0.5);
+	assert_eq!(table.rarity(&hello).rare_extensions, [0x0010]);
+	assert_eq!(table.extension(0x0010), 0.2);
+
+	let empty = FrequencyTable::new();
+	assert_eq!(empty.cipher_suite(0x1301), 0.0);
+	assert_eq!(empty.rarity(&hello).score, 100);
+}
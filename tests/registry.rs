@@ -0,0 +1,102 @@
+/* tests/registry.rs */
+#![allow(missing_docs)]
+
+use clienthello::tls_reader::Reader;
+use clienthello::{Builder, DecoderRegistry, Error, ParseOptions, parse};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Labels(Vec<String>);
+
+fn labels(body: &[u8]) -> Result<Labels, Error> {
+	Reader::new(body).read_nested_u16("label list", |r| {
+		let mut out = Vec::new();
+		while !r.is_empty() {
+			let label = r.read_vec_u8("label")?;
+			out.push(String::from_utf8_lossy(label).into_owned());
+		}
+		Ok(Labels(out))
+	})
+}
+
+fn registry() -> DecoderRegistry {
+	let mut registry = DecoderRegistry::new();
+	registry
+		.register(0xfa00, labels)
+		.register(0xfa01, |body| Ok(body.len()));
+	registry
+}
+
+#[test]
+fn decodes_registered_extensions_in_wire_order() {
+	let msg = Builder::new()
+		.extension(0xfa01, &[1, 2, 3])
+		.alpn(&[b"h2"])
+		.extension(0xfa00, &[0x00, 0x04, 0x03, b'a', b'b', b'c'])
+		.extension(0xfa02, &[])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	let registry = registry();
+
+	let decoded = registry.decode(&hello);
+	assert_eq!(decoded.len(), 2);
+	assert_eq!(decoded[0].type_id, 0xfa01);
+	assert_eq!(decoded[0].downcast_ref::<usize>(), Some(&3));
+	assert_eq!(
+		decoded[1].downcast_ref::<Labels>(),
+		Some(&Labels(vec!["abc".into()]))
+	);
+	assert_eq!(decoded[1].downcast_ref::<usize>(), None);
+
+	assert_eq!(
+		registry.get::<Labels>(&hello, 0xfa00),
+		Some(Ok(Labels(vec!["abc".into()])))
+	);
+	assert_eq!(registry.get::<usize>(&hello, 0xfa00), None);
+	assert_eq!(registry.get::<usize>(&hello, 0xfa02), None);
+}
+
+#[test]
+fn decoder_errors_are_reported() {
+	let msg = Builder::new()
+		.extension(0xfa00, &[0x00, 0x05, 0x01])
+		.build()
+		.unwrap();
+	let hello = parse(&msg).unwrap();
+	assert_eq!(
+		registry().get::<Labels>(&hello, 0xfa00),
+		Some(Err(Error::Truncated {
+			field: "label list"
+		}))
+	);
+}
+
+#[test]
+fn structured_extensions_are_not_redecoded() {
+	let msg = Builder::new().server_name("example.com").build().unwrap();
+	let hello = parse(&msg).unwrap();
+	let mut registry = DecoderRegistry::new();
+	registry.register(0x0000, |body| Ok(body.len()));
+	assert!(registry.decode(&hello).is_empty());
+	assert!(registry.unregister(0x0000));
+	assert!(!registry.contains(0x0000));
+}
+
+#[test]
+fn recovered_malformed_extensions_are_decoded() {
+	// An ALPN body whose list overruns it.
+	let msg = Builder::new()
+		.extension(0x0010, &[0x00, 0x09, 0x02])
+		.build()
+		.unwrap();
+	let hello = ParseOptions::new()
+		.recover_malformed_extensions(true)
+		.parse(&msg)
+		.unwrap();
+	let mut registry = DecoderRegistry::new();
+	registry.register(0x0010, |body| Ok(body.to_vec()));
+	assert_eq!(
+		registry.get::<Vec<u8>>(&hello, 0x0010),
+		Some(Ok(vec![0x00, 0x09, 0x02]))
+	);
+}
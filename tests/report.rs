@@ -0,0 +1,53 @@
+/* tests/report.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, parse_from_record, report};
+
+fn hello_record(sni: &str) -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(&[0x1a1a, 0x1301, 0xc02b])
+		.server_name(sni)
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0304, 0x0303])
+		.build_record()
+		.unwrap()
+}
+
+#[test]
+fn markdown_sections_and_rows() {
+	let msg = hello_record("example.com");
+	let md = report::markdown(&parse_from_record(&msg).unwrap());
+	for heading in ["## Overview", "## Analysis", "## Lists"] {
+		assert!(md.contains(heading), "missing {heading}");
+	}
+	assert!(md.contains("| SNI | example.com |"));
+	assert!(md.contains("| ALPN | h2, http/1.1 |"));
+	assert!(md.contains("| Highest version | TLS 1.3 (0x0304) |"));
+	assert!(md.contains("| GREASE | yes |"));
+	assert!(md.contains("| Cipher suites | 0x1301, 0xc02b |"));
+	assert!(md.contains("| Extensions | 0x0000, 0x0010, 0x002b |"));
+}
+
+#[test]
+fn html_document() {
+	let msg = hello_record("example.com");
+	let html = report::html(&parse_from_record(&msg).unwrap());
+	assert!(html.starts_with("<!DOCTYPE html>"));
+	assert!(html.ends_with("</html>\n"));
+	assert!(html.contains("<tr><th>SNI</th><td>example.com</td></tr>"));
+	assert_eq!(
+		html.matches("<table>").count(),
+		html.matches("</table>").count()
+	);
+}
+
+#[test]
+fn hostile_values_are_escaped() {
+	let msg = hello_record("<script>|x</script>");
+	let hello = parse_from_record(&msg).unwrap();
+	let md = report::markdown(&hello);
+	assert!(md.contains("| SNI | \\<script\\>\\|x\\</script\\> |"));
+	let html = report::html(&hello);
+	assert!(html.contains("<td>&lt;script&gt;|x&lt;/script&gt;</td>"));
+	assert!(!html.contains("<script>"));
+}
@@ -0,0 +1,173 @@
+/* tests/robustness.rs */
+#![allow(missing_docs)]
+
+//! Every entry point must return `Ok` or `Err` on arbitrary input, never
+//! panic. The corpus below holds minimized inputs that previously
+//! exercised length-arithmetic edge cases; the sweeps mutate a
+//! well-formed hello the same way the fuzz targets in `fuzz/` do.
+
+use clienthello::{
+	Builder, extract_sni, extract_sni_from_record, parse, parse_from_chunks, parse_from_record,
+	parse_from_record_chunks,
+};
+
+/// Minimized inputs kept as regression cases.
+const CORPUS: &[&[u8]] = &[
+	&[],
+	&[0x01],
+	&[0x16],
+	&[0x16, 0x03, 0x01, 0xff, 0xff],
+	// Handshake length at the 24-bit maximum with no body.
+	&[0x01, 0xff, 0xff, 0xff],
+	// Record wrapping a handshake whose length exceeds the record.
+	&[0x16, 0x03, 0x01, 0x00, 0x04, 0x01, 0xff, 0xff, 0xff],
+	// Session ID length of 255 with a 35-byte body.
+	&[
+		0x01, 0x00, 0x00, 0x23, 0x03, 0x03, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff,
+	],
+	// Extension block length of 0xffff, one truncated extension header.
+	&[
+		0x01, 0x00, 0x00, 0x2e, 0x03, 0x03, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00, 0xff, 0xff, 0x00,
+		0x00, 0xff,
+	],
+	// SNI entry whose name length overruns the list.
+	&[
+		0x01, 0x00, 0x00, 0x34, 0x03, 0x03, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00, 0x00, 0x09, 0x00,
+		0x00, 0x00, 0x05, 0x00, 0x03, 0x00, 0xff, 0xff,
+	],
+	// QUIC transport parameters with an 8-byte varint length of 2^62 - 1.
+	&[
+		0x01, 0x00, 0x00, 0x38, 0x03, 0x03, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x00, 0x02, 0x13, 0x01, 0x01, 0x00, 0x00, 0x0d, 0x00,
+		0x39, 0x00, 0x09, 0x11, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+	],
+];
+
+fn sample() -> Vec<u8> {
+	Builder::new()
+		.session_id(&[0xaa; 32])
+		.cipher_suites(&[0x0a0a, 0x1301, 0x1302, 0xc02b])
+		.server_name("robust.example")
+		.alpn(&[b"h3", b"h3-29", b"h3-Q050"])
+		.supported_versions(&[0x1a1a, 0x0304, 0x0303])
+		.supported_groups(&[0x001d, 0x0017])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.key_share(&[(0x001d, &[0x42; 32])])
+		.psk_exchange_modes(&[0x01])
+		.renegotiation_info(&[])
+		.extension(0x0039, &[0x11, 0x08, 0, 0, 0, 1, 0xff, 0, 0, 0x1d])
+		.build_record()
+		.unwrap()
+}
+
+/// Run `data` through every public parsing entry point.
+fn exercise(data: &[u8]) {
+	for hello in [parse(data), parse_from_record(data)].into_iter().flatten() {
+		let _ = hello.server_name();
+		let _ = hello.alpn_protocols();
+		let _ = hello.supported_versions();
+		let _ = hello.inferred_quic_versions();
+		let _ = hello.find_extension(0x0039);
+	}
+	let _ = extract_sni(data);
+	let _ = extract_sni_from_record(data);
+
+	let (a, b) = data.split_at(data.len() / 2);
+	let mut scratch = Vec::new();
+	let _ = parse_from_chunks([a, b], &mut scratch);
+	let _ = parse_from_record_chunks([a, b], &mut scratch);
+}
+
+/// Small deterministic xorshift generator so the sweep needs no extra
+/// dependencies and every failure is reproducible.
+struct XorShift(u64);
+
+impl XorShift {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	fn below(&mut self, n: usize) -> usize {
+		(self.next() % n as u64) as usize
+	}
+}
+
+#[test]
+fn corpus_does_not_panic() {
+	for input in CORPUS {
+		exercise(input);
+	}
+}
+
+#[test]
+fn corpus_framing_errors_are_reported() {
+	for input in &CORPUS[..CORPUS.len() - 1] {
+		assert!(parse(input).is_err(), "{input:02x?}");
+		assert!(parse_from_record(input).is_err(), "{input:02x?}");
+	}
+}
+
+#[test]
+fn oversized_quic_varint_is_ignored() {
+	let hello = parse(CORPUS[CORPUS.len() - 1]).unwrap();
+	assert!(hello.inferred_quic_versions().is_empty());
+}
+
+#[test]
+fn every_truncation() {
+	let record = sample();
+	for end in 0..=record.len() {
+		exercise(&record[..end]);
+		exercise(&record[5..end.max(5)]);
+	}
+}
+
+#[test]
+fn every_single_byte_substitution() {
+	let record = sample();
+	let mut data = record.clone();
+	for at in 0..record.len() {
+		for val in [0x00, 0x01, 0x7f, 0x80, 0xfe, 0xff] {
+			data[at] = val;
+			exercise(&data);
+			exercise(&data[5..]);
+		}
+		data[at] = record[at];
+	}
+}
+
+#[test]
+fn random_mutations() {
+	let record = sample();
+	let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+	for _ in 0..20_000 {
+		let mut data = record.clone();
+		for _ in 0..=rng.below(8) {
+			let at = rng.below(data.len());
+			data[at] = rng.next() as u8;
+		}
+		data.truncate(rng.below(data.len() + 1));
+		exercise(&data);
+		exercise(data.get(5..).unwrap_or_default());
+	}
+}
+
+#[test]
+fn random_bytes() {
+	let mut rng = XorShift(0x0123_4567_89ab_cdef);
+	for _ in 0..20_000 {
+		let len = rng.below(96);
+		let mut data: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+		// Bias toward inputs that get past the first type check.
+		if let Some(first) = data.first_mut() {
+			*first = [0x01, 0x16][rng.below(2)];
+		}
+		exercise(&data);
+	}
+}
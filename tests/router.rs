@@ -0,0 +1,102 @@
+/* tests/router.rs */
+#![allow(missing_docs)]
+
+use clienthello::router::{PatternError, SniRouter};
+use clienthello::{Builder, parse_from_record};
+
+fn router() -> SniRouter<&'static str> {
+	let mut router = SniRouter::new();
+	router.insert("example.com", "apex").unwrap();
+	router.insert("*.example.com", "wild").unwrap();
+	router.insert("*.b.example.com", "deep-wild").unwrap();
+	router.insert("x.b.example.com", "exact").unwrap();
+	router
+}
+
+#[test]
+fn exact_beats_wildcard() {
+	let router = router();
+	assert_eq!(router.route_host("x.b.example.com"), Some(&"exact"));
+	assert_eq!(router.route_host("example.com"), Some(&"apex"));
+}
+
+#[test]
+fn longest_wildcard_suffix_wins() {
+	let router = router();
+	assert_eq!(router.route_host("y.b.example.com"), Some(&"deep-wild"));
+	assert_eq!(router.route_host("z.y.b.example.com"), Some(&"deep-wild"));
+	assert_eq!(router.route_host("b.example.com"), Some(&"wild"));
+	assert_eq!(router.route_host("a.example.com"), Some(&"wild"));
+}
+
+#[test]
+fn wildcard_does_not_match_apex_or_lookalikes() {
+	let mut router = SniRouter::new();
+	router.insert("*.example.com", 1).unwrap();
+	assert_eq!(router.route_host("example.com"), None);
+	assert_eq!(router.route_host("badexample.com"), None);
+	assert_eq!(router.route_host("example.com.evil"), None);
+}
+
+#[test]
+fn case_and_trailing_dot_are_ignored() {
+	let mut router = SniRouter::new();
+	router.insert("API.Example.COM.", 1).unwrap();
+	assert_eq!(router.route_host("api.example.com"), Some(&1));
+	assert_eq!(router.route_host("Api.Example.Com."), Some(&1));
+}
+
+#[test]
+fn default_covers_unmatched_and_missing_sni() {
+	let mut router = router();
+	assert_eq!(router.route_host("other.test"), None);
+	assert_eq!(router.set_default("fallback"), None);
+	assert_eq!(router.route_host("other.test"), Some(&"fallback"));
+
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.build_record()
+		.unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert_eq!(router.route(&hello), Some(&"fallback"));
+
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name("q.b.example.com")
+		.build_record()
+		.unwrap();
+	let hello = parse_from_record(&msg).unwrap();
+	assert_eq!(router.route(&hello), Some(&"deep-wild"));
+}
+
+#[test]
+fn insert_replace_and_remove() {
+	let mut router = router();
+	assert_eq!(router.len(), 4);
+	assert_eq!(router.insert("*.Example.com", "new"), Ok(Some("wild")));
+	assert_eq!(router.route_host("a.example.com"), Some(&"new"));
+	assert_eq!(router.remove("*.example.com"), Ok(Some("new")));
+	assert_eq!(router.route_host("a.example.com"), None);
+	assert_eq!(router.len(), 3);
+	assert!(!router.is_empty());
+}
+
+#[test]
+fn invalid_patterns() {
+	let mut router = SniRouter::new();
+	assert_eq!(router.insert("", 1), Err(PatternError::Empty));
+	assert_eq!(router.insert(".", 1), Err(PatternError::Empty));
+	assert_eq!(
+		router.insert("*", 1),
+		Err(PatternError::Wildcard("*".to_owned()))
+	);
+	assert_eq!(
+		router.insert("a.*.com", 1),
+		Err(PatternError::Wildcard("a.*.com".to_owned()))
+	);
+	assert_eq!(
+		router.insert("*foo.com", 1),
+		Err(PatternError::Wildcard("*foo.com".to_owned()))
+	);
+	assert!(router.is_empty());
+}
@@ -0,0 +1,66 @@
+/* tests/scan.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, scan};
+
+fn record(sni: &str) -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name(sni)
+		.build_record()
+		.unwrap()
+}
+
+#[test]
+fn finds_records_between_noise() {
+	let a = record("a.example");
+	let b = record("b.example");
+	let mut haystack = b"GET / HTTP/1.1\r\n".to_vec();
+	haystack.extend_from_slice(&a);
+	haystack.extend_from_slice(&[0x16, 0x03, 0x03, 0x00, 0x02, 0x02]);
+	let b_offset = haystack.len();
+	haystack.extend_from_slice(&b);
+
+	let found: Vec<_> = scan::records(&haystack).collect();
+	assert_eq!(found, [(16, a.as_slice()), (b_offset, b.as_slice())]);
+	let names: Vec<_> = scan::server_names(&haystack).collect();
+	assert_eq!(names, [(16, "a.example"), (b_offset, "b.example")]);
+	let hellos: Vec<_> = scan::client_hellos(&haystack)
+		.map(|(offset, hello)| (offset, hello.cipher_suites))
+		.collect();
+	assert_eq!(hellos, [(16, vec![0x1301]), (b_offset, vec![0x1301])]);
+}
+
+#[test]
+fn rejects_implausible_headers() {
+	let mut msg = record("example.com");
+	// Not a ClientHello handshake.
+	msg[5] = 0x02;
+	assert_eq!(scan::records(&msg).count(), 0);
+	// Handshake length longer than the record.
+	let mut msg = record("example.com");
+	msg[8] = 0xff;
+	assert_eq!(scan::records(&msg).count(), 0);
+	// Unknown record version.
+	let mut msg = record("example.com");
+	msg[2] = 0x05;
+	assert_eq!(scan::records(&msg).count(), 0);
+}
+
+#[test]
+fn truncated_record_is_skipped() {
+	let msg = record("example.com");
+	assert_eq!(scan::records(&msg[..msg.len() - 1]).count(), 0);
+	assert_eq!(scan::records(&[0x16, 0x03]).count(), 0);
+	assert_eq!(scan::records(&[]).count(), 0);
+}
+
+#[test]
+fn record_without_sni_is_found_but_unnamed() {
+	let msg = Builder::new()
+		.cipher_suites(&[0x1301])
+		.build_record()
+		.unwrap();
+	assert_eq!(scan::records(&msg).count(), 1);
+	assert_eq!(scan::server_names(&msg).count(), 0);
+}
@@ -0,0 +1,142 @@
+/* tests/server_hello.rs */
+#![allow(missing_docs)]
+
+use clienthello::{
+	Error, HandshakeType, Ja3Version, Ja4Version, Transport, parse_server_hello,
+	parse_server_hello_from_record,
+};
+
+const HRR_RANDOM: [u8; 32] = [
+	0xcf, 0x21, 0xad, 0x74, 0xe5, 0x9a, 0x61, 0x11, 0xbe, 0x1d, 0x8c, 0x02, 0x1e, 0x65, 0xb8, 0x91,
+	0xc2, 0xa2, 0x11, 0x16, 0x7a, 0xbb, 0x8c, 0x5e, 0x07, 0x9e, 0x09, 0xe2, 0xc8, 0xa8, 0x33, 0x9c,
+];
+
+/// Encode a ServerHello handshake message.
+fn server_hello(random: &[u8; 32], cipher: u16, extensions: &[(u16, &[u8])]) -> Vec<u8> {
+	let mut body = vec![0x03, 0x03];
+	body.extend_from_slice(random);
+	body.push(0x20);
+	body.extend_from_slice(&[0xaa; 32]);
+	body.extend_from_slice(&cipher.to_be_bytes());
+	body.push(0x00);
+	let mut exts = Vec::new();
+	for &(type_id, data) in extensions {
+		exts.extend_from_slice(&type_id.to_be_bytes());
+		exts.extend_from_slice(&u16::try_from(data.len()).unwrap().to_be_bytes());
+		exts.extend_from_slice(data);
+	}
+	body.extend_from_slice(&u16::try_from(exts.len()).unwrap().to_be_bytes());
+	body.extend_from_slice(&exts);
+	let mut msg = vec![0x02];
+	msg.extend_from_slice(&u32::try_from(body.len()).unwrap().to_be_bytes()[1..]);
+	msg.extend_from_slice(&body);
+	msg
+}
+
+fn record(msg: &[u8]) -> Vec<u8> {
+	let mut out = vec![0x16, 0x03, 0x03];
+	out.extend_from_slice(&u16::try_from(msg.len()).unwrap().to_be_bytes());
+	out.extend_from_slice(msg);
+	out
+}
+
+fn tls13() -> Vec<u8> {
+	let mut key_share = vec![0x00, 0x1d, 0x00, 0x20];
+	key_share.extend_from_slice(&[0x42; 32]);
+	server_hello(
+		&[0x11; 32],
+		0x1301,
+		&[(0x002b, &[0x03, 0x04]), (0x0033, &key_share)],
+	)
+}
+
+#[test]
+fn parses_tls13_server_hello() {
+	let msg = tls13();
+	let hello = parse_server_hello(&msg).unwrap();
+	assert_eq!(hello.legacy_version, 0x0303);
+	assert_eq!(hello.session_id, [0xaa; 32]);
+	assert_eq!(hello.cipher_suite, 0x1301);
+	assert_eq!(hello.compression_method, 0);
+	assert_eq!(hello.supported_version(), Some(0x0304));
+	assert_eq!(hello.version(), 0x0304);
+	assert_eq!(hello.transport, Transport::Handshake);
+	assert!(!hello.is_hello_retry_request());
+	let share = hello.key_share().unwrap();
+	assert_eq!(share.group, 0x001d);
+	assert_eq!(share.key, [0x42; 32]);
+	assert_eq!(hello.alpn(), None);
+}
+
+#[test]
+fn tls13_fingerprints() {
+	let msg = tls13();
+	let hello = parse_server_hello(&msg).unwrap();
+	assert_eq!(hello.ja3s(), "771,4865,43-51");
+	assert_eq!(hello.ja3s_hash(), "f4febc55ea12b31ae17cfb7e614afda8");
+	assert_eq!(hello.ja4s(), "q130200_1301_a56c5b993250");
+	assert_eq!(hello.ja4s_raw(), "q130200_1301_002b,0033");
+	assert_eq!(hello.ja3s_with(Ja3Version::V1), hello.ja3s());
+	assert_eq!(hello.ja3s_hash_with(Ja3Version::V1), hello.ja3s_hash());
+	assert_eq!(hello.ja4s_with(Ja4Version::V1), hello.ja4s());
+	assert_eq!(hello.ja4s_raw_with(Ja4Version::V1), hello.ja4s_raw());
+
+	let rec = record(&msg);
+	let hello = parse_server_hello_from_record(&rec).unwrap();
+	assert_eq!(hello.transport, Transport::TlsRecord);
+	assert_eq!(hello.ja4s(), "t130200_1301_a56c5b993250");
+}
+
+#[test]
+fn tls12_server_hello_with_alpn_and_trailing_flight() {
+	let msg = server_hello(
+		&[0x22; 32],
+		0xc02f,
+		&[
+			(0xff01, &[0x00]),
+			(0x0000, &[]),
+			(0x000b, &[0x01, 0x00]),
+			(0x0023, &[]),
+			(0x0010, &[0x00, 0x03, 0x02, b'h', b'2']),
+			(0x0017, &[]),
+		],
+	);
+	// The Certificate message following in the same record is ignored.
+	let mut flight = msg;
+	flight.extend_from_slice(&[0x0b, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00]);
+	let rec = record(&flight);
+	let hello = parse_server_hello_from_record(&rec).unwrap();
+	assert_eq!(hello.version(), 0x0303);
+	assert_eq!(hello.alpn(), Some(b"h2".as_slice()));
+	assert_eq!(hello.key_share(), None);
+	assert_eq!(hello.ja3s(), "771,49199,65281-0-11-35-16-23");
+	assert_eq!(hello.ja3s_hash(), "00447ab319e9d94ba2b4c1248e155917");
+	assert_eq!(hello.ja4s(), "t1206h2_c02f_17136cd5846b");
+}
+
+#[test]
+fn hello_retry_request_names_group_only() {
+	let msg = server_hello(
+		&HRR_RANDOM,
+		0x1301,
+		&[(0x002b, &[0x03, 0x04]), (0x0033, &[0x11, 0xec])],
+	);
+	let hello = parse_server_hello(&msg).unwrap();
+	assert!(hello.is_hello_retry_request());
+	let share = hello.key_share().unwrap();
+	assert_eq!(share.group, 0x11ec);
+	assert!(share.key.is_empty());
+}
+
+#[test]
+fn rejects_client_hello_and_truncation() {
+	let client = clienthello::Builder::new().build().unwrap();
+	assert_eq!(
+		parse_server_hello(&client),
+		Err(Error::NotServerHello(HandshakeType::ClientHello))
+	);
+	let msg = tls13();
+	for len in 0..msg.len() {
+		assert!(parse_server_hello(&msg[..len]).is_err(), "len {len}");
+	}
+}
@@ -0,0 +1,133 @@
+/* tests/session.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, Error, HelloChanges, SessionTracker, Transport, parse};
+
+fn tls13() -> Builder {
+	Builder::new()
+		.random([0x42; 32])
+		.session_id(&[0x5a; 32])
+		.cipher_suites(&[0x1301, 0x1302])
+		.supported_versions(&[0x0304])
+		.supported_groups(&[29, 23])
+		.key_share(&[(29, &[1; 32])])
+		.extension(0x002a, &[])
+		.pre_shared_key(&[(b"ticket", 0)], 32)
+}
+
+/// Wrap a raw handshake message built by [`Builder`] in a DTLS 1.2
+/// record, inserting `cookie` after the session ID.
+fn dtls(builder: Builder, cookie: &[u8]) -> Vec<u8> {
+	let msg = builder.legacy_version(0xfefd).build().unwrap();
+	let mut body = msg[4..4 + 2 + 32 + 1].to_vec();
+	body.push(cookie.len() as u8);
+	body.extend_from_slice(cookie);
+	body.extend_from_slice(&msg[4 + 2 + 32 + 1..]);
+	let len = (body.len() as u32).to_be_bytes();
+	let mut handshake = vec![0x01, len[1], len[2], len[3], 0, 0, 0, 0, 0];
+	handshake.extend_from_slice(&len[1..]);
+	handshake.extend_from_slice(&body);
+	let mut record = vec![0x16, 0xfe, 0xff, 0, 0, 0, 0, 0, 0, 0, 0];
+	record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+	record.extend_from_slice(&handshake);
+	record
+}
+
+#[test]
+fn hello_retry_request_flow() {
+	let first = tls13().build_record().unwrap();
+	let second = Builder::new()
+		.random([0x42; 32])
+		.session_id(&[0x5a; 32])
+		.cipher_suites(&[0x1301, 0x1302])
+		.supported_versions(&[0x0304])
+		.supported_groups(&[29, 23])
+		.key_share(&[(23, &[4; 65])])
+		.extension(0x002c, &[0, 2, 9, 9])
+		.build_record()
+		.unwrap();
+
+	let mut tracker = SessionTracker::new(16);
+	assert_eq!(tracker.observe(7, &first), Ok(None));
+	let changes = tracker.observe(7, &second).unwrap().unwrap();
+	assert!(changes.cookie_added);
+	assert_eq!(changes.key_share_groups, Some((vec![29], vec![23])));
+	assert_eq!(changes.extensions_added, [0x002c]);
+	assert_eq!(changes.extensions_removed, [0x002a, 0x0029]);
+	assert!(!changes.random_changed);
+	assert!(changes.is_valid_retry());
+	assert!(!changes.is_empty());
+
+	// The second hello is now the one later hellos are compared with.
+	let changes = tracker.observe(7, &second).unwrap().unwrap();
+	assert!(changes.is_empty());
+	assert_eq!(tracker.len(), 1);
+}
+
+#[test]
+fn invalid_retry() {
+	let first = tls13().build().unwrap();
+	let second = tls13()
+		.random([0x43; 32])
+		.cipher_suites(&[0x1301])
+		.extension(0x0017, &[])
+		.build()
+		.unwrap();
+	let changes = HelloChanges::between(&parse(&first).unwrap(), &parse(&second).unwrap());
+	assert!(changes.random_changed);
+	assert!(changes.cipher_suites_changed);
+	assert!(!changes.session_id_changed);
+	assert_eq!(changes.extensions_added, [0x0017]);
+	assert_eq!(changes.key_share_groups, None);
+	assert!(!changes.is_valid_retry());
+
+	let added = tls13().extension(0x0017, &[]).build().unwrap();
+	let changes = HelloChanges::between(&parse(&first).unwrap(), &parse(&added).unwrap());
+	assert!(!changes.is_valid_retry());
+}
+
+#[test]
+fn dtls_cookie_exchange() {
+	let hello = Builder::new()
+		.cipher_suites(&[0xc02b])
+		.supported_groups(&[29]);
+	let mut tracker = SessionTracker::new(16);
+	let first = dtls(hello.clone(), b"");
+	assert_eq!(clienthello::parse_any(&first).unwrap().1, Transport::Dtls);
+	assert_eq!(tracker.observe("flow", &first), Ok(None));
+	let changes = tracker
+		.observe("flow", &dtls(hello, b"server cookie"))
+		.unwrap()
+		.unwrap();
+	assert!(changes.cookie_added);
+	assert!(changes.extensions_added.is_empty());
+	assert!(changes.is_valid_retry());
+}
+
+#[test]
+fn forgets_least_recent_flow() {
+	let msg = tls13().build().unwrap();
+	let mut tracker = SessionTracker::new(2);
+	assert_eq!(tracker.observe(1, &msg), Ok(None));
+	assert_eq!(tracker.observe(2, &msg), Ok(None));
+	// Flow 1 is refreshed, so flow 2 is evicted for flow 3.
+	assert!(tracker.observe(1, &msg).unwrap().is_some());
+	assert_eq!(tracker.observe(3, &msg), Ok(None));
+	assert_eq!(tracker.len(), 2);
+	assert_eq!(tracker.observe(2, &msg), Ok(None));
+	assert!(tracker.observe(3, &msg).unwrap().is_some());
+
+	assert!(tracker.remove(&3));
+	assert!(!tracker.remove(&3));
+	assert_eq!(tracker.len(), 1);
+}
+
+#[test]
+fn unparsable_hello_not_recorded() {
+	let mut tracker = SessionTracker::new(4);
+	assert!(matches!(
+		tracker.observe(1, &[0x16, 0x03, 0x01]),
+		Err(Error::BufferTooShort { .. })
+	));
+	assert!(tracker.is_empty());
+}
@@ -0,0 +1,194 @@
+/* tests/stack.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, ExtensionOrder, TlsStack, parse_from_record};
+
+fn infer(builder: &Builder) -> Option<TlsStack> {
+	let msg = builder.build_record().unwrap();
+	parse_from_record(&msg).unwrap().infer_stack()
+}
+
+#[test]
+fn boringssl_grease_and_alps() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x2a2a, 0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f])
+		.extension(0x0a0a, &[])
+		.server_name("example.com")
+		.extension(0x001b, &[0x02, 0x00, 0x02])
+		.extension(0x44cd, &[0x00, 0x03, 0x02, b'h', b'2'])
+		.supported_versions(&[0x0304, 0x0303]);
+	assert_eq!(infer(&builder), Some(TlsStack::BoringSsl));
+}
+
+#[test]
+fn nss_firefox_extensions() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1301, 0x1303, 0x1302, 0xc02b, 0xc02f])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.renegotiation_info(&[])
+		.supported_groups(&[0x001d, 0x0017, 0x0018, 0x0100, 0x0101])
+		.extension(0x0022, &[0x00, 0x04, 0x04, 0x03, 0x05, 0x03])
+		.supported_versions(&[0x0304, 0x0303])
+		.psk_exchange_modes(&[0x01])
+		.extension(0x001c, &[0x40, 0x01])
+		.extension(0x001b, &[0x06, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03]);
+	assert_eq!(infer(&builder), Some(TlsStack::Nss));
+}
+
+#[test]
+fn openssl_defaults() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1302, 0x1303, 0x1301, 0xc02c, 0xc030, 0x009f])
+		.server_name("example.com")
+		.extension(0x000b, &[0x03, 0x00, 0x01, 0x02])
+		.supported_groups(&[0x001d, 0x0017, 0x001e])
+		.extension(0x0016, &[])
+		.extension(0x0017, &[])
+		.supported_versions(&[0x0304, 0x0303])
+		.psk_exchange_modes(&[0x01])
+		.extension(0x0031, &[]);
+	assert_eq!(infer(&builder), Some(TlsStack::OpenSsl));
+}
+
+#[test]
+fn go_extension_order() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1301, 0x1302, 0x1303, 0xc02b, 0xc02f])
+		.server_name("example.com")
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.supported_groups(&[0x001d, 0x0017, 0x0018, 0x0019])
+		.extension(0x000b, &[0x01, 0x00])
+		.signature_algorithms(&[0x0804, 0x0403])
+		.renegotiation_info(&[])
+		.alpn(&[b"h2", b"http/1.1"])
+		.supported_versions(&[0x0304, 0x0303])
+		.key_share(&[(0x001d, &[0x22; 32])]);
+	assert_eq!(infer(&builder), Some(TlsStack::GoCryptoTls));
+}
+
+#[test]
+fn schannel_cipher_and_extension_order() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1302, 0x1301, 0xc02c, 0xc02b, 0xc030, 0xc02f])
+		.server_name("example.com")
+		.extension(0x0005, &[0x01, 0x00, 0x00, 0x00, 0x00])
+		.supported_groups(&[0x001d, 0x0017, 0x0018])
+		.extension(0x000b, &[0x01, 0x00])
+		.signature_algorithms(&[0x0804, 0x0403])
+		.extension(0x0023, &[])
+		.alpn(&[b"h2", b"http/1.1"])
+		.extension(0x0017, &[])
+		.renegotiation_info(&[])
+		.supported_versions(&[0x0304, 0x0303])
+		.psk_exchange_modes(&[0x01])
+		.key_share(&[(0x001d, &[0x33; 32])]);
+	assert_eq!(infer(&builder), Some(TlsStack::Schannel));
+}
+
+#[test]
+fn weak_evidence_is_inconclusive() {
+	assert_eq!(infer(&Builder::new().cipher_suites(&[0x1301])), None);
+}
+
+/// Chrome hello in the pre-110 unshuffled order.
+fn chrome_ordered() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1a1a, 0x1301, 0x1302, 0x1303, 0xc02b])
+		.extension(0x2a2a, &[])
+		.server_name("example.com")
+		.extension(0x0017, &[])
+		.renegotiation_info(&[])
+		.supported_groups(&[0x001d, 0x0017])
+		.extension(0x000b, &[0x01, 0x00])
+		.extension(0x0023, &[])
+		.alpn(&[b"h2"])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.supported_versions(&[0x0304, 0x0303])
+		.extension(0x3a3a, &[0x00])
+		.extension(0x0015, &[0x00; 8])
+}
+
+fn order(builder: &Builder) -> ExtensionOrder {
+	let msg = builder.build_record().unwrap();
+	parse_from_record(&msg).unwrap().extension_order()
+}
+
+#[test]
+fn unshuffled_chrome_order_is_fixed() {
+	assert_eq!(order(&chrome_ordered()), ExtensionOrder::Fixed);
+}
+
+#[test]
+fn shuffled_chrome_order_is_randomized() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1a1a, 0x1301, 0x1302, 0x1303, 0xc02b])
+		.extension(0x2a2a, &[])
+		.alpn(&[b"h2"])
+		.supported_versions(&[0x0304, 0x0303])
+		.extension(0x0023, &[])
+		.server_name("example.com")
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.supported_groups(&[0x001d, 0x0017])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.extension(0x0017, &[])
+		.extension(0x3a3a, &[0x00])
+		.extension(0x0015, &[0x00; 8]);
+	assert_eq!(order(&builder), ExtensionOrder::Randomized);
+}
+
+#[test]
+fn order_without_grease_is_fixed() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1301])
+		.supported_versions(&[0x0304])
+		.server_name("example.com")
+		.alpn(&[b"h2"]);
+	assert_eq!(order(&builder), ExtensionOrder::Fixed);
+}
+
+#[test]
+fn order_with_few_extensions_is_unknown() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1301])
+		.extension(0x0a0a, &[])
+		.supported_versions(&[0x0304])
+		.server_name("example.com");
+	assert_eq!(order(&builder), ExtensionOrder::Unknown);
+}
+
+#[test]
+fn misplaced_padding_is_fixed() {
+	// BoringSSL keeps padding after the shuffled block.
+	let builder = Builder::new()
+		.cipher_suites(&[0x1a1a, 0x1301, 0x1302, 0x1303, 0xc02b])
+		.extension(0x2a2a, &[])
+		.alpn(&[b"h2"])
+		.extension(0x0015, &[0x00; 8])
+		.supported_versions(&[0x0304, 0x0303])
+		.extension(0x0023, &[])
+		.server_name("example.com")
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.extension(0x3a3a, &[0x00]);
+	assert_eq!(order(&builder), ExtensionOrder::Fixed);
+}
+
+#[test]
+fn shuffled_order_with_psk_tail_is_randomized() {
+	let builder = Builder::new()
+		.cipher_suites(&[0x1a1a, 0x1301, 0x1302, 0x1303, 0xc02b])
+		.extension(0x2a2a, &[])
+		.alpn(&[b"h2"])
+		.supported_versions(&[0x0304, 0x0303])
+		.server_name("example.com")
+		.key_share(&[(0x001d, &[0x11; 32])])
+		.psk_exchange_modes(&[0x01])
+		.extension(0x3a3a, &[0x00])
+		.extension(0x0015, &[0x00; 8])
+		.pre_shared_key(&[(b"ticket", 0)], 32);
+	let result = order(&builder);
+	assert_eq!(result, ExtensionOrder::Randomized);
+	assert!(!result.is_stable());
+	assert!(ExtensionOrder::Fixed.is_stable());
+}
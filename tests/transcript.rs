@@ -0,0 +1,70 @@
+/* tests/transcript.rs */
+#![allow(missing_docs)]
+
+use clienthello::{
+	Builder, Error, binder_transcript_hash, binder_transcript_hash_from_record, transcript_hash,
+	transcript_hash_from_record,
+};
+use sha2::{Digest, Sha256, Sha384};
+
+fn hello() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("example.com")
+		.supported_versions(&[0x0304])
+}
+
+#[test]
+fn hashes_the_handshake_message() {
+	let msg = hello().build().unwrap();
+	let expected = Sha256::digest(&msg);
+	assert_eq!(transcript_hash::<Sha256>(&msg).unwrap(), expected);
+
+	// Record headers and trailing bytes are not part of the transcript.
+	let mut record = hello().build_record().unwrap();
+	assert_eq!(
+		transcript_hash_from_record::<Sha256>(&record).unwrap(),
+		expected
+	);
+	let mut padded = msg.clone();
+	padded.extend_from_slice(&[0; 8]);
+	assert_eq!(transcript_hash::<Sha256>(&padded).unwrap(), expected);
+
+	record[0] = 0x17;
+	assert!(transcript_hash_from_record::<Sha256>(&record).is_err());
+	assert_eq!(
+		transcript_hash::<Sha256>(&msg[..msg.len() - 1]),
+		Err(Error::Truncated {
+			field: "handshake body"
+		})
+	);
+}
+
+#[test]
+fn binder_hash_covers_partial_hello() {
+	let psk = hello().pre_shared_key(&[(b"first", 1), (b"second", 2)], 48);
+	let mut partials = Vec::new();
+	let msg = psk
+		.build_with_binders(|_, head, _| partials.push(head.to_vec()))
+		.unwrap();
+	assert_eq!(partials.len(), 2);
+	let expected = Sha384::digest(&partials[0]);
+	assert_eq!(
+		binder_transcript_hash::<Sha384>(&msg).unwrap(),
+		Some(expected)
+	);
+
+	let record = psk.build_record_with_binders(|_, _, _| {}).unwrap();
+	assert_eq!(
+		binder_transcript_hash_from_record::<Sha384>(&record).unwrap(),
+		Some(expected)
+	);
+}
+
+#[test]
+fn binder_hash_without_psk() {
+	let msg = hello().build().unwrap();
+	assert_eq!(binder_transcript_hash::<Sha256>(&msg).unwrap(), None);
+	let bare = Builder::new().cipher_suites(&[0x1301]).build().unwrap();
+	assert_eq!(binder_transcript_hash::<Sha256>(&bare).unwrap(), None);
+}
@@ -0,0 +1,96 @@
+/* tests/tunnel.rs */
+#![allow(missing_docs)]
+
+use std::borrow::Cow;
+
+use clienthello::Builder;
+use clienthello::tunnel::{Tunnel, find_inner_hello};
+
+fn record(host: &str) -> Vec<u8> {
+	Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name(host)
+		.build_record()
+		.unwrap()
+}
+
+/// Wrap `payload` in TLS 1.2 records of `content_type`, `chunk` bytes each.
+fn records(content_type: u8, payload: &[u8], chunk: usize) -> Vec<u8> {
+	let mut out = Vec::new();
+	for part in payload.chunks(chunk) {
+		out.extend_from_slice(&[content_type, 0x03, 0x03]);
+		out.extend_from_slice(&u16::try_from(part.len()).unwrap().to_be_bytes());
+		out.extend_from_slice(part);
+	}
+	out
+}
+
+#[test]
+fn plain_hello_is_not_tunneled() {
+	assert_eq!(find_inner_hello(&record("example.com")), None);
+	assert_eq!(find_inner_hello(b"GET / HTTP/1.1\r\n\r\n"), None);
+}
+
+#[test]
+fn connect_with_proxy_response() {
+	let mut log = b"CONNECT inner.example:443 HTTP/1.1\r\n\r\n".to_vec();
+	log.extend_from_slice(b"HTTP/1.1 200 Connection established\r\n\r\n");
+	let inner = record("inner.example");
+	log.extend_from_slice(&inner);
+
+	let found = find_inner_hello(&log).unwrap();
+	assert_eq!(found.layers, [Tunnel::HttpConnect]);
+	assert!(matches!(found.record, Cow::Borrowed(_)));
+	assert_eq!(found.record, inner.as_slice());
+}
+
+#[test]
+fn connect_without_hello_is_none() {
+	let log = b"CONNECT inner.example:443 HTTP/1.1\r\n\r\nGET / HTTP/1.1\r\n\r\n";
+	assert_eq!(find_inner_hello(log), None);
+}
+
+#[test]
+fn tls_in_tls_reassembles_application_data() {
+	// Outer session to an HTTPS proxy: its hello, a change_cipher_spec,
+	// then the inner hello split over three application data records.
+	let inner = record("inner.example");
+	let mut log = record("proxy.example");
+	log.extend_from_slice(&records(0x14, &[0x01], 1));
+	log.extend_from_slice(&records(0x17, &inner, 40));
+
+	let found = find_inner_hello(&log).unwrap();
+	assert_eq!(found.layers, [Tunnel::TlsApplicationData]);
+	assert_eq!(found.record, inner.as_slice());
+	assert_eq!(found.parse().unwrap().server_name(), Some("inner.example"));
+}
+
+#[test]
+fn connect_inside_tls_inside_connect() {
+	let mut tunneled = b"CONNECT inner.example:443 HTTP/1.1\r\n\r\n".to_vec();
+	tunneled.extend_from_slice(&record("inner.example"));
+	let mut log = b"CONNECT proxy.example:443 HTTP/1.1\r\n\r\n".to_vec();
+	log.extend_from_slice(&record("proxy.example"));
+	log.extend_from_slice(&records(0x17, &tunneled, 64));
+
+	let found = find_inner_hello(&log).unwrap();
+	assert_eq!(
+		found.layers,
+		[
+			Tunnel::HttpConnect,
+			Tunnel::TlsApplicationData,
+			Tunnel::HttpConnect
+		]
+	);
+	assert_eq!(found.parse().unwrap().server_name(), Some("inner.example"));
+}
+
+#[test]
+fn truncated_last_record_contributes_captured_bytes() {
+	let inner = record("inner.example");
+	let mut log = records(0x17, &inner, 1024);
+	log.truncate(log.len() - 3);
+	let found = find_inner_hello(&log).unwrap();
+	assert_eq!(found.record, &inner[..inner.len() - 3]);
+	assert!(found.parse().is_err());
+}
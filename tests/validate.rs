@@ -0,0 +1,156 @@
+/* tests/validate.rs */
+#![allow(missing_docs)]
+
+use clienthello::{Builder, Severity, ValidationIssue, Warning, parse};
+
+/// A well-formed TLS 1.3 hello without its key share.
+fn without_key_share() -> Builder {
+	Builder::new()
+		.cipher_suites(&[0x1301, 0x1302])
+		.server_name("example.com")
+		.supported_groups(&[0x001d, 0x0017])
+		.signature_algorithms(&[0x0403, 0x0804])
+		.supported_versions(&[0x0304, 0x0303])
+		.psk_exchange_modes(&[0x01])
+}
+
+/// A well-formed TLS 1.3 hello.
+fn tls13() -> Builder {
+	without_key_share().key_share(&[(0x001d, &[0x11; 32])])
+}
+
+fn issues(builder: &Builder) -> Vec<ValidationIssue> {
+	let msg = builder.build().unwrap();
+	parse(&msg).unwrap().validate().issues
+}
+
+#[test]
+fn well_formed_hello_is_clean() {
+	let msg = tls13().build().unwrap();
+	let report = parse(&msg).unwrap().validate();
+	assert!(report.is_clean(), "{report:?}");
+	assert!(report.is_valid());
+}
+
+#[test]
+fn tls13_requirements() {
+	let hello = Builder::new()
+		.cipher_suites(&[0x1301])
+		.legacy_version(0x0301)
+		.compression_methods(&[0x01, 0x00])
+		.supported_versions(&[0x0304]);
+	assert_eq!(
+		issues(&hello),
+		[
+			ValidationIssue::CompressionMethods,
+			ValidationIssue::LegacyVersion(0x0301),
+			ValidationIssue::MissingTls13Extension(0x000d),
+			ValidationIssue::MissingTls13Extension(0x000a),
+			ValidationIssue::MissingTls13Extension(0x0033),
+		]
+	);
+
+	// Before TLS 1.3 only the presence of `null` is required.
+	let tls12 = Builder::new()
+		.cipher_suites(&[0xc02f])
+		.compression_methods(&[0x01, 0x00]);
+	assert!(issues(&tls12).is_empty());
+	let no_null = tls12.compression_methods(&[0x01]);
+	assert_eq!(issues(&no_null), [ValidationIssue::CompressionMethods]);
+}
+
+#[test]
+fn extension_placement() {
+	// `Builder::pre_shared_key` always encodes last, so use a raw body.
+	let psk_first = tls13()
+		.extension(0x0029, &[0x00, 0x00, 0x00, 0x00])
+		.extension(0x0015, &[0; 4]);
+	let found = issues(&psk_first);
+	assert_eq!(found, [ValidationIssue::PreSharedKeyNotLast]);
+	assert_eq!(found[0].severity(), Severity::Violation);
+
+	let early = tls13().extension(0x002a, &[]);
+	assert_eq!(issues(&early), [ValidationIssue::EarlyDataWithoutPsk]);
+
+	// The builder replaces repeated extensions, so rename a placeholder.
+	let mut duplicate = tls13()
+		.extension(0x0017, &[])
+		.extension(0xfe00, &[])
+		.build()
+		.unwrap();
+	let at = duplicate
+		.windows(4)
+		.position(|w| w == [0xfe, 0x00, 0x00, 0x00])
+		.unwrap();
+	duplicate[at..at + 2].copy_from_slice(&[0x00, 0x17]);
+	assert_eq!(
+		parse(&duplicate).unwrap().validate().issues,
+		[ValidationIssue::DuplicateExtension(0x0017)]
+	);
+}
+
+#[test]
+fn key_shares_checked_against_supported_groups() {
+	let hello = without_key_share().key_share(&[
+		(0x0017, &[0x04; 65]),
+		(0x001d, &[0x22; 32]),
+		(0x001d, &[0x33; 32]),
+		(0x0018, &[0x04; 97]),
+	]);
+	let found = issues(&hello);
+	assert!(found.contains(&ValidationIssue::DuplicateKeyShareGroup(0x001d)));
+	assert!(found.contains(&ValidationIssue::KeyShareGroupNotOffered(0x0018)));
+	assert!(found.contains(&ValidationIssue::KeyShareOrder));
+}
+
+#[test]
+fn server_name_literals() {
+	let ip = tls13().server_name("192.0.2.1");
+	assert!(issues(&ip).contains(&ValidationIssue::ServerNameIpLiteral));
+	let dot = tls13().server_name("example.com.");
+	assert!(issues(&dot).contains(&ValidationIssue::ServerNameTrailingDot));
+}
+
+#[test]
+fn padding_must_be_zero() {
+	assert!(issues(&tls13().pad_to(512)).is_empty());
+	let covert = tls13().extension(0x0015, &[0x00, 0x00, 0x41, 0x00]);
+	assert_eq!(issues(&covert), [ValidationIssue::NonZeroPadding]);
+	assert_eq!(
+		ValidationIssue::NonZeroPadding.severity(),
+		Severity::Violation
+	);
+}
+
+#[test]
+fn lints_do_not_invalidate() {
+	// renegotiation_info without the SCSV is consistent; both with a
+	// non-empty body is not.
+	let msg = tls13()
+		.cipher_suites(&[0x1301, 0x00ff])
+		.renegotiation_info(&[0x01, 0x00])
+		.build()
+		.unwrap();
+	let report = parse(&msg).unwrap().validate();
+	assert!(report.is_valid(), "{report:?}");
+	assert!(!report.is_clean());
+	assert_eq!(
+		report.lints().collect::<Vec<_>>(),
+		[&ValidationIssue::InconsistentRenegotiation]
+	);
+	assert_eq!(report.violations().count(), 0);
+}
+
+#[test]
+fn parse_warnings_are_included() {
+	let mut trailing = tls13().build().unwrap();
+	trailing.extend_from_slice(&[0; 3]);
+	let len = (trailing.len() - 4) as u32;
+	trailing[1..4].copy_from_slice(&len.to_be_bytes()[1..]);
+	let report = parse(&trailing).unwrap().validate();
+	assert!(!report.is_valid());
+	assert!(report.violations().any(|i| matches!(
+		i,
+		ValidationIssue::Parse(Warning::TrailingBodyBytes { len: 3 })
+	)));
+}
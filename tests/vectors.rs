@@ -0,0 +1,97 @@
+/* tests/vectors.rs */
+#![allow(missing_docs)]
+
+use clienthello::Builder;
+use clienthello::vectors::{self, Expect, TestVector, VectorError};
+
+const FILE: &str = "\
+# Regression cases shared with the proxy team.
+format: 1
+
+vector: minimal handshake
+note: smallest hello accepted # not a comment
+01 00 00 29 03 03  # header and version
+00000000000000000000000000000000
+00000000000000000000000000000000
+00 0002 1301 0100
+
+vector: truncated cipher suites
+expect: fails
+01 00 00 29 03 03
+";
+
+#[test]
+fn parse_hand_written_file() {
+	let loaded = vectors::parse(FILE).unwrap();
+	assert_eq!(loaded.len(), 2);
+	assert_eq!(loaded[0].name, "minimal handshake");
+	assert_eq!(loaded[0].expect, Expect::Parses);
+	assert_eq!(loaded[0].notes, ["smallest hello accepted # not a comment"]);
+	assert_eq!(loaded[0].bytes.len(), 45);
+	assert_eq!(loaded[0].parse().unwrap().cipher_suites, [0x1301]);
+	assert_eq!(loaded[1].expect, Expect::Fails);
+	assert!(loaded.iter().all(TestVector::passes));
+}
+
+#[test]
+fn write_annotates_and_round_trips() {
+	let hello = Builder::new()
+		.cipher_suites(&[0x1301])
+		.server_name("example.com")
+		.supported_versions(&[0x0304]);
+	let mut record = TestVector::new("record", hello.build_record().unwrap());
+	record.notes.push("two\nlines".into());
+	let mut broken = TestVector::new("broken", hello.build().unwrap());
+	broken.bytes.truncate(60);
+	broken.expect = Expect::Fails;
+
+	let text = vectors::write(&[record.clone(), broken.clone()]);
+	for label in [
+		"# record header",
+		"# handshake header",
+		"# random",
+		"# cipher suites",
+		"# extension 0x0000",
+		"# extension 0x002b",
+		"# unparsed",
+	] {
+		assert!(text.contains(label), "{label} missing from\n{text}");
+	}
+	assert!(text.contains("note: two\nnote: lines\n"));
+
+	let loaded = vectors::parse(&text).unwrap();
+	assert_eq!(loaded[0].bytes, record.bytes);
+	assert_eq!(loaded[0].notes, ["two", "lines"]);
+	assert_eq!(loaded[1], broken);
+	assert!(loaded.iter().all(TestVector::passes));
+}
+
+#[test]
+fn errors_name_the_line() {
+	let cases = [
+		("format: 2\n", 1, "unsupported format version"),
+		(
+			"vector: a\nformat: 1\n",
+			2,
+			"format must precede the first vector",
+		),
+		("01 00\n", 1, "bytes outside a vector"),
+		("vector: a\n\n010\n", 3, "odd number of hex digits"),
+		("vector: a\nzz\n", 2, "invalid hex digit"),
+		(
+			"vector: a\nexpect: maybe\n",
+			2,
+			"expect must be `parses` or `fails`",
+		),
+		("vector:\n", 1, "missing vector name"),
+		("vector: a\nsni: example.com\n", 2, "unknown key"),
+	];
+	for (text, line, reason) in cases {
+		assert_eq!(
+			vectors::parse(text),
+			Err(VectorError { line, reason }),
+			"{text:?}"
+		);
+	}
+	assert!(!TestVector::new("empty", []).passes());
+}